@@ -0,0 +1,154 @@
+// Copyright 2024 ADM Contributors
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use anyhow::anyhow;
+use ethers::{
+    abi::Detokenize,
+    middleware::Middleware,
+    prelude::{I256, U256},
+    types::TransactionReceipt,
+};
+use ethers_contract::ContractCall;
+
+use crate::gateway::TRANSACTION_RECEIPT_RETRIES;
+
+/// Receives an input `FunctionCall` and returns a new instance
+/// after estimating an optimal `gas_premium` for the transaction
+pub async fn call_with_premium_estimation<M, B, D, R>(
+    client: &M,
+    call: ethers_contract::FunctionCall<B, D, R>,
+) -> anyhow::Result<ethers_contract::FunctionCall<B, D, R>>
+where
+    M: Middleware + 'static,
+    B: std::borrow::Borrow<D>,
+    R: ethers::abi::Detokenize,
+{
+    let (max_priority_fee_per_gas, _) = premium_estimation(client).await?;
+    Ok(call.gas_price(max_priority_fee_per_gas))
+}
+
+/// Sends a contract call with an estimated gas premium and waits for its receipt
+/// using the configured receipt retries.
+pub async fn send_with_premium_estimation<M, D>(
+    client: &M,
+    call: ContractCall<M, D>,
+) -> anyhow::Result<TransactionReceipt>
+where
+    M: Middleware + 'static,
+    D: Detokenize,
+{
+    let call = call_with_premium_estimation(client, call).await?;
+    let tx = call.send().await?;
+    match tx.retries(TRANSACTION_RECEIPT_RETRIES).await? {
+        Some(receipt) => Ok(receipt),
+        None => Err(anyhow!(
+            "txn sent to network, but receipt cannot be obtained, please check scanner"
+        )),
+    }
+}
+
+/// Returns an estimation of an optimal `gas_premium` and `gas_fee_cap`
+/// for a transaction considering the average premium, base_fee and reward percentile from
+/// past blocks
+/// This is an adaptation of ethers' `eip1559_default_estimator`:
+/// https://github.com/gakonst/ethers-rs/blob/5dcd3b7e754174448f9a8cbfc0523896609629f9/ethers-core/src/utils/mod.rs#L476
+pub async fn premium_estimation<M>(client: &M) -> anyhow::Result<(U256, U256)>
+where
+    M: Middleware + 'static,
+{
+    let base_fee_per_gas = client
+        .get_block(ethers::types::BlockNumber::Latest)
+        .await?
+        .ok_or_else(|| anyhow!("Latest block not found"))?
+        .base_fee_per_gas
+        .ok_or_else(|| anyhow!("EIP-1559 not activated"))?;
+
+    let fee_history = client
+        .fee_history(
+            ethers::utils::EIP1559_FEE_ESTIMATION_PAST_BLOCKS,
+            ethers::types::BlockNumber::Latest,
+            &[ethers::utils::EIP1559_FEE_ESTIMATION_REWARD_PERCENTILE],
+        )
+        .await?;
+
+    let max_priority_fee_per_gas = estimate_priority_fee(fee_history.reward); //overestimate?
+    let potential_max_fee = base_fee_surged(base_fee_per_gas);
+    let max_fee_per_gas = if max_priority_fee_per_gas > potential_max_fee {
+        max_priority_fee_per_gas + potential_max_fee
+    } else {
+        potential_max_fee
+    };
+
+    Ok((max_priority_fee_per_gas, max_fee_per_gas))
+}
+
+/// Implementation borrowed from
+/// https://github.com/gakonst/ethers-rs/blob/ethers-v2.0.8/ethers-core/src/utils/mod.rs#L582
+/// Refer to the implementation for unit tests
+fn base_fee_surged(base_fee_per_gas: U256) -> U256 {
+    if base_fee_per_gas <= U256::from(40_000_000_000u64) {
+        base_fee_per_gas * 2
+    } else if base_fee_per_gas <= U256::from(100_000_000_000u64) {
+        base_fee_per_gas * 16 / 10
+    } else if base_fee_per_gas <= U256::from(200_000_000_000u64) {
+        base_fee_per_gas * 14 / 10
+    } else {
+        base_fee_per_gas * 12 / 10
+    }
+}
+
+/// Implementation borrowed from
+/// https://github.com/gakonst/ethers-rs/blob/ethers-v2.0.8/ethers-core/src/utils/mod.rs#L536
+/// Refer to the implementation for unit tests
+fn estimate_priority_fee(rewards: Vec<Vec<U256>>) -> U256 {
+    let mut rewards: Vec<U256> = rewards
+        .iter()
+        .map(|r| r[0])
+        .filter(|r| *r > U256::zero())
+        .collect();
+    if rewards.is_empty() {
+        return U256::zero();
+    }
+    if rewards.len() == 1 {
+        return rewards[0];
+    }
+    // Sort the rewards as we will eventually take the median.
+    rewards.sort();
+
+    // A copy of the same vector is created for convenience to calculate percentage change
+    // between subsequent fee values.
+    let mut rewards_copy = rewards.clone();
+    rewards_copy.rotate_left(1);
+
+    let mut percentage_change: Vec<I256> = rewards
+        .iter()
+        .zip(rewards_copy.iter())
+        .map(|(a, b)| {
+            let a = I256::try_from(*a).expect("priority fee overflow");
+            let b = I256::try_from(*b).expect("priority fee overflow");
+            ((b - a) * 100) / a
+        })
+        .collect();
+    percentage_change.pop();
+
+    // Fetch the max of the percentage change, and that element's index.
+    let max_change = percentage_change.iter().max().unwrap();
+    let max_change_index = percentage_change
+        .iter()
+        .position(|&c| c == *max_change)
+        .unwrap();
+
+    // If we encountered a big change in fees at a certain position, then consider only
+    // the values >= it.
+    let values = if *max_change >= ethers::utils::EIP1559_FEE_ESTIMATION_THRESHOLD_MAX_CHANGE.into()
+        && (max_change_index >= (rewards.len() / 2))
+    {
+        rewards[max_change_index..].to_vec()
+    } else {
+        rewards
+    };
+
+    // Return the median.
+    values[values.len() / 2]
+}
@@ -0,0 +1,211 @@
+// Copyright 2024 ADM Contributors
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use ethers::{middleware::Middleware, types::TransactionReceipt};
+use ethers_contract::{parse_log, ContractCall};
+use fvm_shared::{address::Address, econ::TokenAmount};
+use ipc_actors_abis::gateway_getter_facet::{
+    GatewayGetterFacet, SubnetID as GatewayGetterSubnetID,
+};
+use ipc_actors_abis::gateway_manager_facet::{
+    FvmAddress, GatewayManagerFacet, SubnetID as GatewaySubnetID,
+};
+use ipc_actors_abis::lib_gateway::NewTopDownMessageFilter;
+use ipc_api::{
+    cross::IpcEnvelope,
+    evm::{ethers_address_to_fil_address, payload_to_evm_address},
+    subnet_id::SubnetID,
+};
+use num_traits::ToPrimitive;
+
+use crate::gas::send_with_premium_estimation;
+
+/// Maximum number of retries to fetch a transaction receipt.
+/// The number of retries should ensure that for the block time
+/// of the network the number of retires considering the polling
+/// time above waits enough tie to get the transaction receipt.
+/// We currently support a low polling time and high number of
+/// retries so these numbers accommodate fast subnets with slow
+/// roots (like Calibration and mainnet).
+pub const TRANSACTION_RECEIPT_RETRIES: usize = 200;
+
+/// A cross-net message committed by a gateway.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CrossMsg {
+    /// The destination subnet.
+    pub subnet: SubnetID,
+    /// The sender in the source subnet.
+    pub from: Address,
+    /// The recipient in the destination subnet.
+    pub to: Address,
+    /// The amount of funds carried by the message.
+    pub amount: TokenAmount,
+    /// The message nonce, which orders messages to the destination subnet.
+    ///
+    /// Only top-down messages have a nonce when they're committed.
+    /// Bottom-up messages are batched into a checkpoint instead of being emitted one by one.
+    /// This is also `None` if the gateway's event could not be decoded.
+    pub nonce: Option<u64>,
+}
+
+impl CrossMsg {
+    /// Decode the top-down messages committed by a transaction from the gateway's events.
+    pub fn top_down(receipt: &TransactionReceipt) -> anyhow::Result<Vec<Self>> {
+        receipt
+            .logs
+            .iter()
+            .filter_map(|log| parse_log::<NewTopDownMessageFilter>(log.clone()).ok())
+            .map(|event| {
+                let envelope = IpcEnvelope::try_from(event.message)
+                    .map_err(|e| anyhow!("invalid cross-net message: {e}"))?;
+                Ok(Self {
+                    subnet: envelope.to.subnet()?,
+                    from: envelope.from.raw_addr()?,
+                    to: envelope.to.raw_addr()?,
+                    amount: envelope.value,
+                    nonce: Some(envelope.nonce),
+                })
+            })
+            .collect()
+    }
+}
+
+/// A gateway transaction receipt with the cross-net message it committed.
+#[derive(Clone, Debug)]
+pub struct CrossMsgReceipt {
+    /// The transaction receipt.
+    pub receipt: TransactionReceipt,
+    /// The committed cross-net message.
+    pub message: CrossMsg,
+}
+
+/// An interface to a subnet's gateway contract using any ethers [`Middleware`].
+///
+/// The middleware must be able to sign transactions, e.g., a [`ethers::middleware::SignerMiddleware`].
+pub struct Gateway<M> {
+    inner: GatewayManagerFacet<M>,
+    getter: GatewayGetterFacet<M>,
+}
+
+impl<M> Gateway<M>
+where
+    M: Middleware + 'static,
+{
+    /// Create a new gateway interface for the contract at the given [`Address`].
+    pub fn new(address: Address, client: Arc<M>) -> anyhow::Result<Self> {
+        let address = payload_to_evm_address(address.payload())?;
+        Ok(Self {
+            inner: GatewayManagerFacet::new(address, client.clone()),
+            getter: GatewayGetterFacet::new(address, client),
+        })
+    }
+
+    /// Returns the underlying [`GatewayManagerFacet`] binding.
+    pub fn inner(&self) -> &GatewayManagerFacet<M> {
+        &self.inner
+    }
+
+    /// Returns the IDs of all child subnets registered with the gateway.
+    ///
+    /// This is a read-only call, so the middleware does not need to sign.
+    pub async fn list_subnets(&self) -> anyhow::Result<Vec<SubnetID>> {
+        let subnets = self.getter.list_subnets().call().await?;
+        subnets
+            .into_iter()
+            .map(|s| SubnetID::try_from(s.id).map_err(|e| anyhow!("invalid subnet ID: {e}")))
+            .collect()
+    }
+
+    /// Returns whether the given child subnet is registered with the gateway.
+    ///
+    /// This is a read-only call, so the middleware does not need to sign.
+    pub async fn has_subnet(&self, subnet_id: &SubnetID) -> anyhow::Result<bool> {
+        let subnet_id = GatewayGetterSubnetID::try_from(subnet_id)?;
+        let (found, _) = self.getter.get_subnet(subnet_id).call().await?;
+        Ok(found)
+    }
+
+    /// Returns the nonce of the next top-down message this subnet's gateway will execute.
+    ///
+    /// This is a read-only call, so the middleware does not need to sign.
+    pub async fn applied_top_down_nonce(&self) -> anyhow::Result<u64> {
+        Ok(self.getter.applied_top_down_nonce().call().await?)
+    }
+
+    /// Deposit funds from the parent into an address in the given child subnet.
+    ///
+    /// The gateway must be the parent's gateway.
+    pub async fn deposit(
+        &self,
+        subnet_id: &SubnetID,
+        to: Address,
+        amount: &TokenAmount,
+    ) -> anyhow::Result<CrossMsgReceipt> {
+        let gateway_subnet_id = GatewaySubnetID::try_from(subnet_id)?;
+        let mut call = self
+            .inner
+            .fund(gateway_subnet_id, FvmAddress::try_from(to)?);
+        call.tx.set_value(to_value(amount)?);
+        let receipt = self.send(call).await?;
+        // The funds are already sent, so fall back to the call args if the event can't be decoded
+        let message = match CrossMsg::top_down(&receipt) {
+            Ok(messages) => messages.into_iter().find(|m| m.subnet == *subnet_id),
+            Err(_) => None,
+        };
+        let message = match message {
+            Some(message) => message,
+            None => CrossMsg {
+                subnet: subnet_id.clone(),
+                from: ethers_address_to_fil_address(&receipt.from)?,
+                to,
+                amount: amount.clone(),
+                nonce: None,
+            },
+        };
+        Ok(CrossMsgReceipt { receipt, message })
+    }
+
+    /// Release funds from the subnet to an address in the parent subnet.
+    ///
+    /// The gateway must be the child subnet's gateway.
+    /// The returned message has no nonce, since it's only numbered once it's batched into a
+    /// bottom-up checkpoint.
+    pub async fn release(
+        &self,
+        subnet_id: &SubnetID,
+        to: Address,
+        amount: &TokenAmount,
+    ) -> anyhow::Result<CrossMsgReceipt> {
+        let parent = subnet_id
+            .parent()
+            .ok_or_else(|| anyhow!("subnet {} has no parent", subnet_id))?;
+        let mut call = self.inner.release(FvmAddress::try_from(to)?);
+        call.tx.set_value(to_value(amount)?);
+        let receipt = self.send(call).await?;
+        let message = CrossMsg {
+            subnet: parent,
+            from: ethers_address_to_fil_address(&receipt.from)?,
+            to,
+            amount: amount.clone(),
+            nonce: None,
+        };
+        Ok(CrossMsgReceipt { receipt, message })
+    }
+
+    /// Sends a contract call with an estimated gas premium and configured receipt retries.
+    pub async fn send(&self, call: ContractCall<M, ()>) -> anyhow::Result<TransactionReceipt> {
+        send_with_premium_estimation(self.inner.client().as_ref(), call).await
+    }
+}
+
+/// Converts a [`TokenAmount`] into a transaction value.
+pub(crate) fn to_value(amount: &TokenAmount) -> anyhow::Result<u128> {
+    amount
+        .atto()
+        .to_u128()
+        .ok_or_else(|| anyhow!("invalid value to fund"))
+}
@@ -0,0 +1,18 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! # ADM ABIs
+//!
+//! EVM gateway and registry helpers for the ADM.
+//!
+//! These helpers only depend on ethers-rs and work with any [`ethers::middleware::Middleware`],
+//! so apps that already manage their own EVM providers and signers can move funds
+//! between a parent chain and an ADM subnet without pulling in the full SDK.
+
+pub mod gas;
+pub mod gateway;
+pub mod registry;
+pub mod subnet_actor;
+
+/// Raw IPC contract bindings, including the gateway and registry facets.
+pub use ipc_actors_abis as bindings;
@@ -0,0 +1,117 @@
+// Copyright 2024 ADM Contributors
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use ethers::{
+    middleware::Middleware,
+    types::{Address as EthAddress, U256},
+};
+use fvm_shared::{address::Address, clock::ChainEpoch, econ::TokenAmount};
+use ipc_actors_abis::register_subnet_facet::{
+    ConstructorParams, RegisterSubnetFacet, SubnetDeployedFilter, SubnetID as RegistrySubnetID,
+    SupplySource,
+};
+use ipc_api::{
+    ethers_address_to_fil_address, evm::payload_to_evm_address, subnet::PermissionMode,
+    subnet_id::SubnetID,
+};
+
+use crate::gas::send_with_premium_estimation;
+use crate::gateway::to_value;
+
+/// Percentage of validator power required to commit checkpoints.
+const SUBNET_MAJORITY_PERCENTAGE: u8 = 67;
+
+/// Power scale used to convert collateral into validator power.
+/// A scale of 3 means power is measured in milli-FIL.
+const SUBNET_POWER_SCALE: i8 = 3;
+
+/// Parameters for creating a new child subnet.
+#[derive(Clone, Debug)]
+pub struct CreateSubnetParams {
+    /// The parent subnet ID, i.e., the subnet where the subnet actor is deployed.
+    pub parent: SubnetID,
+    /// The parent's gateway contract address.
+    pub gateway: Address,
+    /// Minimum collateral required for the subnet to be activated.
+    pub min_validator_stake: TokenAmount,
+    /// Minimum number of validators required for the subnet to be activated.
+    pub min_validators: u64,
+    /// Number of parent blocks between bottom-up checkpoints.
+    pub bottom_up_check_period: ChainEpoch,
+    /// Maximum number of active validators.
+    pub active_validators_limit: u16,
+    /// How validator power is determined.
+    pub permission_mode: PermissionMode,
+}
+
+/// An interface to a subnet registry contract using any ethers [`Middleware`].
+///
+/// The middleware must be able to sign transactions, e.g., a [`ethers::middleware::SignerMiddleware`].
+pub struct Registry<M> {
+    inner: RegisterSubnetFacet<M>,
+}
+
+impl<M> Registry<M>
+where
+    M: Middleware + 'static,
+{
+    /// Create a new registry interface for the contract at the given [`Address`].
+    pub fn new(address: Address, client: Arc<M>) -> anyhow::Result<Self> {
+        let address = payload_to_evm_address(address.payload())?;
+        Ok(Self {
+            inner: RegisterSubnetFacet::new(address, client),
+        })
+    }
+
+    /// Returns the underlying [`RegisterSubnetFacet`] binding.
+    pub fn inner(&self) -> &RegisterSubnetFacet<M> {
+        &self.inner
+    }
+
+    /// Deploy a new subnet actor and return its address.
+    ///
+    /// The subnet is registered with the parent's gateway once it's bootstrapped,
+    /// i.e., once enough validators have joined with enough collateral.
+    pub async fn create_subnet(&self, params: &CreateSubnetParams) -> anyhow::Result<Address> {
+        let route = params
+            .parent
+            .children_as_ref()
+            .iter()
+            .map(|a| payload_to_evm_address(a.payload()))
+            .collect::<anyhow::Result<Vec<EthAddress>>>()?;
+        let params = ConstructorParams {
+            parent_id: RegistrySubnetID {
+                root: params.parent.root_id(),
+                route,
+            },
+            ipc_gateway_addr: payload_to_evm_address(params.gateway.payload())?,
+            consensus: 0, // Fendermint
+            min_activation_collateral: U256::from(to_value(&params.min_validator_stake)?),
+            min_validators: params.min_validators,
+            bottom_up_check_period: params.bottom_up_check_period as u64,
+            majority_percentage: SUBNET_MAJORITY_PERCENTAGE,
+            active_validators_limit: params.active_validators_limit,
+            power_scale: SUBNET_POWER_SCALE,
+            permission_mode: params.permission_mode as u8,
+            supply_source: SupplySource {
+                kind: 0, // Native
+                token_address: EthAddress::zero(),
+            },
+        };
+
+        let call = self.inner.new_subnet_actor(params);
+        let receipt = send_with_premium_estimation(self.inner.client().as_ref(), call).await?;
+        for log in receipt.logs {
+            if let Ok(event) = ethers_contract::parse_log::<SubnetDeployedFilter>(log) {
+                return ethers_address_to_fil_address(&event.subnet_addr);
+            }
+        }
+        Err(anyhow!(
+            "transaction receipt does not contain a subnet deployed event"
+        ))
+    }
+}
@@ -0,0 +1,145 @@
+// Copyright 2024 ADM Contributors
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::sync::Arc;
+
+use ethers::{
+    middleware::Middleware,
+    types::{Address as EthAddress, Bytes, TransactionReceipt, U256},
+};
+use ethers_contract::ContractCall;
+use fvm_shared::{address::Address, econ::TokenAmount};
+use ipc_actors_abis::subnet_actor_getter_facet::SubnetActorGetterFacet;
+use ipc_actors_abis::subnet_actor_manager_facet::SubnetActorManagerFacet;
+use ipc_actors_abis::subnet_actor_reward_facet::SubnetActorRewardFacet;
+use ipc_api::evm::payload_to_evm_address;
+
+use crate::gas::send_with_premium_estimation;
+use crate::gateway::to_value;
+
+/// A subnet validator as tracked by its subnet actor.
+#[derive(Clone, Debug)]
+pub struct Validator {
+    /// The validator's EVM address.
+    pub address: EthAddress,
+    /// Whether the validator is in the active set (otherwise it's waiting).
+    pub active: bool,
+    /// The validator's power in federated subnets.
+    pub federated_power: U256,
+    /// Collateral confirmed by the subnet.
+    pub confirmed_collateral: U256,
+    /// Total collateral, including changes that are not confirmed yet.
+    pub total_collateral: U256,
+}
+
+/// An interface to a subnet actor contract using any ethers [`Middleware`].
+///
+/// Subnet actors live in the parent chain, so the middleware must point at the parent.
+/// Methods that send transactions require a middleware that is able to sign.
+pub struct SubnetActor<M> {
+    inner: SubnetActorManagerFacet<M>,
+    getter: SubnetActorGetterFacet<M>,
+    reward: SubnetActorRewardFacet<M>,
+}
+
+impl<M> SubnetActor<M>
+where
+    M: Middleware + 'static,
+{
+    /// Create a new subnet actor interface for the contract at the given [`Address`].
+    pub fn new(address: Address, client: Arc<M>) -> anyhow::Result<Self> {
+        let address = payload_to_evm_address(address.payload())?;
+        Ok(Self {
+            inner: SubnetActorManagerFacet::new(address, client.clone()),
+            getter: SubnetActorGetterFacet::new(address, client.clone()),
+            reward: SubnetActorRewardFacet::new(address, client),
+        })
+    }
+
+    /// Returns the underlying [`SubnetActorManagerFacet`] binding.
+    pub fn inner(&self) -> &SubnetActorManagerFacet<M> {
+        &self.inner
+    }
+
+    /// Returns the underlying [`SubnetActorGetterFacet`] binding.
+    pub fn getter(&self) -> &SubnetActorGetterFacet<M> {
+        &self.getter
+    }
+
+    /// Returns all active and waiting validators.
+    pub async fn validators(&self) -> anyhow::Result<Vec<Validator>> {
+        let active = self.getter.get_active_validators().call().await?;
+        let waiting = self.getter.get_waiting_validators().call().await?;
+
+        let mut validators = Vec::with_capacity(active.len() + waiting.len());
+        for address in active.into_iter().chain(waiting) {
+            validators.push(self.validator(address).await?);
+        }
+        Ok(validators)
+    }
+
+    /// Returns info about a single validator.
+    ///
+    /// Unknown validators are returned with zero collateral and power.
+    pub async fn validator(&self, address: EthAddress) -> anyhow::Result<Validator> {
+        let active = self.getter.is_active_validator(address).call().await?;
+        let info = self.getter.get_validator(address).call().await?;
+        Ok(Validator {
+            address,
+            active,
+            federated_power: info.federated_power,
+            confirmed_collateral: info.confirmed_collateral,
+            total_collateral: info.total_collateral,
+        })
+    }
+
+    /// Join the subnet as a validator with the given public key and initial collateral.
+    ///
+    /// The public key must be an uncompressed secp256k1 key that matches the signer.
+    pub async fn join(
+        &self,
+        public_key: &[u8],
+        collateral: &TokenAmount,
+    ) -> anyhow::Result<TransactionReceipt> {
+        let mut call = self.inner.join(Bytes::from(public_key.to_vec()));
+        call.tx.set_value(to_value(collateral)?);
+        self.send(call).await
+    }
+
+    /// Leave the subnet, releasing the validator's collateral once the change is confirmed.
+    pub async fn leave(&self) -> anyhow::Result<TransactionReceipt> {
+        self.send(self.inner.leave()).await
+    }
+
+    /// Add collateral to the signer's validator stake.
+    pub async fn stake(&self, amount: &TokenAmount) -> anyhow::Result<TransactionReceipt> {
+        let mut call = self.inner.stake();
+        call.tx.set_value(to_value(amount)?);
+        self.send(call).await
+    }
+
+    /// Remove collateral from the signer's validator stake.
+    ///
+    /// The collateral can be claimed once the change is confirmed by the subnet.
+    pub async fn unstake(&self, amount: &TokenAmount) -> anyhow::Result<TransactionReceipt> {
+        let call = self.inner.unstake(U256::from(to_value(amount)?));
+        self.send(call).await
+    }
+
+    /// Claim any released collateral back to the signer.
+    pub async fn claim(&self) -> anyhow::Result<TransactionReceipt> {
+        let call = self.reward.claim();
+        send_with_premium_estimation(self.reward.client().as_ref(), call).await
+    }
+
+    /// Kill the subnet. This only succeeds once all validators have left.
+    pub async fn kill(&self) -> anyhow::Result<TransactionReceipt> {
+        self.send(self.inner.kill()).await
+    }
+
+    /// Sends a contract call with an estimated gas premium and configured receipt retries.
+    pub async fn send(&self, call: ContractCall<M, ()>) -> anyhow::Result<TransactionReceipt> {
+        send_with_premium_estimation(self.inner.client().as_ref(), call).await
+    }
+}
@@ -1,23 +1,48 @@
 // Copyright 2024 ADM Contributors
 // SPDX-License-Identifier: Apache-2.0, MIT
 
-use clap::{Args, Subcommand};
+use std::fs;
+use std::future::Future;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context};
+use clap::{Args, Subcommand, ValueEnum};
+use clap_complete::engine::ArgValueCompleter;
+use ethers::utils::to_checksum;
 use fendermint_crypto::SecretKey;
 use fendermint_vm_actor_interface::eam::EthAddress;
-use fvm_shared::{address::Address, econ::TokenAmount};
-use reqwest::Url;
-use serde_json::json;
-use std::time::Duration;
+use fendermint_vm_message::query::FvmQueryHeight;
+use fvm_ipld_encoding::RawBytes;
+use fvm_shared::{address::Address, econ::TokenAmount, METHOD_SEND};
+use serde_json::{json, Value};
 
+use adm_abis::gateway::CrossMsgReceipt;
 use adm_provider::{
-    json_rpc::JsonRpcProvider,
-    util::{get_delegated_address, parse_address, parse_token_amount},
+    query::QueryProvider,
+    response::decode_bytes,
+    util::{get_delegated_address, parse_token_amount, TokenUnit},
+};
+use adm_sdk::{
+    account::{Account, DepositOptions},
+    ipc::subnet::EVMSubnet,
+    name::{parse_address_or_name, AddressOrName},
+    network::Network as SdkNetwork,
+    tx::TxBuilder,
+};
+use adm_signer::key::{random_secretkey, to_lotus_key, to_pem, write_eth_keystore};
+use adm_signer::{
+    key::parse_secret_key, keystore::Keystore, AccountKind, Signer, SubnetID, Void, Wallet,
 };
-use adm_sdk::{account::Account, ipc::subnet::EVMSubnet};
-use adm_signer::key::random_secretkey;
-use adm_signer::{key::parse_secret_key, AccountKind, Signer, SubnetID, Void, Wallet};
 
-use crate::{get_address, get_rpc_url, get_subnet_id, print_json, AddressArgs, Cli};
+use crate::complete::complete_contact;
+use crate::contacts::resolve_contact;
+use crate::subnet::{get_parent_subnet_config, get_subnet_config, EvmSubnetArgs};
+use crate::{
+    confirm_signer, get_address, get_keystore_path, get_provider, get_subnet_id, get_units,
+    print_json, reject_interactive, AddressArgs, Cli,
+};
 
 #[derive(Clone, Debug, Args)]
 pub struct AccountArgs {
@@ -28,34 +53,32 @@ pub struct AccountArgs {
 #[derive(Clone, Debug, Subcommand)]
 enum AccountCommands {
     /// Create a new account from a random seed.
-    Create,
+    Create(CreateArgs),
     /// Get account information.
     Info(InfoArgs),
     /// Deposit funds into a subnet from its parent.
-    Deposit(FundArgs),
+    Deposit(DepositArgs),
     /// Withdraw funds from a subnet to its parent.
     Withdraw(FundArgs),
     /// Transfer funds to another account in a subnet.
     Transfer(TransferArgs),
+    /// Move funds between the subnet and its parent, waiting until they arrive.
+    Bridge(BridgeArgs),
+    /// Export the private key to a standard format.
+    Export(ExportArgs),
+    /// Fund an account from a faucet, deposit into the subnet, and print a ready-to-use profile.
+    Bootstrap(BootstrapArgs),
 }
 
 #[derive(Clone, Debug, Args)]
-struct SubnetArgs {
-    /// The Ethereum API rpc http endpoint.
-    #[arg(long)]
-    evm_rpc_url: Option<Url>,
-    /// Timeout for calls to the Ethereum API.
-    #[arg(long, value_parser = humantime::parse_duration, default_value = "60s")]
-    evm_rpc_timeout: Duration,
-    /// Bearer token for any Authorization header.
+struct CreateArgs {
+    /// Save the private key into the local keystore under the given name
+    /// and set it as the default key.
     #[arg(long)]
-    evm_rpc_auth_token: Option<String>,
-    /// The gateway contract address.
-    #[arg(long, value_parser = parse_address)]
-    evm_gateway: Option<Address>,
-    /// The registry contract address.
-    #[arg(long, value_parser = parse_address)]
-    evm_registry: Option<Address>,
+    save: Option<String>,
+    /// Print the private key even if it's saved into the keystore.
+    #[arg(long, default_value_t = false)]
+    show_private_key: bool,
 }
 
 #[derive(Clone, Debug, Args)]
@@ -63,22 +86,33 @@ struct InfoArgs {
     #[command(flatten)]
     address: AddressArgs,
     #[command(flatten)]
-    subnet: SubnetArgs,
+    subnet: EvmSubnetArgs,
 }
 
 #[derive(Clone, Debug, Args)]
-struct FundArgs {
+pub(crate) struct FundArgs {
     /// Wallet private key (ECDSA, secp256k1) for signing transactions.
     #[arg(short, long, env, value_parser = parse_secret_key)]
     private_key: SecretKey,
-    /// The recipient account address. If not present, the signer address is used.
-    #[arg(long, value_parser = parse_address)]
-    to: Option<Address>,
+    /// The recipient account address or contact label.
+    /// If not present, the signer address is used.
+    #[arg(long, value_parser = parse_address_or_name, add = ArgValueCompleter::new(complete_contact))]
+    to: Option<AddressOrName>,
     /// The amount to transfer in FIL.
     #[arg(value_parser = parse_token_amount)]
     amount: TokenAmount,
     #[command(flatten)]
-    subnet: SubnetArgs,
+    subnet: EvmSubnetArgs,
+}
+
+#[derive(Clone, Debug, Args)]
+pub(crate) struct DepositArgs {
+    #[command(flatten)]
+    fund: FundArgs,
+    /// Number of parent blocks that must follow the deposit's block before it's reported as
+    /// successful, which guards against parent chain reorgs. '0' returns once it's included.
+    #[arg(long, default_value_t = 0)]
+    finality: u64,
 }
 
 #[derive(Clone, Debug, Args)]
@@ -86,127 +120,600 @@ struct TransferArgs {
     /// Wallet private key (ECDSA, secp256k1) for signing transactions.
     #[arg(short, long, env, value_parser = parse_secret_key)]
     private_key: SecretKey,
-    /// The recipient account address.
-    #[arg(long, value_parser = parse_address)]
-    to: Address,
+    /// The recipient account address or contact label.
+    #[arg(long, value_parser = parse_address_or_name, add = ArgValueCompleter::new(complete_contact))]
+    to: AddressOrName,
     /// The amount to transfer in FIL.
     #[arg(value_parser = parse_token_amount)]
     amount: TokenAmount,
     #[command(flatten)]
-    subnet: SubnetArgs,
+    subnet: EvmSubnetArgs,
+}
+
+#[derive(Clone, Debug, Args)]
+struct BridgeArgs {
+    /// Wallet private key (ECDSA, secp256k1) for signing transactions.
+    #[arg(short, long, env, value_parser = parse_secret_key)]
+    private_key: SecretKey,
+    /// The recipient account address or contact label.
+    /// If not present, the signer address is used.
+    #[arg(long, value_parser = parse_address_or_name, add = ArgValueCompleter::new(complete_contact))]
+    to: Option<AddressOrName>,
+    /// The direction to move funds.
+    #[arg(long, value_enum)]
+    direction: BridgeDirection,
+    /// The amount to transfer in FIL.
+    #[arg(value_parser = parse_token_amount)]
+    amount: TokenAmount,
+    /// Timeout for calls to the Ethereum API.
+    #[arg(long, value_parser = humantime::parse_duration, default_value = "60s")]
+    evm_rpc_timeout: Duration,
+}
+
+#[derive(Clone, Debug, Copy, PartialEq, Eq, ValueEnum)]
+enum BridgeDirection {
+    /// From the parent into the subnet.
+    Deposit,
+    /// From the subnet to its parent.
+    Withdraw,
+}
+
+#[derive(Clone, Debug, Args)]
+struct ExportArgs {
+    /// Wallet private key (ECDSA, secp256k1) to export.
+    #[arg(short, long, env, value_parser = parse_secret_key)]
+    private_key: SecretKey,
+    /// The export format.
+    #[arg(short, long, value_enum, default_value_t = ExportFormat::Hex)]
+    format: ExportFormat,
+    /// Output file. Required for the keystore format; other formats are printed to stdout by default.
+    #[arg(short, long, required_if_eq("format", "keystore"))]
+    output: Option<PathBuf>,
+    /// Password used to encrypt the keystore.
+    #[arg(long, env, required_if_eq("format", "keystore"))]
+    keystore_password: Option<String>,
+}
+
+#[derive(Clone, Debug, Args)]
+struct BootstrapArgs {
+    /// Wallet private key (ECDSA, secp256k1) of the account to fund.
+    /// A new account is created if not present.
+    #[arg(short, long, env, value_parser = parse_secret_key)]
+    private_key: Option<SecretKey>,
+    /// Save a new account's private key into the local keystore under the given name
+    /// and set it as the default key.
+    #[arg(long)]
+    save: Option<String>,
+    /// Faucet URL that sends funds on the parent chain, or in the subnet on Devnet and Localnet.
+    /// It's sent a POST request with a JSON body of `{"address": "<ADDRESS>"}`.
+    #[arg(long, env, required_unless_present = "genesis_key")]
+    faucet_url: Option<reqwest::Url>,
+    /// Wallet private key (ECDSA, secp256k1) of a funded genesis account on Devnet or Localnet.
+    /// The account is sent funds from it directly in the subnet, without a faucet or the parent chain.
+    #[arg(long, env, value_parser = parse_secret_key, conflicts_with = "faucet_url")]
+    genesis_key: Option<SecretKey>,
+    /// The amount to send from the genesis account in FIL.
+    #[arg(long, value_parser = parse_token_amount, default_value = "10", requires = "genesis_key")]
+    amount: TokenAmount,
+    /// The amount to deposit into the subnet in FIL.
+    /// Defaults to half of the faucet funds, leaving the rest for gas on the parent chain.
+    #[arg(long, value_parser = parse_token_amount)]
+    deposit: Option<TokenAmount>,
+    /// How long to wait for the faucet or genesis funds to arrive.
+    #[arg(long, value_parser = humantime::parse_duration, default_value = "10m")]
+    timeout: Duration,
+    /// Timeout for calls to the Ethereum API.
+    #[arg(long, value_parser = humantime::parse_duration, default_value = "60s")]
+    evm_rpc_timeout: Duration,
+}
+
+#[derive(Clone, Debug, Copy, PartialEq, Eq, ValueEnum)]
+enum ExportFormat {
+    /// Hex-encoded raw private key, e.g., for MetaMask's "Import account".
+    Hex,
+    /// Encrypted Ethereum keystore JSON (Web3 Secret Storage v3).
+    Keystore,
+    /// PEM SEC1 EC private key.
+    Pem,
+    /// Lotus key info for `lotus wallet import`, as a delegated (f410) key.
+    Lotus,
 }
 
 /// Account commmands handler.
 pub async fn handle_account(cli: Cli, args: &AccountArgs) -> anyhow::Result<()> {
-    let provider = JsonRpcProvider::new_http(get_rpc_url(&cli)?, None, None)?;
+    let provider = get_provider(&cli, None)?;
     let subnet_id = get_subnet_id(&cli)?;
 
     match &args.command {
-        AccountCommands::Create => {
+        AccountCommands::Create(args) => {
             let sk = random_secretkey();
             let pk = sk.public_key().serialize();
             let address = Address::from(EthAddress::new_secp256k1(&pk)?);
             let eth_address = get_delegated_address(address)?;
-            let sk_hex = hex::encode(sk.serialize());
 
-            print_json(
-                &json!({"private_key": sk_hex, "address": eth_address, "fvm_address": address.to_string()}),
-            )
+            let mut output = json!({"address": eth_address, "fvm_address": address.to_string()});
+            if let Some(name) = &args.save {
+                let mut keystore = Keystore::open(get_keystore_path()?)?;
+                keystore.insert(name, &sk)?;
+                keystore.set_default(name)?;
+                keystore.save()?;
+                output["name"] = json!(name);
+            }
+            // Without saving, the key would be lost if not printed
+            if args.save.is_none() || args.show_private_key {
+                output["private_key"] = json!(hex::encode(sk.serialize()));
+            }
+
+            print_json(&output)
         }
         AccountCommands::Info(args) => {
-            let address = get_address(args.address.clone(), &subnet_id)?;
+            let address = get_address(&cli, args.address.clone(), &subnet_id)?;
             let eth_address = get_delegated_address(address)?;
-            let sequence =
-                Account::sequence(&provider, &Void::new(address), args.address.height).await?;
-            let balance = Account::balance(
-                &Void::new(address),
-                get_subnet_config(&cli, &subnet_id, args.subnet.clone())?,
-            )
-            .await?;
-            let parent_balance = Account::balance(
-                &Void::new(address),
-                get_parent_subnet_config(&cli, &subnet_id, args.subnet.clone())?,
-            )
-            .await?;
-
-            print_json(
-                &json!({"address": eth_address, "fvm_address": address.to_string(), "sequence": sequence, "balance": balance.to_string(), "parent_balance": parent_balance.to_string()}),
-            )
-        }
-        AccountCommands::Deposit(args) => {
-            let config = get_parent_subnet_config(&cli, &subnet_id, args.subnet.clone())?;
-
-            let signer = Wallet::new_secp256k1(
-                args.private_key.clone(),
-                AccountKind::Ethereum,
-                subnet_id.parent()?, // Signer must target the parent subnet
+            let signer = Void::new(address);
+            let config = get_subnet_config(&cli, &subnet_id, args.subnet.clone())?;
+            let parent_config = get_parent_subnet_config(&cli, &subnet_id, args.subnet.clone())?;
+            // The lookups hit different endpoints, so run them concurrently.
+            let (sequences, balance, parent_balance) = tokio::try_join!(
+                Account::sequences(
+                    &provider,
+                    &signer,
+                    vec![args.address.height, FvmQueryHeight::Pending],
+                ),
+                Account::balance(&signer, config),
+                Account::balance(&signer, parent_config),
             )?;
+            let [sequence, pending_sequence] = sequences[..] else {
+                return Err(anyhow!("expected two sequences, got {}", sequences.len()));
+            };
 
-            let tx = Account::deposit(
-                &signer,
-                args.to.unwrap_or(signer.address()),
-                config,
-                args.amount.clone(),
-            )
-            .await?;
+            let mut warnings = Vec::new();
+            let min_balance = TokenAmount::from_nano(MIN_SUBNET_BALANCE_NANO);
+            if balance < min_balance {
+                warnings.push(format!(
+                    "subnet balance is below {} FIL, which may not cover gas for transactions; \
+                    deposit funds with `adm account deposit`",
+                    TokenUnit::Fil.format(&min_balance)
+                ));
+            }
+            let min_parent_balance = TokenAmount::from_nano(MIN_PARENT_BALANCE_NANO);
+            if parent_balance < min_parent_balance {
+                warnings.push(format!(
+                    "parent balance is below {} FIL, which may not cover gas for deposits",
+                    TokenUnit::Fil.format(&min_parent_balance)
+                ));
+            }
 
-            print_json(&tx)
+            let units = get_units(&cli, TokenUnit::Fil);
+            print_json(&json!({
+                "address": to_checksum(&eth_address, None),
+                "fvm_address": address.to_string(),
+                "sequence": sequence,
+                "pending_sequence": pending_sequence,
+                "balance": units.format(&balance),
+                "parent_balance": units.format(&parent_balance),
+                "balances": {
+                    "subnet": denominations(&balance),
+                    "parent": denominations(&parent_balance),
+                },
+                "warnings": warnings,
+            }))
         }
-        AccountCommands::Withdraw(args) => {
+        AccountCommands::Deposit(args) => handle_deposit(&cli, args).await,
+        AccountCommands::Withdraw(args) => handle_withdraw(&cli, args).await,
+        AccountCommands::Transfer(args) => {
+            reject_interactive(&cli, "transfers")?;
             let config = get_subnet_config(&cli, &subnet_id, args.subnet.clone())?;
 
             let signer =
                 Wallet::new_secp256k1(args.private_key.clone(), AccountKind::Ethereum, subnet_id)?;
 
-            let tx = Account::withdraw(
-                &signer,
-                args.to.unwrap_or(signer.address()),
-                config,
-                args.amount.clone(),
-            )
-            .await?;
+            let to = resolve_contact(&cli, &args.to)?;
+            let tx = Account::transfer(&signer, to, config, args.amount.clone()).await?;
 
             print_json(&tx)
         }
-        AccountCommands::Transfer(args) => {
-            let config = get_subnet_config(&cli, &subnet_id, args.subnet.clone())?;
+        AccountCommands::Bridge(args) => {
+            reject_interactive(&cli, "bridge transfers")?;
+            let network = cli.network.get();
+            let subnet = EVMSubnet {
+                id: subnet_id.clone(),
+                provider_http: network.evm_rpc_url()?,
+                provider_timeout: Some(args.evm_rpc_timeout),
+                auth_token: None,
+                registry_addr: network.evm_registry()?,
+                gateway_addr: network.evm_gateway()?,
+            };
+            let parent = EVMSubnet {
+                id: subnet_id.parent()?,
+                provider_http: network.parent_evm_rpc_url()?,
+                provider_timeout: Some(args.evm_rpc_timeout),
+                auth_token: None,
+                registry_addr: network.parent_evm_registry()?,
+                gateway_addr: network.parent_evm_gateway()?,
+            };
+            let (from, to) = match args.direction {
+                BridgeDirection::Deposit => (parent, subnet),
+                BridgeDirection::Withdraw => (subnet, parent),
+            };
 
-            let signer =
-                Wallet::new_secp256k1(args.private_key.clone(), AccountKind::Ethereum, subnet_id)?;
+            let signer = Wallet::new_secp256k1(
+                args.private_key.clone(),
+                AccountKind::Ethereum,
+                from.id.clone(),
+            )?;
 
-            let tx = Account::transfer(&signer, args.to, config, args.amount.clone()).await?;
+            let recipient = get_recipient(&cli, &args.to, &signer)?;
+            let tx =
+                Account::transfer_cross_subnet(&signer, recipient, from, to, args.amount.clone())
+                    .await?;
 
             print_json(&tx)
         }
+        AccountCommands::Export(args) => {
+            let sk = &args.private_key;
+            let exported = match args.format {
+                ExportFormat::Hex => hex::encode(sk.serialize()),
+                ExportFormat::Pem => to_pem(sk),
+                ExportFormat::Lotus => to_lotus_key(sk, AccountKind::Ethereum)?,
+                ExportFormat::Keystore => {
+                    // Clap requires an output path and password for keystores
+                    let output = args.output.clone().unwrap_or_default();
+                    let password = args.keystore_password.clone().unwrap_or_default();
+                    if output.exists() {
+                        return Err(anyhow!("{} already exists", output.display()));
+                    }
+                    let dir = output.parent().unwrap_or(Path::new(""));
+                    let name = output
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .ok_or_else(|| anyhow!("invalid output path {}", output.display()))?;
+                    let path = write_eth_keystore(sk, dir, name, password)?;
+                    return print_json(&json!({"path": path}));
+                }
+            };
+            match &args.output {
+                Some(path) => {
+                    write_private(path, exported.trim_end())?;
+                    print_json(&json!({"path": path}))
+                }
+                None => {
+                    println!("{}", exported.trim_end());
+                    Ok(())
+                }
+            }
+        }
+        AccountCommands::Bootstrap(args) => handle_bootstrap(&cli, args).await,
     }
 }
 
-/// Returns the subnet configuration from args.
-fn get_subnet_config(cli: &Cli, id: &SubnetID, args: SubnetArgs) -> anyhow::Result<EVMSubnet> {
-    let network = cli.network.get();
-    Ok(EVMSubnet {
-        id: id.clone(),
-        provider_http: args.evm_rpc_url.unwrap_or(network.evm_rpc_url()?),
-        provider_timeout: Some(args.evm_rpc_timeout),
-        auth_token: args.evm_rpc_auth_token,
-        registry_addr: args.evm_registry.unwrap_or(network.evm_registry()?),
-        gateway_addr: args.evm_gateway.unwrap_or(network.evm_gateway()?),
+/// Subnet balance in nanoFIL below which gas for transactions may not be covered.
+const MIN_SUBNET_BALANCE_NANO: u64 = 1_000_000;
+
+/// Parent balance in nanoFIL below which gas for deposits may not be covered.
+const MIN_PARENT_BALANCE_NANO: u64 = 10_000_000;
+
+/// Returns an amount in each denomination.
+fn denominations(amount: &TokenAmount) -> Value {
+    json!({
+        "fil": TokenUnit::Fil.format(amount),
+        "nanofil": TokenUnit::NanoFil.format(amount),
+        "attofil": TokenUnit::AttoFil.format(amount),
     })
 }
 
-/// Returns the parent subnet configuration from args.
-fn get_parent_subnet_config(
+/// Returns the recipient address from an address or contact label,
+/// or the signer address if no recipient is given.
+fn get_recipient(
     cli: &Cli,
-    id: &SubnetID,
-    args: SubnetArgs,
-) -> anyhow::Result<EVMSubnet> {
+    to: &Option<AddressOrName>,
+    signer: &impl Signer,
+) -> anyhow::Result<Address> {
+    match to {
+        Some(to) => resolve_contact(cli, to),
+        None => Ok(signer.address()),
+    }
+}
+
+/// How often to check for faucet funds.
+const FAUCET_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Fund an account from a faucet, deposit half (or the given amount) into the subnet,
+/// and print a profile with the account and the environment to use it.
+///
+/// Devnet and Localnet have no parent chain, so the account is funded directly in the subnet,
+/// either by a faucet or from a genesis account.
+async fn handle_bootstrap(cli: &Cli, args: &BootstrapArgs) -> anyhow::Result<()> {
+    let subnet_id = get_subnet_id(cli)?;
     let network = cli.network.get();
-    Ok(EVMSubnet {
-        id: id.clone(),
-        provider_http: args.evm_rpc_url.unwrap_or(network.parent_evm_rpc_url()?),
-        provider_timeout: Some(args.evm_rpc_timeout),
-        auth_token: args.evm_rpc_auth_token,
-        registry_addr: args.evm_registry.unwrap_or(network.parent_evm_registry()?),
-        gateway_addr: args.evm_gateway.unwrap_or(network.parent_evm_gateway()?),
+    let parent_config = match network {
+        SdkNetwork::Localnet | SdkNetwork::Devnet => None,
+        _ if args.genesis_key.is_some() => {
+            return Err(anyhow!(
+                "--genesis-key is only supported on devnet and localnet"
+            ))
+        }
+        _ => Some((
+            EVMSubnet {
+                id: subnet_id.clone(),
+                provider_http: network.evm_rpc_url()?,
+                provider_timeout: Some(args.evm_rpc_timeout),
+                auth_token: None,
+                registry_addr: network.evm_registry()?,
+                gateway_addr: network.evm_gateway()?,
+            },
+            EVMSubnet {
+                id: subnet_id.parent()?,
+                provider_http: network.parent_evm_rpc_url()?,
+                provider_timeout: Some(args.evm_rpc_timeout),
+                auth_token: None,
+                registry_addr: network.parent_evm_registry()?,
+                gateway_addr: network.parent_evm_gateway()?,
+            },
+        )),
+    };
+
+    let (sk, created) = match args.private_key.clone() {
+        Some(sk) => (sk, false),
+        None => (random_secretkey(), true),
+    };
+    let address =
+        Wallet::new_secp256k1(sk.clone(), AccountKind::Ethereum, subnet_id.clone())?.address();
+    let eth_address = get_delegated_address(address)?;
+
+    let mut profile = json!({"address": eth_address, "fvm_address": address.to_string()});
+    let network_name = cli
+        .network
+        .to_possible_value()
+        .map(|v| v.get_name().to_string())
+        .unwrap_or_default();
+    let mut env = json!({"NETWORK": network_name, "SUBNET": subnet_id.to_string()});
+    if created {
+        // Save the new key before it's funded, so funds are never sent to a lost key
+        match &args.save {
+            Some(name) => {
+                let mut keystore = Keystore::open(get_keystore_path()?)?;
+                keystore.insert(name, &sk)?;
+                keystore.set_default(name)?;
+                keystore.save()?;
+                profile["name"] = json!(name);
+            }
+            None => {
+                let encoded = hex::encode(sk.serialize());
+                profile["private_key"] = json!(encoded);
+                env["PRIVATE_KEY"] = json!(encoded);
+            }
+        }
+    }
+
+    profile["network"] = json!(network_name);
+    profile["subnet"] = json!(subnet_id.to_string());
+    match parent_config {
+        Some((subnet, parent)) => {
+            fund_from_parent(cli, args, &sk, subnet, parent, &mut profile).await?
+        }
+        None => fund_in_subnet(cli, args, &subnet_id, address, &mut profile).await?,
+    }
+    profile["env"] = env;
+    print_json(&profile)
+}
+
+/// Asks the faucet for funds on the parent chain, and deposits them into the subnet.
+async fn fund_from_parent(
+    cli: &Cli,
+    args: &BootstrapArgs,
+    sk: &SecretKey,
+    subnet: EVMSubnet,
+    parent: EVMSubnet,
+    profile: &mut Value,
+) -> anyhow::Result<()> {
+    reject_interactive(cli, "parent deposits")?;
+    let faucet_url = args
+        .faucet_url
+        .as_ref()
+        .ok_or_else(|| anyhow!("--faucet-url is required outside of devnet and localnet"))?;
+    let signer = Wallet::new_secp256k1(sk.clone(), AccountKind::Ethereum, parent.id.clone())?;
+    let address = signer.address();
+    let before = Account::balance(&signer, parent.clone()).await?;
+    request_faucet(
+        faucet_url,
+        &json!({"address": get_delegated_address(address)?}),
+    )
+    .await?;
+    let funded = wait_for_funds(args.timeout, &before, || {
+        Account::balance(&signer, parent.clone())
     })
+    .await?;
+    let received = funded - &before;
+
+    let amount = args
+        .deposit
+        .clone()
+        .unwrap_or_else(|| TokenAmount::from_atto(received.atto() / 2u32));
+    let tx =
+        Account::transfer_cross_subnet(&signer, address, parent.clone(), subnet.clone(), amount)
+            .await?;
+    let (balance, parent_balance) = tokio::try_join!(
+        Account::balance(&signer, subnet),
+        Account::balance(&signer, parent),
+    )?;
+
+    let units = get_units(cli, TokenUnit::Fil);
+    profile["balance"] = json!(units.format(&balance));
+    profile["parent_balance"] = json!(units.format(&parent_balance));
+    profile["deposit_tx"] = json!(tx.transaction_hash);
+    Ok(())
+}
+
+/// Funds the account directly in the subnet, from the genesis account or by asking the faucet.
+async fn fund_in_subnet(
+    cli: &Cli,
+    args: &BootstrapArgs,
+    subnet_id: &SubnetID,
+    address: Address,
+    profile: &mut Value,
+) -> anyhow::Result<()> {
+    let provider = get_provider(cli, None)?;
+    let before = subnet_balance(&provider, address).await?;
+    match (&args.genesis_key, &args.faucet_url) {
+        (Some(sk), _) => {
+            let mut genesis =
+                Wallet::new_secp256k1(sk.clone(), AccountKind::Ethereum, subnet_id.clone())?;
+            genesis
+                .init_sequence(&provider)
+                .await
+                .context("failed to get genesis account sequence; is the genesis key funded?")?;
+            let mut genesis = confirm_signer(cli, genesis);
+            // Sending to a new address creates the account
+            let tx = TxBuilder::new(address, METHOD_SEND, RawBytes::default(), decode_bytes)
+                .value(args.amount.clone())
+                .send(&provider, &mut genesis)
+                .await?;
+            profile["fund_tx"] = json!(tx.hash);
+        }
+        (None, Some(faucet_url)) => {
+            request_faucet(
+                faucet_url,
+                &json!({"address": get_delegated_address(address)?}),
+            )
+            .await?
+        }
+        (None, None) => return Err(anyhow!("either --faucet-url or --genesis-key is required")),
+    }
+    let balance =
+        wait_for_funds(args.timeout, &before, || subnet_balance(&provider, address)).await?;
+
+    profile["balance"] = json!(get_units(cli, TokenUnit::Fil).format(&balance));
+    Ok(())
+}
+
+/// Polls `balance` until it rises above `before`, and returns the new balance.
+async fn wait_for_funds<F, Fut>(
+    timeout: Duration,
+    before: &TokenAmount,
+    mut balance: F,
+) -> anyhow::Result<TokenAmount>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<TokenAmount>>,
+{
+    let started = Instant::now();
+    loop {
+        let balance = balance().await?;
+        if balance > *before {
+            return Ok(balance);
+        }
+        if started.elapsed() > timeout {
+            return Err(anyhow!(
+                "timed out after {} waiting for funds to arrive",
+                humantime::format_duration(timeout)
+            ));
+        }
+        tokio::time::sleep(FAUCET_POLL_INTERVAL).await;
+    }
+}
+
+/// Returns the balance of an account in the subnet, or zero if it doesn't exist yet.
+async fn subnet_balance(
+    provider: &impl QueryProvider,
+    address: Address,
+) -> anyhow::Result<TokenAmount> {
+    let response = provider
+        .actor_state(&address, FvmQueryHeight::Committed)
+        .await?;
+    Ok(response
+        .value
+        .map(|(_, state)| state.balance)
+        .unwrap_or_default())
+}
+
+/// Asks a faucet to send funds to the address in the request body.
+async fn request_faucet(url: &reqwest::Url, body: &serde_json::Value) -> anyhow::Result<()> {
+    let response = reqwest::Client::new()
+        .post(url.clone())
+        .json(body)
+        .send()
+        .await
+        .with_context(|| format!("failed to reach faucet at {}", url))?;
+    let status = response.status();
+    if !status.is_success() {
+        let message = response.text().await.unwrap_or_default();
+        return Err(anyhow!(
+            "faucet request failed with status {}: {}",
+            status.as_u16(),
+            message.trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Writes a secret to a new file that is only readable and writable by its owner.
+fn write_private(path: &Path, contents: &str) -> anyhow::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("failed to create {}", path.display()))?;
+    writeln!(file, "{}", contents)?;
+    Ok(())
+}
+
+/// Deposit funds into a subnet from its parent.
+pub(crate) async fn handle_deposit(cli: &Cli, args: &DepositArgs) -> anyhow::Result<()> {
+    reject_interactive(cli, "deposits")?;
+    let subnet_id = get_subnet_id(cli)?;
+    let config = get_parent_subnet_config(cli, &subnet_id, args.fund.subnet.clone())?;
+
+    let signer = Wallet::new_secp256k1(
+        args.fund.private_key.clone(),
+        AccountKind::Ethereum,
+        subnet_id.parent()?, // Signer must target the parent subnet
+    )?;
+
+    let to = get_recipient(cli, &args.fund.to, &signer)?;
+    let tx = Account::deposit(
+        &signer,
+        to,
+        config,
+        args.fund.amount.clone(),
+        DepositOptions {
+            finality: args.finality,
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    print_json(&cross_msg_receipt(tx, get_units(cli, TokenUnit::Fil))?)
+}
+
+/// Withdraw funds from a subnet to its parent.
+pub(crate) async fn handle_withdraw(cli: &Cli, args: &FundArgs) -> anyhow::Result<()> {
+    reject_interactive(cli, "withdrawals")?;
+    let subnet_id = get_subnet_id(cli)?;
+    let config = get_subnet_config(cli, &subnet_id, args.subnet.clone())?;
+
+    let signer = Wallet::new_secp256k1(args.private_key.clone(), AccountKind::Ethereum, subnet_id)?;
+
+    let to = get_recipient(cli, &args.to, &signer)?;
+    let tx = Account::withdraw(&signer, to, config, args.amount.clone()).await?;
+
+    print_json(&cross_msg_receipt(tx, get_units(cli, TokenUnit::Fil))?)
+}
+
+/// Returns the EVM receipt of a deposit or withdrawal with the decoded cross-net message,
+/// with its amount in `units`.
+fn cross_msg_receipt(tx: CrossMsgReceipt, units: TokenUnit) -> anyhow::Result<Value> {
+    let mut value = serde_json::to_value(&tx.receipt)?;
+    value["message"] = json!({
+        "subnet": tx.message.subnet.to_string(),
+        "from": tx.message.from.to_string(),
+        "to": tx.message.to.to_string(),
+        "amount": units.format(&tx.message.amount),
+        "nonce": tx.message.nonce,
+    });
+    Ok(value)
 }
@@ -0,0 +1,509 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Context};
+use clap::Args;
+use fendermint_crypto::SecretKey;
+use fvm_shared::address::Address;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tendermint_rpc::Url;
+use tokio::fs::File;
+use tokio::time::{interval, sleep, MissedTickBehavior};
+
+use adm_provider::{json_rpc::JsonRpcProvider, response::Cid, tx::TxReceipt};
+use adm_sdk::{
+    machine::{
+        objectstore::{AddOptions, ObjectStore},
+        Machine,
+    },
+    name::parse_address_or_name,
+    TxParams,
+};
+use adm_signer::{key::parse_secret_key, AccountKind, Wallet};
+
+use crate::error::config_error;
+use crate::name::resolve_address;
+use crate::{get_provider, get_subnet_id, BroadcastMode, Cli, TxArgs};
+
+use webhook::{WebhookConfig, Webhooks};
+
+mod webhook;
+
+/// Base delay between retries of a failed upload.
+/// The delay grows linearly with each attempt.
+const AGENT_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Debug, Args)]
+pub struct AgentArgs {
+    /// Wallet private key (ECDSA, secp256k1) for signing transactions.
+    #[arg(short, long, env, value_parser = parse_secret_key)]
+    private_key: SecretKey,
+    /// Node Object API URL.
+    #[arg(long, env)]
+    object_api_url: Option<Url>,
+    /// Bearer token for Object API authentication.
+    #[arg(long, env)]
+    object_api_auth_token: Option<String>,
+    /// Agent config file (JSON) listing the directories to watch.
+    #[arg(short, long, env = "AGENT_CONFIG")]
+    config: PathBuf,
+    /// State file that records uploaded files, so only new or changed files are uploaded.
+    /// Defaults to `$HOME/.adm/agent/state.json`.
+    #[arg(long, env = "AGENT_STATE_FILE")]
+    state_file: Option<PathBuf>,
+    /// Interval between scans of the watched directories.
+    #[arg(long, value_parser = humantime::parse_duration, default_value = "1m")]
+    interval: Duration,
+    /// Only upload files that haven't been modified for this long,
+    /// so files that are still being written are picked up by a later scan.
+    #[arg(long, value_parser = humantime::parse_duration, default_value = "10s")]
+    min_age: Duration,
+    /// Maximum number of times to retry a failed upload within a scan.
+    /// Files that still fail are retried on the next scan.
+    #[arg(long, default_value_t = 3)]
+    max_retries: u32,
+    /// Scan and upload once, then exit.
+    #[arg(long)]
+    once: bool,
+    /// Timeout for each webhook request.
+    #[arg(long, value_parser = humantime::parse_duration, default_value = "10s")]
+    webhook_timeout: Duration,
+    /// Maximum number of times to retry a failed webhook request.
+    #[arg(long, default_value_t = 3)]
+    webhook_retries: u32,
+    /// Broadcast mode for the transactions.
+    #[arg(short, long, value_enum, env, default_value_t = BroadcastMode::Commit)]
+    broadcast_mode: BroadcastMode,
+    #[command(flatten)]
+    tx_args: TxArgs,
+}
+
+/// Agent config file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AgentConfig {
+    /// Directories to watch.
+    watches: Vec<WatchConfig>,
+    /// Webhooks to notify when a file is uploaded or fails to upload.
+    #[serde(default)]
+    webhooks: Vec<WebhookConfig>,
+}
+
+/// A directory whose files are uploaded to an object store.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WatchConfig {
+    /// Directory to watch. Subdirectories are watched too.
+    dir: PathBuf,
+    /// Object store machine address or name.
+    address: String,
+    /// Key prefix for uploaded objects.
+    /// The key is the prefix followed by the file's path relative to `dir`.
+    #[serde(default)]
+    prefix: String,
+    /// Metadata to add to each uploaded object.
+    #[serde(default)]
+    metadata: HashMap<String, String>,
+}
+
+/// A watched directory with its resolved object store address.
+#[derive(Debug)]
+struct Watch {
+    config: WatchConfig,
+    address: Address,
+}
+
+/// Files uploaded by the agent, by object store address and then key.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AgentState {
+    uploads: BTreeMap<String, BTreeMap<String, UploadState>>,
+}
+
+/// The file version that was last uploaded to a key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct UploadState {
+    /// File size in bytes.
+    size: u64,
+    /// File modification time in milliseconds since the Unix epoch.
+    modified: u64,
+    /// Object CID.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cid: Option<String>,
+    /// Hash of the upload transaction.
+    tx_hash: String,
+}
+
+impl AgentState {
+    /// Load the state file. A missing file is treated as empty.
+    fn load(path: &Path) -> anyhow::Result<Self> {
+        match std::fs::read(path) {
+            Ok(data) => serde_json::from_slice(&data)
+                .with_context(|| format!("failed to parse state file {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write the state file.
+    /// The file is replaced atomically, so a crash never leaves a partially written state.
+    fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    fn get(&self, address: &Address, key: &str) -> Option<&UploadState> {
+        self.uploads.get(&address.to_string())?.get(key)
+    }
+
+    fn insert(&mut self, address: &Address, key: String, upload: UploadState) {
+        self.uploads
+            .entry(address.to_string())
+            .or_default()
+            .insert(key, upload);
+    }
+}
+
+/// An agent event, written to stdout as a JSON line.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum Event {
+    /// The agent started.
+    Started { watches: usize, state_file: PathBuf },
+    /// A file was uploaded.
+    Uploaded {
+        path: PathBuf,
+        address: String,
+        key: String,
+        size: u64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cid: Option<String>,
+        tx_hash: String,
+        attempts: u32,
+    },
+    /// A file failed to upload after all retries.
+    UploadFailed {
+        path: PathBuf,
+        address: String,
+        key: String,
+        attempts: u32,
+        error: String,
+    },
+    /// A watched directory couldn't be read.
+    ScanFailed { dir: PathBuf, error: String },
+    /// A webhook couldn't be delivered after all retries.
+    WebhookFailed {
+        url: String,
+        event: String,
+        attempts: u32,
+        error: String,
+    },
+    /// A scan of all watched directories finished.
+    ScanFinished {
+        uploaded: usize,
+        failed: usize,
+        /// Changed files skipped because they were modified too recently.
+        unsettled: usize,
+    },
+}
+
+impl Event {
+    /// Write the event to stdout as a JSON line with a timestamp, and return the line.
+    fn log(&self) -> Value {
+        let level = match self {
+            Event::UploadFailed { .. } | Event::ScanFailed { .. } => "error",
+            Event::WebhookFailed { .. } => "warn",
+            _ => "info",
+        };
+        let mut line = json!({
+            "time": humantime::format_rfc3339_millis(SystemTime::now()).to_string(),
+            "level": level,
+        });
+        if let (Some(line), Ok(serde_json::Value::Object(event))) =
+            (line.as_object_mut(), serde_json::to_value(self))
+        {
+            line.extend(event);
+        }
+        println!("{}", line);
+        line
+    }
+}
+
+/// Agent command handler.
+///
+/// Watched directories are scanned on an interval, and new or changed files are uploaded.
+/// The state file is saved after every upload, so the agent can be stopped at any time.
+pub async fn handle_agent(cli: Cli, args: &AgentArgs) -> anyhow::Result<()> {
+    let config: AgentConfig = std::fs::read(&args.config)
+        .map_err(anyhow::Error::from)
+        .and_then(|data| Ok(serde_json::from_slice(&data)?))
+        .with_context(|| format!("failed to load agent config {}", args.config.display()))
+        .map_err(config_error)?;
+    if config.watches.is_empty() {
+        return Err(config_error(anyhow!("agent config has no watches")));
+    }
+    let state_path = match args.state_file.clone() {
+        Some(path) => path,
+        None => default_state_path()?,
+    };
+    let mut state = AgentState::load(&state_path)?;
+    let mut webhooks = Webhooks::new(config.webhooks, args.webhook_timeout, args.webhook_retries)
+        .map_err(config_error)?;
+
+    let object_api_url = args
+        .object_api_url
+        .clone()
+        .unwrap_or(cli.network.get().object_api_url()?);
+    let provider = get_provider(&cli, Some(object_api_url))?
+        .with_object_auth_token(args.object_api_auth_token.clone())
+        .with_negotiated_object_api()
+        .await?;
+
+    let mut watches = Vec::new();
+    for config in config.watches {
+        let address = parse_address_or_name(&config.address).map_err(config_error)?;
+        let address = resolve_address(&cli, &provider, &address).await?;
+        watches.push(Watch { config, address });
+    }
+
+    let TxParams {
+        sequence,
+        gas_params,
+    } = args.tx_args.to_tx_params(cli.network.get());
+    let mut signer = Wallet::new_secp256k1(
+        args.private_key.clone(),
+        AccountKind::Ethereum,
+        get_subnet_id(&cli)?,
+    )?;
+    signer.set_sequence(sequence, &provider).await?;
+    let options = AddOptions {
+        overwrite: true,
+        broadcast_mode: args.broadcast_mode.get(),
+        gas_params,
+        show_progress: false,
+        ..Default::default()
+    };
+
+    Event::Started {
+        watches: watches.len(),
+        state_file: state_path.clone(),
+    }
+    .log();
+
+    let mut ticker = interval(args.interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        let (mut uploaded, mut failed, mut unsettled) = (0, 0, 0);
+        for watch in &watches {
+            let files = match scan(&watch.config.dir) {
+                Ok(files) => files,
+                Err(e) => {
+                    Event::ScanFailed {
+                        dir: watch.config.dir.clone(),
+                        error: format!("{:#}", e),
+                    }
+                    .log();
+                    continue;
+                }
+            };
+            for file in files {
+                let key = format!("{}{}", watch.config.prefix, file.relative);
+                let current = state.get(&watch.address, &key);
+                if current.is_some_and(|u| u.size == file.size && u.modified == file.modified) {
+                    continue;
+                }
+                let age = SystemTime::now()
+                    .duration_since(UNIX_EPOCH + Duration::from_millis(file.modified))
+                    .unwrap_or_default();
+                if age < args.min_age {
+                    unsettled += 1;
+                    continue;
+                }
+
+                let options = AddOptions {
+                    metadata: watch.config.metadata.clone(),
+                    ..options.clone()
+                };
+                let machine = ObjectStore::attach(watch.address);
+                let (result, attempts) = upload_with_retry(
+                    &machine,
+                    &provider,
+                    &mut signer,
+                    &key,
+                    &file.path,
+                    &options,
+                    args.max_retries,
+                )
+                .await;
+                match result {
+                    Ok(tx) => {
+                        let cid = tx.data.map(|cid| cid.to_string());
+                        let tx_hash = tx.hash.to_string();
+                        state.insert(
+                            &watch.address,
+                            key.clone(),
+                            UploadState {
+                                size: file.size,
+                                modified: file.modified,
+                                cid: cid.clone(),
+                                tx_hash: tx_hash.clone(),
+                            },
+                        );
+                        state.save(&state_path)?;
+                        uploaded += 1;
+                        let event = Event::Uploaded {
+                            path: file.path,
+                            address: watch.address.to_string(),
+                            key,
+                            size: file.size,
+                            cid,
+                            tx_hash,
+                            attempts,
+                        };
+                        webhooks.notify(&event, &event.log());
+                    }
+                    Err(e) => {
+                        failed += 1;
+                        let event = Event::UploadFailed {
+                            path: file.path,
+                            address: watch.address.to_string(),
+                            key,
+                            attempts,
+                            error: format!("{:#}", e),
+                        };
+                        webhooks.notify(&event, &event.log());
+                    }
+                }
+            }
+        }
+        Event::ScanFinished {
+            uploaded,
+            failed,
+            unsettled,
+        }
+        .log();
+
+        if args.once {
+            webhooks.flush().await;
+            return if failed > 0 {
+                Err(anyhow!("{} uploads failed", failed))
+            } else {
+                Ok(())
+            };
+        }
+    }
+}
+
+/// Upload a file, retrying on failure.
+/// Returns the result of the last attempt and the number of attempts.
+async fn upload_with_retry(
+    machine: &ObjectStore,
+    provider: &JsonRpcProvider,
+    signer: &mut Wallet,
+    key: &str,
+    path: &Path,
+    options: &AddOptions,
+    max_retries: u32,
+) -> (anyhow::Result<TxReceipt<Cid>>, u32) {
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let result = match File::open(path).await {
+            Ok(file) => {
+                machine
+                    .add(provider, signer, key, file, options.clone())
+                    .await
+            }
+            Err(e) => Err(e.into()),
+        };
+        match result {
+            Err(_) if attempt <= max_retries => {
+                sleep(AGENT_RETRY_DELAY * attempt).await;
+                // The failed transaction may have used a sequence
+                if let Err(e) = signer.init_sequence(provider).await {
+                    return (Err(e), attempt);
+                }
+            }
+            result => return (result, attempt),
+        }
+    }
+}
+
+/// A regular file found by [`scan`].
+#[derive(Debug)]
+struct ScannedFile {
+    path: PathBuf,
+    /// Path relative to the watched directory, with `/` separators.
+    relative: String,
+    size: u64,
+    /// Modification time in milliseconds since the Unix epoch.
+    modified: u64,
+}
+
+/// Returns the regular files under `dir`, recursively.
+///
+/// Hidden files and directories, i.e., those whose names start with `.`, are skipped,
+/// as are files whose paths aren't valid UTF-8, since they can't be mapped to keys.
+fn scan(dir: &Path) -> anyhow::Result<Vec<ScannedFile>> {
+    let mut files = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(current) = dirs.pop() {
+        let entries = std::fs::read_dir(&current)
+            .with_context(|| format!("failed to read directory {}", current.display()))?;
+        for entry in entries {
+            let entry = entry?;
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let md = entry.metadata()?;
+            let path = entry.path();
+            if md.is_dir() {
+                dirs.push(path);
+            } else if md.is_file() {
+                let Some(relative) = relative_key(dir, &path) else {
+                    continue;
+                };
+                let modified = md.modified()?.duration_since(UNIX_EPOCH)?.as_millis() as u64;
+                files.push(ScannedFile {
+                    path,
+                    relative,
+                    size: md.len(),
+                    modified,
+                });
+            }
+        }
+    }
+    files.sort_by(|a, b| a.relative.cmp(&b.relative));
+    Ok(files)
+}
+
+/// Returns the path relative to `dir` with `/` separators,
+/// or `None` if it isn't under `dir` or isn't valid UTF-8.
+fn relative_key(dir: &Path, path: &Path) -> Option<String> {
+    let parts = path
+        .strip_prefix(dir)
+        .ok()?
+        .components()
+        .map(|c| c.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()?;
+    Some(parts.join("/"))
+}
+
+/// Returns the default state file path, `$HOME/.adm/agent/state.json`.
+fn default_state_path() -> anyhow::Result<PathBuf> {
+    let home = std::env::var("HOME").map_err(|_| anyhow!("failed to get home directory"))?;
+    Ok(PathBuf::from(home)
+        .join(".adm")
+        .join("agent")
+        .join("state.json"))
+}
@@ -0,0 +1,141 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use reqwest::{Client, Url};
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::task::JoinHandle;
+use tokio::time::sleep;
+
+use super::Event;
+
+/// Base delay between retries of a failed webhook delivery.
+/// The delay grows linearly with each attempt.
+const WEBHOOK_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Agent events that can trigger a webhook.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// A file was uploaded.
+    Uploaded,
+    /// A file failed to upload after all retries.
+    UploadFailed,
+}
+
+/// A webhook that receives agent events as an HTTP POST with a JSON body.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookConfig {
+    /// URL to POST events to.
+    url: String,
+    /// Events that trigger the webhook. All events trigger it by default.
+    #[serde(default)]
+    events: Vec<WebhookEvent>,
+    /// Extra request headers, e.g., for authorization.
+    #[serde(default)]
+    headers: HashMap<String, String>,
+}
+
+impl WebhookConfig {
+    fn is_triggered_by(&self, event: WebhookEvent) -> bool {
+        self.events.is_empty() || self.events.contains(&event)
+    }
+}
+
+/// Delivers agent events to webhooks in the background, so slow receivers don't hold up uploads.
+pub struct Webhooks {
+    client: Client,
+    hooks: Vec<WebhookConfig>,
+    max_retries: u32,
+    deliveries: Vec<JoinHandle<()>>,
+}
+
+impl Webhooks {
+    /// Create a new set of webhooks with a per-request timeout.
+    pub fn new(
+        hooks: Vec<WebhookConfig>,
+        timeout: Duration,
+        max_retries: u32,
+    ) -> anyhow::Result<Self> {
+        for hook in &hooks {
+            Url::parse(&hook.url)
+                .map_err(|e| anyhow!("invalid webhook URL '{}': {}", hook.url, e))?;
+        }
+        Ok(Self {
+            client: Client::builder().timeout(timeout).build()?,
+            hooks,
+            max_retries,
+            deliveries: Vec::new(),
+        })
+    }
+
+    /// Send the logged event line to every webhook it triggers.
+    pub fn notify(&mut self, event: &Event, line: &Value) {
+        let kind = match event {
+            Event::Uploaded { .. } => WebhookEvent::Uploaded,
+            Event::UploadFailed { .. } => WebhookEvent::UploadFailed,
+            _ => return,
+        };
+        for hook in self.hooks.iter().filter(|h| h.is_triggered_by(kind)) {
+            let client = self.client.clone();
+            let hook = hook.clone();
+            let body = line.clone();
+            let max_retries = self.max_retries;
+            self.deliveries.push(tokio::spawn(async move {
+                let (result, attempts) = deliver(&client, &hook, &body, max_retries).await;
+                if let Err(e) = result {
+                    Event::WebhookFailed {
+                        url: hook.url.clone(),
+                        event: body["event"].as_str().unwrap_or_default().to_string(),
+                        attempts,
+                        error: format!("{:#}", e),
+                    }
+                    .log();
+                }
+            }));
+        }
+        // Drop finished deliveries so they don't accumulate in a long-running agent
+        self.deliveries.retain(|delivery| !delivery.is_finished());
+    }
+
+    /// Wait for all pending deliveries to finish.
+    pub async fn flush(&mut self) {
+        for delivery in self.deliveries.drain(..) {
+            let _ = delivery.await;
+        }
+    }
+}
+
+/// POST the body to the webhook, retrying on failure.
+/// Returns the result of the last attempt and the number of attempts.
+async fn deliver(
+    client: &Client,
+    hook: &WebhookConfig,
+    body: &Value,
+    max_retries: u32,
+) -> (anyhow::Result<()>, u32) {
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let mut request = client.post(&hook.url).json(body);
+        for (name, value) in &hook.headers {
+            request = request.header(name, value);
+        }
+        let result = match request.send().await {
+            Ok(response) => response
+                .error_for_status()
+                .map(|_| ())
+                .map_err(anyhow::Error::from),
+            Err(e) => Err(e.into()),
+        };
+        match result {
+            Err(_) if attempt <= max_retries => sleep(WEBHOOK_RETRY_DELAY * attempt).await,
+            result => return (result, attempt),
+        }
+    }
+}
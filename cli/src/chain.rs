@@ -0,0 +1,137 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use clap::{Args, Subcommand};
+use fendermint_vm_message::chain::ChainMessage;
+use serde_json::{json, Value};
+use tendermint::Hash;
+
+use adm_provider::{
+    query::{ChainBlock, ChainTx, NotFound, QueryProvider},
+    tx::TxEvent,
+    util::parse_tx_hash,
+};
+use adm_sdk::network::Network as SdkNetwork;
+
+use crate::{get_provider, method_names, print_json, Cli};
+
+#[derive(Clone, Debug, Args)]
+pub struct ChainArgs {
+    #[command(subcommand)]
+    command: ChainCommands,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+enum ChainCommands {
+    /// Get a block with its decoded transactions.
+    Block(ChainBlockArgs),
+    /// Get a committed transaction by hash.
+    Tx(ChainTxArgs),
+    /// Search committed transactions with a CometBFT event query, newest first.
+    Search(ChainSearchArgs),
+}
+
+#[derive(Clone, Debug, Args)]
+struct ChainBlockArgs {
+    /// Block height. The latest block is used if no height is given.
+    height: Option<u64>,
+}
+
+#[derive(Clone, Debug, Args)]
+struct ChainTxArgs {
+    /// Transaction hash (hex, with or without "0x").
+    #[arg(value_parser = parse_tx_hash)]
+    hash: Hash,
+}
+
+#[derive(Clone, Debug, Args)]
+struct ChainSearchArgs {
+    /// Event query, e.g., "tx.height=100" or "message.sender='t410f...'".
+    query: String,
+    /// Page of results, starting with 1.
+    #[arg(long, default_value_t = 1)]
+    page: u32,
+    /// Number of results per page (at most 100).
+    #[arg(long, default_value_t = 30, value_parser = clap::value_parser!(u8).range(1..=100))]
+    per_page: u8,
+}
+
+/// Chain commands handler.
+pub async fn handle_chain(cli: Cli, args: &ChainArgs) -> anyhow::Result<()> {
+    let provider = get_provider(&cli, None)?;
+    let network = cli.network.get();
+    match &args.command {
+        ChainCommands::Block(args) => {
+            let height = match args.height {
+                Some(height) => height,
+                None => provider.chain_head().await?.height.value(),
+            };
+            let block = provider.block(height).await?;
+            print_json(&block_json(&block, network))
+        }
+        ChainCommands::Tx(args) => {
+            let tx = provider
+                .tx_by_hash(args.hash)
+                .await?
+                .ok_or_else(|| NotFound::new(format!("transaction {} not found", args.hash)))?;
+            print_json(&tx_json(&tx, network))
+        }
+        ChainCommands::Search(args) => {
+            let txs = provider
+                .search_txs(&args.query, args.page, args.per_page)
+                .await?;
+            let list = txs
+                .iter()
+                .map(|tx| tx_json(tx, network))
+                .collect::<Vec<_>>();
+            print_json(&list)
+        }
+    }
+}
+
+fn block_json(block: &ChainBlock, network: SdkNetwork) -> Value {
+    json!({
+        "height": block.height.value(),
+        "hash": block.hash.to_string(),
+        "time": block.time.to_string(),
+        "proposer": block.proposer.to_string(),
+        "txs": block.txs.iter().map(|tx| tx_json(tx, network)).collect::<Vec<_>>(),
+    })
+}
+
+/// Returns a transaction as JSON.
+/// Its message is `null` if it's not a signed message, e.g., an IPC message from the parent.
+fn tx_json(tx: &ChainTx, network: SdkNetwork) -> Value {
+    let message = match &tx.message {
+        Some(ChainMessage::Signed(signed)) => {
+            let message = &signed.message;
+            let method = method_names()
+                .into_iter()
+                .find(|(method, _)| *method == message.method_num)
+                .map(|(_, name)| name);
+            json!({
+                "from": network.format_address(&message.from),
+                "to": network.format_address(&message.to),
+                "sequence": message.sequence,
+                "value": message.value.to_string(),
+                "method_num": message.method_num,
+                "method": method,
+                "gas_limit": message.gas_limit,
+            })
+        }
+        _ => Value::Null,
+    };
+    let mut value = json!({
+        "hash": tx.hash.to_string(),
+        "height": tx.height.value(),
+        "index": tx.index,
+        "message": message,
+    });
+    if let Some(result) = &tx.result {
+        value["code"] = json!(result.code.value());
+        value["gas_used"] = json!(result.gas_used);
+        value["info"] = json!(result.info);
+        value["events"] = json!(result.events.iter().map(TxEvent::from).collect::<Vec<_>>());
+    }
+    value
+}
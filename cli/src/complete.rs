@@ -0,0 +1,120 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Dynamic shell completion values.
+//!
+//! Completers run on every tab press without parsed arguments, so they only read local files
+//! and never make network requests. Failures produce no candidates instead of errors.
+
+use std::ffi::OsStr;
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use clap_complete::engine::CompletionCandidate;
+use fvm_shared::address::Address;
+use serde::{Deserialize, Serialize};
+
+use adm_sdk::name::AliasFile;
+
+use crate::contacts::default_contacts_path;
+use crate::name::default_names_path;
+
+/// Object keys from the last `objectstore query`, used to complete keys.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct KeyCache {
+    /// The object store that was queried.
+    address: String,
+    /// Object keys and common prefixes in the query result.
+    keys: Vec<String>,
+}
+
+/// Completes machine addresses with names from the local alias file.
+///
+/// The alias file is read from `NAMES_FILE` if it's set, since arguments aren't parsed yet.
+pub fn complete_address(current: &OsStr) -> Vec<CompletionCandidate> {
+    complete_alias(current, "NAMES_FILE", default_names_path)
+}
+
+/// Completes account addresses with labels from the local contacts file.
+///
+/// The contacts file is read from `CONTACTS_FILE` if it's set, since arguments aren't parsed yet.
+pub fn complete_contact(current: &OsStr) -> Vec<CompletionCandidate> {
+    complete_alias(current, "CONTACTS_FILE", default_contacts_path)
+}
+
+/// Completes names from the alias file at the path in `env`, or the default path.
+fn complete_alias(
+    current: &OsStr,
+    env: &str,
+    default_path: fn() -> anyhow::Result<PathBuf>,
+) -> Vec<CompletionCandidate> {
+    let Some(current) = current.to_str() else {
+        return vec![];
+    };
+    let path = match std::env::var_os(env) {
+        Some(path) => PathBuf::from(path),
+        None => match default_path() {
+            Ok(path) => path,
+            Err(_) => return vec![],
+        },
+    };
+    let Ok(aliases) = AliasFile::load(path) else {
+        return vec![];
+    };
+    aliases
+        .entries()
+        .filter(|(name, _)| name.starts_with(current))
+        .map(|(name, address)| CompletionCandidate::new(name).help(Some(address.into())))
+        .collect()
+}
+
+/// Completes object keys from the last `objectstore query`.
+pub fn complete_key(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(current) = current.to_str() else {
+        return vec![];
+    };
+    let Ok(cache) = read_key_cache() else {
+        return vec![];
+    };
+    let help = format!("from the last query of {}", cache.address);
+    cache
+        .keys
+        .into_iter()
+        .filter(|key| key.starts_with(current))
+        .map(|key| CompletionCandidate::new(key).help(Some(help.clone().into())))
+        .collect()
+}
+
+/// Saves the keys from an `objectstore query` result for completion.
+///
+/// Completion is a convenience, so a failure to write the cache is ignored.
+pub fn cache_keys(address: Address, keys: Vec<String>) {
+    let cache = KeyCache {
+        address: address.to_string(),
+        keys,
+    };
+    let _ = write_key_cache(&cache);
+}
+
+/// Returns the key cache path, `$HOME/.adm/cache/keys.json`.
+fn key_cache_path() -> anyhow::Result<PathBuf> {
+    let home = std::env::var("HOME").map_err(|_| anyhow!("failed to get home directory"))?;
+    Ok(PathBuf::from(home)
+        .join(".adm")
+        .join("cache")
+        .join("keys.json"))
+}
+
+fn read_key_cache() -> anyhow::Result<KeyCache> {
+    let data = std::fs::read(key_cache_path()?)?;
+    Ok(serde_json::from_slice(&data)?)
+}
+
+fn write_key_cache(cache: &KeyCache) -> anyhow::Result<()> {
+    let path = key_cache_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_vec(cache)?)?;
+    Ok(())
+}
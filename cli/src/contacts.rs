@@ -0,0 +1,159 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use clap::{Args, Subcommand};
+use clap_complete::engine::ArgValueCompleter;
+use fvm_shared::address::Address;
+use serde_json::{json, Value};
+
+use adm_provider::util::{get_delegated_address, parse_address, parse_address_strict};
+use adm_sdk::name::{AddressOrName, AliasFile};
+
+use crate::complete::complete_contact;
+use crate::name::parse_name;
+use crate::{print_json, Cli};
+
+#[derive(Clone, Debug, Args)]
+pub struct ContactsArgs {
+    #[command(subcommand)]
+    command: ContactsCommands,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+enum ContactsCommands {
+    /// Add a contact label for an account address.
+    Add(ContactsAddArgs),
+    /// List contacts.
+    #[clap(alias = "ls")]
+    List,
+    /// Remove a contact.
+    #[clap(alias = "remove")]
+    Rm(ContactsRmArgs),
+    /// Import contacts from a JSON file that maps labels to addresses.
+    Import(ContactsImportArgs),
+}
+
+#[derive(Clone, Debug, Args)]
+struct ContactsAddArgs {
+    /// Contact label.
+    #[arg(value_parser = parse_name)]
+    label: String,
+    /// Account address (FVM or EVM).
+    #[arg(value_parser = parse_address)]
+    address: Address,
+    /// Change the address of an existing contact.
+    #[arg(short, long)]
+    overwrite: bool,
+}
+
+#[derive(Clone, Debug, Args)]
+struct ContactsRmArgs {
+    /// Contact label.
+    #[arg(value_parser = parse_name, add = ArgValueCompleter::new(complete_contact))]
+    label: String,
+}
+
+#[derive(Clone, Debug, Args)]
+struct ContactsImportArgs {
+    /// JSON file that maps labels to addresses, e.g., the file of another address book.
+    file: PathBuf,
+    /// Change the address of existing contacts.
+    #[arg(short, long)]
+    overwrite: bool,
+}
+
+/// Contacts commands handler.
+pub async fn handle_contacts(cli: Cli, args: &ContactsArgs) -> anyhow::Result<()> {
+    let mut contacts = AliasFile::load(get_contacts_path(&cli)?)?;
+    let network = cli.network.get().address_network();
+    match &args.command {
+        ContactsCommands::Add(args) => {
+            check_overwrite(&contacts, &args.label, args.address, args.overwrite)?;
+            contacts.insert(&args.label, args.address, network)?;
+            contacts.save()?;
+            print_json(&contact(&args.label, args.address))
+        }
+        ContactsCommands::List => {
+            let list = contacts
+                .entries()
+                .map(|(label, address)| Ok(contact(label, parse_address(address)?)))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            print_json(&list)
+        }
+        ContactsCommands::Rm(args) => {
+            let address = contacts
+                .get(&args.label)?
+                .ok_or_else(|| anyhow!("contact '{}' not found", args.label))?;
+            contacts.remove(&args.label);
+            contacts.save()?;
+            print_json(&contact(&args.label, address))
+        }
+        ContactsCommands::Import(args) => {
+            let imported = AliasFile::load(&args.file)?;
+            // Check every entry before saving, so a bad file leaves the address book unchanged
+            let mut list = Vec::new();
+            for (label, address) in imported.entries() {
+                // Files may come from other networks, so f-addresses must match the network
+                let address = parse_address_strict(address, network)
+                    .map_err(|e| anyhow!("invalid address for contact '{}': {}", label, e))?;
+                check_overwrite(&contacts, label, address, args.overwrite)?;
+                contacts.insert(label, address, network)?;
+                list.push(contact(label, address));
+            }
+            contacts.save()?;
+            print_json(&list)
+        }
+    }
+}
+
+/// Resolves an account address or contact label to an address.
+pub fn resolve_contact(cli: &Cli, address: &AddressOrName) -> anyhow::Result<Address> {
+    match address {
+        AddressOrName::Address(address) => Ok(*address),
+        AddressOrName::Name(label) => AliasFile::load(get_contacts_path(cli)?)?
+            .get(label)?
+            .ok_or_else(|| anyhow!("contact '{}' not found", label)),
+    }
+}
+
+/// Returns an error if `label` is a contact for a different address,
+/// since silently changing where funds are sent is costly.
+fn check_overwrite(
+    contacts: &AliasFile,
+    label: &str,
+    address: Address,
+    overwrite: bool,
+) -> anyhow::Result<()> {
+    match contacts.get(label)? {
+        Some(existing) if existing != address && !overwrite => Err(anyhow!(
+            "contact '{}' already exists with address {}; use --overwrite to change it",
+            label,
+            existing
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn contact(label: &str, address: Address) -> Value {
+    // Only delegated addresses have an EVM form
+    let eth_address = get_delegated_address(address).ok();
+    json!({"label": label, "address": eth_address, "fvm_address": address.to_string()})
+}
+
+/// Returns the contacts file path from the override,
+/// or `$HOME/.adm/contacts.json` by default.
+fn get_contacts_path(cli: &Cli) -> anyhow::Result<PathBuf> {
+    match cli.contacts_file.clone() {
+        Some(path) => Ok(path),
+        None => default_contacts_path(),
+    }
+}
+
+/// Returns the default contacts file path, `$HOME/.adm/contacts.json`.
+pub fn default_contacts_path() -> anyhow::Result<PathBuf> {
+    let home = std::env::var("HOME").map_err(|_| anyhow!("failed to get home directory"))?;
+    Ok(PathBuf::from(home).join(".adm").join("contacts.json"))
+}
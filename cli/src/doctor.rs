@@ -0,0 +1,367 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::anyhow;
+use clap::Args;
+use fendermint_crypto::SecretKey;
+use fendermint_vm_message::query::FvmQueryHeight;
+use fvm_shared::address::Address;
+use reqwest::Url;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use adm_provider::{
+    json_rpc::JsonRpcProvider,
+    query::QueryProvider,
+    util::{parse_address, TokenUnit},
+};
+use adm_sdk::account::Account;
+use adm_signer::{key::parse_secret_key, AccountKind, Signer, Void, Wallet};
+
+use crate::subnet::{get_subnet_config, EvmSubnetArgs};
+use crate::{get_rpc_url, get_subnet_id, get_units, print_json, Cli};
+
+#[derive(Clone, Debug, Args)]
+pub struct DoctorArgs {
+    /// Wallet private key (ECDSA, secp256k1) for signing transactions.
+    #[arg(short, long, env, value_parser = parse_secret_key)]
+    private_key: Option<SecretKey>,
+    /// Account address to check. The signer address is used if no address is given.
+    #[arg(short, long, value_parser = parse_address)]
+    address: Option<Address>,
+    /// Node Object API URL.
+    #[arg(long, env)]
+    object_api_url: Option<Url>,
+    /// Maximum allowed difference between the local clock and the latest block time.
+    #[arg(long, value_parser = humantime::parse_duration, default_value = "30s")]
+    max_clock_skew: Duration,
+    /// Measure RPC and Object API round-trip latencies, and report node sync status and versions.
+    #[arg(long)]
+    probe: bool,
+    #[command(flatten)]
+    subnet: EvmSubnetArgs,
+}
+
+/// The outcome of a single diagnostic check.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Ok,
+    Warn,
+    Fail,
+    Skip,
+}
+
+/// A diagnostic check result with an actionable remediation step if it didn't pass.
+#[derive(Debug, Clone, Serialize)]
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remediation: Option<String>,
+    /// Measurements of the `--probe` checks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    probe: Option<Value>,
+}
+
+impl Check {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Ok,
+            detail: detail.into(),
+            remediation: None,
+            probe: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, remediation: &str) -> Self {
+        Self {
+            name,
+            status: Status::Warn,
+            detail: detail.into(),
+            remediation: Some(remediation.into()),
+            probe: None,
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, remediation: &str) -> Self {
+        Self {
+            name,
+            status: Status::Fail,
+            detail: detail.into(),
+            remediation: Some(remediation.into()),
+            probe: None,
+        }
+    }
+
+    fn skip(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Skip,
+            detail: detail.into(),
+            remediation: None,
+            probe: None,
+        }
+    }
+
+    fn with_probe(mut self, probe: Value) -> Self {
+        self.probe = Some(probe);
+        self
+    }
+}
+
+/// Doctor command handler.
+///
+/// Every check runs even if an earlier one fails, so that all problems are reported at once.
+pub async fn handle_doctor(cli: Cli, args: &DoctorArgs) -> anyhow::Result<()> {
+    let subnet_id = get_subnet_id(&cli)?;
+    let rpc_url = get_rpc_url(&cli)?;
+    let provider = JsonRpcProvider::new_http(rpc_url.clone(), None, None, None)?;
+    let mut checks = Vec::new();
+
+    // RPC reachability and clock skew
+    match provider.chain_head().await {
+        Ok(head) if head.catching_up => checks.push(Check::warn(
+            "rpc",
+            format!(
+                "{} is reachable but still syncing at height {}",
+                rpc_url, head.height
+            ),
+            "Wait for the node to catch up or point --rpc-url at a synced node.",
+        )),
+        Ok(head) => {
+            checks.push(Check::ok(
+                "rpc",
+                format!("{} is reachable at height {}", rpc_url, head.height),
+            ));
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+            let skew = now - head.time.unix_timestamp();
+            if skew.unsigned_abs() > args.max_clock_skew.as_secs() {
+                checks.push(Check::warn(
+                    "clock",
+                    format!("local clock differs from the latest block time by {}s", skew),
+                    "Sync the system clock (e.g., enable NTP). Large skew can also mean the node is stalled.",
+                ));
+            } else {
+                checks.push(Check::ok(
+                    "clock",
+                    format!(
+                        "local clock is within {}s of the latest block time",
+                        skew.abs()
+                    ),
+                ));
+            }
+        }
+        Err(e) => {
+            checks.push(Check::fail(
+                "rpc",
+                format!("{} is unreachable: {}", rpc_url, e),
+                "Check --rpc-url (or RPC_URL) and --network, and your network connection.",
+            ));
+            checks.push(Check::skip("clock", "requires a reachable RPC"));
+        }
+    }
+
+    // Chain ID vs. network preset
+    let expected = u64::from(subnet_id.chain_id());
+    match provider.chain_id().await {
+        Ok(chain_id) if u64::from(chain_id) == expected => checks.push(Check::ok(
+            "chain_id",
+            format!("chain ID {} matches subnet {}", expected, subnet_id),
+        )),
+        Ok(chain_id) => checks.push(Check::fail(
+            "chain_id",
+            format!(
+                "node reports chain ID {} but subnet {} expects {}",
+                u64::from(chain_id),
+                subnet_id,
+                expected
+            ),
+            "The RPC node serves a different subnet. Use a matching --network or --subnet, or fix --rpc-url.",
+        )),
+        Err(e) => checks.push(Check::fail(
+            "chain_id",
+            format!("failed to get chain ID: {}", e),
+            "Check that --rpc-url points at a Fendermint node's CometBFT RPC.",
+        )),
+    }
+
+    // Object API reachability
+    let object_api_url = match &args.object_api_url {
+        Some(url) => Ok(url.clone()),
+        None => cli.network.get().object_api_url(),
+    };
+    match &object_api_url {
+        Ok(url) => match reqwest::get(url.clone()).await {
+            // Any HTTP response means the API is up
+            Ok(_) => checks.push(Check::ok("object_api", format!("{} is reachable", url))),
+            Err(e) => checks.push(Check::fail(
+                "object_api",
+                format!("{} is unreachable: {}", url, e),
+                "Check --object-api-url (or OBJECT_API_URL) and your network connection.",
+            )),
+        },
+        Err(e) => checks.push(Check::skip("object_api", e.to_string())),
+    }
+
+    // Endpoint latency, sync status, and versions
+    if args.probe {
+        probe_endpoints(&mut checks, &rpc_url, object_api_url.as_ref().ok()).await?;
+    }
+
+    // Wallet sequence and balance
+    let address = match (&args.address, &args.private_key) {
+        (Some(address), _) => Some(*address),
+        (None, Some(sk)) => Some(
+            Wallet::new_secp256k1(sk.clone(), AccountKind::Ethereum, subnet_id.clone())?.address(),
+        ),
+        (None, None) => None,
+    };
+    match address {
+        Some(address) => {
+            let signer = Void::new(address);
+            match Account::sequence(&provider, &signer, FvmQueryHeight::Committed).await {
+                Ok(sequence) => checks.push(Check::ok(
+                    "sequence",
+                    format!("account {} has sequence {}", address, sequence),
+                )),
+                Err(e) => checks.push(Check::fail(
+                    "sequence",
+                    e.to_string(),
+                    "Accounts only exist in the subnet once funded. Run `adm account deposit` to fund it.",
+                )),
+            }
+            let balance = match get_subnet_config(&cli, &subnet_id, args.subnet.clone()) {
+                Ok(config) => Account::balance(&signer, config).await,
+                Err(e) => Err(e),
+            };
+            match balance {
+                Ok(balance) if balance.is_zero() => checks.push(Check::warn(
+                    "balance",
+                    format!("account {} has no funds", address),
+                    "Transactions need gas. Run `adm account deposit` to fund the account.",
+                )),
+                Ok(balance) => {
+                    let units = get_units(&cli, TokenUnit::Fil);
+                    checks.push(Check::ok(
+                        "balance",
+                        format!(
+                            "account {} has {} {}",
+                            address,
+                            units.format(&balance),
+                            units
+                        ),
+                    ))
+                }
+                Err(e) => checks.push(Check::fail(
+                    "balance",
+                    format!("failed to get balance: {}", e),
+                    "Check --evm-rpc-url and --network.",
+                )),
+            }
+        }
+        None => {
+            let detail = "pass --private-key or --address to check an account";
+            checks.push(Check::skip("sequence", detail));
+            checks.push(Check::skip("balance", detail));
+        }
+    }
+
+    print_json(&checks)?;
+
+    let failed = checks.iter().filter(|c| c.status == Status::Fail).count();
+    if failed > 0 {
+        return Err(anyhow!("{} of {} checks failed", failed, checks.len()));
+    }
+    Ok(())
+}
+
+/// Adds the `--probe` checks, which time each endpoint without the CLI's middleware.
+async fn probe_endpoints(
+    checks: &mut Vec<Check>,
+    rpc_url: &Url,
+    object_api_url: Option<&Url>,
+) -> anyhow::Result<()> {
+    let provider = JsonRpcProvider::new_http(rpc_url.clone(), None, object_api_url.cloned(), None)?;
+    // If negotiation fails, the Object API probe reports why
+    let provider = match provider.clone().with_negotiated_object_api().await {
+        Ok(provider) => provider,
+        Err(_) => provider,
+    };
+    let probe = provider.probe().await;
+
+    match probe.rpc {
+        Ok(rpc) => {
+            let data = json!({
+                "latency_ms": rpc.latency.as_millis() as u64,
+                "chain_id": rpc.chain_id.to_string(),
+                "node_version": rpc.node_version,
+                "app_version": rpc.app_version,
+                "height": rpc.height.value(),
+                "time": rpc.time.to_string(),
+                "catching_up": rpc.catching_up,
+            });
+            let detail = format!(
+                "{} answered in {}ms at height {} (CometBFT {}, app {})",
+                rpc_url,
+                rpc.latency.as_millis(),
+                rpc.height,
+                rpc.node_version,
+                rpc.app_version
+            );
+            let check = if rpc.catching_up {
+                Check::warn(
+                    "rpc_probe",
+                    detail,
+                    "The node is still syncing, so its state may be stale. Wait or use a synced node.",
+                )
+            } else {
+                Check::ok("rpc_probe", detail)
+            };
+            checks.push(check.with_probe(data));
+        }
+        Err(e) => checks.push(Check::fail(
+            "rpc_probe",
+            format!("{} failed to answer: {}", rpc_url, e),
+            "Check --rpc-url (or RPC_URL) and your network connection.",
+        )),
+    }
+
+    match (probe.object_api, object_api_url) {
+        (Some(Ok(object_api)), Some(url)) => {
+            let data = json!({
+                "latency_ms": object_api.latency.as_millis() as u64,
+                "api_version": object_api.api_version.path(),
+                "status": object_api.status,
+            });
+            let detail = format!(
+                "{} answered in {}ms with status {} (API {})",
+                url,
+                object_api.latency.as_millis(),
+                object_api.status,
+                object_api.api_version.path()
+            );
+            let check = if object_api.healthy {
+                Check::ok("object_api_probe", detail)
+            } else {
+                Check::warn(
+                    "object_api_probe",
+                    detail,
+                    "The Object API reports it's unhealthy. Check the node's Object API logs.",
+                )
+            };
+            checks.push(check.with_probe(data));
+        }
+        (Some(Err(e)), Some(url)) => checks.push(Check::fail(
+            "object_api_probe",
+            format!("{} failed to answer: {}", url, e),
+            "Check --object-api-url (or OBJECT_API_URL) and your network connection.",
+        )),
+        _ => checks.push(Check::skip("object_api_probe", "no Object API URL")),
+    }
+    Ok(())
+}
@@ -0,0 +1,164 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::fmt::{self, Display};
+use std::process::ExitCode;
+
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::json;
+
+use adm_provider::{
+    object::{UploadError, UploadErrorKind},
+    query::NotFound,
+    tx::TxError,
+};
+
+/// FVM exit code for a sender that can't cover the message value and gas.
+const SYS_INSUFFICIENT_FUNDS: u32 = 6;
+
+/// Format of errors written to stderr.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum ErrorFormat {
+    /// Human-readable error messages.
+    Text,
+    /// A JSON object per error.
+    Json,
+}
+
+/// Marks an error as caused by invalid configuration, e.g., a bad network preset or keystore.
+#[derive(Debug)]
+pub struct ConfigError;
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid configuration")
+    }
+}
+
+/// Wraps an error as a [`ConfigError`].
+pub fn config_error(e: anyhow::Error) -> anyhow::Error {
+    e.context(ConfigError)
+}
+
+/// The kind of a CLI error, which determines the process exit code.
+///
+/// Exit codes are stable so scripts can branch on them.
+/// Code `2` is reserved for invalid arguments, which are reported by the argument parser.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// An error that doesn't fit another kind.
+    Other,
+    /// Invalid configuration, e.g., a network preset without the requested URL.
+    Config,
+    /// A node or API could not be reached.
+    Network,
+    /// A transaction was rejected or failed during execution.
+    TxFailed,
+    /// The requested object, account, or machine does not exist.
+    NotFound,
+    /// The account can't cover the transaction value and gas.
+    InsufficientFunds,
+}
+
+impl ErrorKind {
+    /// Returns the process exit code for this kind.
+    pub fn code(&self) -> u8 {
+        match self {
+            ErrorKind::Other => 1,
+            ErrorKind::Config => 3,
+            ErrorKind::Network => 4,
+            ErrorKind::TxFailed => 5,
+            ErrorKind::NotFound => 6,
+            ErrorKind::InsufficientFunds => 7,
+        }
+    }
+
+    /// Classifies an error by the typed errors in its chain of causes.
+    ///
+    /// Errors are never classified by their messages, so an error without a known type,
+    /// e.g., a missing keystore or file, is [`ErrorKind::Other`].
+    pub fn of(err: &anyhow::Error) -> Self {
+        if err.downcast_ref::<ConfigError>().is_some() {
+            return ErrorKind::Config;
+        }
+        if let Some(e) = err.downcast_ref::<UploadError>() {
+            return match e.kind {
+                UploadErrorKind::Unauthorized => ErrorKind::Config,
+                UploadErrorKind::MachineNotFound => ErrorKind::NotFound,
+                _ => ErrorKind::Other,
+            };
+        }
+        if let Some(e) = err.downcast_ref::<TxError>() {
+            return if e.code == SYS_INSUFFICIENT_FUNDS {
+                ErrorKind::InsufficientFunds
+            } else {
+                ErrorKind::TxFailed
+            };
+        }
+        for cause in err.chain() {
+            if cause.is::<NotFound>() {
+                return ErrorKind::NotFound;
+            }
+            if cause.is::<reqwest::Error>() || cause.is::<tendermint_rpc::Error>() {
+                return ErrorKind::Network;
+            }
+            if let Some(e) = cause.downcast_ref::<std::io::Error>() {
+                if is_network_io(e.kind()) {
+                    return ErrorKind::Network;
+                }
+            }
+        }
+        ErrorKind::Other
+    }
+}
+
+/// Returns whether an I/O error kind means a connection failed, rather than, e.g., a missing file.
+fn is_network_io(kind: std::io::ErrorKind) -> bool {
+    use std::io::ErrorKind::*;
+    matches!(
+        kind,
+        ConnectionRefused
+            | ConnectionReset
+            | ConnectionAborted
+            | NotConnected
+            | AddrNotAvailable
+            | BrokenPipe
+            | TimedOut
+    )
+}
+
+/// Returns a suggested fix for the error, if there is one.
+fn hint(err: &anyhow::Error) -> Option<&'static str> {
+    err.chain()
+        .find_map(|cause| cause.downcast_ref::<UploadError>())
+        .and_then(|e| e.remediation())
+}
+
+/// Writes the error to stderr in the given format and returns the matching exit code.
+pub fn report(err: &anyhow::Error, format: ErrorFormat) -> ExitCode {
+    let kind = ErrorKind::of(err);
+    let hint = hint(err);
+    match format {
+        ErrorFormat::Text => {
+            eprintln!("Error: {:#}", err);
+            if let Some(hint) = hint {
+                eprintln!("Hint: {}", hint);
+            }
+        }
+        ErrorFormat::Json => {
+            let value = json!({
+                "error": {
+                    "kind": kind,
+                    "code": kind.code(),
+                    "message": err.to_string(),
+                    "causes": err.chain().skip(1).map(|c| c.to_string()).collect::<Vec<_>>(),
+                    "hint": hint,
+                }
+            });
+            eprintln!("{}", value);
+        }
+    }
+    ExitCode::from(kind.code())
+}
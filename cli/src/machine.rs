@@ -1,19 +1,37 @@
 // Copyright 2024 ADM Contributors
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use std::path::PathBuf;
+
+use anyhow::anyhow;
 use clap::{Args, Subcommand};
+use clap_complete::engine::ArgValueCompleter;
 use ethers::utils::hex::ToHexExt;
+use fendermint_crypto::SecretKey;
 use fendermint_vm_message::query::FvmQueryHeight;
-use fvm_shared::address::Address;
+use fvm_ipld_encoding::RawBytes;
 use serde_json::json;
 
-use adm_provider::{
-    json_rpc::JsonRpcProvider,
-    util::{get_delegated_address, parse_address, parse_query_height},
+use adm_provider::util::{get_delegated_address, parse_query_height};
+use adm_sdk::{
+    machine::{
+        accumulator::Accumulator,
+        custom::{CustomMachine, KindRegistry},
+        info,
+        objectstore::ObjectStore,
+        Kind, Machine,
+    },
+    name::{parse_address_or_name, AddressOrName},
+    TxParams,
 };
-use adm_sdk::machine::info;
+use adm_signer::{key::parse_secret_key, AccountKind, Wallet};
 
-use crate::{get_rpc_url, print_json, Cli};
+use crate::complete::complete_address;
+use crate::name::resolve_address;
+use crate::{
+    confirm_signer, get_provider, get_subnet_id, print_json, print_json_watch, BroadcastMode, Cli,
+    TxArgs, WatchArgs,
+};
 
 pub mod accumulator;
 pub mod objectstore;
@@ -28,13 +46,80 @@ pub struct MachineArgs {
 enum MachineCommands {
     /// Get machine info.
     Info(InfoArgs),
+    /// List the custom machine kinds registered in the kinds file.
+    Kinds(KindsArgs),
+    /// Call a method of a machine of a custom kind.
+    Call(CallArgs),
+}
+
+#[derive(Clone, Debug, Args)]
+struct KindsFileArgs {
+    /// JSON file that registers custom machine kinds.
+    /// Defaults to `$HOME/.adm/kinds.json`.
+    #[arg(long, env = "ADM_KINDS_FILE")]
+    kinds_file: Option<PathBuf>,
+}
+
+impl KindsFileArgs {
+    /// Loads the kinds file, which is empty if it doesn't exist.
+    fn load(&self) -> anyhow::Result<KindRegistry> {
+        let path = match self.kinds_file.clone() {
+            Some(path) => path,
+            None => {
+                let home =
+                    std::env::var("HOME").map_err(|_| anyhow!("failed to get home directory"))?;
+                PathBuf::from(home).join(".adm").join("kinds.json")
+            }
+        };
+        KindRegistry::load(path)
+    }
+}
+
+#[derive(Clone, Debug, Args)]
+struct KindsArgs {
+    #[command(flatten)]
+    kinds_file: KindsFileArgs,
+}
+
+#[derive(Clone, Debug, Args)]
+struct CallArgs {
+    /// Machine address or name.
+    #[arg(value_parser = parse_address_or_name, add = ArgValueCompleter::new(complete_address))]
+    address: AddressOrName,
+    /// Method name, as registered for the machine's kind.
+    method: String,
+    /// CBOR-encoded method params as hex.
+    #[arg(long, default_value = "")]
+    params: String,
+    /// Custom machine kind.
+    /// Detected by the machine's actor code if not given.
+    #[arg(short, long)]
+    kind: Option<String>,
+    #[command(flatten)]
+    kinds_file: KindsFileArgs,
+    /// Wallet private key (ECDSA, secp256k1) for signing transactions.
+    /// Required for methods that aren't read-only.
+    #[arg(short, long, env, value_parser = parse_secret_key)]
+    private_key: Option<SecretKey>,
+    /// Broadcast mode for the transaction.
+    #[arg(short, long, value_enum, env, default_value_t = BroadcastMode::Commit)]
+    broadcast_mode: BroadcastMode,
+    #[command(flatten)]
+    tx_args: TxArgs,
+    /// Query block height for read-only methods.
+    /// Possible values:
+    /// "committed" (latest committed block),
+    /// "pending" (consider pending state changes),
+    /// or a specific block height, e.g., "123".
+    #[arg(long, value_parser = parse_query_height, default_value = "committed")]
+    height: FvmQueryHeight,
 }
 
 #[derive(Clone, Debug, Args)]
 struct InfoArgs {
-    /// Machine address.
-    #[arg(value_parser = parse_address)]
-    address: Address,
+    /// Machine address or name.
+    #[arg(value_parser = parse_address_or_name, add = ArgValueCompleter::new(complete_address))]
+    address: AddressOrName,
     /// Query block height.
     /// Possible values:
     /// "committed" (latest committed block),
@@ -42,17 +127,92 @@ struct InfoArgs {
     /// or a specific block height, e.g., "123".
     #[arg(long, value_parser = parse_query_height, default_value = "committed")]
     height: FvmQueryHeight,
+    /// Include usage statistics, i.e., the object count and total size for object stores,
+    /// the leaf count and root for accumulators, and the last-modified height.
+    #[arg(long)]
+    stats: bool,
+    #[command(flatten)]
+    watch: WatchArgs,
 }
 
 /// Machine commmands handler.
 pub async fn handle_machine(cli: Cli, args: &MachineArgs) -> anyhow::Result<()> {
     match &args.command {
         MachineCommands::Info(args) => {
-            let provider = JsonRpcProvider::new_http(get_rpc_url(&cli)?, None, None)?;
-            let metadata = info(&provider, args.address, args.height).await?;
-            let owner = get_delegated_address(metadata.owner)?.encode_hex_with_prefix();
+            let provider = get_provider(&cli, None)?;
+            let address = resolve_address(&cli, &provider, &args.address).await?;
+            print_json_watch(&args.watch, || async {
+                let metadata = info(&provider, address, args.height).await?;
+                let owner = get_delegated_address(metadata.owner)?.encode_hex_with_prefix();
+                let mut value = json!({"kind": metadata.kind, "owner": owner});
+                if args.stats {
+                    value["stats"] = if matches!(metadata.kind, Kind::ObjectStore) {
+                        let machine = ObjectStore::attach(address);
+                        serde_json::to_value(machine.stats(&provider, args.height).await?)?
+                    } else {
+                        let machine = Accumulator::attach(address);
+                        serde_json::to_value(machine.stats(&provider, args.height).await?)?
+                    };
+                }
+                Ok(value)
+            })
+            .await
+        }
+        MachineCommands::Kinds(args) => {
+            let registry = args.kinds_file.load()?;
+            print_json(&registry.kinds().collect::<Vec<_>>())
+        }
+        MachineCommands::Call(args) => {
+            let provider = get_provider(&cli, None)?;
+            let address = resolve_address(&cli, &provider, &args.address).await?;
+            let registry = args.kinds_file.load()?;
+            let kind = match &args.kind {
+                Some(name) => registry
+                    .get(name)
+                    .ok_or_else(|| anyhow!("unknown machine kind '{}'", name))?,
+                None => registry
+                    .detect(&provider, address, args.height)
+                    .await?
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "machine {} doesn't match a registered kind; pass --kind",
+                            address
+                        )
+                    })?,
+            };
+            let params = hex::decode(args.params.trim_start_matches("0x"))
+                .map_err(|e| anyhow!("invalid params: {}", e))?;
+            let machine = CustomMachine::attach(address, kind.clone());
+
+            if kind.method(&args.method)?.read_only {
+                let value = machine
+                    .query(&provider, &args.method, RawBytes::new(params), args.height)
+                    .await?;
+                return print_json(&json!({"value": value}));
+            }
 
-            print_json(&json!({"kind": metadata.kind, "owner": owner}))
+            let private_key = args.private_key.clone().ok_or_else(|| {
+                anyhow!("--private-key is required for methods that aren't read-only")
+            })?;
+            let TxParams {
+                sequence,
+                gas_params,
+            } = args.tx_args.to_tx_params(cli.network.get());
+            let mut signer =
+                Wallet::new_secp256k1(private_key, AccountKind::Ethereum, get_subnet_id(&cli)?)?;
+            signer.set_sequence(sequence, &provider).await?;
+            let mut signer = confirm_signer(&cli, signer);
+            let tx = machine
+                .send(
+                    &provider,
+                    &mut signer,
+                    &args.method,
+                    RawBytes::new(params),
+                    args.broadcast_mode.get(),
+                    gas_params,
+                )
+                .await?;
+            print_json(&tx)
         }
     }
 }
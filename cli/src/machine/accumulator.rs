@@ -1,33 +1,50 @@
 // Copyright 2024 ADM Contributors
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use std::collections::BTreeMap;
+use std::io::{Cursor, IsTerminal};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context};
 use bytes::Bytes;
 use clap::{Args, Subcommand};
+use clap_complete::engine::ArgValueCompleter;
 use clap_stdin::FileOrStdin;
-use fendermint_actor_machine::WriteAccess;
 use fendermint_crypto::SecretKey;
 use fendermint_vm_message::query::FvmQueryHeight;
-use fvm_shared::address::Address;
 use serde_json::{json, Value};
-use tokio::io::{self, AsyncReadExt, AsyncWriteExt};
+use tendermint::Hash;
+use tokio::io::{self, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
+use tokio::time::{sleep, timeout_at, Instant};
 
 use adm_provider::{
     json_rpc::JsonRpcProvider,
-    util::{parse_address, parse_query_height},
+    query::QueryProvider,
+    tx::{BroadcastMode as TxBroadcastMode, TxError, TxReceipt},
+    util::{parse_metadata, parse_query_height},
 };
 use adm_sdk::{
     machine::{
-        accumulator::{Accumulator, PushOptions},
-        Machine,
+        accumulator::{Accumulator, PushOptions, PushReturn},
+        DeployOptions, Machine, WriteAccess,
     },
+    name::{parse_address_or_name, AddressOrName},
     TxParams,
 };
-use adm_signer::{key::parse_secret_key, AccountKind, Void, Wallet};
+use adm_signer::{key::parse_secret_key, AccountKind, Confirming, NotConfirmed, Void, Wallet};
 
+use crate::complete::complete_address;
+use crate::name::resolve_address;
 use crate::{
-    get_address, get_rpc_url, get_subnet_id, print_json, AddressArgs, BroadcastMode, Cli, TxArgs,
+    confirm_signer, get_address, get_provider, get_subnet_id, print_json, print_json_watch,
+    AddressArgs, BroadcastMode, Cli, TxArgs, WatchArgs,
 };
 
+/// Base delay between retries of a failed push in `follow-push`.
+/// The delay grows linearly with each attempt.
+const FOLLOW_PUSH_RETRY_DELAY: Duration = Duration::from_secs(1);
+
 #[derive(Clone, Debug, Args)]
 pub struct AccumulatorArgs {
     #[command(subcommand)]
@@ -43,14 +60,20 @@ enum AccumulatorCommands {
     List(AddressArgs),
     /// Push a value.
     Push(AccumulatorPushArgs),
+    /// Push each newline-delimited record read from stdin as a leaf.
+    FollowPush(AccumulatorFollowPushArgs),
     /// Get leaf at a given index and height.
     Leaf(AccumulatorLeafArgs),
     /// Get leaf count at a given height.
-    Count(AccumulatorQueryArgs),
+    Count(AccumulatorWatchArgs),
     /// Get peaks at a given height.
     Peaks(AccumulatorQueryArgs),
     /// Get root at a given height.
-    Root(AccumulatorQueryArgs),
+    Root(AccumulatorWatchArgs),
+    /// Export a snapshot of all leaves, peaks, and root at a given height.
+    Export(AccumulatorQueryArgs),
+    /// Import a snapshot by replaying its leaves into an empty accumulator.
+    Import(AccumulatorImportArgs),
 }
 
 #[derive(Clone, Debug, Args)]
@@ -61,6 +84,10 @@ struct AccumulatorCreateArgs {
     /// Allow public write access to the accumulator.
     #[arg(long, default_value_t = false)]
     public_write: bool,
+    /// Broadcast mode for the transaction.
+    /// With "async" or "sync", the predicted address is printed before the accumulator exists.
+    #[arg(short, long, value_enum, env, default_value_t = BroadcastMode::Commit)]
+    broadcast_mode: BroadcastMode,
     #[command(flatten)]
     tx_args: TxArgs,
 }
@@ -70,9 +97,9 @@ struct AccumulatorPushArgs {
     /// Wallet private key (ECDSA, secp256k1) for signing transactions.
     #[arg(short, long, env, value_parser = parse_secret_key)]
     private_key: SecretKey,
-    /// Accumulator machine address.
-    #[arg(short, long, value_parser = parse_address)]
-    address: Address,
+    /// Accumulator machine address or name.
+    #[arg(short, long, value_parser = parse_address_or_name, add = ArgValueCompleter::new(complete_address))]
+    address: AddressOrName,
     /// Input file (or stdin) containing the value to push.
     #[clap(default_value = "-")]
     input: FileOrStdin,
@@ -81,13 +108,71 @@ struct AccumulatorPushArgs {
     broadcast_mode: BroadcastMode,
     #[command(flatten)]
     tx_args: TxArgs,
+    /// Metadata tags to attach to the leaf, e.g., "source=oracle".
+    /// The chain height and time of the push are attached along with the tags.
+    #[arg(short, long, value_parser = parse_metadata)]
+    metadata: Vec<(String, String)>,
+}
+
+#[derive(Clone, Debug, Args)]
+struct AccumulatorFollowPushArgs {
+    /// Wallet private key (ECDSA, secp256k1) for signing transactions.
+    #[arg(short, long, env, value_parser = parse_secret_key)]
+    private_key: SecretKey,
+    /// Accumulator machine address or name.
+    #[arg(short, long, value_parser = parse_address_or_name, add = ArgValueCompleter::new(complete_address))]
+    address: AddressOrName,
+    /// Read records from stdin even if it's an interactive terminal.
+    /// Without this flag, records must be piped to stdin.
+    #[arg(long)]
+    from_stdin: bool,
+    /// Maximum number of records pushed per batch.
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..), default_value_t = 16)]
+    batch_size: u64,
+    /// Maximum time to wait for a batch to fill before pushing it.
+    #[arg(long, value_parser = humantime::parse_duration, default_value = "1s")]
+    batch_timeout: Duration,
+    /// Maximum number of records read ahead of the pusher.
+    /// Reading from stdin pauses when the buffer is full.
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..), default_value_t = 1024)]
+    buffer: u64,
+    /// Number of times a push that failed before it was broadcast is retried before giving up.
+    #[arg(long, default_value_t = 3)]
+    max_retries: u32,
+    /// Broadcast mode for the transactions.
+    #[arg(short, long, value_enum, env, default_value_t = BroadcastMode::Sync)]
+    broadcast_mode: BroadcastMode,
+    #[command(flatten)]
+    tx_args: TxArgs,
+    /// Metadata tags to attach to each leaf, e.g., "source=app".
+    /// The chain height and time of the push are attached along with the tags.
+    #[arg(short, long, value_parser = parse_metadata)]
+    metadata: Vec<(String, String)>,
+}
+
+#[derive(Clone, Debug, Args)]
+struct AccumulatorImportArgs {
+    /// Wallet private key (ECDSA, secp256k1) for signing transactions.
+    #[arg(short, long, env, value_parser = parse_secret_key)]
+    private_key: SecretKey,
+    /// Accumulator machine address or name.
+    #[arg(short, long, value_parser = parse_address_or_name, add = ArgValueCompleter::new(complete_address))]
+    address: AddressOrName,
+    /// Input file (or stdin) containing the snapshot to import.
+    #[clap(default_value = "-")]
+    input: FileOrStdin,
+    /// Broadcast mode for the transactions.
+    #[arg(short, long, value_enum, env, default_value_t = BroadcastMode::Commit)]
+    broadcast_mode: BroadcastMode,
+    #[command(flatten)]
+    tx_args: TxArgs,
 }
 
 #[derive(Clone, Debug, Args)]
 struct AccumulatorQueryArgs {
-    /// Accumulator machine address.
-    #[arg(short, long, value_parser = parse_address)]
-    address: Address,
+    /// Accumulator machine address or name.
+    #[arg(short, long, value_parser = parse_address_or_name, add = ArgValueCompleter::new(complete_address))]
+    address: AddressOrName,
     /// Query block height.
     /// Possible values:
     /// "committed" (latest committed block),
@@ -97,11 +182,19 @@ struct AccumulatorQueryArgs {
     height: FvmQueryHeight,
 }
 
+#[derive(Clone, Debug, Args)]
+struct AccumulatorWatchArgs {
+    #[command(flatten)]
+    query: AccumulatorQueryArgs,
+    #[command(flatten)]
+    watch: WatchArgs,
+}
+
 #[derive(Clone, Debug, Args)]
 struct AccumulatorLeafArgs {
-    /// Accumulator machine address.
-    #[arg(short, long, value_parser = parse_address)]
-    address: Address,
+    /// Accumulator machine address or name.
+    #[arg(short, long, value_parser = parse_address_or_name, add = ArgValueCompleter::new(complete_address))]
+    address: AddressOrName,
     /// Leaf index.
     index: u64,
     /// Query block height.
@@ -111,11 +204,14 @@ struct AccumulatorLeafArgs {
     /// or a specific block height, e.g., "123".
     #[arg(long, value_parser = parse_query_height, default_value = "committed")]
     height: FvmQueryHeight,
+    /// Print the leaf metadata as JSON instead of the leaf payload.
+    #[arg(long)]
+    metadata: bool,
 }
 
 /// Accumulator commmands handler.
 pub async fn handle_accumulator(cli: Cli, args: &AccumulatorArgs) -> anyhow::Result<()> {
-    let provider = JsonRpcProvider::new_http(get_rpc_url(&cli)?, None, None)?;
+    let provider = get_provider(&cli, None)?;
     let subnet_id = get_subnet_id(&cli)?;
 
     match &args.command {
@@ -128,19 +224,24 @@ pub async fn handle_accumulator(cli: Cli, args: &AccumulatorArgs) -> anyhow::Res
             let TxParams {
                 sequence,
                 gas_params,
-            } = args.tx_args.to_tx_params();
+            } = args.tx_args.to_tx_params(cli.network.get());
 
             let mut signer =
                 Wallet::new_secp256k1(args.private_key.clone(), AccountKind::Ethereum, subnet_id)?;
             signer.set_sequence(sequence, &provider).await?;
+            let mut signer = confirm_signer(&cli, signer);
 
-            let (store, tx) =
-                Accumulator::new(&provider, &mut signer, write_access, gas_params).await?;
+            let options = DeployOptions {
+                write_access,
+                broadcast_mode: args.broadcast_mode.get(),
+                gas_params,
+            };
+            let (store, tx) = Accumulator::deploy(&provider, &mut signer, options).await?;
 
             print_json(&json!({"address": store.address().to_string(), "tx": &tx}))
         }
         AccumulatorCommands::List(args) => {
-            let address = get_address(args.clone(), &subnet_id)?;
+            let address = get_address(&cli, args.clone(), &subnet_id)?;
             let metadata = Accumulator::list(&provider, &Void::new(address), args.height).await?;
 
             let metadata = metadata
@@ -155,18 +256,31 @@ pub async fn handle_accumulator(cli: Cli, args: &AccumulatorArgs) -> anyhow::Res
             let TxParams {
                 gas_params,
                 sequence,
-            } = args.tx_args.to_tx_params();
+            } = args.tx_args.to_tx_params(cli.network.get());
 
             let mut signer =
                 Wallet::new_secp256k1(args.private_key.clone(), AccountKind::Ethereum, subnet_id)?;
             signer.set_sequence(sequence, &provider).await?;
+            let mut signer = confirm_signer(&cli, signer);
 
             let mut reader = args.input.into_async_reader().await?;
             let mut buf = Vec::new();
             reader.read_to_end(&mut buf).await?;
             let payload = Bytes::from(buf);
 
-            let machine = Accumulator::attach(args.address);
+            let metadata = if args.metadata.is_empty() {
+                None
+            } else {
+                Some(
+                    args.metadata
+                        .clone()
+                        .into_iter()
+                        .collect::<BTreeMap<_, _>>(),
+                )
+            };
+
+            let address = resolve_address(&cli, &provider, &args.address).await?;
+            let machine = Accumulator::attach(address);
             let tx = machine
                 .push(
                     &provider,
@@ -175,37 +289,273 @@ pub async fn handle_accumulator(cli: Cli, args: &AccumulatorArgs) -> anyhow::Res
                     PushOptions {
                         broadcast_mode,
                         gas_params,
+                        metadata,
+                        cancel: Default::default(),
                     },
                 )
                 .await?;
 
             print_json(&tx)
         }
+        AccumulatorCommands::FollowPush(args) => {
+            if !args.from_stdin && std::io::stdin().is_terminal() {
+                return Err(anyhow!(
+                    "no records piped to stdin; pass --from-stdin to read from the terminal"
+                ));
+            }
+            let TxParams {
+                gas_params,
+                sequence,
+            } = args.tx_args.to_tx_params(cli.network.get());
+
+            let mut signer =
+                Wallet::new_secp256k1(args.private_key.clone(), AccountKind::Ethereum, subnet_id)?;
+            signer.set_sequence(sequence, &provider).await?;
+            let mut signer = confirm_signer(&cli, signer);
+
+            let options = PushOptions {
+                broadcast_mode: args.broadcast_mode.get(),
+                gas_params,
+                metadata: if args.metadata.is_empty() {
+                    None
+                } else {
+                    Some(args.metadata.clone().into_iter().collect())
+                },
+                cancel: Default::default(),
+            };
+
+            // Read records in the background so reading continues while a batch is pushed.
+            // The bounded channel applies backpressure: when it's full, stdin isn't read,
+            // and the writer on the other end of the pipe blocks.
+            let (records, mut rx) = mpsc::channel::<Bytes>(args.buffer as usize);
+            let reader = tokio::spawn(async move {
+                let mut lines = BufReader::new(io::stdin()).lines();
+                while let Some(line) = lines.next_line().await? {
+                    if line.is_empty() {
+                        continue;
+                    }
+                    if records.send(Bytes::from(line)).await.is_err() {
+                        break;
+                    }
+                }
+                Ok::<_, anyhow::Error>(())
+            });
+
+            let address = resolve_address(&cli, &provider, &args.address).await?;
+            let machine = Accumulator::attach(address);
+            let mut total = 0;
+            while let Some(first) = rx.recv().await {
+                // Fill the batch until it's full, the timeout elapses, or stdin is closed
+                let mut batch = vec![first];
+                let deadline = Instant::now() + args.batch_timeout;
+                while batch.len() < args.batch_size as usize {
+                    match timeout_at(deadline, rx.recv()).await {
+                        Ok(Some(record)) => batch.push(record),
+                        Ok(None) | Err(_) => break,
+                    }
+                }
+
+                let mut txs = Vec::with_capacity(batch.len());
+                let pushed = push_batch(
+                    &machine,
+                    &provider,
+                    &mut signer,
+                    batch,
+                    &options,
+                    args.max_retries,
+                    &mut txs,
+                )
+                .await;
+                total += txs.len();
+                pushed.map_err(|e| e.context(format!("failed after pushing {} records", total)))?;
+                print_json(&json!({"pushed": txs.len(), "total": total, "txs": txs}))?;
+            }
+            reader.await?
+        }
         AccumulatorCommands::Leaf(args) => {
-            let machine = Accumulator::attach(args.address);
-            let leaf = machine.leaf(&provider, args.index, args.height).await?;
+            let address = resolve_address(&cli, &provider, &args.address).await?;
+            let machine = Accumulator::attach(address);
+            let leaf = machine
+                .leaf_with_metadata(&provider, args.index, args.height)
+                .await?;
 
+            if args.metadata {
+                return print_json(&json!({"index": args.index, "metadata": leaf.metadata}));
+            }
             let mut stdout = io::stdout();
-            stdout.write_all(&leaf).await?;
+            stdout.write_all(&leaf.payload).await?;
             Ok(())
         }
         AccumulatorCommands::Count(args) => {
-            let machine = Accumulator::attach(args.address);
-            let count = machine.count(&provider, args.height).await?;
-
-            print_json(&json!({"count": count}))
+            let address = resolve_address(&cli, &provider, &args.query.address).await?;
+            let machine = Accumulator::attach(address);
+            print_json_watch(&args.watch, || async {
+                let count = machine.count(&provider, args.query.height).await?;
+                Ok(json!({"count": count}))
+            })
+            .await
         }
         AccumulatorCommands::Peaks(args) => {
-            let machine = Accumulator::attach(args.address);
+            let address = resolve_address(&cli, &provider, &args.address).await?;
+            let machine = Accumulator::attach(address);
             let peaks = machine.peaks(&provider, args.height).await?;
 
             print_json(&json!({"peaks": peaks}))
         }
         AccumulatorCommands::Root(args) => {
-            let machine = Accumulator::attach(args.address);
-            let root = machine.root(&provider, args.height).await?;
+            let address = resolve_address(&cli, &provider, &args.query.address).await?;
+            let machine = Accumulator::attach(address);
+            print_json_watch(&args.watch, || async {
+                let root = machine.root(&provider, args.query.height).await?;
+                Ok(json!({"root": root.to_string()}))
+            })
+            .await
+        }
+        AccumulatorCommands::Export(args) => {
+            let address = resolve_address(&cli, &provider, &args.address).await?;
+            let machine = Accumulator::attach(address);
+            let network = cli.network.get().address_network();
+            machine
+                .export(&provider, io::stdout(), args.height, network)
+                .await?;
+            Ok(())
+        }
+        AccumulatorCommands::Import(args) => {
+            let broadcast_mode = args.broadcast_mode.get();
+            let TxParams {
+                gas_params,
+                sequence,
+            } = args.tx_args.to_tx_params(cli.network.get());
+
+            let mut signer =
+                Wallet::new_secp256k1(args.private_key.clone(), AccountKind::Ethereum, subnet_id)?;
+            signer.set_sequence(sequence, &provider).await?;
+            let mut signer = confirm_signer(&cli, signer);
+
+            let mut reader = args.input.into_async_reader().await?;
+            let mut buf = Vec::new();
+            reader.read_to_end(&mut buf).await?;
 
-            print_json(&json!({"root": root.to_string()}))
+            let address = resolve_address(&cli, &provider, &args.address).await?;
+            let machine = Accumulator::attach(address);
+            let tx = machine
+                .import(
+                    &provider,
+                    &mut signer,
+                    Cursor::new(buf),
+                    PushOptions {
+                        broadcast_mode,
+                        gas_params,
+                        metadata: None,
+                        cancel: Default::default(),
+                    },
+                )
+                .await?;
+
+            print_json(&tx)
+        }
+    }
+}
+
+/// Push a batch of payloads in order, adding the hash of each pushed transaction to `txs`.
+///
+/// In commit mode, the batch is pipelined: only the last push waits for its block,
+/// and the others return once they pass the node's mempool check.
+/// Their sequences come before the last one's, so once it's committed, they are too,
+/// and their results are then checked by looking them up.
+async fn push_batch(
+    machine: &Accumulator,
+    provider: &JsonRpcProvider,
+    signer: &mut Confirming<Wallet>,
+    batch: Vec<Bytes>,
+    options: &PushOptions,
+    max_retries: u32,
+    txs: &mut Vec<Hash>,
+) -> anyhow::Result<()> {
+    let commit = matches!(options.broadcast_mode, TxBroadcastMode::Commit);
+    let pipelined = PushOptions {
+        broadcast_mode: TxBroadcastMode::Sync,
+        ..options.clone()
+    };
+    let last = batch.len() - 1;
+    for (i, payload) in batch.into_iter().enumerate() {
+        let options = if commit && i < last {
+            &pipelined
+        } else {
+            options
+        };
+        let tx = push_with_retry(machine, provider, signer, payload, options, max_retries).await?;
+        txs.push(tx.hash);
+    }
+    if commit {
+        for hash in &txs[..last] {
+            check_committed(provider, *hash).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Check that a pipelined push was committed without an error.
+async fn check_committed(provider: &JsonRpcProvider, hash: Hash) -> anyhow::Result<()> {
+    let tx = provider
+        .tx_by_hash(hash)
+        .await
+        .with_context(|| format!("failed to check the result of push {}", hash))?
+        .ok_or_else(|| anyhow!("push {} was not committed", hash))?;
+    match tx.result {
+        Some(result) if result.code.is_err() => Err(TxError {
+            code: result.code.value(),
+            message: result.log,
+        }
+        .into()),
+        _ => Ok(()),
+    }
+}
+
+/// Push a payload, retrying pushes that failed before they were broadcast up to
+/// `max_retries` times.
+///
+/// A push is only retried if it failed before it was signed, which leaves the signer's
+/// sequence unchanged, or if it was rejected by the node's mempool check in sync mode.
+/// In the latter case, the sequence was already advanced, so it's re-initialized from the
+/// pending state to fill the gap.
+/// Other failures, e.g., a broadcast whose response was lost, may have landed,
+/// so retrying them could push the payload twice.
+async fn push_with_retry(
+    machine: &Accumulator,
+    provider: &JsonRpcProvider,
+    signer: &mut Confirming<Wallet>,
+    payload: Bytes,
+    options: &PushOptions,
+    max_retries: u32,
+) -> anyhow::Result<TxReceipt<PushReturn>> {
+    let mut attempt = 0;
+    loop {
+        let sequence = signer.inner().nonce_manager().next().await;
+        let err = match machine
+            .push(provider, signer, payload.clone(), options.clone())
+            .await
+        {
+            Ok(tx) => return Ok(tx),
+            Err(e) => e,
+        };
+        // A declined push wasn't signed, but asking again would just repeat the prompt
+        if err.downcast_ref::<NotConfirmed>().is_some() {
+            return Err(err);
+        }
+        let signed = signer.inner().nonce_manager().next().await != sequence;
+        let rejected = matches!(options.broadcast_mode, TxBroadcastMode::Sync)
+            && err.downcast_ref::<TxError>().is_some();
+        if signed && !rejected {
+            return Err(err.context("push may have been broadcast, so it was not retried"));
+        }
+        if attempt >= max_retries {
+            return Err(err);
+        }
+        attempt += 1;
+        sleep(FOLLOW_PUSH_RETRY_DELAY * attempt).await;
+        if signed {
+            signer.inner_mut().init_sequence(provider).await?;
         }
     }
 }
@@ -2,35 +2,48 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use std::path::PathBuf;
+use std::time::Duration;
 
 use anyhow::anyhow;
-use clap::{Args, Parser, Subcommand};
-use fendermint_actor_machine::WriteAccess;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use clap_complete::engine::ArgValueCompleter;
 use fendermint_crypto::SecretKey;
 use fendermint_vm_message::query::FvmQueryHeight;
-use fvm_shared::address::Address;
+use fvm_shared::econ::TokenAmount;
 use serde_json::{json, Value};
+use tendermint::Time;
 use tendermint_rpc::Url;
 use tokio::fs::File;
 use tokio::io::{self};
 use std::collections::HashMap;
 
 use adm_provider::{
-    json_rpc::JsonRpcProvider,
-    util::{parse_address, parse_query_height, parse_metadata},
+    query::QueryProvider,
+    response::Cid,
+    util::{
+        parse_cid, parse_metadata, parse_query_height, parse_time, parse_token_amount_from_atto,
+    },
+};
+use adm_sdk::machine::objectstore::{
+    AddOptions, Checksum as SdkChecksum, CopyOptions, DeleteOptions, GetByCidOptions, GetOptions,
+    GetPrefixOptions, Key, ObjectCache, Policy, PolicyOptions, RestoreOptions,
+    DEFAULT_UPLOAD_BUFFER_SIZE,
 };
-use adm_sdk::machine::objectstore::{AddOptions, DeleteOptions, GetOptions};
 use adm_sdk::{
     machine::{
-        objectstore::{ObjectStore, QueryOptions},
-        Machine,
+        objectstore::{Cursor, ObjectStore, QueryOptions},
+        DeployOptions, Machine, WriteAccess,
     },
+    name::{parse_address_or_name, AddressOrName},
     TxParams,
 };
-use adm_signer::{key::parse_secret_key, AccountKind, Void, Wallet};
+use adm_signer::{key::parse_secret_key, AccountKind, Signer, Void, Wallet};
 
+use crate::complete::{cache_keys, complete_address, complete_key};
+use crate::name::resolve_address;
 use crate::{
-    get_address, get_rpc_url, get_subnet_id, print_json, AddressArgs, BroadcastMode, Cli, TxArgs,
+    confirm_signer, get_address, get_provider, get_subnet_id, print_json, AddressArgs,
+    BroadcastMode, Cli, TxArgs,
 };
 
 #[derive(Clone, Debug, Args)]
@@ -50,10 +63,28 @@ enum ObjectstoreCommands {
     Add(ObjectstorePutArgs),
     /// Delete an object.
     Delete(ObjectstoreDeleteArgs),
+    /// Restore a soft-deleted object.
+    Restore(ObjectstoreRestoreArgs),
+    /// Permanently delete all soft-deleted objects.
+    Purge(ObjectstorePurgeArgs),
     /// Get an object.
     Get(ObjectstoreGetArgs),
+    /// Write an object to stdout without progress output.
+    Cat(ObjectstoreCatArgs),
+    /// Write the first bytes of an object to stdout.
+    Head(ObjectstoreHeadArgs),
+    /// Get object content by CID from IPFS gateways.
+    Fetch(ObjectstoreFetchArgs),
     /// Query for objects.
     Query(ObjectstoreQueryArgs),
+    /// Generate a manifest of all objects at a given height.
+    Manifest(ObjectstoreManifestArgs),
+    /// Verify the uploader's attestation of an object.
+    Verify(ObjectstoreVerifyArgs),
+    /// Copy objects to another object store without re-uploading them.
+    Copy(ObjectstoreCopyArgs),
+    /// Get or set the object store's quota and retention policy.
+    Policy(ObjectstorePolicyArgs),
 }
 
 #[derive(Clone, Debug, Args)]
@@ -64,6 +95,10 @@ struct ObjectstoreCreateArgs {
     /// Allow public write access to the object store.
     #[arg(long, default_value_t = false)]
     public_write: bool,
+    /// Broadcast mode for the transaction.
+    /// With "async" or "sync", the predicted address is printed before the object store exists.
+    #[arg(short, long, value_enum, env, default_value_t = BroadcastMode::Commit)]
+    broadcast_mode: BroadcastMode,
     #[command(flatten)]
     tx_args: TxArgs,
 }
@@ -76,9 +111,12 @@ struct ObjectstorePutArgs {
     /// Node Object API URL.
     #[arg(long, env)]
     object_api_url: Option<Url>,
-    /// Object store machine address.
-    #[arg(short, long, value_parser = parse_address)]
-    address: Address,
+    /// Bearer token for Object API authentication.
+    #[arg(long, env)]
+    object_api_auth_token: Option<String>,
+    /// Object store machine address or name.
+    #[arg(short, long, value_parser = parse_address_or_name, add = ArgValueCompleter::new(complete_address))]
+    address: AddressOrName,
     /// Key of the object to upload.
     #[arg(short, long)]
     key: String,
@@ -95,6 +133,42 @@ struct ObjectstorePutArgs {
     tx_args: TxArgs,
     #[arg(short, long, value_parser = parse_metadata)]
     metadata: Vec<(String, String)>,
+    /// Estimate the cost of adding the object and check the balance covers it, without uploading.
+    /// The gas fee cap is used as the gas price, so the gas cost is an upper bound.
+    #[arg(long)]
+    estimate_only: bool,
+    /// Storage fee per byte in attoFIL, for subnets that charge by object size.
+    /// 1FIL = 10**18 attoFIL.
+    #[arg(long, value_parser = parse_token_amount_from_atto, default_value = "0")]
+    fee_per_byte: TokenAmount,
+    /// How many times to retry the upload if it fails with a transient error.
+    #[arg(long, default_value_t = 3)]
+    upload_retries: u32,
+    /// Maximum number of bytes read ahead of the upload and held in memory.
+    #[arg(long, default_value_t = DEFAULT_UPLOAD_BUFFER_SIZE)]
+    upload_buffer_size: usize,
+    /// Sign an attestation of the object's key, CID, size, and upload time,
+    /// and store it in the object metadata.
+    #[arg(long)]
+    attest: bool,
+    /// Compute a checksum of the object and store it in the object metadata,
+    /// e.g., "sha256" under "adm:sha256".
+    #[arg(long, value_enum)]
+    checksum: Option<Checksum>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Checksum {
+    /// Hex-encoded SHA-256 digest.
+    Sha256,
+}
+
+impl Checksum {
+    fn get(&self) -> SdkChecksum {
+        match self {
+            Checksum::Sha256 => SdkChecksum::Sha256,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Parser)]
@@ -102,10 +176,33 @@ struct ObjectstoreDeleteArgs {
     /// Wallet private key (ECDSA, secp256k1) for signing transactions.
     #[arg(short, long, env, value_parser = parse_secret_key)]
     private_key: SecretKey,
-    /// Object store machine address.
-    #[arg(short, long, value_parser = parse_address)]
-    address: Address,
+    /// Object store machine address or name.
+    #[arg(short, long, value_parser = parse_address_or_name, add = ArgValueCompleter::new(complete_address))]
+    address: AddressOrName,
     /// Key of the object to delete.
+    #[arg(add = ArgValueCompleter::new(complete_key))]
+    key: String,
+    /// Mark the object as deleted instead of removing it.
+    /// Soft-deleted objects can be restored or purged later.
+    #[arg(long, default_value_t = false)]
+    soft: bool,
+    /// Broadcast mode for the transaction.
+    #[arg(short, long, value_enum, env, default_value_t = BroadcastMode::Commit)]
+    broadcast_mode: BroadcastMode,
+    #[command(flatten)]
+    tx_args: TxArgs,
+}
+
+#[derive(Clone, Debug, Parser)]
+struct ObjectstoreRestoreArgs {
+    /// Wallet private key (ECDSA, secp256k1) for signing transactions.
+    #[arg(short, long, env, value_parser = parse_secret_key)]
+    private_key: SecretKey,
+    /// Object store machine address or name.
+    #[arg(short, long, value_parser = parse_address_or_name, add = ArgValueCompleter::new(complete_address))]
+    address: AddressOrName,
+    /// Key of the object to restore.
+    #[arg(add = ArgValueCompleter::new(complete_key))]
     key: String,
     /// Broadcast mode for the transaction.
     #[arg(short, long, value_enum, env, default_value_t = BroadcastMode::Commit)]
@@ -114,11 +211,26 @@ struct ObjectstoreDeleteArgs {
     tx_args: TxArgs,
 }
 
+#[derive(Clone, Debug, Parser)]
+struct ObjectstorePurgeArgs {
+    /// Wallet private key (ECDSA, secp256k1) for signing transactions.
+    #[arg(short, long, env, value_parser = parse_secret_key)]
+    private_key: SecretKey,
+    /// Object store machine address or name.
+    #[arg(short, long, value_parser = parse_address_or_name, add = ArgValueCompleter::new(complete_address))]
+    address: AddressOrName,
+    /// Broadcast mode for the transactions.
+    #[arg(short, long, value_enum, env, default_value_t = BroadcastMode::Commit)]
+    broadcast_mode: BroadcastMode,
+    #[command(flatten)]
+    tx_args: TxArgs,
+}
+
 #[derive(Clone, Debug, Args)]
 struct ObjectstoreAddressArgs {
-    /// Object store machine address.
-    #[arg(short, long, value_parser = parse_address)]
-    address: Address,
+    /// Object store machine address or name.
+    #[arg(short, long, value_parser = parse_address_or_name, add = ArgValueCompleter::new(complete_address))]
+    address: AddressOrName,
     /// Query block height.
     /// Possible values:
     /// "committed" (latest committed block),
@@ -133,11 +245,22 @@ struct ObjectstoreGetArgs {
     /// Node Object API URL.
     #[arg(long, env)]
     object_api_url: Option<Url>,
-    /// Object store machine address.
-    #[arg(short, long, value_parser = parse_address)]
-    address: Address,
+    /// Bearer token for Object API authentication.
+    #[arg(long, env)]
+    object_api_auth_token: Option<String>,
+    /// Object store machine address or name.
+    #[arg(short, long, value_parser = parse_address_or_name, add = ArgValueCompleter::new(complete_address))]
+    address: AddressOrName,
     /// Key of the object to get.
-    key: String,
+    #[arg(required_unless_present = "prefix", add = ArgValueCompleter::new(complete_key))]
+    key: Option<String>,
+    /// Get all objects with this key prefix.
+    /// Requires an output directory.
+    #[arg(long, conflicts_with_all = ["key", "range", "wait"])]
+    prefix: Option<String>,
+    /// Number of objects to download in parallel when getting by prefix.
+    #[arg(long, default_value_t = 8)]
+    concurrency: usize,
     /// Range of bytes to get from the object.
     /// Format: "start-end" (inclusive).
     /// Example: "0-99" (first 100 bytes).
@@ -150,13 +273,105 @@ struct ObjectstoreGetArgs {
     /// or a specific block height, e.g., "123".
     #[arg(long, value_parser = parse_query_height, default_value = "committed")]
     height: FvmQueryHeight,
+    /// Wait for the object to be resolved by the network before getting it.
+    #[arg(short, long)]
+    wait: bool,
+    /// Maximum time to wait for the object to be resolved.
+    #[arg(long, value_parser = humantime::parse_duration, default_value = "5m")]
+    wait_timeout: Duration,
+    /// Write the object to a file instead of stdout.
+    /// When getting by prefix, this is the output directory.
+    #[arg(short, long, conflicts_with = "output_dir")]
+    output: Option<PathBuf>,
+    /// Write the object to a file under this directory, using the key as its relative path.
+    #[arg(long)]
+    output_dir: Option<PathBuf>,
+    /// Overwrite the output file if it already exists.
+    /// When getting by prefix, overwrite files that an earlier download didn't create.
+    #[arg(long)]
+    overwrite: bool,
+    /// Local cache directory for object data, keyed by CID.
+    /// Objects whose CID on chain is cached are read from it instead of downloaded.
+    #[arg(long, env = "ADM_CACHE_DIR")]
+    cache_dir: Option<PathBuf>,
+    /// Check the object against the checksum in its metadata, e.g., "adm:sha256".
+    /// Fails if the object has no checksum or it doesn't match.
+    #[arg(long, conflicts_with_all = ["prefix", "range"])]
+    verify_checksum: bool,
+}
+
+#[derive(Clone, Debug, Args)]
+struct ObjectstoreCatArgs {
+    /// Node Object API URL.
+    #[arg(long, env)]
+    object_api_url: Option<Url>,
+    /// Bearer token for Object API authentication.
+    #[arg(long, env)]
+    object_api_auth_token: Option<String>,
+    /// Object store machine address or name.
+    #[arg(short, long, value_parser = parse_address_or_name, add = ArgValueCompleter::new(complete_address))]
+    address: AddressOrName,
+    /// Key of the object to get.
+    #[arg(add = ArgValueCompleter::new(complete_key))]
+    key: String,
+    /// Query block height.
+    /// Possible values:
+    /// "committed" (latest committed block),
+    /// "pending" (consider pending state changes),
+    /// or a specific block height, e.g., "123".
+    #[arg(long, value_parser = parse_query_height, default_value = "committed")]
+    height: FvmQueryHeight,
+    /// Local cache directory for object data, keyed by CID.
+    /// Objects whose CID on chain is cached are read from it instead of downloaded.
+    #[arg(long, env = "ADM_CACHE_DIR")]
+    cache_dir: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, Args)]
+struct ObjectstoreHeadArgs {
+    /// Node Object API URL.
+    #[arg(long, env)]
+    object_api_url: Option<Url>,
+    /// Bearer token for Object API authentication.
+    #[arg(long, env)]
+    object_api_auth_token: Option<String>,
+    /// Object store machine address or name.
+    #[arg(short, long, value_parser = parse_address_or_name, add = ArgValueCompleter::new(complete_address))]
+    address: AddressOrName,
+    /// Key of the object to get.
+    #[arg(add = ArgValueCompleter::new(complete_key))]
+    key: String,
+    /// Number of bytes to get from the start of the object.
+    #[arg(short = 'n', long, default_value_t = 1024, value_parser = clap::value_parser!(u64).range(1..))]
+    bytes: u64,
+    /// Query block height.
+    /// Possible values:
+    /// "committed" (latest committed block),
+    /// "pending" (consider pending state changes),
+    /// or a specific block height, e.g., "123".
+    #[arg(long, value_parser = parse_query_height, default_value = "committed")]
+    height: FvmQueryHeight,
+}
+
+#[derive(Clone, Debug, Args)]
+struct ObjectstoreFetchArgs {
+    /// CID of the content to get, e.g., "bafy..." or "ipfs://bafy...".
+    #[arg(value_parser = parse_cid)]
+    cid: Cid,
+    /// IPFS gateway URL to get the content from, e.g., "https://ipfs.io".
+    /// Can be repeated to try several gateways in order.
+    #[arg(long = "gateway", required = true)]
+    gateways: Vec<String>,
+    /// Write the content to a file instead of stdout.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
 }
 
 #[derive(Clone, Debug, Args)]
 struct ObjectstoreQueryArgs {
-    /// Object store machine address.
-    #[arg(short, long, value_parser = parse_address)]
-    address: Address,
+    /// Object store machine address or name.
+    #[arg(short, long, value_parser = parse_address_or_name, add = ArgValueCompleter::new(complete_address))]
+    address: AddressOrName,
     /// The prefix to filter objects by.
     #[arg(short, long, default_value = "")]
     prefix: String,
@@ -176,6 +391,126 @@ struct ObjectstoreQueryArgs {
     /// or a specific block height, e.g., "123".
     #[arg(long, value_parser = parse_query_height, default_value = "committed")]
     height: FvmQueryHeight,
+    /// Query the last block at or before a time instead of a height,
+    /// as an RFC 3339 timestamp, e.g., "2024-05-01T00:00:00Z".
+    #[arg(long, value_parser = parse_time, conflicts_with = "height")]
+    at: Option<Time>,
+    /// Resume listing from the "next_cursor" of a previous query.
+    /// The offset and height are taken from the cursor.
+    #[arg(long, conflicts_with_all = ["offset", "height", "at"])]
+    cursor: Option<Cursor>,
+}
+
+#[derive(Clone, Debug, Args)]
+struct ObjectstoreManifestArgs {
+    /// Wallet private key (ECDSA, secp256k1) for signing the manifest.
+    /// If not present, the manifest is not signed.
+    #[arg(short, long, env, value_parser = parse_secret_key)]
+    private_key: Option<SecretKey>,
+    /// Object store machine address or name.
+    #[arg(short, long, value_parser = parse_address_or_name, add = ArgValueCompleter::new(complete_address))]
+    address: AddressOrName,
+    /// Query block height.
+    /// Possible values:
+    /// "committed" (latest committed block),
+    /// "pending" (consider pending state changes),
+    /// or a specific block height, e.g., "123".
+    #[arg(long, value_parser = parse_query_height, default_value = "committed")]
+    height: FvmQueryHeight,
+}
+
+#[derive(Clone, Debug, Args)]
+struct ObjectstoreVerifyArgs {
+    /// Object store machine address or name.
+    #[arg(short, long, value_parser = parse_address_or_name, add = ArgValueCompleter::new(complete_address))]
+    address: AddressOrName,
+    /// Key of the object to verify.
+    #[arg(add = ArgValueCompleter::new(complete_key))]
+    key: String,
+    /// Query block height.
+    /// Possible values:
+    /// "committed" (latest committed block),
+    /// "pending" (consider pending state changes),
+    /// or a specific block height, e.g., "123".
+    #[arg(long, value_parser = parse_query_height, default_value = "committed")]
+    height: FvmQueryHeight,
+}
+
+#[derive(Clone, Debug, Args)]
+struct ObjectstoreCopyArgs {
+    /// Wallet private key (ECDSA, secp256k1) for signing transactions.
+    #[arg(short, long, env, value_parser = parse_secret_key)]
+    private_key: SecretKey,
+    /// Source object store machine address or name.
+    #[arg(short, long, value_parser = parse_address_or_name, add = ArgValueCompleter::new(complete_address))]
+    address: AddressOrName,
+    /// Target object store machine address or name.
+    #[arg(long, value_parser = parse_address_or_name, add = ArgValueCompleter::new(complete_address))]
+    to: AddressOrName,
+    /// The key prefix of objects to copy. All objects are copied by default.
+    #[arg(long, default_value = "")]
+    prefix: String,
+    /// Overwrite objects that already exist in the target object store.
+    #[arg(short, long)]
+    overwrite: bool,
+    /// Query block height at which the source objects are listed.
+    /// Possible values:
+    /// "committed" (latest committed block),
+    /// "pending" (consider pending state changes),
+    /// or a specific block height, e.g., "123".
+    #[arg(long, value_parser = parse_query_height, default_value = "committed")]
+    height: FvmQueryHeight,
+    /// Broadcast mode for the transactions.
+    #[arg(short, long, value_enum, env, default_value_t = BroadcastMode::Commit)]
+    broadcast_mode: BroadcastMode,
+    #[command(flatten)]
+    tx_args: TxArgs,
+}
+
+#[derive(Clone, Debug, Args)]
+struct ObjectstorePolicyArgs {
+    #[command(subcommand)]
+    command: ObjectstorePolicyCommands,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+enum ObjectstorePolicyCommands {
+    /// Set the policy, replacing any existing policy.
+    Set(ObjectstorePolicySetArgs),
+    /// Get the policy.
+    Get(ObjectstoreAddressArgs),
+}
+
+#[derive(Clone, Debug, Args)]
+struct ObjectstorePolicySetArgs {
+    /// Wallet private key (ECDSA, secp256k1) for signing transactions.
+    /// Only the object store owner can set its policy.
+    #[arg(short, long, env, value_parser = parse_secret_key)]
+    private_key: SecretKey,
+    /// Node Object API URL.
+    #[arg(long, env)]
+    object_api_url: Option<Url>,
+    /// Bearer token for Object API authentication.
+    #[arg(long, env)]
+    object_api_auth_token: Option<String>,
+    /// Object store machine address or name.
+    #[arg(short, long, value_parser = parse_address_or_name, add = ArgValueCompleter::new(complete_address))]
+    address: AddressOrName,
+    /// Maximum number of objects.
+    #[arg(long)]
+    max_objects: Option<u64>,
+    /// Maximum total size of all objects in bytes.
+    #[arg(long)]
+    max_bytes: Option<u64>,
+    /// How long objects are kept after they're added, e.g., "30d".
+    /// Expired objects are removed by purge.
+    #[arg(long, value_parser = humantime::parse_duration)]
+    ttl: Option<Duration>,
+    /// Broadcast mode for the transaction.
+    #[arg(short, long, value_enum, env, default_value_t = BroadcastMode::Commit)]
+    broadcast_mode: BroadcastMode,
+    #[command(flatten)]
+    tx_args: TxArgs,
 }
 
 /// Objectstore commmands handler.
@@ -184,7 +519,7 @@ pub async fn handle_objectstore(cli: Cli, args: &ObjectstoreArgs) -> anyhow::Res
 
     match &args.command {
         ObjectstoreCommands::Create(args) => {
-            let provider = JsonRpcProvider::new_http(get_rpc_url(&cli)?, None, None)?;
+            let provider = get_provider(&cli, None)?;
 
             let write_access = if args.public_write {
                 WriteAccess::Public
@@ -194,21 +529,26 @@ pub async fn handle_objectstore(cli: Cli, args: &ObjectstoreArgs) -> anyhow::Res
             let TxParams {
                 sequence,
                 gas_params,
-            } = args.tx_args.to_tx_params();
+            } = args.tx_args.to_tx_params(cli.network.get());
 
             let mut signer =
                 Wallet::new_secp256k1(args.private_key.clone(), AccountKind::Ethereum, subnet_id)?;
             signer.set_sequence(sequence, &provider).await?;
+            let mut signer = confirm_signer(&cli, signer);
 
-            let (store, tx) =
-                ObjectStore::new(&provider, &mut signer, write_access, gas_params).await?;
+            let options = DeployOptions {
+                write_access,
+                broadcast_mode: args.broadcast_mode.get(),
+                gas_params,
+            };
+            let (store, tx) = ObjectStore::deploy(&provider, &mut signer, options).await?;
 
             print_json(&json!({"address": store.address().to_string(), "tx": &tx}))
         }
         ObjectstoreCommands::List(args) => {
-            let provider = JsonRpcProvider::new_http(get_rpc_url(&cli)?, None, None)?;
+            let provider = get_provider(&cli, None)?;
 
-            let address = get_address(args.clone(), &subnet_id)?;
+            let address = get_address(&cli, args.clone(), &subnet_id)?;
             let metadata = ObjectStore::list(&provider, &Void::new(address), args.height).await?;
 
             let metadata = metadata
@@ -219,18 +559,58 @@ pub async fn handle_objectstore(cli: Cli, args: &ObjectstoreArgs) -> anyhow::Res
             print_json(&metadata)
         }
         ObjectstoreCommands::Add(args) => {
+            if args.estimate_only {
+                let provider = get_provider(&cli, None)?;
+                let TxParams { gas_params, .. } = args.tx_args.to_tx_params(cli.network.get());
+                let signer = Wallet::new_secp256k1(
+                    args.private_key.clone(),
+                    AccountKind::Ethereum,
+                    subnet_id.clone(),
+                )?;
+                let md = tokio::fs::metadata(&args.input).await?;
+                if !md.is_file() {
+                    return Err(anyhow!("input must be a file"));
+                }
+
+                let address = resolve_address(&cli, &provider, &args.address).await?;
+                let machine = ObjectStore::attach(address);
+                let estimate = machine
+                    .estimate_add_cost(
+                        &provider,
+                        signer.address(),
+                        &args.key,
+                        md.len() as usize,
+                        &gas_params.gas_fee_cap,
+                        &args.fee_per_byte,
+                    )
+                    .await?;
+
+                return print_json(&json!({
+                    "size": md.len(),
+                    "gas_limit": estimate.gas_limit,
+                    "gas_price": gas_params.gas_fee_cap.to_string(),
+                    "gas_cost": estimate.gas_cost.to_string(),
+                    "storage_cost": estimate.storage_cost.to_string(),
+                    "total": estimate.total.to_string(),
+                    "balance": estimate.balance.to_string(),
+                    "sufficient": estimate.is_sufficient(),
+                }));
+            }
+
             let object_api_url = args
                 .object_api_url
                 .clone()
                 .unwrap_or(cli.network.get().object_api_url()?);
-            let provider =
-                JsonRpcProvider::new_http(get_rpc_url(&cli)?, None, Some(object_api_url))?;
+            let provider = get_provider(&cli, Some(object_api_url))?
+                .with_object_auth_token(args.object_api_auth_token.clone())
+                .with_negotiated_object_api()
+                .await?;
 
             let broadcast_mode = args.broadcast_mode.get();
             let TxParams {
                 sequence,
                 gas_params,
-            } = args.tx_args.to_tx_params();
+            } = args.tx_args.to_tx_params(cli.network.get());
             let metadata: HashMap<String, String> = args.metadata.clone().into_iter().collect();
 
             let mut signer = Wallet::new_secp256k1(
@@ -239,6 +619,7 @@ pub async fn handle_objectstore(cli: Cli, args: &ObjectstoreArgs) -> anyhow::Res
                 subnet_id.clone(),
             )?;
             signer.set_sequence(sequence, &provider).await?;
+            let mut signer = confirm_signer(&cli, signer);
 
             let file = File::open(&args.input).await?;
             let md = file.metadata().await?;
@@ -246,7 +627,8 @@ pub async fn handle_objectstore(cli: Cli, args: &ObjectstoreArgs) -> anyhow::Res
                 return Err(anyhow!("input must be a file"));
             }
 
-            let machine = ObjectStore::attach(args.address);
+            let address = resolve_address(&cli, &provider, &args.address).await?;
+            let machine = ObjectStore::attach(address);
             let tx = machine
                 .add(
                     &provider,
@@ -258,7 +640,14 @@ pub async fn handle_objectstore(cli: Cli, args: &ObjectstoreArgs) -> anyhow::Res
                         broadcast_mode,
                         gas_params,
                         show_progress: !cli.quiet,
+                        progress_format: cli.progress_format.get(),
                         metadata,
+                        auth_token: None,
+                        upload_retries: args.upload_retries,
+                        upload_buffer_size: args.upload_buffer_size,
+                        attest: args.attest,
+                        checksum: args.checksum.map(|c| c.get()),
+                        cancel: Default::default(),
                     },
                 )
                 .await?;
@@ -266,13 +655,13 @@ pub async fn handle_objectstore(cli: Cli, args: &ObjectstoreArgs) -> anyhow::Res
             print_json(&tx)
         }
         ObjectstoreCommands::Delete(args) => {
-            let provider = JsonRpcProvider::new_http(get_rpc_url(&cli)?, None, None)?;
+            let provider = get_provider(&cli, None)?;
 
             let broadcast_mode = args.broadcast_mode.get();
             let TxParams {
                 sequence,
                 gas_params,
-            } = args.tx_args.to_tx_params();
+            } = args.tx_args.to_tx_params(cli.network.get());
 
             let mut signer = Wallet::new_secp256k1(
                 args.private_key.clone(),
@@ -280,8 +669,10 @@ pub async fn handle_objectstore(cli: Cli, args: &ObjectstoreArgs) -> anyhow::Res
                 subnet_id.clone(),
             )?;
             signer.set_sequence(sequence, &provider).await?;
+            let mut signer = confirm_signer(&cli, signer);
 
-            let machine = ObjectStore::attach(args.address);
+            let address = resolve_address(&cli, &provider, &args.address).await?;
+            let machine = ObjectStore::attach(address);
             let tx = machine
                 .delete(
                     &provider,
@@ -290,57 +681,260 @@ pub async fn handle_objectstore(cli: Cli, args: &ObjectstoreArgs) -> anyhow::Res
                     DeleteOptions {
                         broadcast_mode,
                         gas_params,
+                        soft: args.soft,
+                        cancel: Default::default(),
                     },
                 )
                 .await?;
 
             print_json(&tx)
         }
+        ObjectstoreCommands::Restore(args) => {
+            let provider = get_provider(&cli, None)?;
+
+            let broadcast_mode = args.broadcast_mode.get();
+            let TxParams {
+                sequence,
+                gas_params,
+            } = args.tx_args.to_tx_params(cli.network.get());
+
+            let mut signer = Wallet::new_secp256k1(
+                args.private_key.clone(),
+                AccountKind::Ethereum,
+                subnet_id.clone(),
+            )?;
+            signer.set_sequence(sequence, &provider).await?;
+            let mut signer = confirm_signer(&cli, signer);
+
+            let address = resolve_address(&cli, &provider, &args.address).await?;
+            let machine = ObjectStore::attach(address);
+            let tx = machine
+                .restore(
+                    &provider,
+                    &mut signer,
+                    &args.key,
+                    RestoreOptions {
+                        broadcast_mode,
+                        gas_params,
+                        cancel: Default::default(),
+                    },
+                )
+                .await?;
+
+            print_json(&tx)
+        }
+        ObjectstoreCommands::Purge(args) => {
+            let provider = get_provider(&cli, None)?;
+
+            let broadcast_mode = args.broadcast_mode.get();
+            let TxParams {
+                sequence,
+                gas_params,
+            } = args.tx_args.to_tx_params(cli.network.get());
+
+            let mut signer = Wallet::new_secp256k1(
+                args.private_key.clone(),
+                AccountKind::Ethereum,
+                subnet_id.clone(),
+            )?;
+            signer.set_sequence(sequence, &provider).await?;
+            let mut signer = confirm_signer(&cli, signer);
+
+            let address = resolve_address(&cli, &provider, &args.address).await?;
+            let machine = ObjectStore::attach(address);
+            let keys = machine
+                .purge(
+                    &provider,
+                    &mut signer,
+                    DeleteOptions {
+                        broadcast_mode,
+                        gas_params,
+                        soft: false,
+                        cancel: Default::default(),
+                    },
+                )
+                .await?;
+
+            print_json(&json!({"purged": keys}))
+        }
         ObjectstoreCommands::Get(args) => {
             let object_api_url = args
                 .object_api_url
                 .clone()
                 .unwrap_or(cli.network.get().object_api_url()?);
-            let provider =
-                JsonRpcProvider::new_http(get_rpc_url(&cli)?, None, Some(object_api_url))?;
+            let provider = get_provider(&cli, Some(object_api_url))?
+                .with_object_auth_token(args.object_api_auth_token.clone())
+                .with_negotiated_object_api()
+                .await?;
+
+            let address = resolve_address(&cli, &provider, &args.address).await?;
+            let machine = ObjectStore::attach(address);
+            if let Some(prefix) = &args.prefix {
+                let dest_dir = args
+                    .output
+                    .as_ref()
+                    .or(args.output_dir.as_ref())
+                    .ok_or_else(|| anyhow!("--prefix requires --output or --output-dir"))?;
+                let result = machine
+                    .get_prefix(
+                        &provider,
+                        prefix,
+                        dest_dir,
+                        args.concurrency,
+                        GetPrefixOptions {
+                            height: args.height,
+                            show_progress: !cli.quiet,
+                            progress_format: cli.progress_format.get(),
+                            auth_token: None,
+                            cancel: Default::default(),
+                            cache: args.cache_dir.as_ref().map(ObjectCache::new),
+                            overwrite: args.overwrite,
+                        },
+                    )
+                    .await?;
+                return print_json(&result);
+            }
+
+            // Clap requires a key if no prefix is given
+            let key = args.key.as_deref().unwrap_or_default();
+            if args.wait {
+                machine
+                    .wait_resolved(&provider, key, args.wait_timeout)
+                    .await?;
+            }
+            let options = GetOptions {
+                range: args.range.clone(),
+                height: args.height,
+                show_progress: true,
+                progress_format: cli.progress_format.get(),
+                auth_token: None,
+                cancel: Default::default(),
+                cache: args.cache_dir.as_ref().map(ObjectCache::new),
+                verify_checksum: args.verify_checksum,
+            };
+            let output = match (&args.output, &args.output_dir) {
+                (Some(path), _) => Some(path.clone()),
+                (None, Some(dir)) => Some(Key::new(key)?.to_path(dir)?),
+                (None, None) => None,
+            };
+            match output {
+                Some(path) => {
+                    machine
+                        .get_to_file(&provider, key, path, args.overwrite, options)
+                        .await
+                }
+                None => machine.get(&provider, key, io::stdout(), options).await,
+            }
+        }
+        ObjectstoreCommands::Cat(args) => {
+            let object_api_url = args
+                .object_api_url
+                .clone()
+                .unwrap_or(cli.network.get().object_api_url()?);
+            let provider = get_provider(&cli, Some(object_api_url))?
+                .with_object_auth_token(args.object_api_auth_token.clone())
+                .with_negotiated_object_api()
+                .await?;
+
+            let address = resolve_address(&cli, &provider, &args.address).await?;
+            let machine = ObjectStore::attach(address);
+            machine
+                .get(
+                    &provider,
+                    &args.key,
+                    io::stdout(),
+                    GetOptions {
+                        range: None,
+                        height: args.height,
+                        show_progress: false,
+                        progress_format: Default::default(),
+                        auth_token: None,
+                        cancel: Default::default(),
+                        cache: args.cache_dir.as_ref().map(ObjectCache::new),
+                        verify_checksum: false,
+                    },
+                )
+                .await
+        }
+        ObjectstoreCommands::Head(args) => {
+            let object_api_url = args
+                .object_api_url
+                .clone()
+                .unwrap_or(cli.network.get().object_api_url()?);
+            let provider = get_provider(&cli, Some(object_api_url))?
+                .with_object_auth_token(args.object_api_auth_token.clone())
+                .with_negotiated_object_api()
+                .await?;
 
-            let machine = ObjectStore::attach(args.address);
+            let address = resolve_address(&cli, &provider, &args.address).await?;
+            let machine = ObjectStore::attach(address);
             machine
                 .get(
                     &provider,
                     &args.key,
                     io::stdout(),
                     GetOptions {
-                        range: args.range.clone(),
+                        range: Some(format!("0-{}", args.bytes - 1)),
                         height: args.height,
-                        show_progress: true,
+                        show_progress: false,
+                        progress_format: Default::default(),
+                        auth_token: None,
+                        cancel: Default::default(),
+                        cache: None,
+                        verify_checksum: false,
                     },
                 )
                 .await
         }
+        ObjectstoreCommands::Fetch(args) => {
+            let options = GetByCidOptions {
+                gateways: args.gateways.clone(),
+                ..Default::default()
+            };
+            match &args.output {
+                Some(path) => {
+                    let file = File::create(path).await?;
+                    let result = ObjectStore::get_by_cid(args.cid, file, options).await;
+                    if result.is_err() {
+                        // Don't leave partial or unverified content behind
+                        let _ = tokio::fs::remove_file(path).await;
+                    }
+                    result
+                }
+                None => ObjectStore::get_by_cid(args.cid, io::stdout(), options).await,
+            }
+        }
         ObjectstoreCommands::Query(args) => {
-            let provider = JsonRpcProvider::new_http(get_rpc_url(&cli)?, None, None)?;
+            let provider = get_provider(&cli, None)?;
 
-            let machine = ObjectStore::attach(args.address);
-            let list = machine
-                .query(
+            let address = resolve_address(&cli, &provider, &args.address).await?;
+            let height = match args.at {
+                Some(time) => FvmQueryHeight::Height(provider.height_at_time(time).await?),
+                None => args.height,
+            };
+            let machine = ObjectStore::attach(address);
+            let page = machine
+                .query_page(
                     &provider,
                     QueryOptions {
                         prefix: args.prefix.clone(),
                         delimiter: args.delimiter.clone(),
                         offset: args.offset,
                         limit: args.limit,
-                        height: args.height,
+                        height,
+                        cursor: args.cursor.clone(),
+                        cancel: Default::default(),
                     },
                 )
                 .await?;
+            let list = page.list;
 
             let objects = list
                 .objects
                 .iter()
                 .map(|(key_bytes, object)| {
                     let key = core::str::from_utf8(&key_bytes).unwrap_or_default().to_string();                    
-                    let cid = cid::Cid::try_from(object.cid.clone().0).unwrap_or_default();                    
+                    let cid = object.cid;                    
                     let value = json!({"cid": cid.to_string(), "resolved": object.resolved, "size": object.size, "metadata": object.metadata});
                     json!({"key": key, "value": value})
                 })
@@ -350,8 +944,137 @@ pub async fn handle_objectstore(cli: Cli, args: &ObjectstoreArgs) -> anyhow::Res
                 .iter()
                 .map(|v| Value::String(core::str::from_utf8(v).unwrap_or_default().to_string()))
                 .collect::<Vec<Value>>();
+            let keys = list
+                .objects
+                .iter()
+                .map(|(key, _)| key)
+                .chain(list.common_prefixes.iter())
+                .map(|key| String::from_utf8_lossy(key).into_owned())
+                .collect();
+            cache_keys(address, keys);
+
+            let next_cursor = page.next.map(|cursor| cursor.to_string());
+            print_json(
+                &json!({"objects": objects, "common_prefixes": common_prefixes, "next_cursor": next_cursor}),
+            )
+        }
+        ObjectstoreCommands::Manifest(args) => {
+            let provider = get_provider(&cli, None)?;
+
+            let address = resolve_address(&cli, &provider, &args.address).await?;
+            let machine = ObjectStore::attach(address);
+            let network = cli.network.get().address_network();
+            let mut manifest = machine.manifest(&provider, args.height, network).await?;
+            if let Some(sk) = args.private_key.clone() {
+                let signer = Wallet::new_secp256k1(sk, AccountKind::Ethereum, subnet_id)?;
+                manifest.sign(&signer)?;
+            }
 
-            print_json(&json!({"objects": objects, "common_prefixes": common_prefixes}))
+            print_json(&manifest)
         }
+        ObjectstoreCommands::Verify(args) => {
+            let provider = get_provider(&cli, None)?;
+
+            let address = resolve_address(&cli, &provider, &args.address).await?;
+            let machine = ObjectStore::attach(address);
+            let attestation = machine
+                .verify_attestation(&provider, &args.key, args.height)
+                .await?;
+
+            print_json(&attestation)
+        }
+        ObjectstoreCommands::Copy(args) => {
+            let provider = get_provider(&cli, None)?;
+
+            let TxParams {
+                sequence,
+                gas_params,
+            } = args.tx_args.to_tx_params(cli.network.get());
+
+            let mut signer = Wallet::new_secp256k1(
+                args.private_key.clone(),
+                AccountKind::Ethereum,
+                subnet_id.clone(),
+            )?;
+            signer.set_sequence(sequence, &provider).await?;
+            let mut signer = confirm_signer(&cli, signer);
+
+            let address = resolve_address(&cli, &provider, &args.address).await?;
+            let target = resolve_address(&cli, &provider, &args.to).await?;
+            let machine = ObjectStore::attach(address);
+            let copy = machine
+                .copy_to(
+                    &provider,
+                    &mut signer,
+                    &ObjectStore::attach(target),
+                    &args.prefix,
+                    CopyOptions {
+                        overwrite: args.overwrite,
+                        height: args.height,
+                        broadcast_mode: args.broadcast_mode.get(),
+                        gas_params,
+                        cancel: Default::default(),
+                    },
+                )
+                .await?;
+
+            print_json(&copy)
+        }
+        ObjectstoreCommands::Policy(args) => match &args.command {
+            ObjectstorePolicyCommands::Set(args) => {
+                let object_api_url = args
+                    .object_api_url
+                    .clone()
+                    .unwrap_or(cli.network.get().object_api_url()?);
+                let provider = get_provider(&cli, Some(object_api_url))?
+                    .with_object_auth_token(args.object_api_auth_token.clone())
+                    .with_negotiated_object_api()
+                    .await?;
+
+                let TxParams {
+                    sequence,
+                    gas_params,
+                } = args.tx_args.to_tx_params(cli.network.get());
+
+                let mut signer = Wallet::new_secp256k1(
+                    args.private_key.clone(),
+                    AccountKind::Ethereum,
+                    subnet_id.clone(),
+                )?;
+                signer.set_sequence(sequence, &provider).await?;
+                let mut signer = confirm_signer(&cli, signer);
+
+                let address = resolve_address(&cli, &provider, &args.address).await?;
+                let machine = ObjectStore::attach(address);
+                let policy = Policy {
+                    max_objects: args.max_objects,
+                    max_bytes: args.max_bytes,
+                    ttl: args.ttl.map(|ttl| ttl.as_secs()),
+                };
+                let tx = machine
+                    .set_policy(
+                        &provider,
+                        &mut signer,
+                        &policy,
+                        PolicyOptions {
+                            broadcast_mode: args.broadcast_mode.get(),
+                            gas_params,
+                            cancel: Default::default(),
+                        },
+                    )
+                    .await?;
+
+                print_json(&tx)
+            }
+            ObjectstorePolicyCommands::Get(args) => {
+                let provider = get_provider(&cli, None)?;
+
+                let address = resolve_address(&cli, &provider, &args.address).await?;
+                let machine = ObjectStore::attach(address);
+                let policy = machine.policy(&provider, args.height).await?;
+
+                print_json(&policy)
+            }
+        },
     }
 }
@@ -1,35 +1,82 @@
 // Copyright 2024 ADM Contributors
 // SPDX-License-Identifier: Apache-2.0, MIT
 
-use clap::{error::ErrorKind, Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use std::future::Future;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Mutex, OnceLock,
+};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context};
+use clap::{error::ErrorKind, Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use clap_complete::{engine::ArgValueCompleter, env::CompleteEnv};
 use fendermint_crypto::SecretKey;
 use fendermint_vm_message::query::FvmQueryHeight;
-use fvm_shared::{address::Address, econ::TokenAmount};
+use fvm_shared::{address::Address, econ::TokenAmount, MethodNum};
 use serde::Serialize;
 use stderrlog::Timestamp;
 use tendermint_rpc::Url;
 
 use adm_provider::{
+    gas::GasReport,
+    json_rpc::JsonRpcProvider,
     message::GasParams,
     tx::BroadcastMode as SDKBroadcastMode,
-    util::{parse_address, parse_query_height, parse_token_amount_from_atto},
+    util::{parse_address, parse_query_height, parse_token_amount_from_atto, TokenUnit},
+};
+use adm_sdk::{
+    name::{parse_address_or_name, AddressOrName},
+    network::Network as SdkNetwork,
+    progress::ProgressFormat as SdkProgressFormat,
+    TxParams,
+};
+use adm_signer::{
+    key::parse_secret_key, keystore::Keystore, AccountKind, Confirming, MessageReview, Signer,
+    SubnetID, Wallet,
 };
-use adm_sdk::{network::Network as SdkNetwork, TxParams};
-use adm_signer::{key::parse_secret_key, AccountKind, Signer, SubnetID, Wallet};
 
 use crate::account::{handle_account, AccountArgs};
+use crate::agent::{handle_agent, AgentArgs};
+use crate::chain::{handle_chain, ChainArgs};
+use crate::complete::complete_contact;
+use crate::contacts::{handle_contacts, resolve_contact, ContactsArgs};
+use crate::doctor::{handle_doctor, DoctorArgs};
+use crate::error::{config_error, report, ErrorFormat};
 use crate::machine::{
     accumulator::{handle_accumulator, AccumulatorArgs},
     handle_machine,
     objectstore::{handle_objectstore, ObjectstoreArgs},
     MachineArgs,
 };
+use crate::name::{handle_name, NameArgs};
+use crate::schema::{handle_schema, schema_arg, schema_path};
+use crate::subnet::{handle_subnet, SubnetArgs};
+use crate::tx::{handle_transaction, TransactionArgs};
+use crate::upgrade::{handle_upgrade, UpgradeArgs};
+use crate::validator::{handle_validator, ValidatorArgs};
 
 mod account;
+mod agent;
+mod chain;
+mod complete;
+mod contacts;
+mod doctor;
+mod error;
 mod machine;
+mod name;
+mod schema;
+mod subnet;
+mod tx;
+mod upgrade;
+mod validator;
 
 #[derive(Clone, Debug, Parser)]
 #[command(name = "adm", author, version, about, long_about = None)]
+#[command(arg = schema_arg())]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
@@ -48,6 +95,37 @@ struct Cli {
     /// Silence logging.
     #[arg(short, long, env, default_value_t = false)]
     quiet: bool,
+    /// Format of errors written to stderr.
+    /// Errors also set a stable exit code for their kind.
+    #[arg(long, env, value_enum, default_value_t = ErrorFormat::Text)]
+    error_format: ErrorFormat,
+    /// Local alias file that maps names to machine addresses.
+    /// Defaults to `$HOME/.adm/names.json`.
+    #[arg(long, env)]
+    names_file: Option<PathBuf>,
+    /// Object store machine used as a shared registry of names.
+    /// Machine addresses can be given as names registered here or in the local alias file.
+    #[arg(long, env, value_parser = parse_address)]
+    name_machine: Option<Address>,
+    /// Local address book that maps contact labels to account addresses.
+    /// Account addresses can be given as labels wherever an address is expected.
+    /// Defaults to `$HOME/.adm/contacts.json`.
+    #[arg(long, env)]
+    contacts_file: Option<PathBuf>,
+    /// Print a summary of gas used, fees paid, and payload sizes per operation
+    /// to stderr when the command exits.
+    #[arg(long, env, default_value_t = false)]
+    gas_report: bool,
+    /// Review each message on the terminal and confirm it before it's signed.
+    #[arg(long, env, default_value_t = false)]
+    interactive: bool,
+    /// Denomination of token amounts in output.
+    /// By default, amounts are in FIL, except for validator collateral, which is in attoFIL.
+    #[arg(long, env, value_enum)]
+    units: Option<Units>,
+    /// Format of progress output on stderr, e.g., for uploads and downloads.
+    #[arg(long, env, value_enum, default_value_t = ProgressFormat::Bars)]
+    progress_format: ProgressFormat,
 }
 
 #[derive(Clone, Debug, Subcommand)]
@@ -65,6 +143,29 @@ enum Commands {
     /// Accumulator related commands (alias: ac).
     #[clap(alias = "ac")]
     Accumulator(AccumulatorArgs),
+    /// Subnet related commands.
+    #[clap(alias = "subnets")]
+    Subnet(SubnetArgs),
+    /// Validator related commands.
+    #[clap(alias = "validators")]
+    Validator(ValidatorArgs),
+    /// Transaction utilities, e.g., decoding transactions.
+    #[clap(alias = "transaction")]
+    Tx(TransactionArgs),
+    /// Inspect blocks and committed transactions.
+    Chain(ChainArgs),
+    /// Check the CLI configuration and connectivity for common problems.
+    Doctor(DoctorArgs),
+    /// Register and resolve names for machine addresses.
+    #[clap(alias = "names")]
+    Name(NameArgs),
+    /// Manage labels for account addresses.
+    #[clap(alias = "contact")]
+    Contacts(ContactsArgs),
+    /// Watch directories and upload new or changed files to object stores on a schedule.
+    Agent(AgentArgs),
+    /// Upgrade the CLI to the latest release.
+    Upgrade(UpgradeArgs),
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -110,17 +211,54 @@ impl BroadcastMode {
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Units {
+    /// FIL.
+    Fil,
+    /// nanoFIL (10**-9 FIL).
+    Nanofil,
+    /// attoFIL (10**-18 FIL).
+    Attofil,
+}
+
+impl Units {
+    pub fn get(&self) -> TokenUnit {
+        match self {
+            Units::Fil => TokenUnit::Fil,
+            Units::Nanofil => TokenUnit::NanoFil,
+            Units::Attofil => TokenUnit::AttoFil,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum ProgressFormat {
+    /// Progress bars for terminals.
+    Bars,
+    /// Newline-delimited JSON progress events, e.g., for desktop apps that wrap the CLI.
+    Json,
+}
+
+impl ProgressFormat {
+    pub fn get(&self) -> SdkProgressFormat {
+        match self {
+            ProgressFormat::Bars => SdkProgressFormat::Bars,
+            ProgressFormat::Json => SdkProgressFormat::Json,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Args)]
 struct TxArgs {
-    /// Gas limit for the transaction.
+    /// Gas limit for the transaction. Defaults to the network preset.
     #[arg(long, env)]
     gas_limit: Option<u64>,
-    /// Maximum gas fee for the transaction in attoFIL.
-    /// 1FIL = 10**18 attoFIL.
+    /// Maximum gas fee for the transaction in attoFIL, or with a unit, e.g., "1 nanoFIL".
+    /// 1FIL = 10**18 attoFIL. Defaults to the network preset.
     #[arg(long, env, value_parser = parse_token_amount_from_atto)]
     gas_fee_cap: Option<TokenAmount>,
-    /// Gas premium for the transaction in attoFIL.
-    /// 1FIL = 10**18 attoFIL.
+    /// Gas premium for the transaction in attoFIL, or with a unit, e.g., "1 nanoFIL".
+    /// 1FIL = 10**18 attoFIL. Defaults to the network preset.
     #[arg(long, env, value_parser = parse_token_amount_from_atto)]
     gas_premium: Option<TokenAmount>,
     /// Sequence for the transaction.
@@ -130,13 +268,15 @@ struct TxArgs {
 
 impl TxArgs {
     /// Creates transaction params from tx related CLI arguments.
-    pub fn to_tx_params(&self) -> TxParams {
+    /// Gas params that aren't set fall back to the network defaults.
+    pub fn to_tx_params(&self, network: SdkNetwork) -> TxParams {
+        let defaults = network.default_gas_params();
         TxParams {
             sequence: self.sequence,
             gas_params: GasParams {
-                gas_limit: self.gas_limit.unwrap_or(fvm_shared::BLOCK_GAS_LIMIT),
-                gas_fee_cap: self.gas_fee_cap.clone().unwrap_or_default(),
-                gas_premium: self.gas_premium.clone().unwrap_or_default(),
+                gas_limit: self.gas_limit.unwrap_or(defaults.gas_limit),
+                gas_fee_cap: self.gas_fee_cap.clone().unwrap_or(defaults.gas_fee_cap),
+                gas_premium: self.gas_premium.clone().unwrap_or(defaults.gas_premium),
             },
         }
     }
@@ -147,9 +287,9 @@ struct AddressArgs {
     /// Wallet private key (ECDSA, secp256k1) for signing transactions.
     #[arg(short, long, env, value_parser = parse_secret_key)]
     private_key: Option<SecretKey>,
-    /// Account address. The signer address is used if no address is given.
-    #[arg(short, long, value_parser = parse_address)]
-    address: Option<Address>,
+    /// Account address or contact label. The signer address is used if no address is given.
+    #[arg(short, long, value_parser = parse_address_or_name, add = ArgValueCompleter::new(complete_contact))]
+    address: Option<AddressOrName>,
     /// Query block height.
     /// Possible values:
     /// "committed" (latest committed block),
@@ -159,9 +299,40 @@ struct AddressArgs {
     height: FvmQueryHeight,
 }
 
+#[derive(Clone, Debug, Args)]
+struct WatchArgs {
+    /// Re-run the query on an interval and print the result whenever it changes.
+    #[arg(short, long, default_value_t = false)]
+    watch: bool,
+    /// Interval between queries in watch mode.
+    #[arg(long, value_parser = humantime::parse_duration, default_value = "2s")]
+    interval: Duration,
+}
+
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
-    let cli = Cli::parse();
+async fn main() -> ExitCode {
+    // Respond to shell completion requests, e.g., from `source <(COMPLETE=bash adm)`
+    CompleteEnv::with_factory(Cli::command).complete();
+
+    // Print an output schema instead of running the command.
+    // This happens before the full parse, so the command's required arguments can be left out.
+    if let Some(path) = schema_path(Cli::command(), std::env::args_os()) {
+        return match handle_schema(&Cli::command(), &path) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => report(&e, ErrorFormat::Text),
+        };
+    }
+
+    let command = match default_private_key() {
+        Ok(Some(sk)) => with_default_private_key(Cli::command(), hex::encode(sk.serialize())),
+        Ok(None) => Cli::command(),
+        // Commands that don't sign shouldn't fail because of the keystore
+        Err(e) => {
+            eprintln!("warning: not using the keystore's default key: {:#}", e);
+            Cli::command()
+        }
+    };
+    let cli = Cli::from_arg_matches(&command.get_matches()).unwrap_or_else(|e| e.exit());
 
     stderrlog::new()
         .module(module_path!())
@@ -172,19 +343,89 @@ async fn main() -> anyhow::Result<()> {
         .unwrap();
 
     cli.network.get().init();
+    if cli.gas_report {
+        GAS_REPORT.get_or_init(|| new_gas_report(get_units(&cli, TokenUnit::Fil)));
+    }
 
-    match &cli.command.clone() {
+    let error_format = cli.error_format;
+    let result = match &cli.command.clone() {
         Commands::Account(args) => handle_account(cli, args).await,
         Commands::Objectstore(args) => handle_objectstore(cli, args).await,
         Commands::Accumulator(args) => handle_accumulator(cli, args).await,
         Commands::Machine(args) => handle_machine(cli, args).await,
+        Commands::Subnet(args) => handle_subnet(cli, args).await,
+        Commands::Validator(args) => handle_validator(cli, args).await,
+        Commands::Tx(args) => handle_transaction(cli, args).await,
+        Commands::Chain(args) => handle_chain(cli, args).await,
+        Commands::Doctor(args) => handle_doctor(cli, args).await,
+        Commands::Name(args) => handle_name(cli, args).await,
+        Commands::Contacts(args) => handle_contacts(cli, args).await,
+        Commands::Agent(args) => handle_agent(cli, args).await,
+        Commands::Upgrade(args) => handle_upgrade(cli, args).await,
+    };
+    // Report gas even if the command failed, since earlier transactions may have landed
+    if let Some(report) = GAS_REPORT.get() {
+        eprint!("{}", report);
+    }
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => report(&e, error_format),
     }
 }
 
+/// Returns the keystore path from the `ADM_KEYSTORE` environment variable,
+/// or `$HOME/.adm/keystore.json` by default.
+fn get_keystore_path() -> anyhow::Result<PathBuf> {
+    if let Ok(path) = std::env::var("ADM_KEYSTORE") {
+        return Ok(PathBuf::from(path));
+    }
+    let home = std::env::var("HOME").map_err(|_| anyhow!("failed to get home directory"))?;
+    Ok(PathBuf::from(home).join(".adm").join("keystore.json"))
+}
+
+/// Returns the keystore's default key, if there's a keystore and no private key is set in
+/// the environment.
+fn default_private_key() -> anyhow::Result<Option<SecretKey>> {
+    if std::env::var_os("PRIVATE_KEY").is_some() {
+        return Ok(None);
+    }
+    let path = match get_keystore_path() {
+        Ok(path) if path.exists() => path,
+        _ => return Ok(None),
+    };
+    Keystore::open(path)?.default_key()
+}
+
+/// Makes `key` the default of every `--private-key` argument, which is used if neither
+/// the argument nor `PRIVATE_KEY` is given.
+///
+/// The key is hidden from help output and never put in the environment,
+/// so it isn't inherited by child processes.
+fn with_default_private_key(mut command: clap::Command, key: String) -> clap::Command {
+    if command
+        .get_arguments()
+        .any(|arg| arg.get_id() == "private_key")
+    {
+        command = command.mut_arg("private_key", |arg| {
+            arg.required(false)
+                .default_value(key.clone())
+                .hide_default_value(true)
+        });
+    }
+    let names = command
+        .get_subcommands()
+        .map(|sub| sub.get_name().to_string())
+        .collect::<Vec<_>>();
+    for name in names {
+        command = command.mut_subcommand(name, |sub| with_default_private_key(sub, key.clone()));
+    }
+    command
+}
+
 /// Returns address from private key or address arg.
-fn get_address(args: AddressArgs, subnet_id: &SubnetID) -> anyhow::Result<Address> {
+fn get_address(cli: &Cli, args: AddressArgs, subnet_id: &SubnetID) -> anyhow::Result<Address> {
     let address = if let Some(addr) = args.address {
-        addr
+        resolve_contact(cli, &addr)?
     } else if let Some(sk) = args.private_key.clone() {
         let signer = Wallet::new_secp256k1(sk, AccountKind::Ethereum, subnet_id.clone())?;
         signer.address()
@@ -199,14 +440,122 @@ fn get_address(args: AddressArgs, subnet_id: &SubnetID) -> anyhow::Result<Addres
     Ok(address)
 }
 
+/// Returns the unit of token amounts in output from the override, or `default`.
+fn get_units(cli: &Cli, default: TokenUnit) -> TokenUnit {
+    cli.units.map(|units| units.get()).unwrap_or(default)
+}
+
 /// Returns subnet ID from the override or network preset.
 fn get_subnet_id(cli: &Cli) -> anyhow::Result<SubnetID> {
-    Ok(cli.subnet.clone().unwrap_or(cli.network.get().subnet_id()?))
+    match cli.subnet.clone() {
+        Some(subnet_id) => Ok(subnet_id),
+        None => cli.network.get().subnet_id().map_err(config_error),
+    }
 }
 
 /// Returns rpc url from the override or network preset.
 fn get_rpc_url(cli: &Cli) -> anyhow::Result<Url> {
-    Ok(cli.rpc_url.clone().unwrap_or(cli.network.get().rpc_url()?))
+    match cli.rpc_url.clone() {
+        Some(url) => Ok(url),
+        None => cli.network.get().rpc_url().map_err(config_error),
+    }
+}
+
+/// Gas usage of the session, if `--gas-report` is set.
+static GAS_REPORT: OnceLock<GasReport> = OnceLock::new();
+
+/// Returns the names of methods sent by the CLI, e.g., for gas reports and confirmation prompts.
+fn method_names() -> [(MethodNum, &'static str); 4] {
+    use fendermint_actor_accumulator::Method as Accumulator;
+    use fendermint_actor_objectstore::Method as ObjectStore;
+    use fendermint_vm_actor_interface::adm::Method as Adm;
+
+    [
+        (Adm::CreateExternal as u64, "create_machine"),
+        (ObjectStore::AddObject as u64, "add_object"),
+        (ObjectStore::DeleteObject as u64, "delete_object"),
+        (Accumulator::Push as u64, "push"),
+    ]
+}
+
+/// Returns a gas report that names the methods sent by the CLI and formats fees in `unit`.
+fn new_gas_report(unit: TokenUnit) -> GasReport {
+    method_names()
+        .into_iter()
+        .fold(GasReport::new(), |report, (method, name)| {
+            report.with_method_name(method, name)
+        })
+        .with_unit(unit)
+}
+
+/// Set when all remaining messages are approved at an `--interactive` prompt.
+static APPROVE_ALL: AtomicBool = AtomicBool::new(false);
+
+/// Held while an `--interactive` prompt is shown, so concurrent messages are reviewed one at a time.
+static PROMPT: Mutex<()> = Mutex::new(());
+
+/// Returns a signer that asks for confirmation on the terminal before signing each message
+/// if `--interactive` is set.
+fn confirm_signer<S: Signer>(cli: &Cli, signer: S) -> Confirming<S> {
+    let interactive = cli.interactive;
+    signer.with_confirmation(move |review| {
+        if !interactive || APPROVE_ALL.load(Ordering::Relaxed) {
+            return Ok(true);
+        }
+        let _prompt = PROMPT.lock().unwrap_or_else(|e| e.into_inner());
+        // Another prompt may have approved all remaining messages while this one waited
+        if APPROVE_ALL.load(Ordering::Relaxed) {
+            return Ok(true);
+        }
+        prompt_confirmation(review)
+    })
+}
+
+/// Fails if `--interactive` is set, since `what` are EVM transactions, which can't be reviewed.
+fn reject_interactive(cli: &Cli, what: &str) -> anyhow::Result<()> {
+    if cli.interactive {
+        return Err(anyhow!(
+            "--interactive can't review {}, which are signed as EVM transactions",
+            what
+        ));
+    }
+    Ok(())
+}
+
+/// Prints a message review to stderr and reads the answer from the terminal.
+///
+/// The terminal is read directly, so stdin can still be used for command input.
+/// The signer calls this on a blocking thread, so the read doesn't stall the async runtime.
+fn prompt_confirmation(review: &MessageReview) -> anyhow::Result<bool> {
+    let name = method_names()
+        .into_iter()
+        .find(|(method, _)| *method == review.method_num)
+        .map_or("message", |(_, name)| name);
+    eprintln!("Review {}:\n{}", name, review);
+    eprint!("Sign this message? [y]es, [N]o, [a]ll remaining: ");
+    std::io::stderr().flush()?;
+    let tty = std::fs::File::open("/dev/tty").context("--interactive requires a terminal")?;
+    let mut answer = String::new();
+    std::io::BufReader::new(tty).read_line(&mut answer)?;
+    match answer.trim().to_lowercase().as_str() {
+        "y" | "yes" => Ok(true),
+        "a" | "all" => {
+            APPROVE_ALL.store(true, Ordering::Relaxed);
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Returns a provider for the RPC URL and an optional Object API URL.
+/// Its calls are added to the gas report if `--gas-report` is set.
+fn get_provider(cli: &Cli, object_api_url: Option<Url>) -> anyhow::Result<JsonRpcProvider> {
+    let provider = JsonRpcProvider::new_http(get_rpc_url(cli)?, None, object_api_url, None)?
+        .with_address_network(cli.network.get().address_network());
+    Ok(match GAS_REPORT.get() {
+        Some(report) => provider.with_middleware(report.clone()),
+        None => provider,
+    })
 }
 
 /// Print serializable to stdout as pretty formatted JSON.
@@ -215,3 +564,25 @@ fn print_json<T: Serialize>(value: &T) -> anyhow::Result<()> {
     println!("{}", json);
     Ok(())
 }
+
+/// Print the result of a query as JSON.
+/// In watch mode, the query is repeated on an interval and the result is printed whenever it changes.
+async fn print_json_watch<F, Fut, T>(args: &WatchArgs, mut query: F) -> anyhow::Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<T>>,
+    T: Serialize + PartialEq,
+{
+    let mut last: Option<T> = None;
+    loop {
+        let value = query().await?;
+        if last.as_ref() != Some(&value) {
+            print_json(&value)?;
+            last = Some(value);
+        }
+        if !args.watch {
+            return Ok(());
+        }
+        tokio::time::sleep(args.interval).await;
+    }
+}
@@ -0,0 +1,230 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use clap::{Args, Subcommand};
+use clap_complete::engine::ArgValueCompleter;
+use fendermint_crypto::SecretKey;
+use fvm_shared::address::Address;
+use serde_json::json;
+use tendermint_rpc::Url;
+
+use adm_provider::{
+    json_rpc::JsonRpcProvider,
+    query::NotFound,
+    util::{parse_address, parse_metadata},
+};
+use adm_sdk::{
+    machine::objectstore::AddOptions,
+    name::{validate_name, AddressOrName, AliasFile, NameResolver, NamingMachine},
+    TxParams,
+};
+use adm_signer::{key::parse_secret_key, AccountKind, Wallet};
+
+use crate::complete::complete_address;
+use crate::error::config_error;
+use crate::{confirm_signer, get_provider, get_subnet_id, print_json, BroadcastMode, Cli, TxArgs};
+
+#[derive(Clone, Debug, Args)]
+pub struct NameArgs {
+    #[command(subcommand)]
+    command: NameCommands,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+enum NameCommands {
+    /// Register a name for a machine address.
+    Register(NameRegisterArgs),
+    /// Resolve a name to a machine address.
+    Resolve(NameResolveArgs),
+}
+
+#[derive(Clone, Debug, Args)]
+struct NameRegisterArgs {
+    /// Name to register.
+    #[arg(value_parser = parse_name)]
+    name: String,
+    /// Machine address the name resolves to.
+    #[arg(value_parser = parse_address)]
+    address: Address,
+    /// Save the name in the local alias file instead of the naming machine.
+    #[arg(long)]
+    local: bool,
+    /// Wallet private key (ECDSA, secp256k1) for signing transactions.
+    #[arg(short, long, env, value_parser = parse_secret_key, required_unless_present = "local")]
+    private_key: Option<SecretKey>,
+    /// Node Object API URL.
+    #[arg(long, env)]
+    object_api_url: Option<Url>,
+    /// Bearer token for Object API authentication.
+    #[arg(long, env)]
+    object_api_auth_token: Option<String>,
+    /// Overwrite the name if it's already registered.
+    #[arg(short, long)]
+    overwrite: bool,
+    /// Broadcast mode for the transaction.
+    #[arg(short, long, value_enum, env, default_value_t = BroadcastMode::Commit)]
+    broadcast_mode: BroadcastMode,
+    #[command(flatten)]
+    tx_args: TxArgs,
+    #[arg(short, long, value_parser = parse_metadata)]
+    metadata: Vec<(String, String)>,
+}
+
+#[derive(Clone, Debug, Args)]
+struct NameResolveArgs {
+    /// Name to resolve.
+    #[arg(value_parser = parse_name, add = ArgValueCompleter::new(complete_address))]
+    name: String,
+}
+
+/// Name commands handler.
+pub async fn handle_name(cli: Cli, args: &NameArgs) -> anyhow::Result<()> {
+    match &args.command {
+        NameCommands::Register(args) => {
+            if args.local {
+                let mut aliases = AliasFile::load(get_names_path(&cli)?)?;
+                aliases.insert(
+                    &args.name,
+                    args.address,
+                    cli.network.get().address_network(),
+                )?;
+                aliases.save()?;
+                return print_json(&json!({
+                    "name": args.name,
+                    "address": args.address.to_string(),
+                    "source": "alias",
+                }));
+            }
+
+            let name_machine = get_name_machine(&cli)?;
+            let private_key = args
+                .private_key
+                .clone()
+                .ok_or_else(|| anyhow!("--private-key is required unless --local is set"))?;
+            let object_api_url = args
+                .object_api_url
+                .clone()
+                .unwrap_or(cli.network.get().object_api_url()?);
+            let provider = get_provider(&cli, Some(object_api_url))?
+                .with_object_auth_token(args.object_api_auth_token.clone())
+                .with_negotiated_object_api()
+                .await?;
+
+            let TxParams {
+                sequence,
+                gas_params,
+            } = args.tx_args.to_tx_params(cli.network.get());
+            let metadata: HashMap<String, String> = args.metadata.clone().into_iter().collect();
+
+            let mut signer =
+                Wallet::new_secp256k1(private_key, AccountKind::Ethereum, get_subnet_id(&cli)?)?;
+            signer.set_sequence(sequence, &provider).await?;
+            let mut signer = confirm_signer(&cli, signer);
+
+            let naming = NamingMachine::new(name_machine, provider.clone());
+            let tx = naming
+                .register(
+                    &provider,
+                    &mut signer,
+                    &args.name,
+                    args.address,
+                    AddOptions {
+                        overwrite: args.overwrite,
+                        broadcast_mode: args.broadcast_mode.get(),
+                        gas_params,
+                        show_progress: !cli.quiet,
+                        progress_format: cli.progress_format.get(),
+                        metadata,
+                        auth_token: None,
+                        upload_retries: Default::default(),
+                        upload_buffer_size: Default::default(),
+                        attest: Default::default(),
+                        checksum: None,
+                        cancel: Default::default(),
+                    },
+                )
+                .await?;
+
+            print_json(&tx)
+        }
+        NameCommands::Resolve(args) => {
+            let aliases = AliasFile::load(get_names_path(&cli)?)?;
+            let (address, source) = match aliases.get(&args.name)? {
+                Some(address) => (address, "alias"),
+                None => {
+                    let provider = get_provider(&cli, None)?;
+                    let naming = NamingMachine::new(get_name_machine(&cli)?, provider);
+                    match naming.resolve(&args.name).await? {
+                        Some(address) => (address, "machine"),
+                        None => {
+                            return Err(
+                                NotFound::new(format!("name '{}' not found", args.name)).into()
+                            )
+                        }
+                    }
+                }
+            };
+            print_json(&json!({
+                "name": args.name,
+                "address": address.to_string(),
+                "source": source,
+            }))
+        }
+    }
+}
+
+/// Resolves a machine address or name to an address.
+///
+/// Names are looked up in the local alias file first, then in the naming machine if one is set.
+pub async fn resolve_address(
+    cli: &Cli,
+    provider: &JsonRpcProvider,
+    address: &AddressOrName,
+) -> anyhow::Result<Address> {
+    if let AddressOrName::Address(address) = address {
+        return Ok(*address);
+    }
+    let aliases = AliasFile::load(get_names_path(cli)?)?;
+    match cli.name_machine {
+        Some(name_machine) => {
+            let naming = NamingMachine::new(name_machine, provider.clone());
+            let resolvers: [&dyn NameResolver; 2] = [&aliases, &naming];
+            address.resolve(&resolvers).await
+        }
+        None => address.resolve(&[&aliases as &dyn NameResolver]).await,
+    }
+}
+
+/// Returns the alias file path from the override,
+/// or `$HOME/.adm/names.json` by default.
+fn get_names_path(cli: &Cli) -> anyhow::Result<PathBuf> {
+    match cli.names_file.clone() {
+        Some(path) => Ok(path),
+        None => default_names_path(),
+    }
+}
+
+/// Returns the default alias file path, `$HOME/.adm/names.json`.
+pub fn default_names_path() -> anyhow::Result<PathBuf> {
+    let home = std::env::var("HOME").map_err(|_| anyhow!("failed to get home directory"))?;
+    Ok(PathBuf::from(home).join(".adm").join("names.json"))
+}
+
+/// Returns the naming machine address, which must be set to use shared names.
+fn get_name_machine(cli: &Cli) -> anyhow::Result<Address> {
+    cli.name_machine.ok_or_else(|| {
+        config_error(anyhow!(
+            "no naming machine is set; use --name-machine or NAME_MACHINE"
+        ))
+    })
+}
+
+/// Parse and validate a name.
+pub fn parse_name(s: &str) -> anyhow::Result<String> {
+    validate_name(s)?;
+    Ok(s.into())
+}
@@ -0,0 +1,759 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! JSON Schemas of command output.
+//!
+//! Command output is a stable interface: new fields may be added,
+//! but existing fields keep their names and types.
+//! Update the schema here whenever a command's output changes.
+
+use anyhow::anyhow;
+use std::ffi::OsString;
+
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use serde_json::{json, Value};
+
+use crate::print_json;
+
+/// The JSON Schema dialect of output schemas.
+const SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Returns the global `--schema` flag.
+///
+/// The flag is checked before the command runs (see [`schema_path`]),
+/// so a command's required arguments can be left out.
+pub fn schema_arg() -> Arg {
+    Arg::new("schema")
+        .long("schema")
+        .global(true)
+        .action(ArgAction::SetTrue)
+        .help("Print the JSON Schema of the command's output and exit")
+}
+
+/// Returns the subcommand names in `args` if the `--schema` flag was given.
+///
+/// Arguments are parsed by `cmd` with errors ignored, so missing required arguments don't fail,
+/// and an option value that happens to be "--schema" isn't mistaken for the flag.
+pub fn schema_path<I, T>(cmd: Command, args: I) -> Option<Vec<String>>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let matches = cmd.ignore_errors(true).try_get_matches_from(args).ok()?;
+    let mut requested = flag(&matches);
+    let mut path = Vec::new();
+    let mut current = &matches;
+    while let Some((name, sub)) = current.subcommand() {
+        requested |= flag(sub);
+        path.push(name.to_string());
+        current = sub;
+    }
+    requested.then_some(path)
+}
+
+fn flag(matches: &ArgMatches) -> bool {
+    matches
+        .try_get_one::<bool>("schema")
+        .ok()
+        .flatten()
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Prints the output schema of the command named by the subcommand names in `path`.
+pub fn handle_schema(cmd: &Command, path: &[String]) -> anyhow::Result<()> {
+    let mut current = cmd;
+    let mut names = Vec::new();
+    for name in path {
+        let sub = current
+            .find_subcommand(name)
+            .ok_or_else(|| anyhow!("unknown command `{}`", name))?;
+        names.push(sub.get_name());
+        current = sub;
+    }
+    let path = names;
+    let name = std::iter::once(cmd.get_name())
+        .chain(path.iter().copied())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if current.has_subcommands() {
+        let subcommands = current
+            .get_subcommands()
+            .map(|c| c.get_name())
+            .collect::<Vec<_>>();
+        return Err(anyhow!(
+            "`{}` has subcommands; use --schema with one of: {}",
+            name,
+            subcommands.join(", ")
+        ));
+    }
+    let mut schema =
+        output_schema(&path).ok_or_else(|| anyhow!("`{}` doesn't print JSON", name))?;
+    if let Some(schema) = schema.as_object_mut() {
+        schema.insert("$schema".into(), json!(SCHEMA_DIALECT));
+        schema.insert("title".into(), json!(name));
+    }
+    print_json(&schema)
+}
+
+/// Returns the output schema of a command, given its subcommand names,
+/// or `None` if the command doesn't print JSON.
+pub fn output_schema(path: &[&str]) -> Option<Value> {
+    let schema = match path {
+        ["account", "create"] => object(
+            json!({
+                "address": described(string(), "Ethereum address (delegated)."),
+                "fvm_address": described(string(), "FVM address."),
+                "name": described(string(), "Keystore name, if the key was saved."),
+                "private_key": described(string(), "Hex-encoded private key, if it's shown."),
+            }),
+            &["address", "fvm_address"],
+        ),
+        ["account", "info"] => object(
+            json!({
+                "address": described(string(), "Checksum-encoded EVM address."),
+                "fvm_address": string(),
+                "sequence": integer(),
+                "pending_sequence": described(
+                    integer(),
+                    "Sequence including transactions in the mempool.",
+                ),
+                "balance": described(string(), "Subnet balance in FIL, or in the `--units` denomination."),
+                "parent_balance": described(string(), "Parent balance in FIL, or in the `--units` denomination."),
+                "balances": object(
+                    json!({"subnet": denominations(), "parent": denominations()}),
+                    &["subnet", "parent"],
+                ),
+                "warnings": described(
+                    array(string()),
+                    "Balances that may be insufficient for common operations.",
+                ),
+            }),
+            &[
+                "address",
+                "fvm_address",
+                "sequence",
+                "pending_sequence",
+                "balance",
+                "parent_balance",
+                "balances",
+                "warnings",
+            ],
+        ),
+        ["account" | "subnet", "deposit" | "withdraw"] => cross_msg_receipt(),
+        ["account", "transfer" | "bridge"] | ["validator", "stake" | "unstake" | "claim"] => {
+            evm_receipt()
+        }
+        ["account", "bootstrap"] => object(
+            json!({
+                "address": described(string(), "Ethereum address (delegated)."),
+                "fvm_address": described(string(), "FVM address."),
+                "name": described(string(), "Keystore name, if a new key was saved."),
+                "private_key": described(string(), "Hex-encoded private key, if not saved."),
+                "network": string(),
+                "subnet": string(),
+                "balance": described(string(), "Subnet balance in FIL, or in the `--units` denomination."),
+                "parent_balance": described(string(), "Parent balance in FIL, or in the `--units` denomination; omitted on devnet and localnet."),
+                "deposit_tx": described(string(), "Deposit transaction hash; omitted on devnet and localnet."),
+                "fund_tx": described(string(), "Transaction hash of the transfer from the genesis account, if `--genesis-key` is given."),
+                "env": described(string_map(), "Environment variables to use the account."),
+            }),
+            &[
+                "address",
+                "fvm_address",
+                "network",
+                "subnet",
+                "balance",
+                "env",
+            ],
+        ),
+        ["account", "export"] => described(
+            object(json!({"path": string()}), &["path"]),
+            "Printed when the key is written to a file; otherwise, the key is printed as is.",
+        ),
+        ["machine", "info"] => described(
+            object(
+                json!({
+                    "kind": string(),
+                    "owner": string(),
+                    "stats": described(
+                        json!({"type": "object"}),
+                        "Usage statistics, if requested with --stats.",
+                    ),
+                }),
+                &["kind", "owner"],
+            ),
+            "With --watch, printed again whenever it changes.",
+        ),
+        ["machine", "kinds"] => array(object(
+            json!({
+                "name": string(),
+                "code": described(string(), "Actor code CID, if known."),
+                "methods": described(
+                    json!({"type": "object", "additionalProperties": object(
+                        json!({
+                            "number": integer(),
+                            "read_only": boolean(),
+                            "decoder": string(),
+                        }),
+                        &["number", "read_only", "decoder"],
+                    )}),
+                    "Methods keyed by name.",
+                ),
+            }),
+            &["name", "methods"],
+        )),
+        ["machine", "call"] => json!({
+            "oneOf": [
+                described(
+                    object(json!({"value": {}}), &["value"]),
+                    "Printed for read-only methods, which are queried.",
+                ),
+                tx_receipt(described(json!({}), "Return value decoded by the method's decoder.")),
+            ]
+        }),
+        ["objectstore" | "accumulator", "create"] => object(
+            json!({"address": string(), "tx": deploy_receipt()}),
+            &["address", "tx"],
+        ),
+        ["objectstore" | "accumulator", "list"] => array(object(
+            json!({"address": string(), "kind": string()}),
+            &["address", "kind"],
+        )),
+        ["objectstore", "add"] => json!({
+            "oneOf": [
+                tx_receipt(described(string(), "Object CID.")),
+                described(
+                    object(
+                        json!({
+                            "size": integer(),
+                            "gas_limit": integer(),
+                            "gas_price": string(),
+                            "gas_cost": string(),
+                            "storage_cost": string(),
+                            "total": string(),
+                            "balance": string(),
+                            "sufficient": boolean(),
+                        }),
+                        &[
+                            "size",
+                            "gas_limit",
+                            "gas_price",
+                            "gas_cost",
+                            "storage_cost",
+                            "total",
+                            "balance",
+                            "sufficient",
+                        ],
+                    ),
+                    "Cost estimate, printed with --estimate-only.",
+                ),
+            ]
+        }),
+        ["objectstore", "delete" | "restore"] => tx_receipt(string()),
+        ["objectstore", "purge"] => object(json!({"purged": array(string())}), &["purged"]),
+        ["objectstore", "get"] => described(
+            object(
+                json!({
+                    "height": integer(),
+                    "downloaded": array(string()),
+                    "skipped": array(string()),
+                }),
+                &["height", "downloaded", "skipped"],
+            ),
+            "Printed with --prefix; otherwise, the object is written to stdout or a file.",
+        ),
+        ["objectstore", "query"] => object(
+            json!({
+                "objects": array(object(
+                    json!({
+                        "key": string(),
+                        "value": object(
+                            json!({
+                                "cid": string(),
+                                "resolved": boolean(),
+                                "size": integer(),
+                                "metadata": string_map(),
+                            }),
+                            &["cid", "resolved", "size", "metadata"],
+                        ),
+                    }),
+                    &["key", "value"],
+                )),
+                "common_prefixes": array(string()),
+                "next_cursor": nullable(string()),
+            }),
+            &["objects", "common_prefixes", "next_cursor"],
+        ),
+        ["objectstore", "manifest"] => object(
+            json!({
+                "address": string(),
+                "height": integer(),
+                "objects": json!({
+                    "type": "object",
+                    "additionalProperties": object(
+                        json!({
+                            "cid": string(),
+                            "size": integer(),
+                            "resolved": boolean(),
+                            "metadata": string_map(),
+                        }),
+                        &["cid", "size", "resolved", "metadata"],
+                    ),
+                }),
+                "signature": object(
+                    json!({
+                        "signer": string(),
+                        "chain_id": integer(),
+                        "signature": described(string(), "Base64-encoded signature."),
+                    }),
+                    &["signer", "chain_id", "signature"],
+                ),
+            }),
+            &["address", "height", "objects"],
+        ),
+        ["objectstore", "verify"] => object(
+            json!({
+                "signer": string(),
+                "chain_id": integer(),
+                "timestamp": described(integer(), "Unix timestamp (in seconds) of the attestation."),
+                "signature": described(string(), "Base64-encoded signature."),
+            }),
+            &["signer", "chain_id", "timestamp", "signature"],
+        ),
+        ["objectstore", "copy"] => object(
+            json!({
+                "height": integer(),
+                "copied": array(string()),
+                "skipped": array(string()),
+            }),
+            &["height", "copied", "skipped"],
+        ),
+        ["objectstore", "policy", "set"] => tx_receipt(described(string(), "Policy object CID.")),
+        ["objectstore", "policy", "get"] => described(
+            nullable(object(
+                json!({
+                    "max_objects": integer(),
+                    "max_bytes": integer(),
+                    "ttl": described(integer(), "Object lifetime in seconds."),
+                }),
+                &[],
+            )),
+            "The policy, or null if the object store has none. Unset limits are omitted.",
+        ),
+        ["accumulator", "push"] => tx_receipt(push_return()),
+        ["accumulator", "follow-push"] => described(
+            object(
+                json!({
+                    "pushed": described(integer(), "Number of records pushed in the batch."),
+                    "total": described(integer(), "Number of records pushed so far."),
+                    "txs": array(described(string(), "Transaction hash.")),
+                }),
+                &["pushed", "total", "txs"],
+            ),
+            "Printed as a JSON line for each batch of pushed records.",
+        ),
+        ["accumulator", "leaf"] => described(
+            object(
+                json!({"index": integer(), "metadata": nullable(json!({"type": "object"}))}),
+                &["index", "metadata"],
+            ),
+            "Printed with --metadata; otherwise, the leaf payload is written to stdout.",
+        ),
+        ["accumulator", "count"] => object(json!({"count": integer()}), &["count"]),
+        ["accumulator", "peaks"] => object(json!({"peaks": array(string())}), &["peaks"]),
+        ["accumulator", "root"] => object(json!({"root": string()}), &["root"]),
+        ["accumulator", "export"] => object(
+            json!({
+                "address": string(),
+                "height": integer(),
+                "root": string(),
+                "peaks": array(string()),
+                "leaves": array(described(string(), "Base64-encoded leaf.")),
+            }),
+            &["address", "height", "root", "peaks", "leaves"],
+        ),
+        ["accumulator", "import"] => described(
+            nullable(tx_receipt(push_return())),
+            "The receipt of the last push, or null if the snapshot has no leaves.",
+        ),
+        ["subnet", "info"] => object(
+            json!({
+                "id": string(),
+                "chain_id": integer(),
+                "parent": string(),
+                "actor": nullable(string()),
+                "registered": nullable(boolean()),
+                "height": integer(),
+                "evm_rpc_url": string(),
+                "gateway": string(),
+                "registry": string(),
+            }),
+            &[
+                "id",
+                "chain_id",
+                "parent",
+                "actor",
+                "registered",
+                "height",
+                "evm_rpc_url",
+                "gateway",
+                "registry",
+            ],
+        ),
+        ["subnet", "list"] => array(object(
+            json!({"id": string(), "chain_id": integer()}),
+            &["id", "chain_id"],
+        )),
+        ["subnet", "validators"] => array(validator()),
+        ["validator", "info"] => validator(),
+        ["tx", "decode"] => object(
+            json!({
+                "from": string(),
+                "to": string(),
+                "sequence": integer(),
+                "value": described(string(), "Value in FIL."),
+                "method_num": integer(),
+                "method": described(nullable(string()), "Method name, if it's known."),
+                "params": described(
+                    json!({"oneOf": [string(), {"type": "object"}]}),
+                    "Decoded params of object store and accumulator methods, or hex-encoded params.",
+                ),
+                "gas_limit": integer(),
+                "gas_fee_cap": described(string(), "Gas fee cap in attoFIL."),
+                "gas_premium": described(string(), "Gas premium in attoFIL."),
+                "signature": described(string(), "Hex-encoded signature."),
+                "object": described(
+                    nullable(object(
+                        json!({"key": string(), "cid": string(), "address": string()}),
+                        &["key", "cid", "address"],
+                    )),
+                    "Object carried by the message, e.g., for object store uploads.",
+                ),
+            }),
+            &[
+                "from",
+                "to",
+                "sequence",
+                "value",
+                "method_num",
+                "method",
+                "params",
+                "gas_limit",
+                "gas_fee_cap",
+                "gas_premium",
+                "signature",
+                "object",
+            ],
+        ),
+        ["chain", "block"] => object(
+            json!({
+                "height": integer(),
+                "hash": string(),
+                "time": described(string(), "RFC 3339 timestamp."),
+                "proposer": described(string(), "Hex-encoded validator address."),
+                "txs": array(chain_tx()),
+            }),
+            &["height", "hash", "time", "proposer", "txs"],
+        ),
+        ["chain", "tx"] => chain_tx(),
+        ["chain", "search"] => array(chain_tx()),
+        ["name", "register"] => json!({"oneOf": [tx_receipt(string()), name_record()]}),
+        ["name", "resolve"] => name_record(),
+        ["contacts", "add" | "rm"] => contact(),
+        ["contacts", "list" | "import"] => array(contact()),
+        ["agent"] => described(
+            object(
+                json!({
+                    "time": described(string(), "RFC 3339 timestamp."),
+                    "level": enumeration(&["info", "warn", "error"]),
+                    "event": enumeration(&[
+                        "started",
+                        "uploaded",
+                        "upload_failed",
+                        "scan_failed",
+                        "webhook_failed",
+                        "scan_finished",
+                    ]),
+                }),
+                &["time", "level", "event"],
+            ),
+            "Printed as a JSON line for each event. The other fields depend on the event.",
+        ),
+        ["doctor"] => array(object(
+            json!({
+                "name": string(),
+                "status": enumeration(&["ok", "warn", "fail", "skip"]),
+                "detail": string(),
+                "remediation": string(),
+                "probe": described(
+                    object(
+                        json!({
+                            "latency_ms": integer(),
+                            "chain_id": string(),
+                            "node_version": string(),
+                            "app_version": string(),
+                            "height": integer(),
+                            "time": string(),
+                            "catching_up": boolean(),
+                            "api_version": string(),
+                            "status": integer(),
+                        }),
+                        &["latency_ms"],
+                    ),
+                    "Measurements of the rpc_probe and object_api_probe checks with --probe.",
+                ),
+            }),
+            &["name", "status", "detail"],
+        )),
+        ["upgrade"] => object(
+            json!({
+                "current_version": string(),
+                "release_version": string(),
+                "update_available": boolean(),
+                "updated": boolean(),
+                "signature_verified": described(
+                    boolean(),
+                    "Whether the release checksums were signed by the release signer.",
+                ),
+                "path": described(string(), "Path of the CLI binary."),
+            }),
+            &[
+                "current_version",
+                "release_version",
+                "update_available",
+                "updated",
+                "signature_verified",
+                "path",
+            ],
+        ),
+        _ => return None,
+    };
+    Some(schema)
+}
+
+fn string() -> Value {
+    json!({"type": "string"})
+}
+
+fn integer() -> Value {
+    json!({"type": "integer"})
+}
+
+fn boolean() -> Value {
+    json!({"type": "boolean"})
+}
+
+fn array(items: Value) -> Value {
+    json!({"type": "array", "items": items})
+}
+
+fn object(properties: Value, required: &[&str]) -> Value {
+    json!({"type": "object", "properties": properties, "required": required})
+}
+
+fn string_map() -> Value {
+    json!({"type": "object", "additionalProperties": string()})
+}
+
+fn enumeration(values: &[&str]) -> Value {
+    json!({"type": "string", "enum": values})
+}
+
+fn nullable(schema: Value) -> Value {
+    json!({"anyOf": [schema, {"type": "null"}]})
+}
+
+fn described(mut schema: Value, description: &str) -> Value {
+    schema["description"] = json!(description);
+    schema
+}
+
+/// Schema of an ADM transaction receipt with the given data.
+fn tx_receipt(data: Value) -> Value {
+    object(
+        json!({
+            "status": enumeration(&["pending", "committed"]),
+            "hash": string(),
+            "height": described(string(), "Block height as a decimal string."),
+            "gas_used": integer(),
+            "data": data,
+            "events": array(object(
+                json!({
+                    "kind": string(),
+                    "attributes": array(object(
+                        json!({"key": string(), "value": string(), "index": boolean()}),
+                        &["key", "value", "index"],
+                    )),
+                    "machine": object(json!({"type": string()}), &["type"]),
+                }),
+                &["kind", "attributes"],
+            )),
+        }),
+        &["status", "hash"],
+    )
+}
+
+/// Schema of a committed transaction.
+fn chain_tx() -> Value {
+    object(
+        json!({
+            "hash": string(),
+            "height": integer(),
+            "index": described(integer(), "Index of the transaction in its block."),
+            "message": described(
+                nullable(object(
+                    json!({
+                        "from": string(),
+                        "to": string(),
+                        "sequence": integer(),
+                        "value": described(string(), "Value in FIL."),
+                        "method_num": integer(),
+                        "method": described(nullable(string()), "Method name, if it's known."),
+                        "gas_limit": integer(),
+                    }),
+                    &[
+                        "from",
+                        "to",
+                        "sequence",
+                        "value",
+                        "method_num",
+                        "method",
+                        "gas_limit",
+                    ],
+                )),
+                "Message summary, or null if it's not a signed message, e.g., a parent chain message.",
+            ),
+            "code": described(integer(), "Result code; 0 is success."),
+            "gas_used": integer(),
+            "info": string(),
+            "events": array(object(
+                json!({
+                    "kind": string(),
+                    "attributes": array(object(
+                        json!({"key": string(), "value": string(), "index": boolean()}),
+                        &["key", "value", "index"],
+                    )),
+                    "machine": object(json!({"type": string()}), &["type"]),
+                }),
+                &["kind", "attributes"],
+            )),
+        }),
+        &["hash", "height", "index", "message"],
+    )
+}
+
+/// Schema of a machine deploy transaction receipt.
+fn deploy_receipt() -> Value {
+    object(
+        json!({
+            "status": enumeration(&["pending", "committed"]),
+            "hash": string(),
+            "height": described(string(), "Block height as a decimal string."),
+            "gas_used": integer(),
+            "actor_id": described(integer(), "Machine actor ID."),
+            "robust_address": described(string(), "Machine robust address."),
+            "evm_address": described(string(), "Machine delegated EVM address."),
+        }),
+        &["status", "hash", "gas_used"],
+    )
+}
+
+/// Schema of an EVM transaction receipt from the parent or subnet EVM RPC.
+fn evm_receipt() -> Value {
+    described(
+        object(
+            json!({
+                "transactionHash": string(),
+                "blockHash": nullable(string()),
+                "blockNumber": nullable(string()),
+                "from": string(),
+                "to": nullable(string()),
+                "gasUsed": nullable(string()),
+                "status": nullable(string()),
+            }),
+            &["transactionHash"],
+        ),
+        "EVM transaction receipt. Numbers are hex strings.",
+    )
+}
+
+fn cross_msg_receipt() -> Value {
+    let mut schema = evm_receipt();
+    schema["properties"]["message"] = described(
+        object(
+            json!({
+                "subnet": string(),
+                "from": string(),
+                "to": string(),
+                "amount": described(string(), "Amount in FIL, or in the `--units` denomination."),
+                "nonce": described(
+                    nullable(integer()),
+                    "Top-down message nonce. Null for withdrawals, which are batched into checkpoints.",
+                ),
+            }),
+            &["subnet", "from", "to", "amount", "nonce"],
+        ),
+        "Cross-net message committed by the gateway.",
+    );
+    schema["required"] = json!(["transactionHash", "message"]);
+    schema
+}
+
+fn push_return() -> Value {
+    object(
+        json!({"root": string(), "index": integer()}),
+        &["root", "index"],
+    )
+}
+
+fn validator() -> Value {
+    object(
+        json!({
+            "address": string(),
+            "active": boolean(),
+            "federated_power": string(),
+            "confirmed_collateral": described(string(), "Collateral in attoFIL, or in the `--units` denomination."),
+            "total_collateral": described(string(), "Collateral in attoFIL, or in the `--units` denomination."),
+        }),
+        &[
+            "address",
+            "active",
+            "federated_power",
+            "confirmed_collateral",
+            "total_collateral",
+        ],
+    )
+}
+
+fn denominations() -> Value {
+    object(
+        json!({"fil": string(), "nanofil": string(), "attofil": string()}),
+        &["fil", "nanofil", "attofil"],
+    )
+}
+
+fn contact() -> Value {
+    object(
+        json!({
+            "label": string(),
+            "address": described(
+                nullable(string()),
+                "EVM address, or null if the address has no EVM form.",
+            ),
+            "fvm_address": string(),
+        }),
+        &["label", "address", "fvm_address"],
+    )
+}
+
+fn name_record() -> Value {
+    object(
+        json!({
+            "name": string(),
+            "address": string(),
+            "source": enumeration(&["alias", "machine"]),
+        }),
+        &["name", "address", "source"],
+    )
+}
@@ -0,0 +1,184 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::time::Duration;
+
+use clap::{Args, Subcommand};
+use fvm_shared::address::Address;
+use reqwest::Url;
+use serde_json::{json, Value};
+
+use adm_provider::{
+    query::QueryProvider,
+    util::{parse_address, TokenUnit},
+};
+use adm_sdk::ipc::{manager::EvmManager, subnet::EVMSubnet};
+use adm_signer::SubnetID;
+
+use crate::account::{handle_deposit, handle_withdraw, DepositArgs, FundArgs};
+use crate::validator::validator_json;
+use crate::{get_provider, get_subnet_id, get_units, print_json, Cli};
+
+#[derive(Clone, Debug, Args)]
+pub struct SubnetArgs {
+    #[command(subcommand)]
+    command: SubnetCommands,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+enum SubnetCommands {
+    /// Get subnet information.
+    Info(InfoArgs),
+    /// Deposit funds into the subnet from its parent.
+    Deposit(DepositArgs),
+    /// Withdraw funds from the subnet to its parent.
+    Withdraw(FundArgs),
+    /// List child subnets registered with the subnet's gateway.
+    #[clap(alias = "ls")]
+    List(ListArgs),
+    /// List the subnet's active and waiting validators.
+    Validators(ValidatorsArgs),
+}
+
+#[derive(Clone, Debug, Args)]
+pub(crate) struct EvmSubnetArgs {
+    /// The Ethereum API rpc http endpoint.
+    #[arg(long)]
+    evm_rpc_url: Option<Url>,
+    /// Timeout for calls to the Ethereum API.
+    #[arg(long, value_parser = humantime::parse_duration, default_value = "60s")]
+    evm_rpc_timeout: Duration,
+    /// Bearer token for any Authorization header.
+    #[arg(long)]
+    evm_rpc_auth_token: Option<String>,
+    /// The gateway contract address.
+    #[arg(long, value_parser = parse_address)]
+    evm_gateway: Option<Address>,
+    /// The registry contract address.
+    #[arg(long, value_parser = parse_address)]
+    evm_registry: Option<Address>,
+}
+
+#[derive(Clone, Debug, Args)]
+struct InfoArgs {
+    #[command(flatten)]
+    subnet: EvmSubnetArgs,
+}
+
+#[derive(Clone, Debug, Args)]
+struct ListArgs {
+    /// List the children of the parent subnet instead, i.e., the subnet and its siblings.
+    #[arg(long, default_value_t = false)]
+    parent: bool,
+    #[command(flatten)]
+    subnet: EvmSubnetArgs,
+}
+
+#[derive(Clone, Debug, Args)]
+struct ValidatorsArgs {
+    /// Only list validators in the active set.
+    #[arg(long, default_value_t = false)]
+    active: bool,
+    #[command(flatten)]
+    subnet: EvmSubnetArgs,
+}
+
+/// Subnet commmands handler.
+pub async fn handle_subnet(cli: Cli, args: &SubnetArgs) -> anyhow::Result<()> {
+    let subnet_id = get_subnet_id(&cli)?;
+
+    match &args.command {
+        SubnetCommands::Info(args) => {
+            let config = get_subnet_config(&cli, &subnet_id, args.subnet.clone())?;
+            let registered = match subnet_id.actor() {
+                Some(_) => {
+                    let parent_config =
+                        get_parent_subnet_config(&cli, &subnet_id, args.subnet.clone())?;
+                    Some(EvmManager::subnet_exists(&subnet_id, parent_config).await?)
+                }
+                None => None,
+            };
+
+            let provider = get_provider(&cli, None)?;
+            let head = provider.chain_head().await?;
+
+            print_json(&json!({
+                "id": subnet_id.to_string(),
+                "chain_id": u64::from(subnet_id.chain_id()),
+                "parent": subnet_id.parent()?.to_string(),
+                "actor": subnet_id.actor().map(|a| a.to_string()),
+                "registered": registered,
+                "height": head.height.value(),
+                "evm_rpc_url": config.provider_http.to_string(),
+                "gateway": config.gateway_addr.to_string(),
+                "registry": config.registry_addr.to_string(),
+            }))
+        }
+        SubnetCommands::Deposit(args) => handle_deposit(&cli, args).await,
+        SubnetCommands::Withdraw(args) => handle_withdraw(&cli, args).await,
+        SubnetCommands::List(args) => {
+            let config = if args.parent {
+                get_parent_subnet_config(&cli, &subnet_id, args.subnet.clone())?
+            } else {
+                get_subnet_config(&cli, &subnet_id, args.subnet.clone())?
+            };
+
+            let subnets = EvmManager::list_subnets(config).await?;
+
+            let subnets = subnets
+                .iter()
+                .map(|id| json!({"id": id.to_string(), "chain_id": u64::from(id.chain_id())}))
+                .collect::<Vec<Value>>();
+
+            print_json(&subnets)
+        }
+        SubnetCommands::Validators(args) => {
+            let config = get_parent_subnet_config(&cli, &subnet_id, args.subnet.clone())?;
+
+            let validators = EvmManager::list_validators(&subnet_id, config).await?;
+
+            let units = get_units(&cli, TokenUnit::AttoFil);
+            let validators = validators
+                .iter()
+                .filter(|v| v.active || !args.active)
+                .map(|v| validator_json(v, units))
+                .collect::<Vec<Value>>();
+
+            print_json(&validators)
+        }
+    }
+}
+
+/// Returns the subnet configuration from args.
+pub(crate) fn get_subnet_config(
+    cli: &Cli,
+    id: &SubnetID,
+    args: EvmSubnetArgs,
+) -> anyhow::Result<EVMSubnet> {
+    let network = cli.network.get();
+    Ok(EVMSubnet {
+        id: id.clone(),
+        provider_http: args.evm_rpc_url.unwrap_or(network.evm_rpc_url()?),
+        provider_timeout: Some(args.evm_rpc_timeout),
+        auth_token: args.evm_rpc_auth_token,
+        registry_addr: args.evm_registry.unwrap_or(network.evm_registry()?),
+        gateway_addr: args.evm_gateway.unwrap_or(network.evm_gateway()?),
+    })
+}
+
+/// Returns the parent subnet configuration from args.
+pub(crate) fn get_parent_subnet_config(
+    cli: &Cli,
+    id: &SubnetID,
+    args: EvmSubnetArgs,
+) -> anyhow::Result<EVMSubnet> {
+    let network = cli.network.get();
+    Ok(EVMSubnet {
+        id: id.clone(),
+        provider_http: args.evm_rpc_url.unwrap_or(network.parent_evm_rpc_url()?),
+        provider_timeout: Some(args.evm_rpc_timeout),
+        auth_token: args.evm_rpc_auth_token,
+        registry_addr: args.evm_registry.unwrap_or(network.parent_evm_registry()?),
+        gateway_addr: args.evm_gateway.unwrap_or(network.parent_evm_gateway()?),
+    })
+}
@@ -0,0 +1,128 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use anyhow::anyhow;
+use base64::{engine::general_purpose, Engine};
+use clap::{Args, Subcommand};
+use fendermint_actor_accumulator::Method::Push;
+use fendermint_actor_objectstore::{
+    AddParams, DeleteParams, GetParams,
+    Method::{AddObject, DeleteObject, GetObject},
+};
+use fendermint_vm_message::{chain::ChainMessage, signed::SignedMessage};
+use fvm_ipld_encoding::{BytesDe, RawBytes};
+use fvm_shared::MethodNum;
+use serde_json::{json, Value};
+
+use adm_sdk::network::Network as SdkNetwork;
+
+use crate::{method_names, print_json, Cli};
+
+#[derive(Clone, Debug, Args)]
+pub struct TransactionArgs {
+    #[command(subcommand)]
+    command: TransactionCommands,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+enum TransactionCommands {
+    /// Decode a serialized transaction, e.g., one copied from a block explorer.
+    Decode(TransactionDecodeArgs),
+}
+
+#[derive(Clone, Debug, Args)]
+struct TransactionDecodeArgs {
+    /// Hex (with or without "0x") or base64-encoded ChainMessage or SignedMessage.
+    tx: String,
+}
+
+/// Transaction commands handler.
+pub async fn handle_transaction(cli: Cli, args: &TransactionArgs) -> anyhow::Result<()> {
+    match &args.command {
+        TransactionCommands::Decode(args) => {
+            let bytes = decode_input(&args.tx)?;
+            print_json(&decode_tx(&bytes, cli.network.get())?)
+        }
+    }
+}
+
+/// Decodes hex, which is tried first, or base64.
+fn decode_input(input: &str) -> anyhow::Result<Vec<u8>> {
+    let input = input.trim();
+    if let Some(hex) = input.strip_prefix("0x") {
+        return hex::decode(hex).map_err(|e| anyhow!("invalid hex transaction: {e}"));
+    }
+    hex::decode(input)
+        .ok()
+        .or_else(|| general_purpose::STANDARD.decode(input).ok())
+        .ok_or_else(|| anyhow!("transaction must be hex or base64-encoded"))
+}
+
+/// Decodes a [`ChainMessage`], as broadcast by the CLI and SDK, or a bare [`SignedMessage`].
+fn decode_tx(bytes: &[u8], network: SdkNetwork) -> anyhow::Result<Value> {
+    let signed = match fvm_ipld_encoding::from_slice::<ChainMessage>(bytes) {
+        Ok(ChainMessage::Signed(signed)) => signed,
+        Ok(_) => return Err(anyhow!("only signed messages can be decoded")),
+        Err(_) => fvm_ipld_encoding::from_slice::<SignedMessage>(bytes).map_err(|e| {
+            anyhow!("failed to decode transaction as a ChainMessage or SignedMessage: {e}")
+        })?,
+    };
+    let message = &signed.message;
+    let method = method_names()
+        .into_iter()
+        .find(|(method, _)| *method == message.method_num)
+        .map(|(_, name)| name);
+    let object = signed.object.as_ref().map(|object| {
+        json!({
+            "key": String::from_utf8_lossy(&object.key),
+            "cid": object.value.to_string(),
+            "address": network.format_address(&object.address),
+        })
+    });
+    Ok(json!({
+        "from": network.format_address(&message.from),
+        "to": network.format_address(&message.to),
+        "sequence": message.sequence,
+        "value": message.value.to_string(),
+        "method_num": message.method_num,
+        "method": method,
+        "params": decode_params(message.method_num, &message.params),
+        "gas_limit": message.gas_limit,
+        "gas_fee_cap": message.gas_fee_cap.atto().to_string(),
+        "gas_premium": message.gas_premium.atto().to_string(),
+        "signature": format!("0x{}", hex::encode(signed.signature.bytes())),
+        "object": object,
+    }))
+}
+
+/// Decodes the params of object store and accumulator methods.
+///
+/// Params of other methods, or that don't decode, e.g., because they're for an older actor
+/// that happens to share a method number, are shown as hex.
+fn decode_params(method_num: MethodNum, params: &RawBytes) -> Value {
+    let decoded = match method_num {
+        m if m == AddObject as u64 => params.deserialize::<AddParams>().ok().map(|p| {
+            json!({
+                "key": String::from_utf8_lossy(&p.key),
+                "cid": p.cid.to_string(),
+                "overwrite": p.overwrite,
+                "metadata": p.metadata,
+                "size": p.size,
+            })
+        }),
+        m if m == DeleteObject as u64 => params
+            .deserialize::<DeleteParams>()
+            .ok()
+            .map(|p| json!({"key": String::from_utf8_lossy(&p.key)})),
+        m if m == GetObject as u64 => params
+            .deserialize::<GetParams>()
+            .ok()
+            .map(|p| json!({"key": String::from_utf8_lossy(&p.key)})),
+        m if m == Push as u64 => params
+            .deserialize::<BytesDe>()
+            .ok()
+            .map(|BytesDe(payload)| json!({"payload": format!("0x{}", hex::encode(payload))})),
+        _ => None,
+    };
+    decoded.unwrap_or_else(|| json!(format!("0x{}", hex::encode(params.bytes()))))
+}
@@ -0,0 +1,293 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context};
+use clap::Args;
+use ethers::types::{Address as EthAddress, Signature};
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{print_json, Cli};
+
+/// The version of the running binary.
+const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Address that signs release checksums, baked in by the release build.
+/// Without it or `--release-signer`, upgrades fail unless `--insecure` is given.
+const RELEASE_SIGNER: Option<&str> = option_env!("ADM_RELEASE_SIGNER");
+
+/// Name of the release asset that lists the SHA-256 checksum of every binary.
+const CHECKSUMS_ASSET: &str = "SHA256SUMS";
+
+/// Name of the release asset that holds the release signer's signature over [`CHECKSUMS_ASSET`].
+const SIGNATURE_ASSET: &str = "SHA256SUMS.sig";
+
+#[derive(Clone, Debug, Args)]
+pub struct UpgradeArgs {
+    /// Only check for a newer release without installing it.
+    #[arg(long, default_value_t = false)]
+    check: bool,
+    /// Release tag to install, e.g., "v0.2.0". Defaults to the latest release.
+    /// Installing an older release requires `--force`.
+    #[arg(long)]
+    tag: Option<String>,
+    /// Reinstall even if the release is already installed, or downgrade to an older `--tag`.
+    #[arg(long, default_value_t = false)]
+    force: bool,
+    /// Release feed URL (a GitHub releases API endpoint).
+    #[arg(
+        long,
+        env = "ADM_RELEASE_FEED_URL",
+        default_value = "https://api.github.com/repos/textileio/basin/releases"
+    )]
+    feed_url: Url,
+    /// Ethereum address of the release signer.
+    /// Overrides the signer built into the binary.
+    #[arg(long, env = "ADM_RELEASE_SIGNER")]
+    release_signer: Option<String>,
+    /// Install without verifying the release signature if no release signer is configured.
+    /// Checksums are still verified.
+    #[arg(long, default_value_t = false)]
+    insecure: bool,
+}
+
+/// A release in the feed.
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    assets: Vec<Asset>,
+}
+
+/// A downloadable file attached to a release.
+#[derive(Debug, Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: Url,
+}
+
+impl Release {
+    fn asset(&self, name: &str) -> anyhow::Result<&Asset> {
+        self.assets
+            .iter()
+            .find(|a| a.name == name)
+            .ok_or_else(|| anyhow!("release {} has no {} asset", self.tag_name, name))
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct UpgradeResult {
+    current_version: String,
+    release_version: String,
+    update_available: bool,
+    updated: bool,
+    signature_verified: bool,
+    path: PathBuf,
+}
+
+/// Upgrade command handler.
+pub async fn handle_upgrade(_cli: Cli, args: &UpgradeArgs) -> anyhow::Result<()> {
+    let client = Client::builder()
+        .user_agent(format!("adm/{}", CURRENT_VERSION))
+        .build()?;
+    let path = std::env::current_exe()
+        .and_then(|path| path.canonicalize())
+        .context("failed to locate the running binary")?;
+
+    let release = fetch_release(&client, &args.feed_url, args.tag.as_deref()).await?;
+    let release_version = release.tag_name.trim_start_matches('v').to_string();
+    let update_available = match args.tag {
+        // An explicitly requested release is installed unless it's the running one
+        Some(_) => release_version != CURRENT_VERSION,
+        None => is_newer(&release_version, CURRENT_VERSION),
+    };
+    let mut result = UpgradeResult {
+        current_version: CURRENT_VERSION.into(),
+        release_version,
+        update_available,
+        updated: false,
+        signature_verified: false,
+        path,
+    };
+    if args.check || !(update_available || args.force) {
+        return print_json(&result);
+    }
+    if is_newer(CURRENT_VERSION, &result.release_version) && !args.force {
+        return Err(anyhow!(
+            "release {} is older than the running version {}; use --force to downgrade",
+            result.release_version,
+            CURRENT_VERSION
+        ));
+    }
+    let signer = match args.release_signer.as_deref().or(RELEASE_SIGNER) {
+        Some(signer) => Some(
+            EthAddress::from_str(signer)
+                .map_err(|e| anyhow!("invalid release signer {}: {}", signer, e))?,
+        ),
+        None if args.insecure => None,
+        None => {
+            return Err(anyhow!(
+                "no release signer is configured, so the release can't be verified; \
+                 set one with --release-signer, or use --insecure to skip signature verification"
+            ))
+        }
+    };
+
+    let name = asset_name();
+    let checksums = download(&client, release.asset(CHECKSUMS_ASSET)?).await?;
+    if let Some(signer) = signer {
+        let signature = download(&client, release.asset(SIGNATURE_ASSET)?).await?;
+        verify_signature(&checksums, &signature, signer)?;
+        result.signature_verified = true;
+    }
+    let expected = find_checksum(&String::from_utf8(checksums)?, &name)?;
+    let binary = download(&client, release.asset(&name)?).await?;
+    let actual = hex::encode(Sha256::digest(&binary));
+    if actual != expected {
+        return Err(anyhow!(
+            "checksum mismatch for {}: expected {}, got {}",
+            name,
+            expected,
+            actual
+        ));
+    }
+
+    replace_binary(&result.path, &binary)?;
+    result.updated = true;
+    print_json(&result)
+}
+
+/// Fetches the release with the given tag, or the latest release.
+async fn fetch_release(
+    client: &Client,
+    feed_url: &Url,
+    tag: Option<&str>,
+) -> anyhow::Result<Release> {
+    let mut url = feed_url.clone();
+    url.path_segments_mut()
+        .map_err(|_| anyhow!("invalid release feed URL: {}", feed_url))?
+        .pop_if_empty()
+        .extend(match tag {
+            Some(tag) => vec!["tags", tag],
+            None => vec!["latest"],
+        });
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .with_context(|| format!("failed to reach release feed at {}", feed_url))?;
+    if !response.status().is_success() {
+        return Err(anyhow!(
+            "failed to fetch release {}: {}",
+            tag.unwrap_or("latest"),
+            response.status()
+        ));
+    }
+    Ok(response.json().await?)
+}
+
+/// Downloads a release asset.
+async fn download(client: &Client, asset: &Asset) -> anyhow::Result<Vec<u8>> {
+    let response = client
+        .get(asset.browser_download_url.clone())
+        .send()
+        .await?
+        .error_for_status()
+        .with_context(|| format!("failed to download {}", asset.name))?;
+    Ok(response.bytes().await?.to_vec())
+}
+
+/// Returns the name of the release asset built for this platform,
+/// e.g., `adm-linux-x86_64` or `adm-windows-x86_64.exe`.
+fn asset_name() -> String {
+    format!(
+        "adm-{}-{}{}",
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        std::env::consts::EXE_SUFFIX
+    )
+}
+
+/// Returns whether `release` is a newer `major.minor.patch` version than `current`.
+fn is_newer(release: &str, current: &str) -> bool {
+    fn parse(version: &str) -> Option<Vec<u64>> {
+        // Ignore pre-release and build suffixes, e.g., "0.2.0-rc.1"
+        let core = version.split(['-', '+']).next()?;
+        core.split('.').map(|part| part.parse().ok()).collect()
+    }
+    match (parse(release), parse(current)) {
+        (Some(release), Some(current)) => release > current,
+        _ => release != current,
+    }
+}
+
+/// Finds the checksum of `name` in a `sha256sum`-style listing.
+fn find_checksum(checksums: &str, name: &str) -> anyhow::Result<String> {
+    checksums
+        .lines()
+        .filter_map(|line| line.split_once(char::is_whitespace))
+        .find(|(_, file)| file.trim().trim_start_matches('*') == name)
+        .map(|(sum, _)| sum.to_lowercase())
+        .ok_or_else(|| anyhow!("no checksum for {} in {}", name, CHECKSUMS_ASSET))
+}
+
+/// Verifies the release signer's personal-sign signature over the checksums.
+fn verify_signature(checksums: &[u8], signature: &[u8], signer: EthAddress) -> anyhow::Result<()> {
+    let signature = Signature::from_str(std::str::from_utf8(signature)?.trim())
+        .map_err(|e| anyhow!("invalid release signature: {}", e))?;
+    signature
+        .verify(checksums, signer)
+        .map_err(|_| anyhow!("release checksums are not signed by {:?}", signer))
+}
+
+/// Replaces the binary at `path` with `data`.
+///
+/// The new binary is written next to the old one and renamed over it,
+/// so an interrupted upgrade never leaves a partially written binary behind.
+fn replace_binary(path: &Path, data: &[u8]) -> anyhow::Result<()> {
+    let dir = path
+        .parent()
+        .ok_or_else(|| anyhow!("invalid binary path: {}", path.display()))?;
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("invalid binary path: {}", path.display()))?
+        .to_string_lossy();
+    let tmp = dir.join(format!(".{}.{}.tmp", file_name, std::process::id()));
+    let result = write_executable(path, &tmp, data).and_then(|()| swap(path, &tmp));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result.with_context(|| format!("failed to replace {}", path.display()))
+}
+
+/// Writes an executable with the same permissions as `original`.
+fn write_executable(original: &Path, path: &Path, data: &[u8]) -> anyhow::Result<()> {
+    let mut file = fs::File::create(path)?;
+    file.write_all(data)?;
+    file.sync_all()?;
+    fs::set_permissions(path, fs::metadata(original)?.permissions())?;
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn swap(path: &Path, new: &Path) -> anyhow::Result<()> {
+    Ok(fs::rename(new, path)?)
+}
+
+/// Windows can't replace a running binary, but it can rename it out of the way.
+#[cfg(windows)]
+fn swap(path: &Path, new: &Path) -> anyhow::Result<()> {
+    let old = path.with_extension("old.exe");
+    let _ = fs::remove_file(&old);
+    fs::rename(path, &old)?;
+    if let Err(e) = fs::rename(new, path) {
+        fs::rename(&old, path)?;
+        return Err(e.into());
+    }
+    Ok(())
+}
@@ -0,0 +1,164 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use clap::{error::ErrorKind, Args, CommandFactory, Subcommand};
+use ethers::types::U256;
+use fendermint_crypto::SecretKey;
+use fvm_shared::{
+    address::Address,
+    bigint::{BigInt, Sign},
+    econ::TokenAmount,
+};
+use serde_json::{json, Value};
+
+use adm_abis::subnet_actor::Validator;
+use adm_provider::util::{parse_address, parse_token_amount, TokenUnit};
+use adm_sdk::ipc::manager::EvmManager;
+use adm_signer::{key::parse_secret_key, AccountKind, Signer, Wallet};
+
+use crate::subnet::{get_parent_subnet_config, EvmSubnetArgs};
+use crate::{get_subnet_id, get_units, print_json, reject_interactive, Cli};
+
+#[derive(Clone, Debug, Args)]
+pub struct ValidatorArgs {
+    #[command(subcommand)]
+    command: ValidatorCommands,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+enum ValidatorCommands {
+    /// Get validator information.
+    Info(InfoArgs),
+    /// Add collateral to the validator's stake.
+    Stake(StakeArgs),
+    /// Remove collateral from the validator's stake.
+    Unstake(StakeArgs),
+    /// Claim collateral released after unstaking or leaving the subnet.
+    Claim(ClaimArgs),
+}
+
+#[derive(Clone, Debug, Args)]
+struct InfoArgs {
+    /// Wallet private key (ECDSA, secp256k1) for signing transactions.
+    #[arg(short, long, env, value_parser = parse_secret_key)]
+    private_key: Option<SecretKey>,
+    /// Validator address. The signer address is used if no address is given.
+    #[arg(short, long, value_parser = parse_address)]
+    address: Option<Address>,
+    #[command(flatten)]
+    subnet: EvmSubnetArgs,
+}
+
+#[derive(Clone, Debug, Args)]
+struct StakeArgs {
+    /// Wallet private key (ECDSA, secp256k1) for signing transactions.
+    #[arg(short, long, env, value_parser = parse_secret_key)]
+    private_key: SecretKey,
+    /// The amount of collateral in FIL.
+    #[arg(value_parser = parse_token_amount)]
+    amount: TokenAmount,
+    #[command(flatten)]
+    subnet: EvmSubnetArgs,
+}
+
+#[derive(Clone, Debug, Args)]
+struct ClaimArgs {
+    /// Wallet private key (ECDSA, secp256k1) for signing transactions.
+    #[arg(short, long, env, value_parser = parse_secret_key)]
+    private_key: SecretKey,
+    #[command(flatten)]
+    subnet: EvmSubnetArgs,
+}
+
+/// Validator commmands handler.
+///
+/// Validators are managed by the subnet actor in the parent chain,
+/// so signers and EVM configs target the parent subnet.
+pub async fn handle_validator(cli: Cli, args: &ValidatorArgs) -> anyhow::Result<()> {
+    let subnet_id = get_subnet_id(&cli)?;
+
+    match &args.command {
+        ValidatorCommands::Info(args) => {
+            let address = if let Some(addr) = args.address {
+                addr
+            } else if let Some(sk) = args.private_key.clone() {
+                Wallet::new_secp256k1(sk, AccountKind::Ethereum, subnet_id.parent()?)?.address()
+            } else {
+                Cli::command()
+                    .error(
+                        ErrorKind::MissingRequiredArgument,
+                        "the following required arguments were not provided: --private-key OR --address",
+                    )
+                    .exit();
+            };
+            let config = get_parent_subnet_config(&cli, &subnet_id, args.subnet.clone())?;
+
+            let validator = EvmManager::validator_info(address, &subnet_id, config).await?;
+
+            print_json(&validator_json(
+                &validator,
+                get_units(&cli, TokenUnit::AttoFil),
+            ))
+        }
+        ValidatorCommands::Stake(args) => {
+            reject_interactive(&cli, "validator stake changes")?;
+            let config = get_parent_subnet_config(&cli, &subnet_id, args.subnet.clone())?;
+            let signer = Wallet::new_secp256k1(
+                args.private_key.clone(),
+                AccountKind::Ethereum,
+                subnet_id.parent()?,
+            )?;
+
+            let tx = EvmManager::stake(&signer, &subnet_id, config, args.amount.clone()).await?;
+
+            print_json(&tx)
+        }
+        ValidatorCommands::Unstake(args) => {
+            reject_interactive(&cli, "validator stake changes")?;
+            let config = get_parent_subnet_config(&cli, &subnet_id, args.subnet.clone())?;
+            let signer = Wallet::new_secp256k1(
+                args.private_key.clone(),
+                AccountKind::Ethereum,
+                subnet_id.parent()?,
+            )?;
+
+            let tx = EvmManager::unstake(&signer, &subnet_id, config, args.amount.clone()).await?;
+
+            print_json(&tx)
+        }
+        ValidatorCommands::Claim(args) => {
+            reject_interactive(&cli, "validator claims")?;
+            let config = get_parent_subnet_config(&cli, &subnet_id, args.subnet.clone())?;
+            let signer = Wallet::new_secp256k1(
+                args.private_key.clone(),
+                AccountKind::Ethereum,
+                subnet_id.parent()?,
+            )?;
+
+            let tx = EvmManager::claim(&signer, &subnet_id, config).await?;
+
+            print_json(&tx)
+        }
+    }
+}
+
+/// Returns a JSON representation of a [`Validator`] with collateral in `units`.
+pub(crate) fn validator_json(validator: &Validator, units: TokenUnit) -> Value {
+    json!({
+        "address": format!("{:?}", validator.address),
+        "active": validator.active,
+        "federated_power": validator.federated_power.to_string(),
+        "confirmed_collateral": format_collateral(validator.confirmed_collateral, units),
+        "total_collateral": format_collateral(validator.total_collateral, units),
+    })
+}
+
+/// Formats collateral, which is given in attoFIL, in `units`.
+fn format_collateral(collateral: U256, units: TokenUnit) -> String {
+    let mut bytes = [0; 32];
+    collateral.to_big_endian(&mut bytes);
+    units.format(&TokenAmount::from_atto(BigInt::from_bytes_be(
+        Sign::Plus,
+        &bytes,
+    )))
+}
@@ -0,0 +1,132 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! # ADM Integration Tests
+//!
+//! End-to-end tests and a reusable harness for running them against a live subnet,
+//! usually a localnet.
+//!
+//! The harness does not start the network. Point it at a running subnet with
+//! the environment variables below; the localnet presets are used for anything not set.
+//!
+//! - `ADM_IT_FAUCET_PRIVATE_KEY` (required): hex-encoded key of a funded account used to fund test wallets.
+//! - `ADM_IT_SUBNET_ID`: subnet ID.
+//! - `ADM_IT_RPC_URL`: CometBFT RPC URL.
+//! - `ADM_IT_OBJECT_API_URL`: Object API URL.
+
+use std::env;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context};
+use fvm_ipld_encoding::RawBytes;
+use fvm_shared::{address::Address, econ::TokenAmount, METHOD_SEND};
+use tendermint_rpc::Url;
+
+use adm_provider::{json_rpc::JsonRpcProvider, response::decode_bytes};
+use adm_sdk::{network::Network, tx::TxBuilder};
+use adm_signer::{
+    key::{parse_secret_key, random_secretkey},
+    AccountKind, Signer, SubnetID, Wallet,
+};
+
+/// Environment variable holding the hex-encoded private key of a funded account.
+pub const FAUCET_PRIVATE_KEY_VAR: &str = "ADM_IT_FAUCET_PRIVATE_KEY";
+/// Environment variable overriding the subnet ID.
+pub const SUBNET_ID_VAR: &str = "ADM_IT_SUBNET_ID";
+/// Environment variable overriding the CometBFT RPC URL.
+pub const RPC_URL_VAR: &str = "ADM_IT_RPC_URL";
+/// Environment variable overriding the Object API URL.
+pub const OBJECT_API_URL_VAR: &str = "ADM_IT_OBJECT_API_URL";
+
+/// A live subnet for integration tests, with a faucet for funding test wallets.
+///
+/// Wallets created with [`TestSubnet::new_wallet`] use fresh random keys,
+/// so tests can run against a shared, long-lived network without interfering with each other.
+pub struct TestSubnet {
+    subnet_id: SubnetID,
+    provider: JsonRpcProvider,
+    faucet: Wallet,
+}
+
+impl TestSubnet {
+    /// Connect to the subnet configured by the environment.
+    ///
+    /// Fails if the faucet key is not set, or the faucet account does not exist in the subnet.
+    pub async fn connect() -> anyhow::Result<Self> {
+        // The network is passed explicitly rather than set globally with `Network::init`,
+        // so tests can share a process with other networks
+        let network = Network::Localnet;
+
+        let subnet_id = match env::var(SUBNET_ID_VAR) {
+            Ok(id) => SubnetID::from_str(&id)?,
+            Err(_) => network.subnet_id()?,
+        };
+        let rpc_url = match env::var(RPC_URL_VAR) {
+            Ok(url) => Url::from_str(&url)?,
+            Err(_) => network.rpc_url()?,
+        };
+        let object_api_url = match env::var(OBJECT_API_URL_VAR) {
+            Ok(url) => Url::from_str(&url)?,
+            Err(_) => network.object_api_url()?,
+        };
+        let provider = JsonRpcProvider::new_http(rpc_url, None, Some(object_api_url), None)?
+            .with_address_network(subnet_id.address_network());
+
+        let sk = env::var(FAUCET_PRIVATE_KEY_VAR).map_err(|_| {
+            anyhow!(
+                "{} must be set to a funded private key",
+                FAUCET_PRIVATE_KEY_VAR
+            )
+        })?;
+        let mut faucet = Wallet::new_secp256k1(
+            parse_secret_key(&sk)?,
+            AccountKind::Ethereum,
+            subnet_id.clone(),
+        )?;
+        faucet
+            .init_sequence(&provider)
+            .await
+            .context("failed to get faucet sequence; is the faucet account funded?")?;
+
+        Ok(Self {
+            subnet_id,
+            provider,
+            faucet,
+        })
+    }
+
+    /// Returns the subnet ID.
+    pub fn subnet_id(&self) -> &SubnetID {
+        &self.subnet_id
+    }
+
+    /// Returns a provider for the subnet, including the Object API.
+    pub fn provider(&self) -> &JsonRpcProvider {
+        &self.provider
+    }
+
+    /// Send `amount` from the faucet to `to`.
+    ///
+    /// Sending to a new address creates the account.
+    pub async fn fund(&self, to: Address, amount: TokenAmount) -> anyhow::Result<()> {
+        // Clones share the faucet's sequence, so concurrent funding is safe
+        let mut faucet = self.faucet.clone();
+        TxBuilder::new(to, METHOD_SEND, RawBytes::default(), decode_bytes)
+            .value(amount)
+            .send(&self.provider, &mut faucet)
+            .await?;
+        Ok(())
+    }
+
+    /// Create a new wallet with a random key, funded with `amount` from the faucet.
+    pub async fn new_wallet(&self, amount: TokenAmount) -> anyhow::Result<Wallet> {
+        let mut wallet = Wallet::new_secp256k1(
+            random_secretkey(),
+            AccountKind::Ethereum,
+            self.subnet_id.clone(),
+        )?;
+        self.fund(wallet.address(), amount).await?;
+        wallet.init_sequence(&self.provider).await?;
+        Ok(wallet)
+    }
+}
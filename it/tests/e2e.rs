@@ -0,0 +1,132 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! End-to-end tests against a running subnet.
+//!
+//! These are ignored by default. Run them with:
+//!
+//! ```text
+//! ADM_IT_FAUCET_PRIVATE_KEY=<key> cargo test -p adm_it -- --ignored
+//! ```
+
+use bytes::Bytes;
+use fendermint_vm_message::query::FvmQueryHeight;
+use fvm_shared::econ::TokenAmount;
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+
+use adm_it::TestSubnet;
+use adm_provider::query::QueryProvider;
+use adm_sdk::machine::{accumulator::Accumulator, objectstore::ObjectStore, Machine, WriteAccess};
+use adm_signer::Signer;
+
+/// Funds given to each test wallet.
+fn test_funds() -> TokenAmount {
+    TokenAmount::from_whole(1)
+}
+
+#[tokio::test]
+#[ignore = "requires a running subnet"]
+async fn test_fund_wallet() -> anyhow::Result<()> {
+    let subnet = TestSubnet::connect().await?;
+    let wallet = subnet.new_wallet(test_funds()).await?;
+
+    let state = subnet
+        .provider()
+        .actor_state(&wallet.address(), FvmQueryHeight::Committed)
+        .await?
+        .value
+        .map(|(_, state)| state);
+    assert_eq!(state.map(|s| s.balance), Some(test_funds()));
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "requires a running subnet"]
+async fn test_create_machine() -> anyhow::Result<()> {
+    let subnet = TestSubnet::connect().await?;
+    let provider = subnet.provider();
+    let mut wallet = subnet.new_wallet(test_funds()).await?;
+
+    let (store, _) = ObjectStore::new(
+        provider,
+        &mut wallet,
+        WriteAccess::OnlyOwner,
+        Default::default(),
+    )
+    .await?;
+
+    let stores = ObjectStore::list(provider, &wallet, FvmQueryHeight::Committed).await?;
+    assert!(stores.iter().any(|m| m.address == store.address()));
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "requires a running subnet"]
+async fn test_object_round_trip() -> anyhow::Result<()> {
+    let subnet = TestSubnet::connect().await?;
+    let provider = subnet.provider();
+    let mut wallet = subnet.new_wallet(test_funds()).await?;
+    let (store, _) = ObjectStore::new(
+        provider,
+        &mut wallet,
+        WriteAccess::OnlyOwner,
+        Default::default(),
+    )
+    .await?;
+
+    let data = b"hello from the integration tests".repeat(1024);
+    let mut file = async_tempfile::TempFile::new().await?;
+    file.write_all(&data).await?;
+    file.flush().await?;
+    file.rewind().await?;
+    store
+        .add(provider, &mut wallet, "it/object", file, Default::default())
+        .await?;
+    store
+        .wait_resolved(provider, "it/object", std::time::Duration::from_secs(120))
+        .await?;
+
+    let path = std::env::temp_dir().join(format!("adm-it-{}", store.address()));
+    store
+        .get_to_file(provider, "it/object", &path, true, Default::default())
+        .await?;
+    let downloaded = tokio::fs::read(&path).await?;
+    tokio::fs::remove_file(&path).await?;
+    assert_eq!(downloaded, data);
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "requires a running subnet"]
+async fn test_accumulator_push() -> anyhow::Result<()> {
+    let subnet = TestSubnet::connect().await?;
+    let provider = subnet.provider();
+    let mut wallet = subnet.new_wallet(test_funds()).await?;
+    let (accumulator, _) = Accumulator::new(
+        provider,
+        &mut wallet,
+        WriteAccess::OnlyOwner,
+        Default::default(),
+    )
+    .await?;
+
+    let tx = accumulator
+        .push(
+            provider,
+            &mut wallet,
+            Bytes::from("first"),
+            Default::default(),
+        )
+        .await?;
+    assert_eq!(tx.data.map(|d| d.index), Some(0));
+
+    let leaf = accumulator
+        .leaf(provider, 0, FvmQueryHeight::Committed)
+        .await?;
+    assert_eq!(leaf, b"first".to_vec());
+    let count = accumulator
+        .count(provider, FvmQueryHeight::Committed)
+        .await?;
+    assert_eq!(count, 1);
+    Ok(())
+}
@@ -0,0 +1,189 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Context};
+use serde::{de::DeserializeOwned, Serialize};
+use tendermint::abci::Event;
+
+use crate::response::Cid;
+
+/// The Tendermint event kind Fendermint uses for actor events.
+pub const ACTOR_EVENT_KIND: &str = "event";
+
+/// The attribute holding the ID of the actor that emitted an event.
+const EMITTER_ID_KEY: &str = "emitter.id";
+
+/// The attribute holding the delegated address of the actor that emitted an event, if any.
+const EMITTER_DELEG_KEY: &str = "emitter.deleg";
+
+/// The actor event entry naming a machine event.
+const EVENT_TYPE_KEY: &str = "type";
+
+/// A typed event emitted by a machine actor.
+///
+/// Fendermint turns each actor event into a Tendermint event of kind [`ACTOR_EVENT_KIND`].
+/// The emitter is stored in the `emitter.id` and `emitter.deleg` attributes, and each
+/// event entry becomes an attribute whose value is the hex encoding of the CBOR entry value.
+/// Machine actors name the event with a `type` entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type")]
+pub enum MachineEvent {
+    /// A machine was created by the ADM actor.
+    MachineCreated {
+        /// The address of the new machine.
+        address: String,
+        /// The machine kind, e.g., `ObjectStore` or `Accumulator`.
+        kind: String,
+        /// The machine owner.
+        owner: String,
+    },
+    /// An object was added to an object store.
+    ObjectAdded {
+        /// The emitting machine's address.
+        machine: String,
+        /// The object key.
+        key: String,
+        /// The object CID.
+        cid: Cid,
+        /// The object size in bytes.
+        size: u64,
+    },
+    /// An object was deleted from an object store.
+    ObjectDeleted {
+        /// The emitting machine's address.
+        machine: String,
+        /// The object key.
+        key: String,
+    },
+    /// A leaf was pushed to an accumulator.
+    Pushed {
+        /// The emitting machine's address.
+        machine: String,
+        /// The index of the new leaf.
+        index: u64,
+        /// The new accumulator root.
+        root: Cid,
+    },
+}
+
+impl MachineEvent {
+    /// Parse a machine event from a Tendermint event.
+    ///
+    /// Returns `Ok(None)` if the event is not an actor event or is not a known machine event.
+    pub fn parse(event: &Event) -> anyhow::Result<Option<Self>> {
+        if event.kind != ACTOR_EVENT_KIND {
+            return Ok(None);
+        }
+        let attrs: HashMap<&str, &str> = event
+            .attributes
+            .iter()
+            .map(|a| (a.key.as_str(), a.value.as_str()))
+            .collect();
+        let kind = match attrs.get(EVENT_TYPE_KEY) {
+            Some(v) => decode_value::<String>(EVENT_TYPE_KEY, v)?,
+            None => return Ok(None),
+        };
+        let machine = || emitter(&attrs);
+        let event = match kind.as_str() {
+            "MachineCreated" => MachineEvent::MachineCreated {
+                address: entry(&attrs, "address")?,
+                kind: entry(&attrs, "kind")?,
+                owner: entry(&attrs, "owner")?,
+            },
+            "ObjectAdded" => MachineEvent::ObjectAdded {
+                machine: machine()?,
+                key: entry(&attrs, "key")?,
+                cid: Cid(entry(&attrs, "cid")?),
+                size: entry(&attrs, "size")?,
+            },
+            "ObjectDeleted" => MachineEvent::ObjectDeleted {
+                machine: machine()?,
+                key: entry(&attrs, "key")?,
+            },
+            "Pushed" => MachineEvent::Pushed {
+                machine: machine()?,
+                index: entry(&attrs, "index")?,
+                root: Cid(entry(&attrs, "root")?),
+            },
+            _ => return Ok(None),
+        };
+        Ok(Some(event))
+    }
+}
+
+/// Returns the emitter's delegated address if it has one, otherwise its ID address.
+fn emitter(attrs: &HashMap<&str, &str>) -> anyhow::Result<String> {
+    if let Some(deleg) = attrs.get(EMITTER_DELEG_KEY) {
+        return Ok(deleg.to_string());
+    }
+    let id = attrs
+        .get(EMITTER_ID_KEY)
+        .ok_or_else(|| anyhow!("missing event attribute {}", EMITTER_ID_KEY))?;
+    Ok(format!("f0{}", id))
+}
+
+/// Decode a required event entry.
+fn entry<T: DeserializeOwned>(attrs: &HashMap<&str, &str>, key: &str) -> anyhow::Result<T> {
+    let value = attrs
+        .get(key)
+        .ok_or_else(|| anyhow!("missing event attribute {}", key))?;
+    decode_value(key, value)
+}
+
+/// Decode a hex encoded CBOR entry value.
+fn decode_value<T: DeserializeOwned>(key: &str, value: &str) -> anyhow::Result<T> {
+    let bytes = hex::decode(value).with_context(|| format!("error decoding {} as hex", key))?;
+    fvm_ipld_encoding::from_slice(&bytes).map_err(|e| anyhow!("error decoding {}: {}", key, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use tendermint::abci::EventAttribute;
+
+    use super::*;
+
+    fn attr(key: &str, value: impl Serialize) -> EventAttribute {
+        EventAttribute {
+            key: key.into(),
+            value: hex::encode(fvm_ipld_encoding::to_vec(&value).unwrap()),
+            index: true,
+        }
+    }
+
+    #[test]
+    fn test_parse_machine_event() {
+        let cid = cid::Cid::default();
+        let event = Event {
+            kind: ACTOR_EVENT_KIND.into(),
+            attributes: vec![
+                EventAttribute {
+                    key: EMITTER_ID_KEY.into(),
+                    value: "1001".into(),
+                    index: true,
+                },
+                attr(EVENT_TYPE_KEY, "ObjectAdded"),
+                attr("key", "foo/bar"),
+                attr("cid", cid),
+                attr("size", 42u64),
+            ],
+        };
+        let parsed = MachineEvent::parse(&event).unwrap();
+        assert_eq!(
+            parsed,
+            Some(MachineEvent::ObjectAdded {
+                machine: "f01001".into(),
+                key: "foo/bar".into(),
+                cid: Cid(cid),
+                size: 42,
+            })
+        );
+
+        let other = Event {
+            kind: "message".into(),
+            attributes: vec![],
+        };
+        assert_eq!(MachineEvent::parse(&other).unwrap(), None);
+    }
+}
@@ -0,0 +1,219 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use fendermint_vm_message::chain::ChainMessage;
+use fvm_shared::{econ::TokenAmount, MethodNum};
+use serde::Serialize;
+
+use crate::middleware::{Call, Middleware, Outcome};
+use crate::util::TokenUnit;
+
+/// Gas usage of one kind of operation, aggregated over a session.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct GasUsage {
+    /// The number of calls.
+    pub count: u64,
+    /// The number of calls that failed.
+    pub failed: u64,
+    /// Total gas used by committed transactions.
+    pub gas_used: u64,
+    /// Upper bound of the fees paid by committed transactions,
+    /// i.e., the gas used priced at each transaction's gas fee cap.
+    pub max_fee: TokenAmount,
+    /// Total size of transaction params and uploaded objects in bytes.
+    pub payload_bytes: u64,
+}
+
+impl GasUsage {
+    fn add(&mut self, other: &GasUsage) {
+        self.count += other.count;
+        self.failed += other.failed;
+        self.gas_used += other.gas_used;
+        self.max_fee = TokenAmount::from_atto(self.max_fee.atto() + other.max_fee.atto());
+        self.payload_bytes += other.payload_bytes;
+    }
+}
+
+/// Middleware that aggregates gas usage per operation, e.g., to print a report when a session ends.
+///
+/// Transactions are grouped by method, which are named with [`GasReport::with_method_name`]
+/// or by number otherwise, and Object API uploads are grouped as `upload`.
+/// Gas is only known for transactions broadcast with [`crate::tx::BroadcastMode::Commit`].
+///
+/// Clones share the same totals, so a report can be added to many providers.
+#[derive(Clone, Default)]
+pub struct GasReport {
+    names: HashMap<MethodNum, String>,
+    unit: TokenUnit,
+    usage: Arc<Mutex<BTreeMap<String, GasUsage>>>,
+}
+
+impl GasReport {
+    /// Create an empty report.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Names the operation of transactions that call the given method.
+    pub fn with_method_name(mut self, method: MethodNum, name: impl Into<String>) -> Self {
+        self.names.insert(method, name.into());
+        self
+    }
+
+    /// Formats fees in the given unit instead of FIL.
+    pub fn with_unit(mut self, unit: TokenUnit) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    /// Returns the usage of each operation so far, keyed by operation name.
+    pub fn usage(&self) -> BTreeMap<String, GasUsage> {
+        self.usage.lock().unwrap().clone()
+    }
+
+    /// Returns whether no operations have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.usage.lock().unwrap().is_empty()
+    }
+
+    /// Returns the operation name of a call and its usage,
+    /// or `None` if the call doesn't cost gas or upload data.
+    fn record(&self, call: &Call<'_>, outcome: &Outcome<'_>) -> Option<(String, GasUsage)> {
+        let mut usage = GasUsage {
+            count: 1,
+            failed: outcome.error.is_some() as u64,
+            ..Default::default()
+        };
+        let name = match call {
+            Call::Broadcast {
+                message: ChainMessage::Signed(signed),
+                ..
+            } => {
+                let message = &signed.message;
+                let gas_used = outcome.gas_used.unwrap_or_default().max(0) as u64;
+                usage.gas_used = gas_used;
+                usage.max_fee = TokenAmount::from_atto(message.gas_fee_cap.atto() * gas_used);
+                usage.payload_bytes = message.params.bytes().len() as u64;
+                match self.names.get(&message.method_num) {
+                    Some(name) => name.clone(),
+                    None => format!("method {}", message.method_num),
+                }
+            }
+            Call::Upload { size, .. } => {
+                usage.payload_bytes = *size as u64;
+                "upload".into()
+            }
+            _ => return None,
+        };
+        Some((name, usage))
+    }
+}
+
+#[async_trait]
+impl Middleware for GasReport {
+    async fn after(&self, call: &Call<'_>, outcome: &Outcome<'_>) {
+        if let Some((name, usage)) = self.record(call, outcome) {
+            self.usage
+                .lock()
+                .unwrap()
+                .entry(name)
+                .or_default()
+                .add(&usage);
+        }
+    }
+}
+
+/// Formats the report as a table with a row per operation and a total row.
+impl Display for GasReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let usage = self.usage();
+        let mut total = GasUsage::default();
+        for u in usage.values() {
+            total.add(u);
+        }
+        let row = |name: &str, u: &GasUsage| {
+            [
+                name.to_string(),
+                u.count.to_string(),
+                u.failed.to_string(),
+                u.gas_used.to_string(),
+                self.unit.format(&u.max_fee),
+                u.payload_bytes.to_string(),
+            ]
+        };
+        let mut rows = vec![[
+            "operation".to_string(),
+            "count".into(),
+            "failed".into(),
+            "gas used".into(),
+            format!("max fee ({})", self.unit),
+            "payload bytes".into(),
+        ]];
+        rows.extend(usage.iter().map(|(name, u)| row(name, u)));
+        rows.push(row("total", &total));
+
+        let mut widths = [0; 6];
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+        for row in rows {
+            // Left-align operation names and right-align numbers
+            write!(f, "{:<width$}", row[0], width = widths[0])?;
+            for (cell, width) in row.iter().zip(widths).skip(1) {
+                write!(f, "  {:>width$}", cell, width = width)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_gas_report() {
+        let report = GasReport::new();
+        let shared = report.clone();
+        let call = Call::Upload {
+            size: 100,
+            chain_id: 1,
+        };
+        let ok = Outcome {
+            elapsed: Default::default(),
+            error: None,
+            gas_used: None,
+        };
+        shared.after(&call, &ok).await;
+        let error = anyhow::anyhow!("failed");
+        let failed = Outcome {
+            error: Some(&error),
+            ..ok
+        };
+        shared.after(&call, &failed).await;
+        shared.after(&Call::ChainHead, &ok).await;
+
+        let usage = report.usage();
+        assert_eq!(usage.len(), 1);
+        assert_eq!(
+            usage["upload"],
+            GasUsage {
+                count: 2,
+                failed: 1,
+                payload_bytes: 200,
+                ..Default::default()
+            }
+        );
+        let table = report.to_string();
+        assert_eq!(table.lines().count(), 3);
+        assert!(table.lines().last().unwrap().starts_with("total"));
+    }
+}
@@ -3,7 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use std::fmt::Display;
+use std::future::Future;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Context};
 use async_trait::async_trait;
@@ -11,46 +14,431 @@ use fendermint_vm_message::{
     chain::ChainMessage,
     query::{FvmQuery, FvmQueryHeight},
 };
-use fvm_shared::address::Address;
+use fvm_shared::address::{Address, Network};
 use reqwest::multipart::{Form, Part};
+use reqwest::StatusCode;
+use sha2::{Digest, Sha256};
 use tendermint::abci::response::DeliverTx;
 use tendermint::block::Height;
+use tendermint::{Hash, Time};
 use tendermint_rpc::{
-    endpoint::abci_query::AbciQuery, Client, HttpClient, Scheme, Url, WebSocketClient,
-    WebSocketClientDriver, WebSocketClientUrl,
+    endpoint::{abci_query::AbciQuery, tx},
+    error::ErrorDetail,
+    query::Query,
+    response_error::Code,
+    Client, HttpClient, Order, Scheme, Url, WebSocketClient, WebSocketClientDriver,
+    WebSocketClientUrl,
 };
 
-use crate::object::ObjectProvider;
-use crate::query::QueryProvider;
+use crate::middleware::{Call, Middleware, Outcome};
+use crate::object::{ObjectApiVersion, ObjectProvider, UploadError, UploadErrorKind, UploadFields};
+use crate::probe::{ObjectApiProbe, Probe, RpcProbe};
+use crate::query::{ChainBlock, ChainHead, ChainTx, QueryProvider};
 use crate::response::Cid;
-use crate::tx::{BroadcastMode, TxProvider, TxReceipt};
+use crate::tx::{BroadcastMode, TxError, TxEvent, TxProvider, TxReceipt};
+use crate::util::{format_address, is_not_found};
 use crate::{Provider, TendermintClient};
 
+/// How long to look for a transaction after its commit broadcast timed out.
+const COMMIT_SEARCH_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Delay between lookups of a transaction after its commit broadcast timed out.
+const COMMIT_SEARCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Error data CometBFT responds with when a commit broadcast times out.
+const COMMIT_TIMEOUT_DATA: &str = "timed out waiting for tx to be included in a block";
+
+/// Options for the Object API client's connection pool.
+///
+/// The defaults are tuned for many concurrent uploads and downloads to the same host:
+/// connections are kept alive and, if the server supports it, HTTP/2 is negotiated so that
+/// requests are multiplexed over a few TLS connections instead of opening one per request.
+#[derive(Clone, Debug)]
+pub struct ObjectClientOptions {
+    /// Max number of idle connections kept alive per host.
+    pub pool_max_idle_per_host: usize,
+    /// How long an idle connection is kept in the pool.
+    pub pool_idle_timeout: Duration,
+    /// TCP keep-alive interval.
+    pub tcp_keepalive: Duration,
+    /// Only use HTTP/1.1, disabling HTTP/2 negotiation.
+    pub http1_only: bool,
+    /// Use adaptive HTTP/2 flow control windows, which helps throughput on high-latency links.
+    pub http2_adaptive_window: bool,
+    /// Interval for HTTP/2 keep-alive pings. `None` disables pings.
+    pub http2_keep_alive_interval: Option<Duration>,
+    /// Object API version.
+    /// If `None`, the newest known version is used until it's negotiated with
+    /// [`JsonRpcProvider::with_negotiated_object_api`].
+    pub api_version: Option<ObjectApiVersion>,
+    /// Upload form field names.
+    /// If `None`, the API version's field names are used.
+    pub upload_fields: Option<UploadFields>,
+}
+
+impl Default for ObjectClientOptions {
+    fn default() -> Self {
+        Self {
+            pool_max_idle_per_host: 256,
+            pool_idle_timeout: Duration::from_secs(90),
+            tcp_keepalive: Duration::from_secs(60),
+            http1_only: false,
+            http2_adaptive_window: true,
+            http2_keep_alive_interval: Some(Duration::from_secs(30)),
+            api_version: None,
+            upload_fields: None,
+        }
+    }
+}
+
 /// A JSON RPC ADM chain provider.
+///
+/// Cloning is cheap: clones share the same underlying connection pools,
+/// so a single provider can be cloned into many concurrent tasks without creating new connections.
 #[derive(Clone)]
 pub struct JsonRpcProvider<C = HttpClient> {
     inner: C,
-    objects: Option<ObjectClient>,
+    objects: Option<Arc<ObjectClient>>,
+    middleware: Vec<Arc<dyn Middleware>>,
 }
 
 #[derive(Clone)]
 struct ObjectClient {
     inner: reqwest::Client,
-    url: Url,
+    url: reqwest::Url,
+    auth_token: Option<String>,
+    address_network: Option<Network>,
+    api_version: ObjectApiVersion,
+    /// Whether the API version can be negotiated, i.e., it wasn't set in the options.
+    negotiable: bool,
+    upload_fields: Option<UploadFields>,
+}
+
+impl ObjectClient {
+    /// Create a new Object API client with a connection pool tuned for concurrent use.
+    fn new(url: Url, options: ObjectClientOptions) -> anyhow::Result<Self> {
+        let mut builder = reqwest::Client::builder()
+            .pool_max_idle_per_host(options.pool_max_idle_per_host)
+            .pool_idle_timeout(options.pool_idle_timeout)
+            .tcp_keepalive(options.tcp_keepalive)
+            .tcp_nodelay(true);
+        if options.http1_only {
+            builder = builder.http1_only();
+        } else {
+            builder = builder
+                .http2_adaptive_window(options.http2_adaptive_window)
+                .http2_keep_alive_interval(options.http2_keep_alive_interval)
+                .http2_keep_alive_while_idle(true);
+        }
+        let inner = builder.build().context("failed to create object client")?;
+        let url = reqwest::Url::parse(&url.to_string()).context("invalid object API URL")?;
+        if url.cannot_be_a_base() {
+            return Err(anyhow!("invalid object API URL: {}", url));
+        }
+        Ok(Self {
+            inner,
+            url,
+            auth_token: None,
+            address_network: None,
+            api_version: options.api_version.unwrap_or(ObjectApiVersion::ALL[0]),
+            negotiable: options.api_version.is_none(),
+            upload_fields: options.upload_fields,
+        })
+    }
+
+    /// Finds the newest API version the node supports by probing each version's health endpoint.
+    async fn negotiate(&self) -> anyhow::Result<ObjectApiVersion> {
+        for version in ObjectApiVersion::ALL {
+            let url = self.endpoint(*version, ["health"]);
+            let response = self
+                .authorize(self.inner.get(url), None)
+                .send()
+                .await
+                .with_context(|| format!("failed to reach object API at {}", self.url))?;
+            if response.status() != StatusCode::NOT_FOUND {
+                tracing::debug!("Using object API {} at {}", version, self.url);
+                return Ok(*version);
+            }
+        }
+        // Nodes that predate the health endpoint only serve v1
+        tracing::warn!(
+            "Object API at {} has no health endpoint; assuming {}",
+            self.url,
+            ObjectApiVersion::V1
+        );
+        Ok(ObjectApiVersion::V1)
+    }
+
+    /// Builds an endpoint URL from path segments under the given API version.
+    ///
+    /// Segments are percent-encoded, so object keys can contain characters like `?` and `#`.
+    fn endpoint<'a>(
+        &self,
+        version: ObjectApiVersion,
+        segments: impl IntoIterator<Item = &'a str>,
+    ) -> reqwest::Url {
+        let mut url = self.url.clone();
+        url.path_segments_mut()
+            .expect("object API URL is a base")
+            .pop_if_empty()
+            .push(version.path())
+            .extend(segments);
+        url
+    }
+
+    /// Builds the URL of an object at the given height.
+    fn object_url(&self, address: Address, key: &str, height: u64) -> reqwest::Url {
+        let address = match self.address_network {
+            Some(network) => format_address(&address, network),
+            None => address.to_string(),
+        };
+        let mut url = self.endpoint(
+            self.api_version,
+            ["objects", address.as_str()]
+                .into_iter()
+                .chain(key.split('/')),
+        );
+        url.query_pairs_mut()
+            .append_pair("height", &height.to_string());
+        url
+    }
+
+    /// Adds a bearer authorization header to the request.
+    /// The `auth_token` overrides the client's default token if given.
+    fn authorize(
+        &self,
+        request: reqwest::RequestBuilder,
+        auth_token: Option<String>,
+    ) -> reqwest::RequestBuilder {
+        match auth_token.or_else(|| self.auth_token.clone()) {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
 }
 
 impl JsonRpcProvider<HttpClient> {
+    /// Create a new provider over HTTP.
+    ///
+    /// The Object API client is only created if `object_url` is given.
+    /// `object_options` configures its connection pool; the defaults are used if `None`.
     pub fn new_http(
         url: Url,
         proxy_url: Option<Url>,
         object_url: Option<Url>,
+        object_options: Option<ObjectClientOptions>,
     ) -> anyhow::Result<Self> {
         let inner = http_client(url, proxy_url)?;
-        let objects = object_url.map(|url| ObjectClient {
-            inner: reqwest::Client::new(),
-            url,
-        });
-        Ok(Self { inner, objects })
+        let objects = object_url
+            .map(|url| ObjectClient::new(url, object_options.unwrap_or_default()).map(Arc::new))
+            .transpose()?;
+        Ok(Self {
+            inner,
+            objects,
+            middleware: Vec::new(),
+        })
+    }
+}
+
+impl<C> JsonRpcProvider<C> {
+    /// Sets a bearer token sent with every Object API request,
+    /// for deployments that front the Object API with authentication.
+    ///
+    /// This has no effect if the provider was created without an Object API URL.
+    pub fn with_object_auth_token(mut self, auth_token: Option<String>) -> Self {
+        if let Some(objects) = self.objects.as_mut() {
+            Arc::make_mut(objects).auth_token = auth_token;
+        }
+        self
+    }
+
+    /// Sets the network used to format addresses in Object API requests,
+    /// instead of the global network set with [`fvm_shared::address::set_current_network`].
+    ///
+    /// This has no effect if the provider was created without an Object API URL.
+    pub fn with_address_network(mut self, network: Network) -> Self {
+        if let Some(objects) = self.objects.as_mut() {
+            Arc::make_mut(objects).address_network = Some(network);
+        }
+        self
+    }
+
+    /// Adds middleware that intercepts every query, broadcast, and Object API call.
+    ///
+    /// Middleware runs in the order it's added. See [`Middleware`] for details.
+    pub fn with_middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// Negotiates the Object API version with the node once, so every later request uses the
+    /// newest version both support.
+    ///
+    /// Each known version's health endpoint is probed, newest first.
+    /// Nodes that predate the health endpoint only serve v1, so it's used if none respond.
+    /// This has no effect if the provider was created without an Object API URL,
+    /// or if the version was set in [`ObjectClientOptions`].
+    /// Call it after [`JsonRpcProvider::with_object_auth_token`] if the Object API requires a token.
+    pub async fn with_negotiated_object_api(mut self) -> anyhow::Result<Self> {
+        if let Some(objects) = self.objects.as_mut() {
+            if objects.negotiable {
+                let version = objects.negotiate().await?;
+                let objects = Arc::make_mut(objects);
+                objects.api_version = version;
+                objects.negotiable = false;
+            }
+        }
+        Ok(self)
+    }
+
+    /// Returns the Object API version used by the provider.
+    pub fn object_api_version(&self) -> anyhow::Result<ObjectApiVersion> {
+        Ok(self.object_client()?.api_version)
+    }
+
+    /// Probes the CometBFT RPC and, if configured, the Object API concurrently,
+    /// returning their round-trip latencies, the node's sync status, and versions.
+    ///
+    /// Probes bypass middleware, so latencies are the endpoints' own.
+    pub async fn probe(&self) -> Probe
+    where
+        C: Client + Sync + Send,
+    {
+        let object_api = async {
+            match self.objects {
+                Some(_) => Some(self.probe_object_api().await),
+                None => None,
+            }
+        };
+        let (rpc, object_api) = tokio::join!(self.probe_rpc(), object_api);
+        Probe { rpc, object_api }
+    }
+
+    /// Probes the CometBFT RPC with a status request.
+    pub async fn probe_rpc(&self) -> anyhow::Result<RpcProbe>
+    where
+        C: Client + Sync + Send,
+    {
+        let started = Instant::now();
+        let status = self.inner.status().await?;
+        let latency = started.elapsed();
+        let info = self.inner.abci_info().await?;
+        Ok(RpcProbe {
+            latency,
+            chain_id: status.node_info.network,
+            node_version: status.node_info.version.to_string(),
+            app_version: info.version,
+            height: status.sync_info.latest_block_height,
+            time: status.sync_info.latest_block_time,
+            catching_up: status.sync_info.catching_up,
+        })
+    }
+
+    /// Probes the Object API with a health request.
+    pub async fn probe_object_api(&self) -> anyhow::Result<ObjectApiProbe> {
+        let client = self.object_client()?;
+        let api_version = client.api_version;
+        let url = client.endpoint(api_version, ["health"]);
+        let started = Instant::now();
+        let response = client
+            .authorize(client.inner.get(url), None)
+            .send()
+            .await
+            .with_context(|| format!("failed to reach object API at {}", client.url))?;
+        let latency = started.elapsed();
+        let status = response.status();
+        Ok(ObjectApiProbe {
+            latency,
+            api_version,
+            status: status.as_u16(),
+            healthy: status.is_success(),
+        })
+    }
+
+    /// Returns the Object API client if one was configured.
+    fn object_client(&self) -> anyhow::Result<&ObjectClient> {
+        self.objects
+            .as_deref()
+            .ok_or_else(|| anyhow!("object provider is required"))
+    }
+
+    /// Runs a call through the middleware.
+    async fn intercept<T>(
+        &self,
+        call: &Call<'_>,
+        f: impl Future<Output = anyhow::Result<T>>,
+    ) -> anyhow::Result<T> {
+        self.intercept_with(call, f, |_| None).await
+    }
+
+    /// Runs a call through the middleware, reporting the gas used by its result.
+    async fn intercept_with<T>(
+        &self,
+        call: &Call<'_>,
+        f: impl Future<Output = anyhow::Result<T>>,
+        gas_used: impl FnOnce(&T) -> Option<i64>,
+    ) -> anyhow::Result<T> {
+        for middleware in &self.middleware {
+            middleware.before(call).await?;
+        }
+        let started = Instant::now();
+        let result = f.await;
+        let outcome = Outcome {
+            elapsed: started.elapsed(),
+            error: result.as_ref().err(),
+            gas_used: result.as_ref().ok().and_then(gas_used),
+        };
+        for middleware in self.middleware.iter().rev() {
+            middleware.after(call, &outcome).await;
+        }
+        result
+    }
+
+    /// Finds the result of a transaction whose commit broadcast timed out.
+    ///
+    /// The transaction is looked up by hash until it's committed.
+    /// If it isn't found at first, the same signed transaction is broadcast once more in case
+    /// the node dropped it. This can't execute it twice, since its sequence (nonce) can only
+    /// be used once, and the node rejects it if it's still in the mempool.
+    /// Finding the transaction requires the node to index transactions.
+    async fn find_commit(&self, data: Vec<u8>) -> anyhow::Result<tx::Response>
+    where
+        C: Client + Sync + Send,
+    {
+        let hash = tx_hash(&data);
+        let started = Instant::now();
+        let mut data = Some(data);
+        loop {
+            if let Ok(response) = self.inner.tx(hash, false).await {
+                return Ok(response);
+            }
+            if let Some(data) = data.take() {
+                // Otherwise, it's still in the mempool, or it was executed but isn't indexed yet
+                let response = self.inner.broadcast_tx_sync(data).await;
+                if response.is_ok_and(|response| response.code.is_ok()) {
+                    tracing::warn!("Transaction {} was dropped; rebroadcasting...", hash);
+                }
+            }
+            if started.elapsed() >= COMMIT_SEARCH_TIMEOUT {
+                return Err(anyhow!(
+                    "timed out waiting for transaction {} to be committed; \
+                    check its status before resubmitting",
+                    hash
+                ));
+            }
+            tokio::time::sleep(COMMIT_SEARCH_INTERVAL).await;
+        }
+    }
+
+    /// Applies the middleware to an Object API request.
+    fn object_request(
+        &self,
+        call: &Call<'_>,
+        request: reqwest::RequestBuilder,
+    ) -> reqwest::RequestBuilder {
+        self.middleware.iter().fold(request, |request, middleware| {
+            middleware.object_request(call, request)
+        })
     }
 }
 
@@ -71,14 +459,103 @@ where
     C: Client + Sync + Send,
 {
     async fn query(&self, query: FvmQuery, height: FvmQueryHeight) -> anyhow::Result<AbciQuery> {
-        let data = fvm_ipld_encoding::to_vec(&query).context("failed to encode query")?;
-        let height: u64 = height.into();
-        let height = Height::try_from(height).context("failed to conver to Height")?;
-        let res = self
-            .inner
-            .abci_query(None, data, Some(height), false)
-            .await?;
-        Ok(res)
+        let call = Call::Query {
+            query: &query,
+            height,
+        };
+        self.intercept(&call, async {
+            let data = fvm_ipld_encoding::to_vec(&query).context("failed to encode query")?;
+            let height: u64 = height.into();
+            let height = Height::try_from(height).context("failed to conver to Height")?;
+            let res = self
+                .inner
+                .abci_query(None, data, Some(height), false)
+                .await?;
+            Ok(res)
+        })
+        .await
+    }
+
+    async fn chain_head(&self) -> anyhow::Result<ChainHead> {
+        self.intercept(&Call::ChainHead, async {
+            let status = self.inner.status().await?;
+            Ok(ChainHead {
+                height: status.sync_info.latest_block_height,
+                hash: status.sync_info.latest_block_hash,
+                time: status.sync_info.latest_block_time,
+                catching_up: status.sync_info.catching_up,
+            })
+        })
+        .await
+    }
+
+    async fn block_time(&self, height: u64) -> anyhow::Result<Time> {
+        self.intercept(&Call::BlockTime { height }, async {
+            let height = Height::try_from(height).context("failed to conver to Height")?;
+            let res = self.inner.block(height).await?;
+            Ok(res.block.header.time)
+        })
+        .await
+    }
+
+    async fn block(&self, height: u64) -> anyhow::Result<ChainBlock> {
+        self.intercept(&Call::Block { height }, async {
+            let height = Height::try_from(height).context("failed to conver to Height")?;
+            let res = self.inner.block(height).await?;
+            let results = self
+                .inner
+                .block_results(height)
+                .await?
+                .txs_results
+                .unwrap_or_default();
+            let txs = res
+                .block
+                .data
+                .iter()
+                .enumerate()
+                .map(|(index, data)| {
+                    let result = results.get(index).cloned();
+                    ChainTx::decode(tx_hash(data), height, index as u32, data, result)
+                })
+                .collect();
+            Ok(ChainBlock {
+                height,
+                hash: res.block_id.hash,
+                time: res.block.header.time,
+                proposer: res.block.header.proposer_address,
+                txs,
+            })
+        })
+        .await
+    }
+
+    async fn tx_by_hash(&self, hash: Hash) -> anyhow::Result<Option<ChainTx>> {
+        self.intercept(&Call::Tx { hash }, async {
+            match self.inner.tx(hash, false).await {
+                Ok(res) => Ok(Some(chain_tx(res))),
+                Err(e) if is_not_found(&e) => Ok(None),
+                Err(e) => Err(e.into()),
+            }
+        })
+        .await
+    }
+
+    async fn search_txs(
+        &self,
+        query: &str,
+        page: u32,
+        per_page: u8,
+    ) -> anyhow::Result<Vec<ChainTx>> {
+        self.intercept(&Call::SearchTxs { query }, async {
+            let parsed =
+                Query::from_str(query).map_err(|e| anyhow!("invalid query '{}': {}", query, e))?;
+            let res = self
+                .inner
+                .tx_search(parsed, false, page, per_page, Order::Descending)
+                .await?;
+            Ok(res.txs.into_iter().map(chain_tx).collect())
+        })
+        .await
     }
 }
 
@@ -97,47 +574,61 @@ where
         F: FnOnce(&DeliverTx) -> anyhow::Result<T> + Sync + Send,
         T: Sync + Send,
     {
-        match broadcast_mode {
-            BroadcastMode::Async => {
-                let data = crate::message::serialize(&message)?;
-                let response = self.inner.broadcast_tx_async(data).await?;
+        let call = Call::Broadcast {
+            message: &message,
+            mode: broadcast_mode,
+        };
+        let broadcast = async {
+            match broadcast_mode {
+                BroadcastMode::Async => {
+                    let data = crate::message::serialize(&message)?;
+                    let response = self.inner.broadcast_tx_async(data).await?;
 
-                Ok(TxReceipt::pending(response.hash))
-            }
-            BroadcastMode::Sync => {
-                let data = crate::message::serialize(&message)?;
-                let response = self.inner.broadcast_tx_sync(data).await?;
-                if response.code.is_err() {
-                    return Err(anyhow!(response.log));
+                    Ok(TxReceipt::pending(response.hash))
                 }
-                Ok(TxReceipt::pending(response.hash))
-            }
-            BroadcastMode::Commit => {
-                let data = crate::message::serialize(&message)?;
-                let response = self.inner.broadcast_tx_commit(data).await?;
-                if response.check_tx.code.is_err() {
-                    return Err(anyhow!(format_err(
-                        &response.check_tx.info,
-                        &response.check_tx.log
-                    )));
-                } else if response.deliver_tx.code.is_err() {
-                    return Err(anyhow!(format_err(
-                        &response.deliver_tx.info,
-                        &response.deliver_tx.log
-                    )));
+                BroadcastMode::Sync => {
+                    let data = crate::message::serialize(&message)?;
+                    let response = self.inner.broadcast_tx_sync(data).await?;
+                    if response.code.is_err() {
+                        return Err(TxError {
+                            code: response.code.value(),
+                            message: response.log,
+                        }
+                        .into());
+                    }
+                    Ok(TxReceipt::pending(response.hash))
+                }
+                BroadcastMode::Commit => {
+                    let data = crate::message::serialize(&message)?;
+                    let response = match self.inner.broadcast_tx_commit(data.clone()).await {
+                        Ok(response) => response,
+                        Err(e) if is_timeout(&e) => {
+                            // The transaction may or may not have landed,
+                            // so look for it before anyone resubmits a new one
+                            tracing::warn!("Broadcast timed out; checking for the transaction...");
+                            let response = self.find_commit(data).await?;
+                            return commit_receipt(
+                                response.hash,
+                                response.height,
+                                &response.tx_result,
+                                f,
+                            );
+                        }
+                        Err(e) => return Err(e.into()),
+                    };
+                    if response.check_tx.code.is_err() {
+                        return Err(TxError {
+                            code: response.check_tx.code.value(),
+                            message: format_err(&response.check_tx.info, &response.check_tx.log),
+                        }
+                        .into());
+                    }
+                    commit_receipt(response.hash, response.height, &response.deliver_tx, f)
                 }
-
-                let return_data = f(&response.deliver_tx)
-                    .context("error decoding data from deliver_tx in commit")?;
-
-                Ok(TxReceipt::committed(
-                    response.hash,
-                    response.height,
-                    response.deliver_tx.gas_used,
-                    Some(return_data),
-                ))
             }
-        }
+        };
+        let gas_used = |receipt: &TxReceipt<T>| receipt.height.map(|_| receipt.gas_used);
+        self.intercept_with(&call, broadcast, gas_used).await
     }
 }
 
@@ -152,34 +643,53 @@ where
         total_bytes: usize,
         msg: String,
         chain_id: u64,
+        auth_token: Option<String>,
     ) -> anyhow::Result<Cid> {
-        let client = self
-            .objects
-            .clone()
-            .ok_or_else(|| anyhow!("object provider is required"))?;
-
-        let part = Part::stream_with_length(body, total_bytes as u64)
-            .file_name("upload")
-            .mime_str("application/octet-stream")?;
-
-        let form = Form::new()
-            .text("chain_id", chain_id.to_string())
-            .text("msg", msg)
-            .part("object", part);
-
-        let url = format!("{}v1/objects", client.url);
-        let response = client.inner.post(url).multipart(form).send().await?;
-        if !response.status().is_success() {
-            return Err(anyhow!(format!(
-                "failed to upload object: {}",
-                response.text().await?
-            )));
-        }
+        let call = Call::Upload {
+            size: total_bytes,
+            chain_id,
+        };
+        self.intercept(&call, async {
+            let client = self.object_client()?;
+            let version = client.api_version;
+            let fields = client
+                .upload_fields
+                .clone()
+                .unwrap_or_else(|| version.upload_fields());
+
+            let part = Part::stream_with_length(body, total_bytes as u64)
+                .file_name("upload")
+                .mime_str("application/octet-stream")?;
 
-        let cid_str = response.text().await?;
-        let cid = Cid::from_str(&cid_str)?;
+            let form = Form::new()
+                .text(fields.chain_id, chain_id.to_string())
+                .text(fields.msg, msg)
+                .part(fields.object, part);
 
-        Ok(cid)
+            let url = client.endpoint(version, ["objects"]);
+            let request = client
+                .authorize(client.inner.post(url.clone()), auth_token)
+                .multipart(form);
+            let response = self.object_request(&call, request).send().await?;
+            if !response.status().is_success() {
+                let status = response.status();
+                let headers = response.headers().clone();
+                let mut err = UploadError::from_response(status, &headers, response.text().await?);
+                if err.kind == UploadErrorKind::EndpointNotFound {
+                    err.message = format!(
+                        "{} not found; the node may not support object API {}",
+                        url, version
+                    );
+                }
+                return Err(err.into());
+            }
+
+            let cid_str = response.text().await?;
+            let cid = Cid::from_str(&cid_str)?;
+
+            Ok(cid)
+        })
+        .await
     }
 
     async fn download(
@@ -188,58 +698,139 @@ where
         key: &str,
         range: Option<String>,
         height: u64,
+        auth_token: Option<String>,
     ) -> anyhow::Result<reqwest::Response> {
-        let client = self
-            .objects
-            .clone()
-            .ok_or_else(|| anyhow!("object provider is required"))?;
-
-        let url = format!("{}v1/objects/{}/{}?height={}", client.url, address, key, height);
-        let response = if let Some(range) = range {
-            client
-                .inner
-                .get(url)
-                .header("Range", format!("bytes={}", range))
-                .send()
-                .await?
-        } else {
-            client.inner.get(url).send().await?
+        let call = Call::Download {
+            address,
+            key,
+            range: range.as_deref(),
+            height,
         };
-        if !response.status().is_success() {
-            return Err(anyhow!(format!(
-                "failed to download object: {}",
-                response.text().await?
-            )));
-        }
+        self.intercept(&call, async {
+            let client = self.object_client()?;
+
+            let url = client.object_url(address, key, height);
+            let request = client.authorize(client.inner.get(url), auth_token);
+            let request = self.object_request(&call, request);
+            let response = if let Some(range) = &range {
+                request
+                    .header("Range", format!("bytes={}", range))
+                    .send()
+                    .await?
+            } else {
+                request.send().await?
+            };
+            if !response.status().is_success() {
+                return Err(anyhow!(format!(
+                    "failed to download object: {}",
+                    response.text().await?
+                )));
+            }
 
-        Ok(response)
+            Ok(response)
+        })
+        .await
     }
 
-    async fn size(&self, address: Address, key: &str, height: u64) -> anyhow::Result<usize> {
-        let client = self
-            .objects
-            .clone()
-            .ok_or_else(|| anyhow!("object provider is required"))?;
+    async fn size(
+        &self,
+        address: Address,
+        key: &str,
+        height: u64,
+        auth_token: Option<String>,
+    ) -> anyhow::Result<usize> {
+        let call = Call::Size {
+            address,
+            key,
+            height,
+        };
+        self.intercept(&call, async {
+            let client = self.object_client()?;
+
+            let url = client.object_url(address, key, height);
+            let request = client.authorize(client.inner.head(url), auth_token);
+            let response = self.object_request(&call, request).send().await?;
+            if !response.status().is_success() {
+                return Err(anyhow!(format!(
+                    "failed to get object size: {}",
+                    response.text().await?
+                )));
+            }
+
+            let size: usize = response
+                .headers()
+                .get("content-length")
+                .ok_or_else(|| {
+                    anyhow!("missing content-length header in response for object size")
+                })?
+                .to_str()?
+                .parse()?;
+            Ok(size)
+        })
+        .await
+    }
+}
 
-        let url = format!("{}v1/objects/{}/{}?height={}", client.url, address, key, height);
-        let response = client.inner.head(url).send().await?;
-        if !response.status().is_success() {
-            return Err(anyhow!(format!(
-                "failed to get object size: {}",
-                response.text().await?
-            )));
+/// Builds the receipt of a committed transaction, or returns its delivery error.
+fn commit_receipt<F, T>(
+    hash: Hash,
+    height: Height,
+    deliver_tx: &DeliverTx,
+    f: F,
+) -> anyhow::Result<TxReceipt<T>>
+where
+    F: FnOnce(&DeliverTx) -> anyhow::Result<T>,
+{
+    if deliver_tx.code.is_err() {
+        return Err(TxError {
+            code: deliver_tx.code.value(),
+            message: format_err(&deliver_tx.info, &deliver_tx.log),
         }
+        .into());
+    }
+
+    let return_data = f(deliver_tx).context("error decoding data from deliver_tx in commit")?;
 
-        let size: usize = response
-            .headers()
-            .get("content-length")
-            .ok_or_else(|| anyhow!("missing content-length header in response for object size"))?
-            .to_str()?
-            .parse()?;
-        Ok(size)
+    Ok(TxReceipt::committed(
+        hash,
+        height,
+        deliver_tx.gas_used,
+        Some(return_data),
+        deliver_tx.events.iter().map(TxEvent::from).collect(),
+    ))
+}
+
+/// Returns whether an RPC error is a timeout, e.g., the node gave up waiting for a
+/// transaction to be committed.
+fn is_timeout(err: &tendermint_rpc::Error) -> bool {
+    match err.detail() {
+        ErrorDetail::Timeout(_) | ErrorDetail::WebSocketTimeout(_) => true,
+        ErrorDetail::Hyper(e) => e.source.is_timeout(),
+        ErrorDetail::Io(e) => e.source.kind() == std::io::ErrorKind::TimedOut,
+        // CometBFT reports a commit timeout as an internal error, with the reason as data
+        ErrorDetail::Response(e) => {
+            e.source.code() == Code::InternalError && e.source.data() == Some(COMMIT_TIMEOUT_DATA)
+        }
+        _ => false,
     }
 }
 
+/// Decodes a transaction looked up by hash or found by a search.
+fn chain_tx(res: tx::Response) -> ChainTx {
+    ChainTx::decode(
+        res.hash,
+        res.height,
+        res.index,
+        &res.tx,
+        Some(res.tx_result),
+    )
+}
+
+/// Returns the CometBFT hash of a transaction.
+fn tx_hash(data: &[u8]) -> Hash {
+    Hash::Sha256(Sha256::digest(data).into())
+}
+
 /// Format transaction receipt errors.
 fn format_err(info: &str, log: &str) -> String {
     if log.is_empty() {
@@ -311,3 +902,103 @@ where
         .with_context(|| format!("failed to create WS client to: {}", url))?;
     Ok((client, driver))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_object_url() {
+        let options = ObjectClientOptions {
+            api_version: Some(ObjectApiVersion::V1),
+            ..Default::default()
+        };
+        let client =
+            ObjectClient::new(Url::from_str("http://localhost:8001").unwrap(), options).unwrap();
+        let address = Address::new_id(1001);
+
+        let url = client.object_url(address, "foo/bar baz?", 10);
+        assert_eq!(
+            url.as_str(),
+            "http://localhost:8001/v1/objects/f01001/foo/bar%20baz%3F?height=10"
+        );
+        let testnet = ObjectClient {
+            address_network: Some(Network::Testnet),
+            ..client.clone()
+        };
+        let url = testnet.object_url(address, "foo", 10);
+        assert_eq!(
+            url.as_str(),
+            "http://localhost:8001/v1/objects/t01001/foo?height=10"
+        );
+        assert_eq!(
+            client.endpoint(ObjectApiVersion::V1, ["objects"]).as_str(),
+            "http://localhost:8001/v1/objects"
+        );
+    }
+
+    struct Recorder {
+        name: &'static str,
+        calls: Arc<std::sync::Mutex<Vec<String>>>,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl Middleware for Recorder {
+        async fn before(&self, call: &Call<'_>) -> anyhow::Result<()> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("{} before {}", self.name, call.name()));
+            if self.fail {
+                return Err(anyhow!("injected fault"));
+            }
+            Ok(())
+        }
+
+        async fn after(&self, call: &Call<'_>, outcome: &Outcome<'_>) {
+            self.calls.lock().unwrap().push(format!(
+                "{} after {} failed={}",
+                self.name,
+                call.name(),
+                outcome.error.is_some()
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_middleware() {
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorder = |name, fail| Recorder {
+            name,
+            calls: calls.clone(),
+            fail,
+        };
+        // Nothing listens on port 1, so calls that reach the client fail
+        let url = Url::from_str("http://127.0.0.1:1").unwrap();
+        let provider = JsonRpcProvider::new_http(url.clone(), None, None, None)
+            .unwrap()
+            .with_middleware(recorder("a", false))
+            .with_middleware(recorder("b", false));
+        assert!(provider.chain_head().await.is_err());
+        assert_eq!(
+            *calls.lock().unwrap(),
+            [
+                "a before chain_head",
+                "b before chain_head",
+                "b after chain_head failed=true",
+                "a after chain_head failed=true",
+            ]
+        );
+
+        // A failing before hook aborts the call
+        calls.lock().unwrap().clear();
+        let provider = JsonRpcProvider::new_http(url, None, None, None)
+            .unwrap()
+            .with_middleware(recorder("a", true))
+            .with_middleware(recorder("b", false));
+        let err = provider.block_time(1).await.unwrap_err();
+        assert_eq!(err.to_string(), "injected fault");
+        assert_eq!(*calls.lock().unwrap(), ["a before block_time"]);
+    }
+}
@@ -4,13 +4,23 @@
 //! # ADM Provider
 //!
 //! A chain and object provider for the ADM.
+//!
+//! ## Features
+//!
+//! - `otel`: OpenTelemetry export of provider calls with [`telemetry::OtelMiddleware`].
 
+pub mod event;
+pub mod gas;
 pub mod json_rpc;
 pub mod message;
+pub mod middleware;
 pub mod object;
+pub mod probe;
 mod provider;
 pub mod query;
 pub mod response;
+#[cfg(feature = "otel")]
+pub mod telemetry;
 pub mod tx;
 pub mod util;
 
@@ -0,0 +1,121 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use fendermint_vm_message::{
+    chain::ChainMessage,
+    query::{FvmQuery, FvmQueryHeight},
+};
+use fvm_shared::address::Address;
+use tendermint::Hash;
+
+use crate::tx::BroadcastMode;
+
+/// A provider call seen by [`Middleware`].
+#[derive(Debug)]
+pub enum Call<'a> {
+    /// An ABCI query.
+    Query {
+        query: &'a FvmQuery,
+        height: FvmQueryHeight,
+    },
+    /// A chain head (status) request.
+    ChainHead,
+    /// A block request to get a block's time.
+    BlockTime { height: u64 },
+    /// A block request with the results of its transactions.
+    Block { height: u64 },
+    /// A transaction lookup by hash.
+    Tx { hash: Hash },
+    /// A transaction search.
+    SearchTxs { query: &'a str },
+    /// A transaction broadcast.
+    Broadcast {
+        message: &'a ChainMessage,
+        mode: BroadcastMode,
+    },
+    /// An Object API upload.
+    Upload { size: usize, chain_id: u64 },
+    /// An Object API download.
+    Download {
+        address: Address,
+        key: &'a str,
+        range: Option<&'a str>,
+        height: u64,
+    },
+    /// An Object API object size request.
+    Size {
+        address: Address,
+        key: &'a str,
+        height: u64,
+    },
+}
+
+impl Call<'_> {
+    /// Returns a short name for the call, e.g., for logging.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Call::Query { .. } => "query",
+            Call::ChainHead => "chain_head",
+            Call::BlockTime { .. } => "block_time",
+            Call::Block { .. } => "block",
+            Call::Tx { .. } => "tx",
+            Call::SearchTxs { .. } => "search_txs",
+            Call::Broadcast { .. } => "broadcast",
+            Call::Upload { .. } => "upload",
+            Call::Download { .. } => "download",
+            Call::Size { .. } => "size",
+        }
+    }
+
+    /// Returns whether the call is an Object API request.
+    pub fn is_object(&self) -> bool {
+        matches!(
+            self,
+            Call::Upload { .. } | Call::Download { .. } | Call::Size { .. }
+        )
+    }
+}
+
+/// The outcome of a provider call seen by [`Middleware::after`].
+#[derive(Debug)]
+pub struct Outcome<'a> {
+    /// Time the call took, excluding [`Middleware::before`] hooks.
+    pub elapsed: Duration,
+    /// The error, if the call failed.
+    pub error: Option<&'a anyhow::Error>,
+    /// Gas used by a broadcast transaction, if it was committed.
+    pub gas_used: Option<i64>,
+}
+
+/// Intercepts provider calls, e.g., to add auth headers, write audit logs, or inject faults.
+///
+/// Middleware is added with [`crate::json_rpc::JsonRpcProvider::with_middleware`].
+/// For each call, [`Middleware::before`] hooks run in the order the middleware was added,
+/// and [`Middleware::after`] hooks run in reverse order.
+/// All methods have no-op defaults, so implementors only override the hooks they need.
+#[async_trait]
+pub trait Middleware: Send + Sync {
+    /// Called before a call is made.
+    /// Returning an error aborts the call with that error, which is useful for fault injection.
+    async fn before(&self, _call: &Call<'_>) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Modify an Object API HTTP request before it's sent, e.g., to add headers.
+    ///
+    /// CometBFT RPC requests don't go through this hook,
+    /// since the underlying client doesn't support custom headers.
+    fn object_request(
+        &self,
+        _call: &Call<'_>,
+        request: reqwest::RequestBuilder,
+    ) -> reqwest::RequestBuilder {
+        request
+    }
+
+    /// Called after a call completes, whether it succeeded or not.
+    async fn after(&self, _call: &Call<'_>, _outcome: &Outcome<'_>) {}
+}
@@ -1,12 +1,213 @@
 // Copyright 2024 ADM Contributors
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use std::fmt::{self, Display};
+
 use async_trait::async_trait;
 use fvm_shared::address::Address;
+use reqwest::StatusCode;
 
 use crate::response::Cid;
 
+/// An Object API version.
+///
+/// Versions can differ in their endpoint paths and upload form field names.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ObjectApiVersion {
+    /// The `v1` API.
+    V1,
+}
+
+impl ObjectApiVersion {
+    /// All known versions, newest first.
+    /// Negotiation picks the first version the node supports.
+    pub const ALL: &'static [ObjectApiVersion] = &[ObjectApiVersion::V1];
+
+    /// Returns the path segment that prefixes the version's endpoints.
+    pub fn path(&self) -> &'static str {
+        match self {
+            ObjectApiVersion::V1 => "v1",
+        }
+    }
+
+    /// Returns the version's upload form field names.
+    pub fn upload_fields(&self) -> UploadFields {
+        match self {
+            ObjectApiVersion::V1 => UploadFields {
+                chain_id: "chain_id".into(),
+                msg: "msg".into(),
+                object: "object".into(),
+            },
+        }
+    }
+}
+
+impl Display for ObjectApiVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path())
+    }
+}
+
+/// Multipart form field names used to upload an object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UploadFields {
+    /// The field holding the chain ID.
+    pub chain_id: String,
+    /// The field holding the serialized signed message that adds the object.
+    pub msg: String,
+    /// The field holding the object data.
+    pub object: String,
+}
+
+/// Response headers that may carry a request ID for correlating with server logs.
+const REQUEST_ID_HEADERS: &[&str] = &["x-request-id", "x-amzn-requestid", "x-correlation-id"];
+
+/// The reason an upload failed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UploadErrorKind {
+    /// The Object API rejected the request's credentials.
+    Unauthorized,
+    /// The object exceeds the Object API's size limit.
+    PayloadTooLarge,
+    /// The CID of the uploaded data doesn't match the CID computed by the client.
+    CidMismatch,
+    /// The target machine doesn't exist.
+    MachineNotFound,
+    /// The upload endpoint doesn't exist, usually because the node serves a different API version.
+    EndpointNotFound,
+    /// Any other failure.
+    Other,
+}
+
+impl Display for UploadErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            UploadErrorKind::Unauthorized => "unauthorized",
+            UploadErrorKind::PayloadTooLarge => "payload too large",
+            UploadErrorKind::CidMismatch => "CID mismatch",
+            UploadErrorKind::MachineNotFound => "machine not found",
+            UploadErrorKind::EndpointNotFound => "endpoint not found",
+            UploadErrorKind::Other => "upload failed",
+        };
+        write!(f, "{}", kind)
+    }
+}
+
+/// An error returned when an object upload fails.
+#[derive(Debug, Clone)]
+pub struct UploadError {
+    /// The kind of failure.
+    pub kind: UploadErrorKind,
+    /// The HTTP status returned by the Object API.
+    /// `None` if the failure was detected by the client.
+    pub status: Option<StatusCode>,
+    /// The request ID returned by the Object API, if any.
+    pub request_id: Option<String>,
+    /// The response body or a description of the failure.
+    pub message: String,
+}
+
+impl UploadError {
+    /// Classify a failed upload response.
+    pub fn from_response(
+        status: StatusCode,
+        headers: &reqwest::header::HeaderMap,
+        body: String,
+    ) -> Self {
+        let lower = body.to_lowercase();
+        let kind = match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => UploadErrorKind::Unauthorized,
+            StatusCode::PAYLOAD_TOO_LARGE => UploadErrorKind::PayloadTooLarge,
+            StatusCode::NOT_FOUND if lower.contains("machine") || lower.contains("actor") => {
+                UploadErrorKind::MachineNotFound
+            }
+            StatusCode::NOT_FOUND => UploadErrorKind::EndpointNotFound,
+            _ if lower.contains("cid") && lower.contains("match") => UploadErrorKind::CidMismatch,
+            _ if lower.contains("too large") || lower.contains("exceeds") => {
+                UploadErrorKind::PayloadTooLarge
+            }
+            _ => UploadErrorKind::Other,
+        };
+        let request_id = REQUEST_ID_HEADERS
+            .iter()
+            .find_map(|name| headers.get(*name))
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+        Self {
+            kind,
+            status: Some(status),
+            request_id,
+            message: body.trim().to_string(),
+        }
+    }
+
+    /// Create an error for an upload whose remote CID doesn't match the locally computed CID.
+    pub fn cid_mismatch(expected: &Cid, actual: &Cid) -> Self {
+        Self {
+            kind: UploadErrorKind::CidMismatch,
+            status: None,
+            request_id: None,
+            message: format!(
+                "expected {} but the Object API returned {}",
+                expected, actual
+            ),
+        }
+    }
+
+    /// Returns whether the upload may succeed if it's retried,
+    /// i.e., the Object API failed or was overloaded rather than rejecting the request.
+    pub fn is_retryable(&self) -> bool {
+        self.kind == UploadErrorKind::Other
+            && self.status.map_or(true, |status| {
+                status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+            })
+    }
+
+    /// Returns a suggested fix for the failure, if there is one.
+    pub fn remediation(&self) -> Option<&'static str> {
+        match self.kind {
+            UploadErrorKind::Unauthorized => {
+                Some("Check the Object API auth token, or whether the Object API requires one.")
+            }
+            UploadErrorKind::PayloadTooLarge => {
+                Some("The object exceeds the Object API's size limit. Split it into smaller objects.")
+            }
+            UploadErrorKind::CidMismatch => Some(
+                "The object may have changed while it was read, or was corrupted in transit. Retry the upload.",
+            ),
+            UploadErrorKind::MachineNotFound => {
+                Some("Check the object store address and that it exists in the target subnet.")
+            }
+            UploadErrorKind::EndpointNotFound => Some(
+                "Check the Object API URL. The node may serve an incompatible Object API version.",
+            ),
+            UploadErrorKind::Other => None,
+        }
+    }
+}
+
+impl Display for UploadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to upload object ({})", self.kind)?;
+        if let Some(status) = self.status {
+            write!(f, " with status {}", status.as_u16())?;
+        }
+        if !self.message.is_empty() {
+            write!(f, ": {}", self.message)?;
+        }
+        if let Some(request_id) = &self.request_id {
+            write!(f, " (request ID {})", request_id)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for UploadError {}
+
 /// Provider for object interactions.
+///
+/// Each method accepts an optional bearer `auth_token` that overrides
+/// any token configured on the provider for that request.
 #[async_trait]
 pub trait ObjectProvider: Send + Sync {
     /// Upload an object.
@@ -16,6 +217,7 @@ pub trait ObjectProvider: Send + Sync {
         size: usize,
         msg: String,
         chain_id: u64,
+        auth_token: Option<String>,
     ) -> anyhow::Result<Cid>;
 
     /// Download an object.
@@ -25,8 +227,64 @@ pub trait ObjectProvider: Send + Sync {
         key: &str,
         range: Option<String>,
         height: u64,
+        auth_token: Option<String>,
     ) -> anyhow::Result<reqwest::Response>;
 
     /// Gets the object size.
-    async fn size(&self, address: Address, key: &str, height: u64) -> anyhow::Result<usize>;
+    async fn size(
+        &self,
+        address: Address,
+        key: &str,
+        height: u64,
+        auth_token: Option<String>,
+    ) -> anyhow::Result<usize>;
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::{HeaderMap, HeaderValue};
+
+    use super::*;
+
+    #[test]
+    fn test_upload_error_from_response() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-request-id", HeaderValue::from_static("abc123"));
+
+        let err = UploadError::from_response(StatusCode::UNAUTHORIZED, &headers, "".into());
+        assert_eq!(err.kind, UploadErrorKind::Unauthorized);
+        assert!(!err.is_retryable());
+        assert_eq!(err.request_id.as_deref(), Some("abc123"));
+        assert_eq!(
+            err.to_string(),
+            "failed to upload object (unauthorized) with status 401 (request ID abc123)"
+        );
+
+        let headers = HeaderMap::new();
+        let err = UploadError::from_response(
+            StatusCode::NOT_FOUND,
+            &headers,
+            "machine t2abc not found".into(),
+        );
+        assert_eq!(err.kind, UploadErrorKind::MachineNotFound);
+        let err = UploadError::from_response(StatusCode::NOT_FOUND, &headers, "".into());
+        assert_eq!(err.kind, UploadErrorKind::EndpointNotFound);
+        let err = UploadError::from_response(
+            StatusCode::BAD_REQUEST,
+            &headers,
+            "computed cid does not match".into(),
+        );
+        assert_eq!(err.kind, UploadErrorKind::CidMismatch);
+        assert!(err.remediation().is_some());
+        let err =
+            UploadError::from_response(StatusCode::BAD_REQUEST, &headers, "CID mismatch".into());
+        assert_eq!(err.kind, UploadErrorKind::CidMismatch);
+
+        let err =
+            UploadError::from_response(StatusCode::BAD_GATEWAY, &headers, "bad gateway".into());
+        assert_eq!(err.kind, UploadErrorKind::Other);
+        assert!(err.is_retryable());
+        let err = UploadError::from_response(StatusCode::BAD_REQUEST, &headers, "bad".into());
+        assert!(!err.is_retryable());
+    }
 }
@@ -0,0 +1,64 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::time::Duration;
+
+use tendermint::{block::Height, chain, Time};
+
+use crate::object::ObjectApiVersion;
+
+/// Round-trip latencies, sync status, and versions of a provider's endpoints,
+/// e.g., to pick the fastest of several nodes.
+#[derive(Debug)]
+pub struct Probe {
+    /// The CometBFT RPC probe, or the error if the RPC is unreachable.
+    pub rpc: anyhow::Result<RpcProbe>,
+    /// The Object API probe, or the error if the Object API is unreachable.
+    /// `None` if the provider has no Object API URL.
+    pub object_api: Option<anyhow::Result<ObjectApiProbe>>,
+}
+
+impl Probe {
+    /// Returns whether every probed endpoint is reachable and the node is synced.
+    pub fn is_healthy(&self) -> bool {
+        let rpc = matches!(&self.rpc, Ok(rpc) if !rpc.catching_up);
+        let object_api = match &self.object_api {
+            Some(Ok(object_api)) => object_api.healthy,
+            Some(Err(_)) => false,
+            None => true,
+        };
+        rpc && object_api
+    }
+}
+
+/// The result of probing a CometBFT RPC endpoint.
+#[derive(Clone, Debug)]
+pub struct RpcProbe {
+    /// Round-trip time of a status request.
+    pub latency: Duration,
+    /// The chain ID reported by the node.
+    pub chain_id: chain::Id,
+    /// The CometBFT version.
+    pub node_version: String,
+    /// The ABCI application version, e.g., of Fendermint.
+    pub app_version: String,
+    /// The latest block height.
+    pub height: Height,
+    /// The latest block time.
+    pub time: Time,
+    /// Whether the node is still catching up with the chain.
+    pub catching_up: bool,
+}
+
+/// The result of probing an Object API endpoint.
+#[derive(Clone, Debug)]
+pub struct ObjectApiProbe {
+    /// Round-trip time of a health request.
+    pub latency: Duration,
+    /// The API version used by the provider.
+    pub api_version: ObjectApiVersion,
+    /// The HTTP status of the health request.
+    pub status: u16,
+    /// Whether the health request succeeded.
+    pub healthy: bool,
+}
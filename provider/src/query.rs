@@ -5,18 +5,47 @@
 use anyhow::{anyhow, Context};
 use async_trait::async_trait;
 use cid::Cid;
-use fendermint_vm_message::query::{
-    ActorState, BuiltinActors, FvmQuery, FvmQueryHeight, GasEstimate, StateParams,
+use fendermint_vm_message::{
+    chain::ChainMessage,
+    query::{ActorState, BuiltinActors, FvmQuery, FvmQueryHeight, GasEstimate, StateParams},
 };
-use fvm_shared::{address::Address, error::ExitCode, message::Message, ActorID};
+use futures_util::{StreamExt, TryStreamExt};
+use fvm_shared::{address::Address, chainid::ChainID, error::ExitCode, message::Message, ActorID};
 use prost::Message as ProstMessage;
 use serde::Serialize;
-use tendermint::{abci::response::DeliverTx, block::Height};
+use tendermint::{abci::response::DeliverTx, account, block::Height, Hash, Time};
 use tendermint_proto::abci::ResponseDeliverTx;
 use tendermint_rpc::endpoint::abci_query::AbciQuery;
 
 use crate::response::encode_data;
 
+/// Maximum number of queries in flight for [`QueryProvider::query_many`].
+pub const MAX_CONCURRENT_QUERIES: usize = 8;
+
+/// An error returned when a queried account, machine, object, or other value does not exist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotFound {
+    /// A description of what was not found.
+    pub message: String,
+}
+
+impl NotFound {
+    /// Create a new error with a description of what was not found.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for NotFound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for NotFound {}
+
 /// The parsed query response.
 #[derive(Debug, Clone, Serialize)]
 pub struct QueryResponse<T> {
@@ -26,6 +55,68 @@ pub struct QueryResponse<T> {
     pub value: T,
 }
 
+/// The latest block of the chain.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainHead {
+    /// The latest block height.
+    pub height: Height,
+    /// The latest block hash.
+    pub hash: Hash,
+    /// The latest block time.
+    pub time: Time,
+    /// Whether the node is still catching up with the chain.
+    pub catching_up: bool,
+}
+
+/// A block with its decoded transactions, e.g., for chain inspection.
+#[derive(Debug, Clone)]
+pub struct ChainBlock {
+    /// The block height.
+    pub height: Height,
+    /// The block hash.
+    pub hash: Hash,
+    /// The block time.
+    pub time: Time,
+    /// The address of the validator that proposed the block.
+    pub proposer: account::Id,
+    /// The transactions in the block, in order.
+    pub txs: Vec<ChainTx>,
+}
+
+/// A committed transaction with its decoded message.
+#[derive(Debug, Clone)]
+pub struct ChainTx {
+    /// The transaction hash.
+    pub hash: Hash,
+    /// The height of the block that includes the transaction.
+    pub height: Height,
+    /// The index of the transaction in its block.
+    pub index: u32,
+    /// The decoded message, or `None` if the transaction isn't a [`ChainMessage`].
+    pub message: Option<ChainMessage>,
+    /// The result of delivering the transaction, if the node returned it.
+    pub result: Option<DeliverTx>,
+}
+
+impl ChainTx {
+    /// Decode a raw transaction at the given position.
+    pub fn decode(
+        hash: Hash,
+        height: Height,
+        index: u32,
+        data: &[u8],
+        result: Option<DeliverTx>,
+    ) -> Self {
+        Self {
+            hash,
+            height,
+            index,
+            message: fvm_ipld_encoding::from_slice(data).ok(),
+            result,
+        }
+    }
+}
+
 /// Provider for submitting queries.
 #[async_trait]
 pub trait QueryProvider: Send + Sync {
@@ -49,6 +140,37 @@ pub trait QueryProvider: Send + Sync {
         Ok(QueryResponse { height, value })
     }
 
+    /// Run multiple messages in a read-only fashion.
+    ///
+    /// The queries are issued with [`QueryProvider::query_many`], and responses are returned
+    /// in the same order as the messages.
+    /// Fails if any of the queries fail.
+    async fn call_many<F, T>(
+        &self,
+        messages: Vec<Message>,
+        height: FvmQueryHeight,
+        f: F,
+    ) -> anyhow::Result<Vec<QueryResponse<T>>>
+    where
+        F: Fn(&DeliverTx) -> anyhow::Result<T> + Sync + Send,
+        T: Sync + Send,
+    {
+        let queries = messages
+            .into_iter()
+            .map(|message| (FvmQuery::Call(Box::new(message)), height))
+            .collect();
+        self.query_many(queries)
+            .await?
+            .into_iter()
+            .map(|res| {
+                let height = res.height;
+                let tx = extract(res, parse_deliver_tx)?;
+                let value = f(&tx)?;
+                Ok(QueryResponse { height, value })
+            })
+            .collect()
+    }
+
     /// Estimate the gas limit of a message.
     async fn estimate_gas(
         &self,
@@ -81,6 +203,31 @@ pub trait QueryProvider: Send + Sync {
         Ok(QueryResponse { height, value })
     }
 
+    /// Query the state of an actor at multiple heights, e.g., its committed and pending
+    /// sequence.
+    ///
+    /// The queries are issued with [`QueryProvider::query_many`], and responses are returned
+    /// in the same order as the heights.
+    async fn actor_states(
+        &self,
+        address: &Address,
+        heights: Vec<FvmQueryHeight>,
+    ) -> anyhow::Result<Vec<QueryResponse<Option<(ActorID, ActorState)>>>> {
+        let queries = heights
+            .into_iter()
+            .map(|height| (FvmQuery::ActorState(*address), height))
+            .collect();
+        self.query_many(queries)
+            .await?
+            .into_iter()
+            .map(|res| {
+                let height = res.height;
+                let value = extract_actor_state(res)?;
+                Ok(QueryResponse { height, value })
+            })
+            .collect()
+    }
+
     /// Query the contents of a CID from the IPLD store.
     async fn ipld(&self, cid: &Cid, height: FvmQueryHeight) -> anyhow::Result<Option<Vec<u8>>> {
         let res = self.query(FvmQuery::Ipld(*cid), height).await?;
@@ -118,8 +265,118 @@ pub trait QueryProvider: Send + Sync {
         Ok(QueryResponse { height, value })
     }
 
+    /// Get the chain ID.
+    async fn chain_id(&self) -> anyhow::Result<ChainID> {
+        let res = self.state_params(FvmQueryHeight::Committed).await?;
+        Ok(ChainID::from(res.value.chain_id))
+    }
+
+    /// Get the latest block of the chain.
+    ///
+    /// The default implementation fails, for providers that can't read blocks.
+    async fn chain_head(&self) -> anyhow::Result<ChainHead> {
+        Err(anyhow!("provider does not support getting the chain head"))
+    }
+
+    /// Get the time of the block at the given height.
+    ///
+    /// The default implementation fails, for providers that can't read blocks.
+    async fn block_time(&self, height: u64) -> anyhow::Result<Time> {
+        Err(anyhow!(
+            "provider does not support getting the time of block {}",
+            height
+        ))
+    }
+
+    /// Get the block at the given height, with its decoded transactions and their results.
+    ///
+    /// The default implementation fails, for providers that can't read blocks.
+    async fn block(&self, height: u64) -> anyhow::Result<ChainBlock> {
+        Err(anyhow!(
+            "provider does not support getting block {}",
+            height
+        ))
+    }
+
+    /// Get a committed transaction by its hash, or `None` if it's not found.
+    ///
+    /// Finding transactions requires the node to index them.
+    /// The default implementation fails, for providers that can't look up transactions.
+    async fn tx_by_hash(&self, hash: Hash) -> anyhow::Result<Option<ChainTx>> {
+        Err(anyhow!(
+            "provider does not support getting transaction {}",
+            hash
+        ))
+    }
+
+    /// Search committed transactions with a CometBFT event query, e.g., `"tx.height=100"`,
+    /// newest first.
+    ///
+    /// Results are paged, starting with page 1, and at most 100 are returned per page.
+    /// Searching requires the node to index transactions.
+    /// The default implementation fails, for providers that can't search transactions.
+    async fn search_txs(
+        &self,
+        query: &str,
+        _page: u32,
+        _per_page: u8,
+    ) -> anyhow::Result<Vec<ChainTx>> {
+        Err(anyhow!(
+            "provider does not support searching transactions with {:?}",
+            query
+        ))
+    }
+
+    /// Get the height of the last block at or before the given time,
+    /// e.g., to query state as it was at a wall-clock time.
+    ///
+    /// Blocks are found with a binary search over block times, so this takes a number of
+    /// [`QueryProvider::block_time`] calls that's logarithmic in the chain height.
+    /// Fails if the time is before the first block, or the chain has no blocks yet.
+    async fn height_at_time(&self, time: Time) -> anyhow::Result<u64> {
+        let head = self.chain_head().await?;
+        if head.height.value() == 0 {
+            return Err(anyhow!("chain has no blocks yet"));
+        }
+        if time >= head.time {
+            return Ok(head.height.value());
+        }
+        let mut low = 1;
+        if self.block_time(low).await? > time {
+            return Err(anyhow!("time {} is before the first block", time));
+        }
+        // Block times increase with height, so the block is between `low` (inclusive)
+        // and `high` (exclusive).
+        let mut high = head.height.value();
+        while high.saturating_sub(low) > 1 {
+            let mid = low + (high - low) / 2;
+            if self.block_time(mid).await? <= time {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        Ok(low)
+    }
+
     /// Run an ABCI query.
     async fn query(&self, query: FvmQuery, height: FvmQueryHeight) -> anyhow::Result<AbciQuery>;
+
+    /// Run multiple ABCI queries, e.g., to cut latency on high round-trip links.
+    ///
+    /// Queries are issued concurrently, with at most [`MAX_CONCURRENT_QUERIES`] in flight,
+    /// and responses are returned in the same order as the queries.
+    /// Fails if any of the queries fail.
+    async fn query_many(
+        &self,
+        queries: Vec<(FvmQuery, FvmQueryHeight)>,
+    ) -> anyhow::Result<Vec<AbciQuery>> {
+        futures_util::stream::iter(queries)
+            .map(|(query, height)| self.query(query, height))
+            .buffered(MAX_CONCURRENT_QUERIES)
+            .try_collect()
+            .await
+    }
 }
 
 /// Extract some value from the query result, unless it's not found or another error.
@@ -135,11 +392,20 @@ where
 }
 
 /// Extract some value from the query result, unless there was an error.
+///
+/// Not-found exit codes are returned as [`NotFound`].
 fn extract<T, F>(res: AbciQuery, f: F) -> anyhow::Result<T>
 where
     F: FnOnce(AbciQuery) -> anyhow::Result<T>,
 {
-    if res.code.is_err() {
+    if is_not_found(&res) {
+        Err(NotFound::new(format!(
+            "query returned non-zero exit code: {}; {}",
+            res.code.value(),
+            res.info,
+        ))
+        .into())
+    } else if res.code.is_err() {
         Err(anyhow!(
             "query returned non-zero exit code: {}; {}",
             res.code.value(),
@@ -183,9 +449,52 @@ fn parse_deliver_tx(res: AbciQuery) -> anyhow::Result<DeliverTx> {
 
 #[cfg(test)]
 mod tests {
-    use tendermint_rpc::endpoint::abci_query::AbciQuery;
+    use super::*;
+
+    /// A chain of `head` blocks, ten seconds apart.
+    struct MockChain {
+        head: u64,
+    }
+
+    #[async_trait]
+    impl QueryProvider for MockChain {
+        async fn chain_head(&self) -> anyhow::Result<ChainHead> {
+            Ok(ChainHead {
+                height: Height::try_from(self.head)?,
+                hash: Hash::None,
+                time: self.block_time(self.head).await?,
+                catching_up: false,
+            })
+        }
+
+        async fn block_time(&self, height: u64) -> anyhow::Result<Time> {
+            Ok(Time::from_unix_timestamp(height as i64 * 10, 0)?)
+        }
 
-    use super::parse_deliver_tx;
+        async fn query(
+            &self,
+            _query: FvmQuery,
+            _height: FvmQueryHeight,
+        ) -> anyhow::Result<AbciQuery> {
+            Err(anyhow!("query not supported by mock"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_height_at_time() {
+        let chain = MockChain { head: 1000 };
+        let at = |secs| Time::from_unix_timestamp(secs, 0).unwrap();
+        assert_eq!(chain.height_at_time(at(10)).await.unwrap(), 1);
+        assert_eq!(chain.height_at_time(at(15)).await.unwrap(), 1);
+        assert_eq!(chain.height_at_time(at(5000)).await.unwrap(), 500);
+        assert_eq!(chain.height_at_time(at(5009)).await.unwrap(), 500);
+        assert_eq!(chain.height_at_time(at(9999)).await.unwrap(), 999);
+        assert_eq!(chain.height_at_time(at(20000)).await.unwrap(), 1000);
+        assert!(chain.height_at_time(at(5)).await.is_err());
+
+        let empty = MockChain { head: 0 };
+        assert!(empty.height_at_time(at(5)).await.is_err());
+    }
 
     #[test]
     fn parse_call_query_response() {
@@ -9,7 +9,7 @@ use anyhow::{anyhow, Context};
 use base64::Engine;
 use bytes::Bytes;
 use fvm_ipld_encoding::RawBytes;
-use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use serde::{de::DeserializeOwned, de::Error, Deserialize, Deserializer, Serialize, Serializer};
 use tendermint::abci::response::DeliverTx;
 
 /// Apply the encoding that Tendermint does to the bytes inside [`DeliverTx`].
@@ -38,6 +38,16 @@ pub fn decode_bytes(deliver_tx: &DeliverTx) -> anyhow::Result<RawBytes> {
     decode_data(&deliver_tx.data)
 }
 
+/// Parse what Tendermint returns in the `data` field of [`DeliverTx`] as any CBOR-decodable type.
+///
+/// This is useful as the decode function passed to [`crate::tx::TxProvider::perform`]
+/// or [`crate::query::QueryProvider::call`] for return types without a dedicated decoder.
+pub fn decode_as<T: DeserializeOwned>(deliver_tx: &DeliverTx) -> anyhow::Result<T> {
+    let data = decode_data(&deliver_tx.data)?;
+    fvm_ipld_encoding::from_slice::<T>(&data)
+        .map_err(|e| anyhow!("error parsing as {}: {e}", std::any::type_name::<T>()))
+}
+
 /// Parse what Tendermint returns in the `data` field of [`DeliverTx`] as a [`Cid`].
 pub fn decode_cid(deliver_tx: &DeliverTx) -> anyhow::Result<Cid> {
     let data = decode_data(&deliver_tx.data)?;
@@ -82,3 +92,22 @@ impl Serialize for Cid {
         self.to_string().serialize(serializer)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tendermint::abci::response::DeliverTx;
+
+    use super::{decode_as, encode_data};
+
+    #[test]
+    fn decode_as_roundtrip() {
+        let value = (42u64, "hello".to_string());
+        let deliver_tx = DeliverTx {
+            data: encode_data(&fvm_ipld_encoding::to_vec(&value).unwrap()),
+            ..Default::default()
+        };
+        let decoded: (u64, String) = decode_as(&deliver_tx).unwrap();
+        assert_eq!(decoded, value);
+        assert!(decode_as::<Vec<u8>>(&deliver_tx).is_err());
+    }
+}
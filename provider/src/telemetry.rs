@@ -0,0 +1,197 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! OpenTelemetry export of provider calls, enabled by the `otel` feature.
+//!
+//! Spans and metrics are recorded with the global tracer and meter providers,
+//! so they go wherever the embedding service already exports its own telemetry.
+//! Install the global providers before the first call, since metric instruments are only
+//! created once.
+
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant, SystemTime};
+
+use async_trait::async_trait;
+use opentelemetry::{
+    global,
+    metrics::{Counter, Histogram},
+    trace::{FutureExt, Span, SpanKind, Status, TraceContextExt, Tracer},
+    Context, KeyValue,
+};
+
+use crate::middleware::{Call, Middleware, Outcome};
+
+/// Instrumentation scope of the spans and metrics.
+pub const SCOPE: &str = "adm";
+
+/// Metric instruments shared by all middleware and [`instrument`] calls.
+struct Metrics {
+    calls: Counter<u64>,
+    duration: Histogram<f64>,
+    upload_bytes: Counter<u64>,
+}
+
+fn metrics() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(|| {
+        let meter = global::meter(SCOPE);
+        Metrics {
+            calls: meter
+                .u64_counter("adm.calls")
+                .with_description("Number of ADM calls.")
+                .init(),
+            duration: meter
+                .f64_histogram("adm.call.duration")
+                .with_description("Duration of ADM calls.")
+                .with_unit("s")
+                .init(),
+            upload_bytes: meter
+                .u64_counter("adm.upload.size")
+                .with_description("Bytes uploaded to the Object API.")
+                .with_unit("By")
+                .init(),
+        }
+    })
+}
+
+/// Records the metrics of a finished call.
+fn record_metrics(name: &'static str, api: &'static str, elapsed: Duration, failed: bool) {
+    let attributes = [
+        KeyValue::new("adm.call", name),
+        KeyValue::new("adm.api", api),
+        KeyValue::new("error", failed),
+    ];
+    let metrics = metrics();
+    metrics.calls.add(1, &attributes);
+    metrics.duration.record(elapsed.as_secs_f64(), &attributes);
+}
+
+/// Middleware that exports provider calls to OpenTelemetry.
+///
+/// Each call is a client span named after the call, e.g., `adm.broadcast`, and is counted and
+/// timed in the `adm.calls` and `adm.call.duration` metrics, with the call name, the API
+/// (`rpc` or `object`), and whether it failed as attributes.
+/// Object API uploads are also added to the `adm.upload.size` metric.
+///
+/// Calls are timed by the provider, so Object API downloads are timed until their response
+/// headers arrive, not until their body is read.
+#[derive(Clone, Debug, Default)]
+pub struct OtelMiddleware;
+
+impl OtelMiddleware {
+    /// Create the middleware.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Middleware for OtelMiddleware {
+    async fn after(&self, call: &Call<'_>, outcome: &Outcome<'_>) {
+        let api = if call.is_object() { "object" } else { "rpc" };
+        record_metrics(call.name(), api, outcome.elapsed, outcome.error.is_some());
+        if let (Call::Upload { size, .. }, None) = (call, outcome.error) {
+            metrics()
+                .upload_bytes
+                .add(*size as u64, &[KeyValue::new("adm.api", api)]);
+        }
+
+        // The span is recorded after the fact, with the start time the call took
+        let end = SystemTime::now();
+        let tracer = global::tracer(SCOPE);
+        let mut span = tracer
+            .span_builder(format!("adm.{}", call.name()))
+            .with_kind(SpanKind::Client)
+            .with_start_time(end - outcome.elapsed)
+            .with_attributes(span_attributes(call, api, outcome))
+            .start(&tracer);
+        if let Some(error) = outcome.error {
+            span.set_status(Status::error(error.to_string()));
+        }
+        span.end_with_timestamp(end);
+    }
+}
+
+/// Returns span attributes that identify what a call acted on.
+fn span_attributes(call: &Call<'_>, api: &'static str, outcome: &Outcome<'_>) -> Vec<KeyValue> {
+    let mut attributes = vec![KeyValue::new("adm.api", api)];
+    match call {
+        Call::Query { height, .. } => {
+            attributes.push(KeyValue::new("adm.height", format!("{:?}", height)))
+        }
+        Call::BlockTime { height } | Call::Block { height } => {
+            attributes.push(KeyValue::new("adm.height", *height as i64))
+        }
+        Call::Tx { hash } => attributes.push(KeyValue::new("adm.tx.hash", hash.to_string())),
+        Call::SearchTxs { query } => attributes.push(KeyValue::new("adm.query", query.to_string())),
+        Call::Broadcast { mode, .. } => {
+            attributes.push(KeyValue::new("adm.broadcast_mode", format!("{:?}", mode)))
+        }
+        Call::Upload { size, chain_id } => {
+            attributes.push(KeyValue::new("adm.size", *size as i64));
+            attributes.push(KeyValue::new("adm.chain_id", *chain_id as i64));
+        }
+        Call::Download {
+            address,
+            key,
+            range,
+            height,
+        } => {
+            attributes.push(KeyValue::new("adm.address", address.to_string()));
+            attributes.push(KeyValue::new("adm.key", key.to_string()));
+            if let Some(range) = range {
+                attributes.push(KeyValue::new("adm.range", range.to_string()));
+            }
+            attributes.push(KeyValue::new("adm.height", *height as i64));
+        }
+        Call::Size {
+            address,
+            key,
+            height,
+        } => {
+            attributes.push(KeyValue::new("adm.address", address.to_string()));
+            attributes.push(KeyValue::new("adm.key", key.to_string()));
+            attributes.push(KeyValue::new("adm.height", *height as i64));
+        }
+        Call::ChainHead => {}
+    }
+    if let Some(gas_used) = outcome.gas_used {
+        attributes.push(KeyValue::new("adm.gas_used", gas_used));
+    }
+    attributes
+}
+
+/// Runs `future` in a span named `adm.<name>` and records it in the call metrics,
+/// e.g., for operations that don't go through a provider, like cross-chain transfers.
+///
+/// Provider calls made by `future` are recorded as children of the span.
+pub async fn instrument<F, T>(
+    name: &'static str,
+    api: &'static str,
+    attributes: Vec<KeyValue>,
+    future: F,
+) -> anyhow::Result<T>
+where
+    F: Future<Output = anyhow::Result<T>>,
+{
+    let tracer = global::tracer(SCOPE);
+    let span = tracer
+        .span_builder(format!("adm.{}", name))
+        .with_kind(SpanKind::Client)
+        .with_attributes(
+            [KeyValue::new("adm.api", api)]
+                .into_iter()
+                .chain(attributes),
+        )
+        .start(&tracer);
+    let cx = Context::current_with_span(span);
+    let started = Instant::now();
+    let result = future.with_context(cx.clone()).await;
+    record_metrics(name, api, started.elapsed(), result.is_err());
+    if let Err(error) = &result {
+        cx.span().set_status(Status::error(error.to_string()));
+    }
+    cx.span().end();
+    result
+}
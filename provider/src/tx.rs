@@ -9,7 +9,13 @@ use async_trait::async_trait;
 use fendermint_vm_message::chain::ChainMessage;
 use num_traits::Zero;
 use serde::Serialize;
-use tendermint::{abci::response::DeliverTx, block::Height, Hash};
+use tendermint::{
+    abci::{response::DeliverTx, Event},
+    block::Height,
+    Hash,
+};
+
+use crate::event::MachineEvent;
 
 /// Controls how the provider waits for the result of a transaction.
 #[derive(Debug, Default, Copy, Clone)]
@@ -46,8 +52,71 @@ pub enum TxStatus {
     Committed,
 }
 
+/// An error returned when a transaction fails the check or delivery phase.
+///
+/// The code is the FVM exit code reported by the node, e.g., `6` for insufficient funds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxError {
+    /// The non-zero result code.
+    pub code: u32,
+    /// The error message reported by the node.
+    pub message: String,
+}
+
+impl std::fmt::Display for TxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for TxError {}
+
+/// An event emitted during transaction execution, e.g., an actor event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TxEvent {
+    /// The event type.
+    pub kind: String,
+    /// The event attributes.
+    pub attributes: Vec<TxEventAttribute>,
+    /// The typed machine event, if this is a known machine actor event.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub machine: Option<MachineEvent>,
+}
+
+/// A key-value attribute of a [`TxEvent`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TxEventAttribute {
+    /// The attribute key.
+    pub key: String,
+    /// The attribute value.
+    pub value: String,
+    /// Whether the attribute is indexed by the node.
+    pub index: bool,
+}
+
+impl From<&Event> for TxEvent {
+    fn from(event: &Event) -> Self {
+        TxEvent {
+            kind: event.kind.clone(),
+            attributes: event
+                .attributes
+                .iter()
+                .map(|a| TxEventAttribute {
+                    key: a.key.clone(),
+                    value: a.value.clone(),
+                    index: a.index,
+                })
+                .collect(),
+            machine: MachineEvent::parse(event).unwrap_or_else(|e| {
+                tracing::warn!("failed to parse machine event: {}", e);
+                None
+            }),
+        }
+    }
+}
+
 /// The receipt of a transaction.
-#[derive(Debug, Copy, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct TxReceipt<T> {
     /// The transaction's current status.
     pub status: TxStatus,
@@ -62,6 +131,9 @@ pub struct TxReceipt<T> {
     /// Data returned by the transaction.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub data: Option<T>,
+    /// Events emitted by the transaction.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<TxEvent>,
 }
 
 impl<D> TxReceipt<D> {
@@ -73,19 +145,32 @@ impl<D> TxReceipt<D> {
             height: None,
             gas_used: 0,
             data: None,
+            events: Vec::new(),
         }
     }
 
     /// Create a new receipt with status committed.
-    pub fn committed(hash: Hash, height: Height, gas_used: i64, data: Option<D>) -> Self {
+    pub fn committed(
+        hash: Hash,
+        height: Height,
+        gas_used: i64,
+        data: Option<D>,
+        events: Vec<TxEvent>,
+    ) -> Self {
         TxReceipt {
             status: TxStatus::Committed,
             hash,
             height: Some(height),
             gas_used,
             data,
+            events,
         }
     }
+
+    /// Returns the typed machine events emitted by the transaction.
+    pub fn machine_events(&self) -> impl Iterator<Item = &MachineEvent> {
+        self.events.iter().filter_map(|e| e.machine.as_ref())
+    }
 }
 
 /// Provider for submitting transactions.
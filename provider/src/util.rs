@@ -3,28 +3,85 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use fendermint_vm_message::query::FvmQueryHeight;
+use std::fmt;
 use std::str::FromStr;
 
+use anyhow::anyhow;
 use fvm_shared::{
     address::{Address, Error, Network},
-    bigint::BigInt,
+    bigint::{BigInt, Sign},
     econ::TokenAmount,
 };
 use ipc_api::{ethers_address_to_fil_address, evm::payload_to_evm_address};
+use tendermint::{Hash, Time};
+
+use crate::response::Cid;
 
 /// Parse an f/eth-address from string.
+///
+/// f-addresses of any network are accepted.
+/// Use [`parse_address_strict`] to reject addresses of other networks.
 pub fn parse_address(s: &str) -> anyhow::Result<Address> {
-    let addr = Network::Mainnet
+    Ok(parse_address_network(s)?.0)
+}
+
+/// Parse an f/eth-address from string, and return the network an f-address belongs to.
+///
+/// Eth-addresses are the same on every network, so their network is `None`.
+pub fn parse_address_network(s: &str) -> anyhow::Result<(Address, Option<Network>)> {
+    let parsed = Network::Mainnet
         .parse_address(s)
+        .map(|addr| (addr, Network::Mainnet))
         .or_else(|e| match e {
-            Error::UnknownNetwork => Network::Testnet.parse_address(s),
+            Error::UnknownNetwork => Network::Testnet
+                .parse_address(s)
+                .map(|addr| (addr, Network::Testnet)),
             _ => Err(e),
-        })
-        .or_else(|_| {
+        });
+    match parsed {
+        Ok((addr, network)) => Ok((addr, Some(network))),
+        Err(_) => {
             let addr = ethers::types::Address::from_str(s)?;
-            ethers_address_to_fil_address(&addr)
-        })?;
-    Ok(addr)
+            Ok((ethers_address_to_fil_address(&addr)?, None))
+        }
+    }
+}
+
+/// Parse an f/eth-address from string, failing if an f-address belongs to a network other
+/// than `network`, e.g., a `t`-address given for mainnet.
+///
+/// The error names the network the address belongs to.
+pub fn parse_address_strict(s: &str, network: Network) -> anyhow::Result<Address> {
+    match parse_address_network(s)? {
+        (_, Some(found)) if found != network => Err(anyhow!(
+            "address {} belongs to {}, not {}",
+            s,
+            network_name(found),
+            network_name(network)
+        )),
+        (addr, _) => Ok(addr),
+    }
+}
+
+fn network_name(network: Network) -> &'static str {
+    match network {
+        Network::Mainnet => "mainnet",
+        Network::Testnet => "testnet",
+    }
+}
+
+/// Format an f-address for the given network, e.g., `t410f...` for [`Network::Testnet`].
+///
+/// Unlike [`Address`]'s `Display`, this doesn't depend on the global network set with
+/// [`fvm_shared::address::set_current_network`], so it's safe in processes that handle
+/// more than one network.
+pub fn format_address(address: &Address, network: Network) -> String {
+    let prefix = match network {
+        Network::Mainnet => "f",
+        Network::Testnet => "t",
+    };
+    // Encoded addresses start with a one-letter network prefix
+    format!("{}{}", prefix, &address.to_string()[1..])
 }
 
 /// Converts f-address to eth-address. Only delegated address is supported.
@@ -32,20 +89,119 @@ pub fn get_delegated_address(a: Address) -> anyhow::Result<ethers::types::Addres
     payload_to_evm_address(a.payload())
 }
 
-/// We only support up to 9 decimal digits for transaction.
-const FIL_AMOUNT_NANO_DIGITS: u32 = 9;
-
-/// Parse token amount from string.
+/// Parse a token amount with an optional unit, e.g., "1.5", "1.5 FIL", "100 nanoFIL",
+/// or "2000 attoFIL". Amounts without a unit are in FIL.
 pub fn parse_token_amount(s: &str) -> anyhow::Result<TokenAmount> {
-    let f: f64 = s.parse()?;
-    // no rounding, just the integer part
-    let nano = f64::trunc(f * (10u64.pow(FIL_AMOUNT_NANO_DIGITS) as f64));
-    Ok(TokenAmount::from_nano(nano as u128))
+    parse_token_amount_in(s, TokenUnit::Fil)
 }
 
-/// Parse token amount in attoFIL (10**18) from string.
+/// Parse a token amount with an optional unit, like [`parse_token_amount`],
+/// except that amounts without a unit are in attoFIL (10**-18 FIL).
 pub fn parse_token_amount_from_atto(s: &str) -> anyhow::Result<TokenAmount> {
-    Ok(TokenAmount::from_atto(BigInt::from_str(s)?))
+    parse_token_amount_in(s, TokenUnit::AttoFil)
+}
+
+/// Parse a token amount with an optional unit, using `default_unit` if there's no unit.
+fn parse_token_amount_in(s: &str, default_unit: TokenUnit) -> anyhow::Result<TokenAmount> {
+    let s = s.trim();
+    let end = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (amount, unit) = s.split_at(end);
+    if amount.is_empty() {
+        return Err(anyhow!("invalid token amount '{}'", s));
+    }
+    let unit = match unit.trim() {
+        "" => default_unit,
+        unit => unit.parse()?,
+    };
+    unit.parse(amount)
+}
+
+/// A denomination of FIL.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum TokenUnit {
+    /// FIL.
+    #[default]
+    Fil,
+    /// nanoFIL (10**-9 FIL).
+    NanoFil,
+    /// attoFIL (10**-18 FIL).
+    AttoFil,
+}
+
+impl TokenUnit {
+    /// Returns the number of attoFIL decimal digits in one unit.
+    pub fn decimals(&self) -> usize {
+        match self {
+            TokenUnit::Fil => 18,
+            TokenUnit::NanoFil => 9,
+            TokenUnit::AttoFil => 0,
+        }
+    }
+
+    /// Parse a non-negative decimal amount in this unit, e.g., "1.5".
+    ///
+    /// Amounts that can't be represented exactly in attoFIL are rejected instead of truncated.
+    pub fn parse(&self, s: &str) -> anyhow::Result<TokenAmount> {
+        let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+        let digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+        if (int.is_empty() && frac.is_empty()) || !digits(int) || !digits(frac) {
+            return Err(anyhow!("invalid token amount '{}'", s));
+        }
+        let decimals = self.decimals();
+        let frac = frac.trim_end_matches('0');
+        if frac.len() > decimals {
+            return Err(anyhow!(
+                "token amount '{} {}' has more than {} decimal places and would lose precision",
+                s,
+                self,
+                decimals
+            ));
+        }
+        let atto = format!("{}{:0<width$}", int, frac, width = decimals);
+        Ok(TokenAmount::from_atto(BigInt::from_str(&atto)?))
+    }
+
+    /// Format an amount in this unit as a decimal without trailing zeros, e.g., "1.5".
+    pub fn format(&self, amount: &TokenAmount) -> String {
+        let atto = amount.atto();
+        let sign = if atto.sign() == Sign::Minus { "-" } else { "" };
+        let decimals = self.decimals();
+        let magnitude = atto.magnitude().to_string();
+        let digits = format!("{:0>width$}", magnitude, width = decimals + 1);
+        let (int, frac) = digits.split_at(digits.len() - decimals);
+        match frac.trim_end_matches('0') {
+            "" => format!("{sign}{int}"),
+            frac => format!("{sign}{int}.{frac}"),
+        }
+    }
+}
+
+impl fmt::Display for TokenUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenUnit::Fil => write!(f, "FIL"),
+            TokenUnit::NanoFil => write!(f, "nanoFIL"),
+            TokenUnit::AttoFil => write!(f, "attoFIL"),
+        }
+    }
+}
+
+impl FromStr for TokenUnit {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "fil" => Ok(TokenUnit::Fil),
+            "nanofil" => Ok(TokenUnit::NanoFil),
+            "attofil" => Ok(TokenUnit::AttoFil),
+            _ => Err(anyhow!(
+                "invalid token unit '{}': must be FIL, nanoFIL, or attoFIL",
+                s
+            )),
+        }
+    }
 }
 
 /// Parse query height from string.
@@ -58,6 +214,29 @@ pub fn parse_query_height(s: &str) -> anyhow::Result<FvmQueryHeight> {
     Ok(height)
 }
 
+/// Parse an RFC 3339 timestamp, e.g., "2024-05-01T00:00:00Z".
+pub fn parse_time(s: &str) -> anyhow::Result<Time> {
+    Ok(Time::parse_from_rfc3339(s)?)
+}
+
+/// Parse a transaction hash from hex, with or without "0x", in either case.
+pub fn parse_tx_hash(s: &str) -> anyhow::Result<Hash> {
+    let s = s.trim();
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    Hash::from_str(&s.to_uppercase())
+        .map_err(|e| anyhow!("invalid transaction hash '{}': {}", s, e))
+}
+
+/// Parse a CID from string, optionally given as an `ipfs://<cid>` URI or `/ipfs/<cid>` path.
+pub fn parse_cid(s: &str) -> anyhow::Result<Cid> {
+    let s = s.trim();
+    let s = s
+        .strip_prefix("ipfs://")
+        .or_else(|| s.strip_prefix("/ipfs/"))
+        .unwrap_or(s);
+    Cid::from_str(s.trim_end_matches('/'))
+}
+
 /// Parse metadata from string.
 pub fn parse_metadata(s: &str) -> anyhow::Result<(String, String)> {
     let pos = s
@@ -67,3 +246,111 @@ pub fn parse_metadata(s: &str) -> anyhow::Result<(String, String)> {
     let val = s[pos + 1..].to_string();
     Ok((key, val))
 }
+
+/// Returns whether an RPC error means the requested item wasn't found,
+/// e.g., a transaction that isn't indexed yet.
+pub fn is_not_found(err: &tendermint_rpc::Error) -> bool {
+    err.to_string().to_lowercase().contains("not found")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_address_strict() {
+        let (address, network) = parse_address_network("t01001").unwrap();
+        assert_eq!(address, Address::new_id(1001));
+        assert_eq!(network, Some(Network::Testnet));
+        assert_eq!(
+            parse_address_network("f01001").unwrap().1,
+            Some(Network::Mainnet)
+        );
+
+        assert_eq!(
+            parse_address_strict("t01001", Network::Testnet).unwrap(),
+            address
+        );
+        let err = parse_address_strict("t01001", Network::Mainnet).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "address t01001 belongs to testnet, not mainnet"
+        );
+
+        // Eth-addresses are valid on any network
+        let eth = "0x0000000000000000000000000000000000000001";
+        assert_eq!(parse_address_network(eth).unwrap().1, None);
+        assert!(parse_address_strict(eth, Network::Mainnet).is_ok());
+        assert!(parse_address_strict("t0", Network::Testnet).is_err());
+    }
+
+    #[test]
+    fn test_format_address() {
+        let address = parse_address("0x0000000000000000000000000000000000000001").unwrap();
+        let testnet = format_address(&address, Network::Testnet);
+        let mainnet = format_address(&address, Network::Mainnet);
+        assert!(testnet.starts_with("t410f"));
+        assert!(mainnet.starts_with("f410f"));
+        assert_eq!(testnet[1..], mainnet[1..]);
+        assert_eq!(parse_address(&testnet).unwrap(), address);
+        assert_eq!(
+            format_address(&Address::new_id(100), Network::Testnet),
+            "t0100"
+        );
+    }
+
+    #[test]
+    fn test_token_unit_format() {
+        let amount = TokenAmount::from_atto(1_500_000_000_000_000_000u128);
+        assert_eq!(TokenUnit::Fil.format(&amount), "1.5");
+        assert_eq!(TokenUnit::NanoFil.format(&amount), "1500000000");
+        assert_eq!(TokenUnit::AttoFil.format(&amount), "1500000000000000000");
+
+        let amount = TokenAmount::from_atto(-5);
+        assert_eq!(TokenUnit::Fil.format(&amount), "-0.000000000000000005");
+        assert_eq!(TokenUnit::NanoFil.format(&amount), "-0.000000005");
+        assert_eq!(TokenUnit::Fil.format(&TokenAmount::default()), "0");
+    }
+
+    #[test]
+    fn test_parse_token_amount() {
+        let atto = |n: u128| TokenAmount::from_atto(n);
+        assert_eq!(
+            parse_token_amount("1.5").unwrap(),
+            atto(15 * 10u128.pow(17))
+        );
+        assert_eq!(
+            parse_token_amount("1.5 FIL").unwrap(),
+            atto(15 * 10u128.pow(17))
+        );
+        assert_eq!(
+            parse_token_amount("100 nanoFIL").unwrap(),
+            atto(100 * 10u128.pow(9))
+        );
+        assert_eq!(parse_token_amount("2000attofil").unwrap(), atto(2000));
+        assert_eq!(parse_token_amount(".000000000000000001").unwrap(), atto(1));
+        assert_eq!(parse_token_amount_from_atto("2000").unwrap(), atto(2000));
+        assert_eq!(
+            parse_token_amount_from_atto("1 FIL").unwrap(),
+            atto(10u128.pow(18))
+        );
+
+        // Precision loss and malformed amounts are rejected
+        assert!(parse_token_amount("0.0000000000000000001").is_err());
+        assert!(parse_token_amount("0.5 attoFIL").is_err());
+        assert!(parse_token_amount_from_atto("1.5").is_err());
+        assert!(parse_token_amount("-1").is_err());
+        assert!(parse_token_amount("1 BTC").is_err());
+        assert!(parse_token_amount(".").is_err());
+        assert!(parse_token_amount("1.2.3").is_err());
+    }
+
+    #[test]
+    fn test_parse_tx_hash() {
+        let hex = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+        let hash = parse_tx_hash(hex).unwrap();
+        assert_eq!(hash.to_string(), hex.to_uppercase());
+        assert_eq!(parse_tx_hash(&format!("0x{}", hex)).unwrap(), hash);
+        assert!(parse_tx_hash("0x1234").is_err());
+    }
+}
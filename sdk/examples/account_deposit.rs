@@ -35,6 +35,7 @@ async fn main() -> anyhow::Result<()> {
         signer.address(),
         network.parent_subnet_config(Default::default())?,
         TokenAmount::from_whole(1),
+        Default::default(),
     )
     .await?;
 
@@ -44,8 +45,11 @@ async fn main() -> anyhow::Result<()> {
     );
     println!(
         "Transaction hash: 0x{}",
-        hex::encode(tx.transaction_hash.to_fixed_bytes())
+        hex::encode(tx.receipt.transaction_hash.to_fixed_bytes())
     );
+    if let Some(nonce) = tx.message.nonce {
+        println!("Top-down message nonce: {}", nonce);
+    }
 
     Ok(())
 }
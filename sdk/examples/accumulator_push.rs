@@ -5,12 +5,11 @@ use std::env;
 
 use anyhow::anyhow;
 use bytes::Bytes;
-use fendermint_actor_machine::WriteAccess;
 use fendermint_vm_message::query::FvmQueryHeight;
 
 use adm_provider::json_rpc::JsonRpcProvider;
 use adm_sdk::{
-    machine::{accumulator::Accumulator, Machine},
+    machine::{accumulator::Accumulator, Machine, WriteAccess},
     network::Network,
 };
 use adm_signer::{key::parse_secret_key, AccountKind, Wallet};
@@ -28,7 +27,7 @@ async fn main() -> anyhow::Result<()> {
     let network = Network::Testnet.init();
 
     // Setup network provider
-    let provider = JsonRpcProvider::new_http(network.rpc_url()?, None, None)?;
+    let provider = JsonRpcProvider::new_http(network.rpc_url()?, None, None, None)?;
 
     // Setup local wallet using private key from arg
     let mut signer = Wallet::new_secp256k1(pk, AccountKind::Ethereum, network.subnet_id()?)?;
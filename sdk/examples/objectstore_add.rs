@@ -4,7 +4,6 @@
 use std::env;
 
 use anyhow::anyhow;
-use fendermint_actor_machine::WriteAccess;
 use rand::{thread_rng, Rng};
 use tokio::io::{AsyncSeekExt, AsyncWriteExt};
 use tokio::time::{sleep, Duration};
@@ -12,7 +11,7 @@ use tokio::time::{sleep, Duration};
 use adm_provider::json_rpc::JsonRpcProvider;
 use adm_sdk::machine::objectstore::QueryOptions;
 use adm_sdk::{
-    machine::{objectstore::ObjectStore, Machine},
+    machine::{objectstore::ObjectStore, Machine, WriteAccess},
     network::Network,
 };
 use adm_signer::{key::parse_secret_key, AccountKind, Wallet};
@@ -30,8 +29,12 @@ async fn main() -> anyhow::Result<()> {
     let network = Network::Testnet.init();
 
     // Setup network provider
-    let provider =
-        JsonRpcProvider::new_http(network.rpc_url()?, None, Some(network.object_api_url()?))?;
+    let provider = JsonRpcProvider::new_http(
+        network.rpc_url()?,
+        None,
+        Some(network.object_api_url()?),
+        None,
+    )?;
 
     // Setup local wallet using private key from arg
     let mut signer = Wallet::new_secp256k1(pk, AccountKind::Ethereum, network.subnet_id()?)?;
@@ -81,7 +84,7 @@ async fn main() -> anyhow::Result<()> {
     for (key_bytes, object) in list.objects {
         let key = core::str::from_utf8(&key_bytes).unwrap_or_default();
         // `resolved` indicates the validators were able to fetch and verify the file
-        let cid = cid::Cid::try_from(object.cid.0)?;
+        let cid = object.cid;
         println!(
             "Query result cid: {} (key={}; detached; resolved={})",
             cid, key, object.resolved
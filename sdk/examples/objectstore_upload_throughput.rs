@@ -0,0 +1,115 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::env;
+use std::time::Instant;
+
+use anyhow::anyhow;
+use rand::{thread_rng, Rng};
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use tokio::task::JoinSet;
+
+use adm_provider::{
+    json_rpc::{JsonRpcProvider, ObjectClientOptions},
+    tx::BroadcastMode,
+};
+use adm_sdk::{
+    machine::{
+        objectstore::{AddOptions, ObjectStore},
+        Machine, WriteAccess,
+    },
+    network::Network,
+};
+use adm_signer::{key::parse_secret_key, AccountKind, Wallet};
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 {
+        return Err(anyhow!("missing hex-encoded private key"));
+    }
+    let pk = parse_secret_key(&args[1])?;
+    let num_objects: usize = match args.get(2) {
+        Some(n) => n.parse()?,
+        None => 32,
+    };
+    let object_size: usize = match args.get(3) {
+        Some(n) => n.parse::<usize>()? * 1024 * 1024,
+        None => 4 * 1024 * 1024, // 4 MiB
+    };
+
+    // Use testnet network defaults
+    let network = Network::Testnet.init();
+
+    // Setup local wallet using private key from arg
+    let provider = JsonRpcProvider::new_http(network.rpc_url()?, None, None, None)?;
+    let mut signer = Wallet::new_secp256k1(pk, AccountKind::Ethereum, network.subnet_id()?)?;
+    signer.init_sequence(&provider).await?;
+
+    // Create a new object store
+    let (machine, _) = ObjectStore::new(
+        &provider,
+        &mut signer,
+        WriteAccess::OnlyOwner,
+        Default::default(),
+    )
+    .await?;
+    println!("Created new object store {}", machine.address());
+
+    // Compare HTTP/1.1 with the default HTTP/2 and keep-alive tuned client
+    let http1 = ObjectClientOptions {
+        http1_only: true,
+        ..Default::default()
+    };
+    for (name, options) in [("HTTP/1.1", http1), ("default", Default::default())] {
+        let provider = JsonRpcProvider::new_http(
+            network.rpc_url()?,
+            None,
+            Some(network.object_api_url()?),
+            Some(options),
+        )?;
+
+        // Add objects in parallel using clones of the same provider and signer.
+        // Transactions are broadcast in async mode, so this measures upload throughput,
+        // not the time it takes for the transactions to be committed.
+        let start = Instant::now();
+        let mut tasks = JoinSet::new();
+        for i in 0..num_objects {
+            let provider = provider.clone();
+            let mut signer = signer.clone();
+            let machine = ObjectStore::attach(machine.address());
+            let key = format!("{}/{}", name, i);
+            tasks.spawn(async move {
+                let mut file = async_tempfile::TempFile::new().await?;
+                let mut random_data = vec![0; object_size];
+                thread_rng().fill(&mut random_data[..]);
+                file.write_all(&random_data).await?;
+                file.flush().await?;
+                file.rewind().await?;
+                let options = AddOptions {
+                    broadcast_mode: BroadcastMode::Async,
+                    ..Default::default()
+                };
+                machine
+                    .add(&provider, &mut signer, &key, file, options)
+                    .await
+            });
+        }
+        while let Some(res) = tasks.join_next().await {
+            res??;
+        }
+        let elapsed = start.elapsed();
+
+        let total_mib = (num_objects * object_size) as f64 / (1024.0 * 1024.0);
+        println!(
+            "{}: uploaded {} objects ({:.0} MiB) in {:?} ({:.2} MiB/s)",
+            name,
+            num_objects,
+            total_mib,
+            elapsed,
+            total_mib / elapsed.as_secs_f64()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::env;
+use std::time::Instant;
+
+use tokio::task::JoinSet;
+
+use adm_provider::{json_rpc::JsonRpcProvider, query::QueryProvider};
+use adm_sdk::network::Network;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = env::args().collect();
+    let num_queries: usize = match args.get(1) {
+        Some(n) => n.parse()?,
+        None => 500,
+    };
+
+    // Use testnet network defaults
+    let network = Network::Testnet.init();
+
+    // Setup network provider
+    let provider = JsonRpcProvider::new_http(network.rpc_url()?, None, None, None)?;
+
+    // Run queries in parallel using clones of the same provider.
+    // Clones share a connection pool, so connections are reused across tasks.
+    let start = Instant::now();
+    let mut tasks = JoinSet::new();
+    for _ in 0..num_queries {
+        let provider = provider.clone();
+        tasks.spawn(async move { provider.chain_head().await });
+    }
+    let mut max_height = 0;
+    while let Some(res) = tasks.join_next().await {
+        let head = res??;
+        max_height = max_height.max(head.height.value());
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "Completed {} parallel queries in {:?}",
+        num_queries, elapsed
+    );
+    println!(
+        "Average: {:?} per query",
+        elapsed / num_queries.max(1) as u32
+    );
+    println!("Latest height: {}", max_height);
+
+    Ok(())
+}
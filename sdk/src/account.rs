@@ -1,15 +1,47 @@
 // Copyright 2024 ADM Contributors
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use std::time::{Duration, Instant};
+
 use anyhow::anyhow;
 use ethers::prelude::TransactionReceipt;
 use fendermint_vm_message::query::FvmQueryHeight;
 use fvm_shared::{address::Address, econ::TokenAmount};
 
-use adm_provider::query::QueryProvider;
+use adm_abis::gateway::CrossMsgReceipt;
+use adm_provider::query::{NotFound, QueryProvider};
 use adm_signer::Signer;
 
 use crate::ipc::{manager::EvmManager, subnet::EVMSubnet};
+use crate::telemetry::cross_chain;
+
+/// How often to check whether a cross-subnet transfer has been executed.
+const CROSS_SUBNET_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long to wait for a cross-subnet transfer to be executed.
+/// Withdrawals are only executed in the parent once a bottom-up checkpoint is committed,
+/// which can take a while on slow parent chains.
+const CROSS_SUBNET_TIMEOUT: Duration = Duration::from_secs(1800);
+
+/// Options for [`Account::deposit`].
+#[derive(Clone, Debug)]
+pub struct DepositOptions {
+    /// Number of parent blocks that must follow the deposit's block before it's reported as
+    /// successful, e.g., `900` for Filecoin's deterministic finality.
+    /// Zero returns as soon as the deposit is included in a block.
+    pub finality: u64,
+    /// How often to check the parent chain head while waiting for finality.
+    pub poll_interval: Duration,
+}
+
+impl Default for DepositOptions {
+    fn default() -> Self {
+        Self {
+            finality: 0,
+            poll_interval: CROSS_SUBNET_POLL_INTERVAL,
+        }
+    }
+}
 
 /// A static wrapper around ADM account methods.
 pub struct Account {}
@@ -25,26 +57,70 @@ impl Account {
 
         match response.value {
             Some((_, state)) => Ok(state.sequence),
-            None => Err(anyhow!(
+            None => Err(NotFound::new(format!(
                 "failed to get sequence; actor {} cannot be found",
                 signer.address()
-            )),
+            ))
+            .into()),
         }
     }
 
+    /// Get the sequence (nonce) for a [`Signer`] at each of the given heights,
+    /// e.g., its committed and pending sequence.
+    ///
+    /// The lookups are issued together with [`QueryProvider::actor_states`].
+    pub async fn sequences(
+        provider: &impl QueryProvider,
+        signer: &impl Signer,
+        heights: Vec<FvmQueryHeight>,
+    ) -> anyhow::Result<Vec<u64>> {
+        let address = signer.address();
+        provider
+            .actor_states(&address, heights)
+            .await?
+            .into_iter()
+            .map(|response| match response.value {
+                Some((_, state)) => Ok(state.sequence),
+                None => Err(NotFound::new(format!(
+                    "failed to get sequence; actor {} cannot be found",
+                    address
+                ))
+                .into()),
+            })
+            .collect()
+    }
+
     /// Get the balance for a [`Signer`] at the given height.
     pub async fn balance(signer: &impl Signer, subnet: EVMSubnet) -> anyhow::Result<TokenAmount> {
         EvmManager::balance(signer.address(), subnet).await
     }
 
     /// Deposit funds from a [`Signer`] to an address in the given subnet.
+    ///
+    /// Use [`DepositOptions::finality`] to wait until the deposit can't be undone by a
+    /// parent chain reorg.
     pub async fn deposit(
         signer: &impl Signer,
         to: Address,
         subnet: EVMSubnet,
         amount: TokenAmount,
-    ) -> anyhow::Result<TransactionReceipt> {
-        EvmManager::deposit(signer, to, subnet, amount).await
+        options: DepositOptions,
+    ) -> anyhow::Result<CrossMsgReceipt> {
+        let id = subnet.id.clone();
+        cross_chain("deposit", &id, async {
+            let mut sent = EvmManager::deposit(signer, to, subnet.clone(), amount).await?;
+            if options.finality > 0 {
+                sent.receipt = EvmManager::wait_finality(
+                    &sent.receipt,
+                    subnet,
+                    options.finality,
+                    options.poll_interval,
+                )
+                .await?;
+            }
+            Ok(sent)
+        })
+        .await
     }
 
     /// Withdraw funds from a [`Signer`] to an address in the given subnet.
@@ -53,8 +129,14 @@ impl Account {
         to: Address,
         subnet: EVMSubnet,
         amount: TokenAmount,
-    ) -> anyhow::Result<TransactionReceipt> {
-        EvmManager::withdraw(signer, to, subnet, amount).await
+    ) -> anyhow::Result<CrossMsgReceipt> {
+        let id = subnet.id.clone();
+        cross_chain(
+            "withdraw",
+            &id,
+            EvmManager::withdraw(signer, to, subnet, amount),
+        )
+        .await
     }
 
     /// Transfer funds from [`Signer`] to an address in the given subnet.
@@ -66,4 +148,87 @@ impl Account {
     ) -> anyhow::Result<TransactionReceipt> {
         EvmManager::transfer(signer, to, subnet, amount).await
     }
+
+    /// Transfer funds from a [`Signer`] in one subnet to an address in another subnet.
+    ///
+    /// Funds can be moved from a parent to one of its children (a deposit),
+    /// or from a child to its parent (a withdrawal).
+    /// The signer must target `from_subnet`, and each config must point at its own subnet's chain.
+    ///
+    /// After the transaction is committed in the source subnet, this waits until the funds
+    /// arrive in the destination subnet, and then returns the source transaction receipt.
+    pub async fn transfer_cross_subnet(
+        signer: &impl Signer,
+        to: Address,
+        from_subnet: EVMSubnet,
+        to_subnet: EVMSubnet,
+        amount: TokenAmount,
+    ) -> anyhow::Result<TransactionReceipt> {
+        // The child is the subnet the funds move into or out of
+        let child = if to_subnet.id.is_child_of(&from_subnet.id) {
+            to_subnet.id.clone()
+        } else {
+            from_subnet.id.clone()
+        };
+        let transfer = Self::send_cross_subnet(signer, to, from_subnet, to_subnet, amount);
+        cross_chain("transfer_cross_subnet", &child, transfer).await
+    }
+
+    /// Sends a cross-subnet transfer for [`Account::transfer_cross_subnet`]
+    /// and waits until it's executed.
+    async fn send_cross_subnet(
+        signer: &impl Signer,
+        to: Address,
+        from_subnet: EVMSubnet,
+        to_subnet: EVMSubnet,
+        amount: TokenAmount,
+    ) -> anyhow::Result<TransactionReceipt> {
+        if signer.subnet_id().map(|id| id.chain_id()) != Some(from_subnet.id.chain_id()) {
+            return Err(anyhow!(
+                "signer must target the source subnet {}",
+                from_subnet.id
+            ));
+        }
+
+        let starting_balance = EvmManager::balance(to, to_subnet.clone()).await?;
+
+        let sent = if to_subnet.id.is_child_of(&from_subnet.id) {
+            // The gateway's deposit targets the child in the config ID
+            let config = EVMSubnet {
+                id: to_subnet.id.clone(),
+                ..from_subnet
+            };
+            EvmManager::deposit(signer, to, config, amount.clone()).await?
+        } else if from_subnet.id.is_child_of(&to_subnet.id) {
+            EvmManager::withdraw(signer, to, from_subnet, amount.clone()).await?
+        } else {
+            return Err(anyhow!(
+                "cannot transfer from {} to {}; subnets must be parent and child",
+                from_subnet.id,
+                to_subnet.id
+            ));
+        };
+
+        let CrossMsgReceipt { receipt, message } = sent;
+        let target_balance = starting_balance + amount;
+        let started = Instant::now();
+        loop {
+            // Top-down messages can be matched by nonce; bottom-up messages only by balance
+            let executed = match message.nonce {
+                Some(_) => EvmManager::top_down_executed(&message, to_subnet.clone()).await?,
+                None => EvmManager::balance(to, to_subnet.clone()).await? >= target_balance,
+            };
+            if executed {
+                return Ok(receipt);
+            }
+            if started.elapsed() > CROSS_SUBNET_TIMEOUT {
+                return Err(anyhow!(
+                    "timed out waiting for transfer to be executed in {}; transaction hash: {:?}",
+                    to_subnet.id,
+                    receipt.transaction_hash
+                ));
+            }
+            tokio::time::sleep(CROSS_SUBNET_POLL_INTERVAL).await;
+        }
+    }
 }
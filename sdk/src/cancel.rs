@@ -0,0 +1,136 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Cooperative cancellation of long-running operations.
+//!
+//! Operations that take a [`Cancellation`] in their options stop at the next await point
+//! after its token is cancelled or its deadline passes, and fail with a [`Cancelled`] error.
+
+use std::fmt;
+use std::future::{pending, Future};
+use std::time::Duration;
+
+use tokio::time::{sleep_until, Instant};
+
+pub use tokio_util::sync::CancellationToken;
+
+/// The error returned by a cancelled operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cancelled {
+    /// The cancellation token was cancelled.
+    Token,
+    /// The deadline passed.
+    Deadline,
+}
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cancelled::Token => write!(f, "operation was cancelled"),
+            Cancelled::Deadline => write!(f, "operation deadline exceeded"),
+        }
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// A cancellation token and deadline for an operation.
+///
+/// The default is never cancelled.
+#[derive(Clone, Debug, Default)]
+pub struct Cancellation {
+    token: Option<CancellationToken>,
+    deadline: Option<Instant>,
+}
+
+impl Cancellation {
+    /// Cancel when the token is cancelled.
+    pub fn with_token(mut self, token: CancellationToken) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    /// Cancel when the deadline passes.
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Cancel when the timeout, measured from now, passes.
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        self.with_deadline(Instant::now() + timeout)
+    }
+
+    /// Returns an error if the operation is cancelled.
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.token.as_ref().is_some_and(|t| t.is_cancelled()) {
+            return Err(Cancelled::Token);
+        }
+        if self.deadline.is_some_and(|d| d <= Instant::now()) {
+            return Err(Cancelled::Deadline);
+        }
+        Ok(())
+    }
+
+    /// Waits until the operation is cancelled, which may be never.
+    pub async fn cancelled(&self) -> Cancelled {
+        let token = async {
+            match &self.token {
+                Some(token) => token.cancelled().await,
+                None => pending().await,
+            }
+        };
+        let deadline = async {
+            match self.deadline {
+                Some(deadline) => sleep_until(deadline).await,
+                None => pending().await,
+            }
+        };
+        tokio::select! {
+            _ = token => Cancelled::Token,
+            _ = deadline => Cancelled::Deadline,
+        }
+    }
+
+    /// Runs the future until it completes or the operation is cancelled.
+    ///
+    /// The future is dropped when cancelled, which aborts any HTTP request it has in flight.
+    pub async fn run<F, T>(&self, future: F) -> anyhow::Result<T>
+    where
+        F: Future<Output = anyhow::Result<T>>,
+    {
+        self.check()?;
+        tokio::select! {
+            biased;
+            result = future => result,
+            reason = self.cancelled() => Err(reason.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cancellation() {
+        let never = Cancellation::default();
+        assert!(never.check().is_ok());
+        assert_eq!(never.run(async { Ok(1) }).await.unwrap(), 1);
+
+        let token = CancellationToken::new();
+        let cancel = Cancellation::default().with_token(token.clone());
+        let run = cancel.run(pending::<anyhow::Result<()>>());
+        token.cancel();
+        let err = run.await.unwrap_err();
+        assert_eq!(err.downcast_ref::<Cancelled>(), Some(&Cancelled::Token));
+        assert_eq!(cancel.check(), Err(Cancelled::Token));
+
+        let cancel = Cancellation::default().with_timeout(Duration::from_millis(10));
+        let err = cancel
+            .run(pending::<anyhow::Result<()>>())
+            .await
+            .unwrap_err();
+        assert_eq!(err.downcast_ref::<Cancelled>(), Some(&Cancelled::Deadline));
+    }
+}
@@ -0,0 +1,258 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Height-pinned, cached queries for explorers and other read-only UIs.
+//!
+//! An [`ExplorerClient`] snapshots a block height and serves every query at that height,
+//! so paging through a listing stays consistent while new blocks land.
+//! Results never change for a given height, so they are cached until the client is
+//! [refreshed](ExplorerClient::refresh).
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+
+use fendermint_vm_message::query::FvmQueryHeight;
+use fvm_shared::address::Address;
+use serde::{de::DeserializeOwned, Serialize};
+
+use adm_provider::{query::QueryProvider, response::Cid};
+
+#[cfg(feature = "objects")]
+use crate::machine::objectstore::{Cursor, Key, Object, ObjectPage, ObjectStore, QueryOptions};
+use crate::machine::{accumulator::Accumulator, info, list_owned, Machine, Metadata, OwnedMachine};
+
+/// Identifies a cached query result.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum CacheKey {
+    Info(Address),
+    Machines(Address),
+    #[cfg(feature = "objects")]
+    Objects {
+        address: Address,
+        prefix: String,
+        delimiter: String,
+        offset: u64,
+        limit: u64,
+        cursor: Option<Cursor>,
+    },
+    #[cfg(feature = "objects")]
+    Object(Address, String),
+    Count(Address),
+    Leaf(Address, u64),
+    Peaks(Address),
+    Root(Address),
+}
+
+/// A read-only client that pins all machine, object, and accumulator queries to one height.
+///
+/// Results are cached for the pinned height.
+/// Use [`ExplorerClient::at_height`] to resume a snapshot, e.g., from a height carried in a
+/// page URL, so every page of a listing is read from the same state.
+pub struct ExplorerClient<P> {
+    provider: P,
+    height: u64,
+    /// CBOR-encoded query results.
+    cache: Mutex<HashMap<CacheKey, Vec<u8>>>,
+}
+
+impl<P: QueryProvider> ExplorerClient<P> {
+    /// Create a client pinned to the latest committed height.
+    pub async fn new(provider: P) -> anyhow::Result<Self> {
+        let height = committed_height(&provider).await?;
+        Ok(Self::at_height(provider, height))
+    }
+
+    /// Create a client pinned to the given height.
+    ///
+    /// Querying old heights requires a node that retains historical state.
+    pub fn at_height(provider: P, height: u64) -> Self {
+        Self {
+            provider,
+            height,
+            cache: Default::default(),
+        }
+    }
+
+    /// Returns the pinned height.
+    pub fn height(&self) -> u64 {
+        self.height
+    }
+
+    /// Returns the underlying provider.
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Pin the client to the latest committed height and clear the cache.
+    ///
+    /// Returns the new height.
+    pub async fn refresh(&mut self) -> anyhow::Result<u64> {
+        self.height = committed_height(&self.provider).await?;
+        self.cache.get_mut().unwrap().clear();
+        Ok(self.height)
+    }
+
+    /// Get machine info (the owner and machine kind).
+    pub async fn info(&self, address: Address) -> anyhow::Result<Metadata> {
+        self.cached(
+            CacheKey::Info(address),
+            info(&self.provider, address, self.query_height()),
+        )
+        .await
+    }
+
+    /// List machines of all kinds owned by the given address.
+    pub async fn machines(&self, owner: Address) -> anyhow::Result<Vec<OwnedMachine>> {
+        self.cached(
+            CacheKey::Machines(owner),
+            list_owned(&self.provider, owner, self.query_height()),
+        )
+        .await
+    }
+
+    /// Query for a page of objects in an object store.
+    ///
+    /// The height in `options` is ignored in favor of the pinned height.
+    /// Cursors from the returned pages are pinned to this height too.
+    #[cfg(feature = "objects")]
+    pub async fn objects(
+        &self,
+        address: Address,
+        options: QueryOptions,
+    ) -> anyhow::Result<ObjectPage> {
+        let key = CacheKey::Objects {
+            address,
+            prefix: options.prefix.clone(),
+            delimiter: options.delimiter.clone(),
+            offset: options.offset,
+            limit: options.limit,
+            cursor: options.cursor.clone(),
+        };
+        let options = QueryOptions {
+            height: self.query_height(),
+            ..options
+        };
+        let store = ObjectStore::attach(address);
+        self.cached(key, store.query_page(&self.provider, options))
+            .await
+    }
+
+    /// Get an object's state, or `None` if the key does not exist.
+    #[cfg(feature = "objects")]
+    pub async fn object(&self, address: Address, key: &str) -> anyhow::Result<Option<Object>> {
+        let key = Key::new(key)?;
+        let store = ObjectStore::attach(address);
+        self.cached(
+            CacheKey::Object(address, key.as_str().to_string()),
+            store.get_object(&self.provider, &key, self.query_height()),
+        )
+        .await
+    }
+
+    /// Get the total leaf count of an accumulator.
+    pub async fn count(&self, address: Address) -> anyhow::Result<u64> {
+        let accumulator = Accumulator::attach(address);
+        self.cached(
+            CacheKey::Count(address),
+            accumulator.count(&self.provider, self.query_height()),
+        )
+        .await
+    }
+
+    /// Get the accumulator leaf stored at the given index.
+    pub async fn leaf(&self, address: Address, index: u64) -> anyhow::Result<Vec<u8>> {
+        let accumulator = Accumulator::attach(address);
+        self.cached(
+            CacheKey::Leaf(address, index),
+            accumulator.leaf(&self.provider, index, self.query_height()),
+        )
+        .await
+    }
+
+    /// Get all peaks of an accumulator.
+    pub async fn peaks(&self, address: Address) -> anyhow::Result<Vec<Cid>> {
+        let accumulator = Accumulator::attach(address);
+        self.cached(
+            CacheKey::Peaks(address),
+            accumulator.peaks(&self.provider, self.query_height()),
+        )
+        .await
+    }
+
+    /// Get the root of an accumulator.
+    pub async fn root(&self, address: Address) -> anyhow::Result<Cid> {
+        let accumulator = Accumulator::attach(address);
+        self.cached(
+            CacheKey::Root(address),
+            accumulator.root(&self.provider, self.query_height()),
+        )
+        .await
+    }
+
+    fn query_height(&self) -> FvmQueryHeight {
+        FvmQueryHeight::Height(self.height)
+    }
+}
+
+impl<P> ExplorerClient<P> {
+    /// Returns the cached result for `key`, or runs the query and caches its result.
+    ///
+    /// Errors are not cached.
+    async fn cached<T, F>(&self, key: CacheKey, query: F) -> anyhow::Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: Future<Output = anyhow::Result<T>>,
+    {
+        let hit = self.cache.lock().unwrap().get(&key).cloned();
+        if let Some(data) = hit {
+            return Ok(fvm_ipld_encoding::from_slice(&data)?);
+        }
+        let value = query.await?;
+        let data = fvm_ipld_encoding::to_vec(&value)?;
+        self.cache.lock().unwrap().insert(key, data);
+        Ok(value)
+    }
+}
+
+/// Returns the height the node resolves committed-state queries to.
+async fn committed_height(provider: &impl QueryProvider) -> anyhow::Result<u64> {
+    let response = provider.state_params(FvmQueryHeight::Committed).await?;
+    Ok(response.height.value())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cached() {
+        let client = ExplorerClient::<()> {
+            provider: (),
+            height: 1,
+            cache: Default::default(),
+        };
+        let address = Address::new_id(100);
+        let first: u64 = client
+            .cached(CacheKey::Count(address), async { Ok(1) })
+            .await
+            .unwrap();
+        let second: u64 = client
+            .cached(CacheKey::Count(address), async { Ok(2) })
+            .await
+            .unwrap();
+        assert_eq!(first, second);
+
+        let failed = client
+            .cached::<u64, _>(CacheKey::Root(address), async {
+                Err(anyhow::anyhow!("not found"))
+            })
+            .await;
+        assert!(failed.is_err());
+        let root: u64 = client
+            .cached(CacheKey::Root(address), async { Ok(3) })
+            .await
+            .unwrap();
+        assert_eq!(root, 3);
+    }
+}
@@ -1,5 +1,5 @@
 // Copyright 2024 ADM Contributors
 // SPDX-License-Identifier: Apache-2.0, MIT
 
-pub(crate) mod manager;
+pub mod manager;
 pub mod subnet;
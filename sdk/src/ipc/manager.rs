@@ -11,19 +11,24 @@ use ethers::{
     middleware::{Middleware, SignerMiddleware},
     prelude::{
         Authorization, Eip1559TransactionRequest, Http, LocalWallet, Provider, Signer as EthSigner,
-        Wallet, I256, U256,
+        Wallet,
     },
     types::TransactionReceipt,
 };
-use ethers_contract::ContractCall;
-use fvm_shared::{address::Address, econ::TokenAmount};
-use gateway_manager_facet::{FvmAddress, GatewayManagerFacet, SubnetID as GatewaySubnetID};
-use ipc_actors_abis::gateway_manager_facet;
-use ipc_api::evm::{fil_to_eth_amount, payload_to_evm_address};
-use num_traits::ToPrimitive;
+use fvm_shared::{address::Address, clock::ChainEpoch, econ::TokenAmount};
+use ipc_api::{
+    evm::{fil_to_eth_amount, payload_to_evm_address},
+    subnet::PermissionMode,
+};
 use reqwest::{header::HeaderValue, Client};
 
-use adm_signer::Signer;
+use adm_abis::{
+    gas::premium_estimation,
+    gateway::{CrossMsg, CrossMsgReceipt, Gateway},
+    registry::{CreateSubnetParams, Registry},
+    subnet_actor::{SubnetActor, Validator},
+};
+use adm_signer::{Signer, SubnetID};
 
 use crate::ipc::subnet::EVMSubnet;
 
@@ -33,14 +38,33 @@ type DefaultSignerMiddleware = SignerMiddleware<Provider<Http>, Wallet<SigningKe
 /// transactions and events. Default is 7, and for our child subnets we
 /// can reduce it to the block time (or potentially less)
 const ETH_PROVIDER_POLLING_TIME: Duration = Duration::from_secs(1);
-/// Maximum number of retries to fetch a transaction receipt.
-/// The number of retries should ensure that for the block time
-/// of the network the number of retires considering the polling
-/// time above waits enough tie to get the transaction receipt.
-/// We currently support a low polling time and high number of
-/// retries so these numbers accommodate fast subnets with slow
-/// roots (like Calibration and mainnet).
-const TRANSACTION_RECEIPT_RETRIES: usize = 200;
+
+/// Options for creating a child subnet.
+#[derive(Clone, Debug)]
+pub struct CreateSubnetOptions {
+    /// Minimum collateral required for the subnet to be activated.
+    pub min_validator_stake: TokenAmount,
+    /// Minimum number of validators required for the subnet to be activated.
+    pub min_validators: u64,
+    /// Number of parent blocks between bottom-up checkpoints.
+    pub bottom_up_check_period: ChainEpoch,
+    /// Maximum number of active validators.
+    pub active_validators_limit: u16,
+    /// How validator power is determined.
+    pub permission_mode: PermissionMode,
+}
+
+impl Default for CreateSubnetOptions {
+    fn default() -> Self {
+        Self {
+            min_validator_stake: TokenAmount::from_whole(1),
+            min_validators: 1,
+            bottom_up_check_period: 100,
+            active_validators_limit: 100,
+            permission_mode: PermissionMode::Collateral,
+        }
+    }
+}
 
 /// Returns an Ethereum provider for the given subnet configuration.
 fn get_eth_provider(subnet: &EVMSubnet) -> anyhow::Result<Provider<Http>> {
@@ -91,19 +115,28 @@ fn get_eth_signer(
     Ok(SignerMiddleware::new(provider, wallet))
 }
 
-/// Returns an interface to a [`GatewayManagerFacet`]
+/// Returns an interface to a [`Gateway`]
 /// using [`Signer`] for the given subnet configuration.
 fn get_gateway(
     signer: &impl Signer,
     subnet: &EVMSubnet,
-) -> anyhow::Result<Box<GatewayManagerFacet<DefaultSignerMiddleware>>> {
-    let address = payload_to_evm_address(subnet.gateway_addr.payload())?;
+) -> anyhow::Result<Gateway<DefaultSignerMiddleware>> {
     let signer = get_eth_signer(signer, subnet)?;
+    Gateway::new(subnet.gateway_addr, Arc::new(signer))
+}
 
-    Ok(Box::new(GatewayManagerFacet::new(
-        address,
-        Arc::new(signer),
-    )))
+/// Returns an interface to the subnet actor of the given subnet
+/// using [`Signer`] for the given parent subnet configuration.
+fn get_subnet_actor(
+    signer: &impl Signer,
+    id: &SubnetID,
+    parent: &EVMSubnet,
+) -> anyhow::Result<SubnetActor<DefaultSignerMiddleware>> {
+    let actor = id
+        .actor()
+        .ok_or_else(|| anyhow!("subnet {} has no subnet actor", id))?;
+    let signer = get_eth_signer(signer, parent)?;
+    SubnetActor::new(actor, Arc::new(signer))
 }
 
 /// A static wrapper around common EVM subnet methods.
@@ -119,45 +152,252 @@ impl EvmManager {
         Ok(TokenAmount::from_atto(balance.as_u128()))
     }
 
+    /// List the child subnets registered with the subnet's gateway.
+    pub async fn list_subnets(subnet: EVMSubnet) -> anyhow::Result<Vec<SubnetID>> {
+        let provider = get_eth_provider(&subnet)?;
+        let gateway = Gateway::new(subnet.gateway_addr, Arc::new(provider))?;
+        let subnets = gateway.list_subnets().await?;
+        Ok(subnets.into_iter().map(SubnetID::from).collect())
+    }
+
+    /// Returns whether the given subnet is registered as a child with the parent's gateway.
+    ///
+    /// The `parent` config must point at the gateway of the subnet's parent chain.
+    pub async fn subnet_exists(id: &SubnetID, parent: EVMSubnet) -> anyhow::Result<bool> {
+        let provider = get_eth_provider(&parent)?;
+        let gateway = Gateway::new(parent.gateway_addr, Arc::new(provider))?;
+        gateway.has_subnet(&id.inner()).await
+    }
+
+    /// List the active and waiting validators of a subnet.
+    ///
+    /// The `parent` config must point at the subnet's parent chain, where its subnet actor lives.
+    pub async fn list_validators(
+        id: &SubnetID,
+        parent: EVMSubnet,
+    ) -> anyhow::Result<Vec<Validator>> {
+        let actor = id
+            .actor()
+            .ok_or_else(|| anyhow!("subnet {} has no subnet actor", id))?;
+        let provider = get_eth_provider(&parent)?;
+        let subnet_actor = SubnetActor::new(actor, Arc::new(provider))?;
+        subnet_actor.validators().await
+    }
+
+    /// Create a new child subnet by deploying a subnet actor with the parent's registry.
+    ///
+    /// The `parent` config must point at the parent chain, and the signer must target
+    /// the parent subnet. Returns the ID of the new subnet.
+    pub async fn create_subnet(
+        signer: &impl Signer,
+        parent: EVMSubnet,
+        options: CreateSubnetOptions,
+    ) -> anyhow::Result<SubnetID> {
+        let signer = get_eth_signer(signer, &parent)?;
+        let registry = Registry::new(parent.registry_addr, Arc::new(signer))?;
+        let actor = registry
+            .create_subnet(&CreateSubnetParams {
+                parent: parent.id.inner(),
+                gateway: parent.gateway_addr,
+                min_validator_stake: options.min_validator_stake,
+                min_validators: options.min_validators,
+                bottom_up_check_period: options.bottom_up_check_period,
+                active_validators_limit: options.active_validators_limit,
+                permission_mode: options.permission_mode,
+            })
+            .await?;
+        parent.id.child(actor)
+    }
+
+    /// Join a subnet as a validator with the given initial collateral.
+    ///
+    /// The `parent` config must point at the subnet's parent chain, and the signer must target
+    /// the parent subnet. The signer's public key is registered as the validator key.
+    pub async fn join_subnet(
+        signer: &impl Signer,
+        id: &SubnetID,
+        parent: EVMSubnet,
+        collateral: TokenAmount,
+    ) -> anyhow::Result<TransactionReceipt> {
+        let public_key = signer
+            .secret_key()
+            .ok_or_else(|| anyhow!("failed to get secret key from signer"))?
+            .public_key()
+            .serialize();
+        let subnet_actor = get_subnet_actor(signer, id, &parent)?;
+        subnet_actor.join(&public_key, &collateral).await
+    }
+
+    /// Leave a subnet as a validator.
+    ///
+    /// The `parent` config must point at the subnet's parent chain, and the signer must target
+    /// the parent subnet.
+    pub async fn leave_subnet(
+        signer: &impl Signer,
+        id: &SubnetID,
+        parent: EVMSubnet,
+    ) -> anyhow::Result<TransactionReceipt> {
+        let subnet_actor = get_subnet_actor(signer, id, &parent)?;
+        subnet_actor.leave().await
+    }
+
+    /// Kill a subnet. All validators must have left the subnet.
+    ///
+    /// The `parent` config must point at the subnet's parent chain, and the signer must target
+    /// the parent subnet.
+    pub async fn kill_subnet(
+        signer: &impl Signer,
+        id: &SubnetID,
+        parent: EVMSubnet,
+    ) -> anyhow::Result<TransactionReceipt> {
+        let subnet_actor = get_subnet_actor(signer, id, &parent)?;
+        subnet_actor.kill().await
+    }
+
+    /// Get info about a validator of a subnet.
+    ///
+    /// The `parent` config must point at the subnet's parent chain, where its subnet actor lives.
+    pub async fn validator_info(
+        address: Address,
+        id: &SubnetID,
+        parent: EVMSubnet,
+    ) -> anyhow::Result<Validator> {
+        let actor = id
+            .actor()
+            .ok_or_else(|| anyhow!("subnet {} has no subnet actor", id))?;
+        let provider = get_eth_provider(&parent)?;
+        let subnet_actor = SubnetActor::new(actor, Arc::new(provider))?;
+        subnet_actor
+            .validator(payload_to_evm_address(address.payload())?)
+            .await
+    }
+
+    /// Add collateral to the signer's validator stake in a subnet.
+    ///
+    /// The `parent` config must point at the subnet's parent chain, and the signer must target
+    /// the parent subnet.
+    pub async fn stake(
+        signer: &impl Signer,
+        id: &SubnetID,
+        parent: EVMSubnet,
+        amount: TokenAmount,
+    ) -> anyhow::Result<TransactionReceipt> {
+        let subnet_actor = get_subnet_actor(signer, id, &parent)?;
+        subnet_actor.stake(&amount).await
+    }
+
+    /// Remove collateral from the signer's validator stake in a subnet.
+    ///
+    /// The `parent` config must point at the subnet's parent chain, and the signer must target
+    /// the parent subnet. Use [`EvmManager::claim`] to collect the collateral once released.
+    pub async fn unstake(
+        signer: &impl Signer,
+        id: &SubnetID,
+        parent: EVMSubnet,
+        amount: TokenAmount,
+    ) -> anyhow::Result<TransactionReceipt> {
+        let subnet_actor = get_subnet_actor(signer, id, &parent)?;
+        subnet_actor.unstake(&amount).await
+    }
+
+    /// Claim the signer's released collateral from a subnet.
+    ///
+    /// The `parent` config must point at the subnet's parent chain, and the signer must target
+    /// the parent subnet.
+    pub async fn claim(
+        signer: &impl Signer,
+        id: &SubnetID,
+        parent: EVMSubnet,
+    ) -> anyhow::Result<TransactionReceipt> {
+        let subnet_actor = get_subnet_actor(signer, id, &parent)?;
+        subnet_actor.claim().await
+    }
+
     /// Deposit funds into a subnet.
+    ///
+    /// Returns the receipt along with the top-down message committed by the parent's gateway.
     pub async fn deposit(
         signer: &impl Signer,
         to: Address,
         subnet: EVMSubnet,
         amount: TokenAmount,
-    ) -> anyhow::Result<TransactionReceipt> {
+    ) -> anyhow::Result<CrossMsgReceipt> {
         let gateway = get_gateway(signer, &subnet)?;
-        let subnet_id = GatewaySubnetID::try_from(&subnet.id.inner())?;
-
-        let value = amount
-            .atto()
-            .to_u128()
-            .ok_or_else(|| anyhow!("invalid value to fund"))?;
-
-        let mut call = gateway.fund(subnet_id, FvmAddress::try_from(to)?);
-        call.tx.set_value(value);
-
-        client_send(gateway.client(), call).await
+        gateway.deposit(&subnet.id.inner(), to, &amount).await
     }
 
     /// Withdraw funds from a subnet.
+    ///
+    /// Returns the receipt along with the bottom-up message queued by the subnet's gateway.
     pub async fn withdraw(
         signer: &impl Signer,
         to: Address,
         subnet: EVMSubnet,
         amount: TokenAmount,
-    ) -> anyhow::Result<TransactionReceipt> {
+    ) -> anyhow::Result<CrossMsgReceipt> {
         let gateway = get_gateway(signer, &subnet)?;
+        gateway.release(&subnet.id.inner(), to, &amount).await
+    }
 
-        let value = amount
-            .atto()
-            .to_u128()
-            .ok_or_else(|| anyhow!("invalid value to fund"))?;
-
-        let mut call = gateway.release(FvmAddress::try_from(to)?);
-        call.tx.set_value(value);
+    /// Wait until `finality` blocks follow the block that includes a transaction,
+    /// and return its receipt from that block.
+    ///
+    /// If a reorg moves the transaction to another block, the wait restarts from that block.
+    /// An error is returned if a reorg drops or reverts the transaction.
+    pub async fn wait_finality(
+        receipt: &TransactionReceipt,
+        subnet: EVMSubnet,
+        finality: u64,
+        poll_interval: Duration,
+    ) -> anyhow::Result<TransactionReceipt> {
+        let provider = get_eth_provider(&subnet)?;
+        let hash = receipt.transaction_hash;
+        let mut included = receipt
+            .block_number
+            .ok_or_else(|| anyhow!("transaction {:?} is not included in a block", hash))?
+            .as_u64();
+        loop {
+            let head = provider.get_block_number().await?.as_u64();
+            if head >= included + finality {
+                // Check the transaction is still in the same block now that it's final
+                let receipt = provider
+                    .get_transaction_receipt(hash)
+                    .await?
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "transaction {:?} was dropped by a reorg in {}",
+                            hash,
+                            subnet.id
+                        )
+                    })?;
+                if receipt.status == Some(0u64.into()) {
+                    return Err(anyhow!(
+                        "transaction {:?} was reverted after a reorg in {}",
+                        hash,
+                        subnet.id
+                    ));
+                }
+                match receipt.block_number {
+                    Some(block) if block.as_u64() == included => return Ok(receipt),
+                    Some(block) => included = block.as_u64(),
+                    None => {}
+                }
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
 
-        client_send(gateway.client(), call).await
+    /// Returns whether a top-down message, e.g., from [`EvmManager::deposit`],
+    /// has been executed in its destination subnet.
+    ///
+    /// The `subnet` config must point at the destination subnet's chain.
+    pub async fn top_down_executed(message: &CrossMsg, subnet: EVMSubnet) -> anyhow::Result<bool> {
+        let nonce = message
+            .nonce
+            .ok_or_else(|| anyhow!("cross-net message has no nonce"))?;
+        let provider = get_eth_provider(&subnet)?;
+        let gateway = Gateway::new(subnet.gateway_addr, Arc::new(provider))?;
+        Ok(gateway.applied_top_down_nonce().await? > nonce)
     }
 
     /// Transfer funds between two accounts in a subnet.
@@ -169,7 +409,7 @@ impl EvmManager {
     ) -> anyhow::Result<TransactionReceipt> {
         let signer = Arc::new(get_eth_signer(signer, &subnet)?);
 
-        let (fee, fee_cap) = premium_estimation(signer.clone()).await?;
+        let (fee, fee_cap) = premium_estimation(signer.as_ref()).await?;
         let tx = Eip1559TransactionRequest::new()
             .to(payload_to_evm_address(to.payload())?)
             .value(fil_to_eth_amount(&amount)?)
@@ -182,134 +422,3 @@ impl EvmManager {
             .ok_or(anyhow!("transfer did not return receipt"))
     }
 }
-
-/// Sends a contract call with configured retries using the provided client.
-async fn client_send(
-    client: Arc<DefaultSignerMiddleware>,
-    call: ContractCall<DefaultSignerMiddleware, ()>,
-) -> anyhow::Result<TransactionReceipt> {
-    let call = call_with_premium_estimation(client, call).await?;
-    let tx = call.send().await?;
-    match tx.retries(TRANSACTION_RECEIPT_RETRIES).await? {
-        Some(receipt) => Ok(receipt),
-        None => Err(anyhow!(
-            "txn sent to network, but receipt cannot be obtained, please check scanner"
-        )),
-    }
-}
-
-/// Receives an input `FunctionCall` and returns a new instance
-/// after estimating an optimal `gas_premium` for the transaction
-async fn call_with_premium_estimation<B, D, M>(
-    signer: Arc<DefaultSignerMiddleware>,
-    call: ethers_contract::FunctionCall<B, D, M>,
-) -> anyhow::Result<ethers_contract::FunctionCall<B, D, M>>
-where
-    B: std::borrow::Borrow<D>,
-    M: ethers::abi::Detokenize,
-{
-    let (max_priority_fee_per_gas, _) = premium_estimation(signer).await?;
-    Ok(call.gas_price(max_priority_fee_per_gas))
-}
-
-/// Returns an estimation of an optimal `gas_premium` and `gas_fee_cap`
-/// for a transaction considering the average premium, base_fee and reward percentile from
-/// past blocks
-/// This is an adaptation of ethers' `eip1559_default_estimator`:
-/// https://github.com/gakonst/ethers-rs/blob/5dcd3b7e754174448f9a8cbfc0523896609629f9/ethers-core/src/utils/mod.rs#L476
-async fn premium_estimation(signer: Arc<DefaultSignerMiddleware>) -> anyhow::Result<(U256, U256)> {
-    let base_fee_per_gas = signer
-        .get_block(ethers::types::BlockNumber::Latest)
-        .await?
-        .ok_or_else(|| anyhow!("Latest block not found"))?
-        .base_fee_per_gas
-        .ok_or_else(|| anyhow!("EIP-1559 not activated"))?;
-
-    let fee_history = signer
-        .fee_history(
-            ethers::utils::EIP1559_FEE_ESTIMATION_PAST_BLOCKS,
-            ethers::types::BlockNumber::Latest,
-            &[ethers::utils::EIP1559_FEE_ESTIMATION_REWARD_PERCENTILE],
-        )
-        .await?;
-
-    let max_priority_fee_per_gas = estimate_priority_fee(fee_history.reward); //overestimate?
-    let potential_max_fee = base_fee_surged(base_fee_per_gas);
-    let max_fee_per_gas = if max_priority_fee_per_gas > potential_max_fee {
-        max_priority_fee_per_gas + potential_max_fee
-    } else {
-        potential_max_fee
-    };
-
-    Ok((max_priority_fee_per_gas, max_fee_per_gas))
-}
-
-/// Implementation borrowed from
-/// https://github.com/gakonst/ethers-rs/blob/ethers-v2.0.8/ethers-core/src/utils/mod.rs#L582
-/// Refer to the implementation for unit tests
-fn base_fee_surged(base_fee_per_gas: U256) -> U256 {
-    if base_fee_per_gas <= U256::from(40_000_000_000u64) {
-        base_fee_per_gas * 2
-    } else if base_fee_per_gas <= U256::from(100_000_000_000u64) {
-        base_fee_per_gas * 16 / 10
-    } else if base_fee_per_gas <= U256::from(200_000_000_000u64) {
-        base_fee_per_gas * 14 / 10
-    } else {
-        base_fee_per_gas * 12 / 10
-    }
-}
-
-/// Implementation borrowed from
-/// https://github.com/gakonst/ethers-rs/blob/ethers-v2.0.8/ethers-core/src/utils/mod.rs#L536
-/// Refer to the implementation for unit tests
-fn estimate_priority_fee(rewards: Vec<Vec<U256>>) -> U256 {
-    let mut rewards: Vec<U256> = rewards
-        .iter()
-        .map(|r| r[0])
-        .filter(|r| *r > U256::zero())
-        .collect();
-    if rewards.is_empty() {
-        return U256::zero();
-    }
-    if rewards.len() == 1 {
-        return rewards[0];
-    }
-    // Sort the rewards as we will eventually take the median.
-    rewards.sort();
-
-    // A copy of the same vector is created for convenience to calculate percentage change
-    // between subsequent fee values.
-    let mut rewards_copy = rewards.clone();
-    rewards_copy.rotate_left(1);
-
-    let mut percentage_change: Vec<I256> = rewards
-        .iter()
-        .zip(rewards_copy.iter())
-        .map(|(a, b)| {
-            let a = I256::try_from(*a).expect("priority fee overflow");
-            let b = I256::try_from(*b).expect("priority fee overflow");
-            ((b - a) * 100) / a
-        })
-        .collect();
-    percentage_change.pop();
-
-    // Fetch the max of the percentage change, and that element's index.
-    let max_change = percentage_change.iter().max().unwrap();
-    let max_change_index = percentage_change
-        .iter()
-        .position(|&c| c == *max_change)
-        .unwrap();
-
-    // If we encountered a big change in fees at a certain position, then consider only
-    // the values >= it.
-    let values = if *max_change >= ethers::utils::EIP1559_FEE_ESTIMATION_THRESHOLD_MAX_CHANGE.into()
-        && (max_change_index >= (rewards.len() / 2))
-    {
-        rewards[max_change_index..].to_vec()
-    } else {
-        rewards
-    };
-
-    // Return the median.
-    values[values.len() / 2]
-}
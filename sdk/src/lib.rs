@@ -4,14 +4,30 @@
 //! # ADM SDK
 //!
 //! The top-level user interface for managing ADM object storage and state accumulators.
+//!
+//! ## Features
+//!
+//! - `objects` (default): Object stores, including the upload stack, and name registries.
+//! - `progress` (default): Progress bars for object store operations in command-line interfaces.
+//! - `otel`: OpenTelemetry export of provider calls and cross-chain operations.
+//! - `testkit`: In-memory mock provider for unit testing apps without a network.
 
 use adm_provider::message::GasParams;
 
 pub mod account;
+pub mod cancel;
+pub mod explorer;
 pub mod ipc;
 pub mod machine;
+#[cfg(feature = "objects")]
+pub mod name;
 pub mod network;
+#[cfg(feature = "objects")]
 pub mod progress;
+mod telemetry;
+#[cfg(feature = "testkit")]
+pub mod testkit;
+pub mod tx;
 
 /// Arguments common to transactions.
 #[derive(Clone, Default, Debug)]
@@ -1,38 +1,206 @@
 // Copyright 2024 ADM Contributors
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use std::fmt;
+use std::time::Duration;
+
 use anyhow::anyhow;
 use async_trait::async_trait;
-use fendermint_actor_machine::{Metadata, WriteAccess, GET_METADATA_METHOD};
+use fendermint_actor_machine::GET_METADATA_METHOD;
 use fendermint_vm_actor_interface::adm::{
-    self, CreateExternalParams, CreateExternalReturn, Kind, ListMetadataParams,
-    Method::CreateExternal, Method::ListMetadata, ADM_ACTOR_ADDR,
+    self, CreateExternalParams, CreateExternalReturn, ListMetadataParams, Method::CreateExternal,
+    Method::ListMetadata, ADM_ACTOR_ADDR,
 };
-use fendermint_vm_message::query::FvmQueryHeight;
+use fendermint_vm_message::{chain::ChainMessage, query::FvmQueryHeight};
 use fvm_ipld_encoding::RawBytes;
-use fvm_shared::address::Address;
-use serde::Serialize;
+use fvm_shared::{address::Address, ActorID};
+use serde::{Deserialize, Serialize, Serializer};
 use tendermint::{abci::response::DeliverTx, block::Height, Hash};
 use tendermint_rpc::Client;
+use tokio::time::Instant;
 
 use adm_provider::{
     message::{local_message, GasParams},
     query::QueryProvider,
     response::decode_bytes,
-    tx::BroadcastMode,
-    Provider,
+    tx::{BroadcastMode, TxError, TxReceipt, TxStatus},
+    util::{get_delegated_address, is_not_found},
+    Provider, TendermintClient,
 };
 use adm_signer::Signer;
 
 pub mod accumulator;
+pub mod custom;
+#[cfg(feature = "objects")]
 pub mod objectstore;
 
+/// Delay between lookups of a deploy transaction in [`wait_deployed`].
+const DEPLOY_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The kind of a machine.
+///
+/// This and the other machine types mirror their actor counterparts,
+/// so the SDK API doesn't change with actor upgrades.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Kind {
+    /// An object store with S3-like key semantics.
+    ObjectStore,
+    /// An MMR accumulator.
+    Accumulator,
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&adm::Kind::from(*self), f)
+    }
+}
+
+impl From<Kind> for adm::Kind {
+    fn from(v: Kind) -> Self {
+        match v {
+            Kind::ObjectStore => adm::Kind::ObjectStore,
+            Kind::Accumulator => adm::Kind::Accumulator,
+        }
+    }
+}
+
+impl From<adm::Kind> for Kind {
+    fn from(v: adm::Kind) -> Self {
+        match v {
+            adm::Kind::ObjectStore => Kind::ObjectStore,
+            adm::Kind::Accumulator => Kind::Accumulator,
+        }
+    }
+}
+
+/// Which accounts can mutate a machine.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WriteAccess {
+    /// Only the owner can mutate the machine.
+    #[default]
+    OnlyOwner,
+    /// Any account can mutate the machine.
+    Public,
+}
+
+impl From<WriteAccess> for fendermint_actor_machine::WriteAccess {
+    fn from(v: WriteAccess) -> Self {
+        match v {
+            WriteAccess::OnlyOwner => fendermint_actor_machine::WriteAccess::OnlyOwner,
+            WriteAccess::Public => fendermint_actor_machine::WriteAccess::Public,
+        }
+    }
+}
+
+impl From<fendermint_actor_machine::WriteAccess> for WriteAccess {
+    fn from(v: fendermint_actor_machine::WriteAccess) -> Self {
+        match v {
+            fendermint_actor_machine::WriteAccess::OnlyOwner => WriteAccess::OnlyOwner,
+            fendermint_actor_machine::WriteAccess::Public => WriteAccess::Public,
+        }
+    }
+}
+
+/// Machine info returned by [`info`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metadata {
+    /// The machine kind.
+    pub kind: Kind,
+    /// The machine owner.
+    pub owner: Address,
+}
+
+impl From<fendermint_actor_machine::Metadata> for Metadata {
+    fn from(v: fendermint_actor_machine::Metadata) -> Self {
+        Self {
+            kind: v.kind.into(),
+            owner: v.owner,
+        }
+    }
+}
+
+/// A machine returned by [`Machine::list`] and [`list_owned`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OwnedMachine {
+    /// The machine kind.
+    pub kind: Kind,
+    /// The machine address.
+    pub address: Address,
+}
+
+impl From<adm::Metadata> for OwnedMachine {
+    fn from(v: adm::Metadata) -> Self {
+        Self {
+            kind: v.kind.into(),
+            address: v.address,
+        }
+    }
+}
+
 /// Deployed machine transaction receipt details.
+///
+/// The machine fields are only set once the transaction is committed.
 #[derive(Copy, Clone, Debug, Serialize)]
 pub struct DeployTxReceipt {
+    /// The transaction's current status.
+    pub status: TxStatus,
+    /// The hash of the transaction.
     pub hash: Hash,
-    pub height: Height,
+    /// The block height at which the transaction was included.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<Height>,
+    /// Gas used by the transaction.
     pub gas_used: i64,
+    /// The machine's actor ID.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor_id: Option<ActorID>,
+    /// The machine's robust address.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_address"
+    )]
+    pub robust_address: Option<Address>,
+    /// The machine's delegated EVM address, i.e., the ID-masked address of its actor ID.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub evm_address: Option<ethers::types::Address>,
+}
+
+impl DeployTxReceipt {
+    /// Create a receipt from a create transaction receipt.
+    fn new(tx: TxReceipt<CreateExternalReturn>) -> Self {
+        let actor_id = tx.data.as_ref().map(|data| data.actor_id);
+        Self {
+            status: tx.status,
+            hash: tx.hash,
+            height: tx.height,
+            gas_used: tx.gas_used,
+            actor_id,
+            robust_address: tx.data.and_then(|data| data.robust_address),
+            evm_address: actor_id.and_then(|id| get_delegated_address(Address::new_id(id)).ok()),
+        }
+    }
+}
+
+fn serialize_address<S: Serializer>(
+    address: &Option<Address>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    address.map(|a| a.to_string()).serialize(serializer)
+}
+
+/// Machine deployment options.
+#[derive(Clone, Default, Debug)]
+pub struct DeployOptions {
+    /// Which accounts can mutate the machine.
+    pub write_access: WriteAccess,
+    /// Broadcast mode for the transaction.
+    ///
+    /// With [`BroadcastMode::Async`] or [`BroadcastMode::Sync`], the machine is returned at its
+    /// predicted address before it exists, along with a pending receipt.
+    /// Use [`wait_deployed`] to wait for the machine to be created.
+    pub broadcast_mode: BroadcastMode,
+    /// Gas params for the transaction.
+    pub gas_params: GasParams,
 }
 
 /// Trait implemented by different machine kinds.
@@ -51,6 +219,25 @@ pub trait Machine: Send + Sync + Sized {
         write_access: WriteAccess,
         gas_params: GasParams,
     ) -> anyhow::Result<(Self, DeployTxReceipt)>
+    where
+        C: Client + Send + Sync,
+    {
+        let options = DeployOptions {
+            write_access,
+            broadcast_mode: BroadcastMode::Commit,
+            gas_params,
+        };
+        Self::deploy(provider, signer, options).await
+    }
+
+    /// Create a new machine instance with [`DeployOptions`].
+    ///
+    /// Unlike [`Machine::new`], this doesn't have to wait for the deploy to be committed.
+    async fn deploy<C>(
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        options: DeployOptions,
+    ) -> anyhow::Result<(Self, DeployTxReceipt)>
     where
         C: Client + Send + Sync;
 
@@ -59,22 +246,14 @@ pub trait Machine: Send + Sync + Sized {
         provider: &impl QueryProvider,
         signer: &impl Signer,
         height: FvmQueryHeight,
-    ) -> anyhow::Result<Vec<adm::Metadata>> {
-        let input = ListMetadataParams {
-            owner: signer.address(),
-        };
-        let params = RawBytes::serialize(input)?;
-        let message = local_message(ADM_ACTOR_ADDR, ListMetadata as u64, params);
-        let response = provider.call(message, height, decode_list).await?;
-
+    ) -> anyhow::Result<Vec<OwnedMachine>> {
         // Filtering "kind" on the client is a bit silly.
         // Maybe we can add a filter on "kind" in the adm actor.
-        // TODO: Implement PartialEq on Kind to avoid the string comparison.
-        let list: Vec<adm::Metadata> = response
-            .value
+        let list = list_owned(provider, signer.address(), height)
+            .await?
             .into_iter()
-            .filter(|m| m.kind.to_string() == Self::KIND.to_string())
-            .collect::<Vec<adm::Metadata>>();
+            .filter(|m| m.kind == Self::KIND)
+            .collect();
 
         Ok(list)
     }
@@ -94,21 +273,140 @@ pub async fn info(
 ) -> anyhow::Result<Metadata> {
     let message = local_message(address, GET_METADATA_METHOD, Default::default());
     let response = provider.call(message, height, decode_info).await?;
-    Ok(response.value)
+    Ok(response.value.into())
+}
+
+/// List machines of all kinds owned by the given address.
+pub async fn list_owned(
+    provider: &impl QueryProvider,
+    owner: Address,
+    height: FvmQueryHeight,
+) -> anyhow::Result<Vec<OwnedMachine>> {
+    let params = RawBytes::serialize(ListMetadataParams { owner })?;
+    let message = local_message(ADM_ACTOR_ADDR, ListMetadata as u64, params);
+    let response = provider.call(message, height, decode_list).await?;
+    Ok(response.value.into_iter().map(OwnedMachine::from).collect())
+}
+
+/// Returns the height at which a machine's state last changed, at or before `height`.
+///
+/// The machine actor's state root is compared across heights with a binary search,
+/// so this requires a node that retains historical state.
+pub(crate) async fn last_modified(
+    provider: &impl QueryProvider,
+    address: Address,
+    height: u64,
+) -> anyhow::Result<u64> {
+    let state = |height: u64| async move {
+        let response = provider
+            .actor_state(&address, FvmQueryHeight::Height(height))
+            .await?;
+        Ok::<_, anyhow::Error>(response.value.map(|(_, state)| state.state))
+    };
+    let current = state(height).await?;
+
+    // Height zero means the latest height to the node, so start at one
+    let (mut low, mut high) = (1, height.max(1));
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if state(mid).await? == current {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    Ok(low)
+}
+
+/// Predict the robust address of the machine created by a deploy message
+/// sent from `origin` with the given `sequence` (nonce).
+///
+/// This mirrors the FVM's actor address derivation, which hashes the message origin,
+/// its sequence, and the number of actors already created by the message.
+/// Deploys create exactly one actor, so the address is known before the
+/// deploy transaction is confirmed.
+pub fn predict_address(origin: &Address, sequence: u64) -> anyhow::Result<Address> {
+    let mut bytes = fvm_ipld_encoding::to_vec(origin)?;
+    bytes.extend_from_slice(&sequence.to_be_bytes());
+    bytes.extend_from_slice(&0u64.to_be_bytes());
+    Ok(Address::new_actor(&bytes))
+}
+
+/// Wait for a machine deployed with a non-commit [`BroadcastMode`] to be created.
+///
+/// The transaction is looked up by hash until it's committed, which requires the node to
+/// index transactions. Returns the machine address and the committed receipt,
+/// or an error if the deploy failed or isn't committed within `timeout`.
+/// Lookup errors other than the transaction not being found are returned right away.
+pub async fn wait_deployed<C>(
+    provider: &impl Provider<C>,
+    hash: Hash,
+    timeout: Duration,
+) -> anyhow::Result<(Address, DeployTxReceipt)>
+where
+    C: Client + Send + Sync,
+{
+    let started = Instant::now();
+    let response = loop {
+        match provider.underlying().tx(hash, false).await {
+            Ok(response) => break response,
+            // The transaction isn't committed and indexed yet
+            Err(e) if is_not_found(&e) => {}
+            Err(e) => {
+                return Err(anyhow::Error::new(e)
+                    .context(format!("failed to look up deploy transaction {}", hash)))
+            }
+        }
+        let elapsed = started.elapsed();
+        if elapsed >= timeout {
+            return Err(anyhow!(
+                "deploy transaction {} was not committed within {}s",
+                hash,
+                timeout.as_secs()
+            ));
+        }
+        tokio::time::sleep(DEPLOY_POLL_INTERVAL.min(timeout - elapsed)).await;
+    };
+    let result = &response.tx_result;
+    if result.code.is_err() {
+        return Err(TxError {
+            code: result.code.value(),
+            message: result.info.clone(),
+        }
+        .into());
+    }
+    let tx = TxReceipt::committed(
+        response.hash,
+        response.height,
+        result.gas_used,
+        Some(decode_create(result)?),
+        Vec::new(),
+    );
+    let receipt = DeployTxReceipt::new(tx);
+    let address = receipt
+        .robust_address
+        .ok_or_else(|| anyhow!("deploy transaction {} returned no machine address", hash))?;
+    Ok((address, receipt))
 }
 
 /// Deploys a machine.
+///
+/// The created machine address is returned, with a warning if it doesn't match the one returned
+/// by [`predict_address`], since the machine exists either way.
+/// With a non-commit broadcast mode, the predicted address is returned with a pending receipt.
 async fn deploy_machine<C>(
     provider: &impl Provider<C>,
     signer: &mut impl Signer,
     kind: Kind,
-    write_access: WriteAccess,
-    gas_params: GasParams,
+    options: DeployOptions,
 ) -> anyhow::Result<(Address, DeployTxReceipt)>
 where
     C: Client + Send + Sync,
 {
-    let params = CreateExternalParams { kind, write_access };
+    let params = CreateExternalParams {
+        kind: kind.into(),
+        write_access: options.write_access.into(),
+    };
     let params = RawBytes::serialize(params)?;
     let message = signer
         .transaction(
@@ -117,28 +415,39 @@ where
             CreateExternal as u64,
             params,
             None,
-            gas_params,
+            options.gas_params,
         )
         .await?;
+    let predicted = match &message {
+        ChainMessage::Signed(signed) => Some(predict_address(
+            &signed.message.from,
+            signed.message.sequence,
+        )?),
+        _ => None,
+    };
     let tx = provider
-        .perform(message, BroadcastMode::Commit, decode_create)
+        .perform(message, options.broadcast_mode, decode_create)
         .await?;
+    let receipt = DeployTxReceipt::new(tx);
 
-    // In commit broadcast mode, if the data or address do not exist, something fatal happened.
-    let address = tx
-        .data
-        .expect("data exists")
-        .robust_address
-        .expect("address exists");
-
-    Ok((
-        address,
-        DeployTxReceipt {
-            hash: tx.hash,
-            height: tx.height.expect("height exists"),
-            gas_used: tx.gas_used,
-        },
-    ))
+    let address = match (receipt.robust_address, predicted) {
+        (Some(address), Some(predicted)) if address != predicted => {
+            tracing::warn!(
+                "Machine deployed to {} but the predicted address was {}",
+                address,
+                predicted
+            );
+            address
+        }
+        (Some(address), _) | (None, Some(address)) => address,
+        (None, None) => {
+            return Err(anyhow!(
+                "machine address is unknown; use wait_deployed with transaction {} to find it",
+                receipt.hash
+            ));
+        }
+    };
+    Ok((address, receipt))
 }
 
 fn decode_create(deliver_tx: &DeliverTx) -> anyhow::Result<CreateExternalReturn> {
@@ -153,7 +462,45 @@ fn decode_list(deliver_tx: &DeliverTx) -> anyhow::Result<Vec<adm::Metadata>> {
         .map_err(|e| anyhow!("error parsing as Vec<adm::Metadata>: {e}"))
 }
 
-fn decode_info(deliver_tx: &DeliverTx) -> anyhow::Result<Metadata> {
+fn decode_info(deliver_tx: &DeliverTx) -> anyhow::Result<fendermint_actor_machine::Metadata> {
     let data = decode_bytes(deliver_tx)?;
     fvm_ipld_encoding::from_slice(&data).map_err(|e| anyhow!("error parsing as Metadata: {e}"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_predict_address() {
+        let origin = Address::new_id(1001);
+        let a = predict_address(&origin, 0).unwrap();
+        assert_eq!(a.protocol(), fvm_shared::address::Protocol::Actor);
+        assert_eq!(a, predict_address(&origin, 0).unwrap());
+        assert_ne!(a, predict_address(&origin, 1).unwrap());
+        assert_ne!(a, predict_address(&Address::new_id(1002), 0).unwrap());
+    }
+
+    #[test]
+    fn test_deploy_receipt() {
+        let pending = DeployTxReceipt::new(TxReceipt::pending(Hash::None));
+        assert!(pending.height.is_none());
+        assert!(pending.actor_id.is_none());
+        assert!(pending.robust_address.is_none());
+        assert!(pending.evm_address.is_none());
+
+        let robust_address = predict_address(&Address::new_id(1001), 0).unwrap();
+        let mut tx = TxReceipt::pending(Hash::None);
+        tx.data = Some(CreateExternalReturn {
+            actor_id: 1069,
+            robust_address: Some(robust_address),
+        });
+        let committed = DeployTxReceipt::new(tx);
+        assert_eq!(committed.actor_id, Some(1069));
+        assert_eq!(committed.robust_address, Some(robust_address));
+        assert_eq!(
+            format!("{:?}", committed.evm_address.unwrap()),
+            "0xff0000000000000000000000000000000000042d"
+        );
+    }
+}
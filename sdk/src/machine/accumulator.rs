@@ -1,32 +1,50 @@
 // Copyright 2024 ADM Contributors
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use anyhow::anyhow;
 use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine};
 use bytes::Bytes;
 use fendermint_actor_accumulator::Method::{Count, Get, Peaks, Push, Root};
-use fendermint_actor_machine::WriteAccess;
-use fendermint_vm_actor_interface::adm::Kind;
 use fendermint_vm_message::query::FvmQueryHeight;
-use fvm_ipld_encoding::{BytesSer, RawBytes};
-use fvm_shared::address::Address;
-use serde::{Deserialize, Serialize};
+use fvm_ipld_encoding::{BytesDe, BytesSer, RawBytes};
+use fvm_shared::address::{Address, Network};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use tendermint::abci::response::DeliverTx;
 use tendermint_rpc::Client;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 
 use adm_provider::{
     message::{local_message, GasParams},
-    query::QueryProvider,
+    query::{NotFound, QueryProvider},
     response::{decode_bytes, decode_cid, Cid},
     tx::{BroadcastMode, TxReceipt},
+    util::format_address,
     Provider,
 };
 use adm_signer::Signer;
+use adm_verify::accumulator::bag_peaks;
+
+use crate::cancel::Cancellation;
+use crate::machine::{
+    deploy_machine, last_modified, DeployOptions, DeployTxReceipt, Kind, Machine,
+};
+use crate::tx::TxBuilder;
 
-use crate::machine::{deploy_machine, DeployTxReceipt, Machine};
+mod consumer;
+
+pub use consumer::{
+    ConsumedLeaf, Consumer, ConsumerOptions, FileOffsetStore, MemoryOffsetStore, OffsetStore,
+};
 
 const MAX_ACC_PAYLOAD_SIZE: usize = 1024 * 500;
 
+/// Prefix that marks a leaf as a [`Leaf`] envelope carrying metadata.
+const LEAF_ENVELOPE_PREFIX: &[u8] = b"adm:leaf:v1\0";
+
 /// Payload push options.
 #[derive(Clone, Default, Debug)]
 pub struct PushOptions {
@@ -34,6 +52,92 @@ pub struct PushOptions {
     pub broadcast_mode: BroadcastMode,
     /// Gas params for the transaction.
     pub gas_params: GasParams,
+    /// Tags to attach to the leaf.
+    /// If set, the payload is pushed in a [`Leaf`] envelope along with
+    /// the current chain height and time (see [`LeafMetadata`]).
+    pub metadata: Option<BTreeMap<String, String>>,
+    /// Cancels the push until its transaction is broadcast.
+    /// An import stops before the next leaf.
+    pub cancel: Cancellation,
+}
+
+/// Metadata attached to a leaf when it was pushed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeafMetadata {
+    /// Caller-supplied tags.
+    pub tags: BTreeMap<String, String>,
+    /// The latest chain height seen by the pusher.
+    /// The leaf was included at or after this height.
+    pub height: u64,
+    /// Unix timestamp (in seconds) of the push according to the pusher's clock.
+    pub timestamp: u64,
+}
+
+/// A leaf payload with the metadata it was pushed with, if any.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Leaf {
+    /// The pushed payload.
+    pub payload: Vec<u8>,
+    /// Leaf metadata, or `None` if the payload was pushed without metadata.
+    pub metadata: Option<LeafMetadata>,
+}
+
+impl Leaf {
+    /// Encode the leaf as stored in the accumulator.
+    ///
+    /// Leaves without metadata are stored as the raw payload.
+    pub fn encode(&self) -> anyhow::Result<Vec<u8>> {
+        match &self.metadata {
+            Some(metadata) => {
+                let mut data = LEAF_ENVELOPE_PREFIX.to_vec();
+                data.extend(fvm_ipld_encoding::to_vec(&(
+                    BytesSer(&self.payload),
+                    metadata,
+                ))?);
+                Ok(data)
+            }
+            None => Ok(self.payload.clone()),
+        }
+    }
+
+    /// Decode a leaf as stored in the accumulator.
+    ///
+    /// Data is only decoded as an envelope if it re-encodes to exactly the same bytes,
+    /// so a raw payload that happens to start with the envelope prefix is returned as is.
+    pub fn decode(data: Vec<u8>) -> anyhow::Result<Self> {
+        if let Some(leaf) = Self::decode_envelope(&data) {
+            return Ok(leaf);
+        }
+        Ok(Self {
+            payload: data,
+            metadata: None,
+        })
+    }
+
+    /// Returns the leaf in a well-formed envelope, or `None` if the data isn't one.
+    fn decode_envelope(data: &[u8]) -> Option<Self> {
+        let envelope = data.strip_prefix(LEAF_ENVELOPE_PREFIX)?;
+        let (payload, metadata): (BytesDe, LeafMetadata) =
+            fvm_ipld_encoding::from_slice(envelope).ok()?;
+        let leaf = Self {
+            payload: payload.0,
+            metadata: Some(metadata),
+        };
+        (leaf.encode().ok()? == data).then_some(leaf)
+    }
+}
+
+/// Usage statistics of an accumulator.
+#[derive(Clone, Debug, Serialize)]
+pub struct AccumulatorStats {
+    /// The height the statistics were computed at.
+    pub height: u64,
+    /// The number of leaves.
+    pub leaves: u64,
+    /// The accumulator root.
+    pub root: Cid,
+    /// The height at which the accumulator was last modified.
+    pub last_modified: u64,
 }
 
 /// JSON serialization friendly version of [`fendermint_actor_accumulator::PushReturn`].
@@ -54,6 +158,50 @@ impl From<fendermint_actor_accumulator::PushReturn> for PushReturn {
     }
 }
 
+/// A verifiable snapshot of an accumulator's leaves, peaks, and root at a given height.
+///
+/// Replaying the leaves in order into an empty accumulator must yield the same root.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// The address of the exported accumulator.
+    pub address: String,
+    /// The block height at which the snapshot was taken.
+    pub height: u64,
+    /// The accumulator root at the snapshot height.
+    pub root: Cid,
+    /// The accumulator peaks at the snapshot height.
+    pub peaks: Vec<Cid>,
+    /// All leaves in push order.
+    #[serde(serialize_with = "serialize_leaves")]
+    #[serde(deserialize_with = "deserialize_leaves")]
+    pub leaves: Vec<Vec<u8>>,
+}
+
+impl Snapshot {
+    /// Check that the peaks match the number of leaves and bag to the root.
+    pub fn verify(&self) -> anyhow::Result<()> {
+        let count = self.leaves.len() as u64;
+        if self.peaks.len() != count.count_ones() as usize {
+            return Err(anyhow!(
+                "invalid snapshot: expected {} peaks for {} leaves, got {}",
+                count.count_ones(),
+                count,
+                self.peaks.len()
+            ));
+        }
+        let peaks: Vec<cid::Cid> = self.peaks.iter().map(|c| c.0).collect();
+        let bagged = bag_peaks(&peaks)?;
+        if bagged != self.root.0 {
+            return Err(anyhow!(
+                "invalid snapshot: peaks bag to {}, expected root {}",
+                bagged,
+                self.root
+            ));
+        }
+        Ok(())
+    }
+}
+
 /// A machine for event stream accumulation.
 pub struct Accumulator {
     address: Address,
@@ -63,23 +211,15 @@ pub struct Accumulator {
 impl Machine for Accumulator {
     const KIND: Kind = Kind::Accumulator;
 
-    async fn new<C>(
+    async fn deploy<C>(
         provider: &impl Provider<C>,
         signer: &mut impl Signer,
-        write_access: WriteAccess,
-        gas_params: GasParams,
+        options: DeployOptions,
     ) -> anyhow::Result<(Self, DeployTxReceipt)>
     where
         C: Client + Send + Sync,
     {
-        let (address, tx) = deploy_machine(
-            provider,
-            signer,
-            Kind::Accumulator,
-            write_access,
-            gas_params,
-        )
-        .await?;
+        let (address, tx) = deploy_machine(provider, signer, Kind::Accumulator, options).await?;
         Ok((Self::attach(address), tx))
     }
 
@@ -104,6 +244,31 @@ impl Accumulator {
     where
         C: Client + Send + Sync,
     {
+        let payload = match options.metadata {
+            Some(tags) => {
+                let head = options.cancel.run(provider.chain_head()).await?;
+                let leaf = Leaf {
+                    payload: payload.to_vec(),
+                    metadata: Some(LeafMetadata {
+                        tags,
+                        height: head.height.value(),
+                        timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+                    }),
+                };
+                Bytes::from(leaf.encode()?)
+            }
+            None => payload,
+        };
+        options.cancel.check()?;
+        self.tx_push(payload)?
+            .gas_params(options.gas_params)
+            .broadcast(options.broadcast_mode)
+            .send(provider, signer)
+            .await
+    }
+
+    /// Returns a [`TxBuilder`] that pushes a payload into the accumulator.
+    pub fn tx_push(&self, payload: Bytes) -> anyhow::Result<TxBuilder<PushReturn>> {
         if payload.len() > MAX_ACC_PAYLOAD_SIZE {
             return Err(anyhow!(
                 "max payload size is {} bytes",
@@ -112,19 +277,12 @@ impl Accumulator {
         }
 
         let params = RawBytes::serialize(BytesSer(&payload))?;
-        let message = signer
-            .transaction(
-                self.address,
-                Default::default(),
-                Push as u64,
-                params,
-                None,
-                options.gas_params,
-            )
-            .await?;
-        provider
-            .perform(message, options.broadcast_mode, decode_push_return)
-            .await
+        Ok(TxBuilder::new(
+            self.address,
+            Push as u64,
+            params,
+            decode_push_return,
+        ))
     }
 
     /// Get leaf stored at a given index and height.
@@ -139,10 +297,20 @@ impl Accumulator {
         let response = provider.call(message, height, |tx| decode_leaf(tx)).await?;
         let leaf = response
             .value
-            .ok_or_else(|| anyhow!("leaf not found for index '{}'", index))?;
+            .ok_or_else(|| NotFound::new(format!("leaf not found for index '{}'", index)))?;
         Ok(leaf)
     }
 
+    /// Get leaf stored at a given index and height along with its metadata.
+    pub async fn leaf_with_metadata(
+        &self,
+        provider: &impl QueryProvider,
+        index: u64,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<Leaf> {
+        Leaf::decode(self.leaf(provider, index, height).await?)
+    }
+
     /// Get total leaf count at a given height.
     pub async fn count(
         &self,
@@ -175,6 +343,171 @@ impl Accumulator {
         let response = provider.call(message, height, decode_cid).await?;
         Ok(response.value)
     }
+
+    /// Get usage statistics of the accumulator at a given height.
+    ///
+    /// Finding the last-modified height takes a few historical state queries.
+    pub async fn stats(
+        &self,
+        provider: &impl QueryProvider,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<AccumulatorStats> {
+        let message = local_message(self.address, Count as u64, Default::default());
+        let response = provider.call(message, height, decode_count).await?;
+        let height = response.height.value();
+        let root = self.root(provider, FvmQueryHeight::Height(height)).await?;
+        let last_modified = last_modified(provider, self.address, height).await?;
+        Ok(AccumulatorStats {
+            height,
+            leaves: response.value,
+            root,
+            last_modified,
+        })
+    }
+
+    /// Take a [`Snapshot`] of all leaves, peaks, and the root at a given height.
+    ///
+    /// All values are read at the same concrete block height, even if `height` is
+    /// [`FvmQueryHeight::Committed`] or [`FvmQueryHeight::Pending`].
+    /// Leaves are fetched with [`QueryProvider::call_many`].
+    /// The accumulator address is formatted for `network`.
+    pub async fn snapshot(
+        &self,
+        provider: &impl QueryProvider,
+        height: FvmQueryHeight,
+        network: Network,
+    ) -> anyhow::Result<Snapshot> {
+        let message = local_message(self.address, Count as u64, Default::default());
+        let response = provider.call(message, height, decode_count).await?;
+        let pinned = FvmQueryHeight::Height(response.height.value());
+
+        let messages = (0..response.value)
+            .map(|index| {
+                let params = RawBytes::serialize(index)?;
+                Ok(local_message(self.address, Get as u64, params))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let leaves = provider
+            .call_many(messages, pinned, decode_leaf)
+            .await?
+            .into_iter()
+            .zip(0..)
+            .map(|(response, index)| {
+                response
+                    .value
+                    .ok_or_else(|| NotFound::new(format!("leaf not found for index '{}'", index)))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let peaks = self.peaks(provider, pinned).await?;
+        let root = self.root(provider, pinned).await?;
+
+        Ok(Snapshot {
+            address: format_address(&self.address, network),
+            height: response.height.value(),
+            root,
+            peaks,
+            leaves,
+        })
+    }
+
+    /// Export a JSON-encoded [`Snapshot`] at a given height to the writer,
+    /// with the accumulator address formatted for `network`.
+    pub async fn export<W>(
+        &self,
+        provider: &impl QueryProvider,
+        mut writer: W,
+        height: FvmQueryHeight,
+        network: Network,
+    ) -> anyhow::Result<Snapshot>
+    where
+        W: AsyncWrite + Unpin + Send,
+    {
+        let snapshot = self.snapshot(provider, height, network).await?;
+        let json = serde_json::to_vec(&snapshot)?;
+        writer.write_all(&json).await?;
+        writer.flush().await?;
+        Ok(snapshot)
+    }
+
+    /// Import a JSON-encoded [`Snapshot`] from the reader by replaying its leaves.
+    ///
+    /// The snapshot is checked with [`Snapshot::verify`] before anything is pushed,
+    /// and the accumulator must be empty. If the broadcast mode is [`BroadcastMode::Commit`],
+    /// the resulting root is also verified against the snapshot root.
+    /// Returns the receipt of the last push, if any.
+    pub async fn import<C, R>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        mut reader: R,
+        options: PushOptions,
+    ) -> anyhow::Result<Option<TxReceipt<PushReturn>>>
+    where
+        C: Client + Send + Sync,
+        R: AsyncRead + Unpin + Send,
+    {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).await?;
+        let snapshot: Snapshot = serde_json::from_slice(&buf)
+            .map_err(|e| anyhow!("error parsing accumulator snapshot: {e}"))?;
+        snapshot.verify()?;
+
+        let count = options
+            .cancel
+            .run(self.count(provider, FvmQueryHeight::Pending))
+            .await?;
+        if count != 0 {
+            return Err(anyhow!(
+                "cannot import into accumulator with {} existing leaves",
+                count
+            ));
+        }
+
+        // Snapshot leaves are replayed as is, including any metadata envelopes.
+        let options = PushOptions {
+            metadata: None,
+            ..options
+        };
+        let mut last = None;
+        for leaf in snapshot.leaves {
+            let tx = self
+                .push(provider, signer, Bytes::from(leaf), options.clone())
+                .await?;
+            last = Some(tx);
+        }
+
+        if let Some(data) = last.as_ref().and_then(|tx| tx.data.as_ref()) {
+            if data.root != snapshot.root {
+                return Err(anyhow!(
+                    "imported root {} does not match snapshot root {}",
+                    data.root,
+                    snapshot.root
+                ));
+            }
+        }
+        Ok(last)
+    }
+}
+
+fn serialize_leaves<S: Serializer>(leaves: &[Vec<u8>], serializer: S) -> Result<S::Ok, S::Error> {
+    leaves
+        .iter()
+        .map(|l| general_purpose::STANDARD.encode(l))
+        .collect::<Vec<String>>()
+        .serialize(serializer)
+}
+
+fn deserialize_leaves<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Vec<u8>>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|l| {
+            general_purpose::STANDARD
+                .decode(l)
+                .map_err(serde::de::Error::custom)
+        })
+        .collect()
 }
 
 fn decode_push_return(deliver_tx: &DeliverTx) -> anyhow::Result<PushReturn> {
@@ -202,3 +535,66 @@ fn decode_peaks(deliver_tx: &DeliverTx) -> anyhow::Result<Vec<Cid>> {
         .map_err(|e| anyhow!("error parsing as Vec<Cid>: {e}"))?;
     Ok(items)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leaf_envelope() {
+        let plain = Leaf {
+            payload: b"hello".to_vec(),
+            metadata: None,
+        };
+        assert_eq!(plain.encode().unwrap(), b"hello".to_vec());
+        assert_eq!(Leaf::decode(plain.encode().unwrap()).unwrap(), plain);
+
+        let tagged = Leaf {
+            payload: b"hello".to_vec(),
+            metadata: Some(LeafMetadata {
+                tags: BTreeMap::from([("source".to_string(), "oracle".to_string())]),
+                height: 42,
+                timestamp: 1_700_000_000,
+            }),
+        };
+        let encoded = tagged.encode().unwrap();
+        assert!(encoded.starts_with(LEAF_ENVELOPE_PREFIX));
+        assert_eq!(Leaf::decode(encoded.clone()).unwrap(), tagged);
+
+        // Raw payloads that only look like envelopes are returned as is
+        for raw in [
+            [LEAF_ENVELOPE_PREFIX, b"garbage"].concat(),
+            [encoded.as_slice(), b"trailing"].concat(),
+        ] {
+            let leaf = Leaf::decode(raw.clone()).unwrap();
+            assert_eq!(leaf.payload, raw);
+            assert!(leaf.metadata.is_none());
+        }
+    }
+
+    #[test]
+    fn test_verify_snapshot() {
+        use adm_verify::accumulator::{leaf_hash, node_hash};
+
+        let leaves = vec![vec![0u8], vec![1], vec![2]];
+        let hashes: Vec<cid::Cid> = leaves.iter().map(|l| leaf_hash(l).unwrap()).collect();
+        let peaks = vec![node_hash(&hashes[0], &hashes[1]).unwrap(), hashes[2]];
+        let mut snapshot = Snapshot {
+            address: "t01000".into(),
+            height: 1,
+            root: bag_peaks(&peaks).unwrap().into(),
+            peaks: peaks.into_iter().map(Cid::from).collect(),
+            leaves,
+        };
+        assert!(snapshot.verify().is_ok());
+
+        // Peaks must match the leaf count
+        snapshot.leaves.push(vec![3]);
+        assert!(snapshot.verify().is_err());
+        snapshot.leaves.pop();
+
+        // Peaks must bag to the root
+        snapshot.root = hashes[0].into();
+        assert!(snapshot.verify().is_err());
+    }
+}
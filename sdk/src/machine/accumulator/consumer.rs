@@ -0,0 +1,275 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use fendermint_actor_accumulator::Method::Count;
+use fendermint_vm_message::query::FvmQueryHeight;
+
+use adm_provider::{message::local_message, query::QueryProvider};
+
+use super::{decode_count, Accumulator, Leaf};
+
+/// Persists consumer offsets, so a [`Consumer`] can resume where it left off.
+///
+/// An offset is the index of the next leaf to process, i.e., one past the last-processed leaf.
+#[async_trait]
+pub trait OffsetStore: Send + Sync {
+    /// Returns the offset of `consumer`, or `None` if it hasn't committed any leaves.
+    async fn load(&self, consumer: &str) -> anyhow::Result<Option<u64>>;
+
+    /// Save the offset of `consumer`.
+    async fn save(&self, consumer: &str, offset: u64) -> anyhow::Result<()>;
+}
+
+/// Offsets kept in memory, e.g., for tests or consumers that always start over.
+#[derive(Debug, Default)]
+pub struct MemoryOffsetStore {
+    offsets: Mutex<HashMap<String, u64>>,
+}
+
+#[async_trait]
+impl OffsetStore for MemoryOffsetStore {
+    async fn load(&self, consumer: &str) -> anyhow::Result<Option<u64>> {
+        let offsets = self.offsets.lock().map_err(|_| anyhow!("lock poisoned"))?;
+        Ok(offsets.get(consumer).copied())
+    }
+
+    async fn save(&self, consumer: &str, offset: u64) -> anyhow::Result<()> {
+        let mut offsets = self.offsets.lock().map_err(|_| anyhow!("lock poisoned"))?;
+        offsets.insert(consumer.into(), offset);
+        Ok(())
+    }
+}
+
+/// Offsets kept in a local JSON file that maps consumer names to offsets.
+///
+/// A missing file is treated as empty. The file is replaced atomically on each save,
+/// so a crash never leaves it half-written.
+#[derive(Clone, Debug)]
+pub struct FileOffsetStore {
+    path: PathBuf,
+}
+
+impl FileOffsetStore {
+    /// Create a store backed by the file at `path`, which is created on first save.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Returns the file path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    async fn read(&self) -> anyhow::Result<BTreeMap<String, u64>> {
+        match tokio::fs::read(&self.path).await {
+            Ok(data) => serde_json::from_slice(&data)
+                .with_context(|| format!("failed to parse offset file {}", self.path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[async_trait]
+impl OffsetStore for FileOffsetStore {
+    async fn load(&self, consumer: &str) -> anyhow::Result<Option<u64>> {
+        Ok(self.read().await?.get(consumer).copied())
+    }
+
+    async fn save(&self, consumer: &str, offset: u64) -> anyhow::Result<()> {
+        let mut offsets = self.read().await?;
+        offsets.insert(consumer.into(), offset);
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut part_path = self.path.clone().into_os_string();
+        part_path.push(".part");
+        tokio::fs::write(&part_path, serde_json::to_vec_pretty(&offsets)?).await?;
+        tokio::fs::rename(&part_path, &self.path).await?;
+        Ok(())
+    }
+}
+
+/// Consumer options.
+#[derive(Clone, Debug)]
+pub struct ConsumerOptions {
+    /// Maximum number of leaves returned by each poll.
+    pub batch_size: u64,
+    /// Offset to start at if the store has none for the consumer.
+    pub start: u64,
+    /// Query block height.
+    pub height: FvmQueryHeight,
+}
+
+impl Default for ConsumerOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: 100,
+            start: 0,
+            height: FvmQueryHeight::Committed,
+        }
+    }
+}
+
+/// A leaf delivered by a [`Consumer`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsumedLeaf {
+    /// The leaf index.
+    pub index: u64,
+    /// The leaf with its metadata, if any.
+    pub leaf: Leaf,
+}
+
+/// Reads an accumulator as an event log, tracking the last-processed leaf in an [`OffsetStore`].
+///
+/// Delivery is at least once: [`Consumer::poll_new_leaves`] starts at the committed offset
+/// every time, so leaves are delivered again until they're passed to [`Consumer::commit`].
+/// Commit leaves only after they're processed, and make processing idempotent,
+/// since a crash between processing and committing redelivers the batch.
+pub struct Consumer<S> {
+    accumulator: Accumulator,
+    name: String,
+    store: S,
+    options: ConsumerOptions,
+    offset: Option<u64>,
+}
+
+impl<S> Consumer<S>
+where
+    S: OffsetStore,
+{
+    /// Create a consumer of `accumulator` whose offset is saved in `store` under `name`.
+    ///
+    /// Use a distinct name for each independent consumer of the same store.
+    pub fn new(
+        accumulator: Accumulator,
+        name: &str,
+        store: S,
+        options: ConsumerOptions,
+    ) -> anyhow::Result<Self> {
+        if name.is_empty() {
+            return Err(anyhow!("consumer name must not be empty"));
+        }
+        if options.batch_size == 0 {
+            return Err(anyhow!("batch size must be greater than zero"));
+        }
+        Ok(Self {
+            accumulator,
+            name: name.into(),
+            store,
+            options,
+            offset: None,
+        })
+    }
+
+    /// Returns the consumer name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the committed offset, i.e., the index of the next leaf to deliver.
+    pub async fn offset(&mut self) -> anyhow::Result<u64> {
+        if let Some(offset) = self.offset {
+            return Ok(offset);
+        }
+        let offset = self
+            .store
+            .load(&self.name)
+            .await?
+            .unwrap_or(self.options.start);
+        self.offset = Some(offset);
+        Ok(offset)
+    }
+
+    /// Returns up to `batch_size` leaves after the committed offset, in index order.
+    ///
+    /// Returns an empty list if there are no new leaves.
+    /// All leaves are read at the same concrete block height.
+    pub async fn poll_new_leaves(
+        &mut self,
+        provider: &impl QueryProvider,
+    ) -> anyhow::Result<Vec<ConsumedLeaf>> {
+        let offset = self.offset().await?;
+        // Read every leaf at the height the count was read at
+        let message = local_message(self.accumulator.address, Count as u64, Default::default());
+        let response = provider
+            .call(message, self.options.height, decode_count)
+            .await?;
+        let count = response.value;
+        let height = FvmQueryHeight::Height(response.height.value());
+        if count < offset {
+            return Err(anyhow!(
+                "consumer '{}' offset {} is past the accumulator's {} leaves",
+                self.name,
+                offset,
+                count
+            ));
+        }
+        let end = count.min(offset.saturating_add(self.options.batch_size));
+        let mut leaves = Vec::with_capacity((end - offset) as usize);
+        for index in offset..end {
+            let leaf = self
+                .accumulator
+                .leaf_with_metadata(provider, index, height)
+                .await?;
+            leaves.push(ConsumedLeaf { index, leaf });
+        }
+        Ok(leaves)
+    }
+
+    /// Mark all leaves up to and including `index` as processed, and save the new offset.
+    ///
+    /// Committing an index that's already processed is a no-op, so retried commits are safe.
+    pub async fn commit(&mut self, index: u64) -> anyhow::Result<()> {
+        let offset = index
+            .checked_add(1)
+            .ok_or_else(|| anyhow!("leaf index overflow"))?;
+        if offset <= self.offset().await? {
+            return Ok(());
+        }
+        self.store.save(&self.name, offset).await?;
+        self.offset = Some(offset);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::machine::Machine;
+
+    #[tokio::test]
+    async fn test_file_offset_store() {
+        let path = std::env::temp_dir().join(format!("adm-offsets-{}.json", std::process::id()));
+        let store = FileOffsetStore::new(&path);
+        assert_eq!(store.load("indexer").await.unwrap(), None);
+        store.save("indexer", 5).await.unwrap();
+        store.save("mirror", 2).await.unwrap();
+
+        let store = FileOffsetStore::new(&path);
+        assert_eq!(store.load("indexer").await.unwrap(), Some(5));
+        assert_eq!(store.load("mirror").await.unwrap(), Some(2));
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_commit() {
+        let accumulator = Accumulator::attach(fvm_shared::address::Address::new_id(1001));
+        let store = MemoryOffsetStore::default();
+        let mut consumer =
+            Consumer::new(accumulator, "indexer", store, Default::default()).unwrap();
+        assert_eq!(consumer.offset().await.unwrap(), 0);
+        consumer.commit(4).await.unwrap();
+        assert_eq!(consumer.offset().await.unwrap(), 5);
+        // Older commits don't rewind the offset
+        consumer.commit(1).await.unwrap();
+        assert_eq!(consumer.offset().await.unwrap(), 5);
+        assert_eq!(consumer.store.load("indexer").await.unwrap(), Some(5));
+    }
+}
@@ -0,0 +1,433 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::anyhow;
+use fendermint_vm_message::query::FvmQueryHeight;
+use fvm_ipld_encoding::{BytesDe, RawBytes};
+use fvm_shared::{address::Address, MethodNum};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tendermint_rpc::Client;
+
+use adm_provider::{
+    message::{local_message, GasParams},
+    query::QueryProvider,
+    response::{decode_bytes, Cid},
+    tx::{BroadcastMode, TxReceipt},
+    Provider,
+};
+use adm_signer::Signer;
+
+use crate::tx::TxBuilder;
+
+/// Names of the built-in machine kinds, which custom kinds can't use.
+const BUILTIN_KINDS: &[&str] = &["objectstore", "accumulator"];
+
+/// Decodes the return value of a custom machine method as JSON.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Decoder {
+    /// The raw CBOR return value as a hex string.
+    #[default]
+    Raw,
+    /// Ignore the return value.
+    Unit,
+    /// A CBOR boolean.
+    Bool,
+    /// A CBOR unsigned integer.
+    U64,
+    /// A CBOR signed integer.
+    I64,
+    /// A CBOR text string.
+    String,
+    /// A CBOR byte string as a hex string.
+    Bytes,
+    /// A CBOR CID as a string.
+    Cid,
+    /// A decoder supplied by the machine's SDK, e.g., for structured return values.
+    /// Custom decoders can only be registered in code.
+    #[serde(skip)]
+    Custom(fn(&[u8]) -> anyhow::Result<Value>),
+}
+
+impl Decoder {
+    /// Decode a CBOR return value.
+    pub fn decode(&self, data: &[u8]) -> anyhow::Result<Value> {
+        let from_slice = |e| anyhow!("error parsing return value as {:?}: {}", self, e);
+        Ok(match self {
+            Decoder::Raw => Value::String(hex::encode(data)),
+            Decoder::Unit => Value::Null,
+            Decoder::Bool => fvm_ipld_encoding::from_slice::<bool>(data)
+                .map_err(from_slice)?
+                .into(),
+            Decoder::U64 => fvm_ipld_encoding::from_slice::<u64>(data)
+                .map_err(from_slice)?
+                .into(),
+            Decoder::I64 => fvm_ipld_encoding::from_slice::<i64>(data)
+                .map_err(from_slice)?
+                .into(),
+            Decoder::String => fvm_ipld_encoding::from_slice::<String>(data)
+                .map_err(from_slice)?
+                .into(),
+            Decoder::Bytes => {
+                let bytes: BytesDe = fvm_ipld_encoding::from_slice(data).map_err(from_slice)?;
+                Value::String(hex::encode(bytes.0))
+            }
+            Decoder::Cid => fvm_ipld_encoding::from_slice::<cid::Cid>(data)
+                .map_err(from_slice)?
+                .to_string()
+                .into(),
+            Decoder::Custom(f) => f(data)?,
+        })
+    }
+}
+
+/// A method of a [`CustomKind`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MethodSpec {
+    /// The actor method number.
+    pub number: MethodNum,
+    /// Whether the method only reads state, so it's called with a query instead of a transaction.
+    #[serde(default)]
+    pub read_only: bool,
+    /// Decoder for the method's return value.
+    #[serde(default)]
+    pub decoder: Decoder,
+}
+
+/// A third-party machine kind, described by its method numbers and return value decoders.
+///
+/// Custom kinds let teams building new machine actors use them with the SDK and CLI without
+/// forking either. Machines of a custom kind are deployed outside the SDK, since the ADM actor
+/// only creates built-in kinds, and attached with [`CustomMachine::attach`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CustomKind {
+    /// The kind name, e.g., "timehub".
+    pub name: String,
+    /// The actor code CID, which identifies machines of this kind on chain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Cid>,
+    /// Methods keyed by name.
+    #[serde(default)]
+    pub methods: BTreeMap<String, MethodSpec>,
+}
+
+impl CustomKind {
+    /// Create a kind without methods.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            code: None,
+            methods: BTreeMap::new(),
+        }
+    }
+
+    /// Set the actor code CID, so machines of this kind can be detected with [`KindRegistry::detect`].
+    pub fn with_code(mut self, code: Cid) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /// Add a method that's called with transactions.
+    pub fn with_method(
+        mut self,
+        name: impl Into<String>,
+        number: MethodNum,
+        decoder: Decoder,
+    ) -> Self {
+        self.methods.insert(
+            name.into(),
+            MethodSpec {
+                number,
+                read_only: false,
+                decoder,
+            },
+        );
+        self
+    }
+
+    /// Add a read-only method that's called with queries.
+    pub fn with_query(
+        mut self,
+        name: impl Into<String>,
+        number: MethodNum,
+        decoder: Decoder,
+    ) -> Self {
+        self.methods.insert(
+            name.into(),
+            MethodSpec {
+                number,
+                read_only: true,
+                decoder,
+            },
+        );
+        self
+    }
+
+    /// Returns the method with the given name.
+    pub fn method(&self, name: &str) -> anyhow::Result<&MethodSpec> {
+        self.methods
+            .get(name)
+            .ok_or_else(|| anyhow!("machine kind '{}' has no method '{}'", self.name, name))
+    }
+}
+
+/// A set of registered [`CustomKind`]s.
+///
+/// Registries can be built in code with [`KindRegistry::register`],
+/// or loaded from a JSON file that lists kinds, e.g., to share them with the CLI.
+#[derive(Clone, Debug, Default)]
+pub struct KindRegistry {
+    kinds: BTreeMap<String, CustomKind>,
+}
+
+impl KindRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a registry from a JSON file with an array of kinds.
+    /// A missing file is an empty registry.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let data = std::fs::read_to_string(path)?;
+        Self::from_json(&data).map_err(|e| {
+            anyhow!(
+                "failed to load machine kinds from {}: {}",
+                path.display(),
+                e
+            )
+        })
+    }
+
+    /// Parse a registry from a JSON array of kinds.
+    pub fn from_json(data: &str) -> anyhow::Result<Self> {
+        let kinds: Vec<CustomKind> = serde_json::from_str(data)?;
+        let mut registry = Self::default();
+        for kind in kinds {
+            registry.register(kind)?;
+        }
+        Ok(registry)
+    }
+
+    /// Register a kind.
+    ///
+    /// Fails if the name is taken by a built-in or registered kind.
+    pub fn register(&mut self, kind: CustomKind) -> anyhow::Result<()> {
+        let name = kind.name.to_lowercase();
+        if name.is_empty() {
+            return Err(anyhow!("machine kind name cannot be empty"));
+        }
+        if BUILTIN_KINDS.contains(&name.as_str()) {
+            return Err(anyhow!("machine kind '{}' is built in", kind.name));
+        }
+        if self.kinds.contains_key(&name) {
+            return Err(anyhow!(
+                "machine kind '{}' is already registered",
+                kind.name
+            ));
+        }
+        self.kinds.insert(name, kind);
+        Ok(())
+    }
+
+    /// Returns the kind with the given name, ignoring case.
+    pub fn get(&self, name: &str) -> Option<&CustomKind> {
+        self.kinds.get(&name.to_lowercase())
+    }
+
+    /// Returns all registered kinds.
+    pub fn kinds(&self) -> impl Iterator<Item = &CustomKind> {
+        self.kinds.values()
+    }
+
+    /// Detect the kind of the machine at `address` by its actor code.
+    ///
+    /// Returns `None` if the machine doesn't exist or its code doesn't match a registered kind.
+    pub async fn detect(
+        &self,
+        provider: &impl QueryProvider,
+        address: Address,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<Option<&CustomKind>> {
+        let Some((_, state)) = provider.actor_state(&address, height).await?.value else {
+            return Ok(None);
+        };
+        Ok(self
+            .kinds()
+            .find(|kind| kind.code.is_some_and(|code| code.0 == state.code)))
+    }
+}
+
+/// A machine of a [`CustomKind`].
+///
+/// Methods are called by name, with CBOR-encoded params,
+/// and their return values are decoded with the method's [`Decoder`].
+#[derive(Clone, Debug)]
+pub struct CustomMachine {
+    address: Address,
+    kind: CustomKind,
+}
+
+impl CustomMachine {
+    /// Attach to an existing machine of the given kind.
+    pub fn attach(address: Address, kind: CustomKind) -> Self {
+        Self { address, kind }
+    }
+
+    /// Returns the machine address.
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Returns the machine kind.
+    pub fn kind(&self) -> &CustomKind {
+        &self.kind
+    }
+
+    /// Call a method with a query, without sending a transaction.
+    ///
+    /// Any method can be called this way, but only read-only methods have lasting results.
+    pub async fn query(
+        &self,
+        provider: &impl QueryProvider,
+        method: &str,
+        params: RawBytes,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<Value> {
+        let spec = self.kind.method(method)?;
+        let message = local_message(self.address, spec.number, params);
+        let response = provider
+            .call(message, height, |tx| {
+                if tx.code.is_err() {
+                    return Err(anyhow!(
+                        "method '{}' failed: {} (exit code {})",
+                        method,
+                        tx.info,
+                        tx.code.value()
+                    ));
+                }
+                decode_bytes(tx)
+            })
+            .await?;
+        spec.decoder.decode(response.value.bytes())
+    }
+
+    /// Returns a [`TxBuilder`] that calls a method.
+    ///
+    /// The transaction returns the raw CBOR return value,
+    /// which can be decoded with [`CustomMachine::decode`].
+    pub fn tx(&self, method: &str, params: RawBytes) -> anyhow::Result<TxBuilder<RawBytes>> {
+        let spec = self.kind.method(method)?;
+        if spec.read_only {
+            return Err(anyhow!(
+                "method '{}' is read-only; query it instead",
+                method
+            ));
+        }
+        Ok(TxBuilder::new(
+            self.address,
+            spec.number,
+            params,
+            decode_bytes,
+        ))
+    }
+
+    /// Call a method with a transaction.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn send<C>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        method: &str,
+        params: RawBytes,
+        broadcast_mode: BroadcastMode,
+        gas_params: GasParams,
+    ) -> anyhow::Result<TxReceipt<Value>>
+    where
+        C: Client + Send + Sync,
+    {
+        let tx = self
+            .tx(method, params)?
+            .gas_params(gas_params)
+            .broadcast(broadcast_mode)
+            .send(provider, signer)
+            .await?;
+        self.decode(method, tx)
+    }
+
+    /// Decode the return value of a transaction built with [`CustomMachine::tx`].
+    pub fn decode(
+        &self,
+        method: &str,
+        tx: TxReceipt<RawBytes>,
+    ) -> anyhow::Result<TxReceipt<Value>> {
+        let spec = self.kind.method(method)?;
+        let data = match &tx.data {
+            Some(data) => Some(spec.decoder.decode(data.bytes())?),
+            None => None,
+        };
+        Ok(TxReceipt {
+            status: tx.status,
+            hash: tx.hash,
+            height: tx.height,
+            gas_used: tx.gas_used,
+            data,
+            events: tx.events,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_registry() {
+        let json = r#"[{
+            "name": "timehub",
+            "methods": {
+                "push": {"number": 3, "decoder": "raw"},
+                "count": {"number": 4, "read_only": true, "decoder": "u64"}
+            }
+        }]"#;
+        let mut registry = KindRegistry::from_json(json).unwrap();
+        let kind = registry.get("TimeHub").unwrap();
+        assert_eq!(kind.method("count").unwrap().number, 4);
+        assert!(kind.method("count").unwrap().read_only);
+        assert!(kind.method("missing").is_err());
+
+        assert!(registry.register(CustomKind::new("timehub")).is_err());
+        assert!(registry.register(CustomKind::new("ObjectStore")).is_err());
+        assert!(registry.register(CustomKind::new("")).is_err());
+        registry
+            .register(CustomKind::new("other").with_query("get", 2, Decoder::String))
+            .unwrap();
+        assert_eq!(registry.kinds().count(), 2);
+
+        let machine = CustomMachine::attach(Address::new_id(100), kind.clone());
+        assert!(machine.tx("count", RawBytes::default()).is_err());
+        assert!(machine.tx("push", RawBytes::default()).is_ok());
+    }
+
+    #[test]
+    fn test_decoder() {
+        let data = fvm_ipld_encoding::to_vec(&42u64).unwrap();
+        assert_eq!(Decoder::U64.decode(&data).unwrap(), Value::from(42));
+        assert_eq!(
+            Decoder::Raw.decode(&data).unwrap(),
+            Value::from(hex::encode(&data))
+        );
+        assert_eq!(Decoder::Unit.decode(&data).unwrap(), Value::Null);
+        assert!(Decoder::String.decode(&data).is_err());
+        let custom = Decoder::Custom(|data| Ok(Value::from(data.len())));
+        assert_eq!(custom.decode(&data).unwrap(), Value::from(data.len()));
+    }
+}
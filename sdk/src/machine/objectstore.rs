@@ -1,49 +1,96 @@
 // Copyright 2024 ADM Contributors
 // SPDX-License-Identifier: Apache-2.0, MIT
 
-use std::{cmp::min, collections::HashMap};
+use std::{
+    cmp::min,
+    collections::{BTreeMap, HashMap},
+    ffi::OsString,
+    path::Path,
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use async_trait::async_trait;
 use base64::{engine::general_purpose, Engine};
-use bytes::Bytes;
-use fendermint_actor_machine::WriteAccess;
+use bytes::{Bytes, BytesMut};
+use ethers::types::TransactionReceipt;
 use fendermint_actor_objectstore::{
     AddParams, DeleteParams, GetParams,
     Method::{AddObject, DeleteObject, GetObject, ListObjects},
-    Object, ObjectList,
 };
-use fendermint_vm_actor_interface::adm::Kind;
 use fendermint_vm_message::{query::FvmQueryHeight, signed::Object as MessageObject};
+use futures_util::StreamExt;
 use fvm_ipld_encoding::RawBytes;
-use fvm_shared::address::Address;
-use indicatif::HumanDuration;
+use fvm_shared::{
+    address::{Address, Network},
+    econ::TokenAmount,
+};
+use serde::{Deserialize, Serialize};
 use tendermint::abci::response::DeliverTx;
 use tendermint_rpc::Client;
 use tokio::{
+    fs,
     io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, AsyncWrite, AsyncWriteExt},
+    sync::mpsc,
     time::Instant,
 };
-use tokio_stream::StreamExt;
-use tokio_util::io::ReaderStream;
-use unixfs_v1::file::adder::{Chunker, FileAdder};
 
 use adm_provider::{
     message::{local_message, object_upload_message, GasParams},
-    object::ObjectProvider,
-    query::QueryProvider,
+    object::{ObjectProvider, UploadError},
+    query::{NotFound, QueryProvider},
     response::{decode_bytes, decode_cid, Cid},
-    tx::{BroadcastMode, TxReceipt},
+    tx::{BroadcastMode, TxReceipt, TxStatus},
+    util::{format_address, parse_address},
     Provider,
 };
-use adm_signer::Signer;
+use adm_signer::{AccountKind, Signer, Wallet};
+use adm_verify::{
+    object::{verify_object, ObjectHasher, CHUNK_SIZE},
+    signature::verify_params,
+};
 
-use crate::progress::{new_message_bar, new_multi_bar, SPARKLE};
+use crate::account::Account;
+use crate::cancel::Cancellation;
+use crate::ipc::subnet::EVMSubnet;
+use crate::progress::{
+    new_message_bar, new_multi_bar, HumanDuration, ProgressBar, ProgressFormat, SPARKLE,
+};
+use crate::tx::TxBuilder;
 use crate::{
-    machine::{deploy_machine, DeployTxReceipt, Machine},
+    machine::{
+        accumulator::{Accumulator, PushOptions},
+        deploy_machine, info, last_modified, DeployOptions, DeployTxReceipt, Kind, Machine,
+        WriteAccess,
+    },
     progress::new_progress_bar,
 };
 
+mod attestation;
+mod audit;
+mod cache;
+mod checksum;
+mod cursor;
+mod key;
+mod namespace;
+mod policy;
+mod schema;
+
+use checksum::{metadata_hasher, verify_checksum, ChecksumHasher};
+use schema::{csv_schema, parquet_metadata_len, parquet_schema, PARQUET_FOOTER_LEN};
+
+pub use attestation::{Attestation, ATTESTATION_METADATA_KEY};
+pub use audit::{AuditAction, AuditError, AuditRecord};
+pub use cache::ObjectCache;
+pub use checksum::{Checksum, SHA256_METADATA_KEY};
+pub use cursor::Cursor;
+pub use key::{Key, MAX_KEY_LENGTH};
+pub use namespace::NamespacedObjectStore;
+pub use policy::{is_expired, Policy, EXPIRES_METADATA_KEY, POLICY_KEY, POLICY_METADATA_KEY};
+use policy::{expired, Usage, UsageChange};
+pub use schema::{ObjectSchema, SchemaColumn, SchemaFormat};
+
 /// Object add options.
 #[derive(Clone, Default, Debug)]
 pub struct AddOptions {
@@ -55,10 +102,35 @@ pub struct AddOptions {
     pub gas_params: GasParams,
     /// Whether to show progress-related output (useful for command-line interfaces).
     pub show_progress: bool,
+    /// How progress is shown if `show_progress` is set.
+    pub progress_format: ProgressFormat,
     /// Metadata to add to the object.
     pub metadata: HashMap<String, String>,
+    /// Bearer token for the Object API, overriding any token set on the provider.
+    pub auth_token: Option<String>,
+    /// How many times to retry an upload that failed with a transient error,
+    /// e.g., a dropped connection or a server error.
+    /// The Object API doesn't support partial uploads, so each retry streams the whole object again.
+    pub upload_retries: u32,
+    /// Maximum number of bytes read ahead of the upload and held in memory,
+    /// e.g., while the network is slower than the disk.
+    /// Zero uses [`DEFAULT_UPLOAD_BUFFER_SIZE`]. At least one 256 KiB chunk is always buffered.
+    pub upload_buffer_size: usize,
+    /// Sign an [`Attestation`] of the object's key, CID, size, and upload time,
+    /// and store it in the object metadata under [`ATTESTATION_METADATA_KEY`].
+    pub attest: bool,
+    /// Compute a plain [`Checksum`] of the object while generating its CID,
+    /// and store it in the object metadata, e.g., under [`SHA256_METADATA_KEY`].
+    pub checksum: Option<Checksum>,
+    /// Cancels the add until its transaction is broadcast.
+    /// An upload in progress is aborted, so the Object API discards the partial object.
+    pub cancel: Cancellation,
 }
 
+/// Metadata key used to mark an object as soft-deleted.
+/// The value is the Unix timestamp (in seconds) of the deletion.
+pub const DELETED_METADATA_KEY: &str = "adm:deleted";
+
 /// Object delete options.
 #[derive(Clone, Default, Debug)]
 pub struct DeleteOptions {
@@ -66,6 +138,45 @@ pub struct DeleteOptions {
     pub broadcast_mode: BroadcastMode,
     /// Gas params for the transaction.
     pub gas_params: GasParams,
+    /// Mark the object as deleted in its metadata instead of removing the key.
+    /// Soft-deleted objects can be brought back with [`ObjectStore::restore`]
+    /// or removed for good with [`ObjectStore::purge`].
+    pub soft: bool,
+    /// Cancels the delete until its transaction is broadcast.
+    /// A purge stops before the next object.
+    pub cancel: Cancellation,
+}
+
+/// Object restore options.
+#[derive(Clone, Default, Debug)]
+pub struct RestoreOptions {
+    /// Broadcast mode for the transaction.
+    pub broadcast_mode: BroadcastMode,
+    /// Gas params for the transaction.
+    pub gas_params: GasParams,
+    /// Cancels the restore until its transaction is broadcast.
+    pub cancel: Cancellation,
+}
+
+/// Object store policy options.
+#[derive(Clone, Default, Debug)]
+pub struct PolicyOptions {
+    /// Broadcast mode for the transaction.
+    pub broadcast_mode: BroadcastMode,
+    /// Gas params for the transaction.
+    pub gas_params: GasParams,
+    /// Cancels setting the policy until its transaction is broadcast.
+    pub cancel: Cancellation,
+}
+
+/// Get-by-CID options.
+#[derive(Clone, Default, Debug)]
+pub struct GetByCidOptions {
+    /// IPFS gateway URLs to try, in order, e.g., "https://ipfs.io".
+    /// None are used by default, since a gateway learns which CIDs are requested.
+    pub gateways: Vec<String>,
+    /// Cancels the download.
+    pub cancel: Cancellation,
 }
 
 /// Object get options.
@@ -81,8 +192,113 @@ pub struct GetOptions {
     pub height: FvmQueryHeight,
     /// Whether to show progress-related output (useful for command-line interfaces).
     pub show_progress: bool,
+    /// How progress is shown if `show_progress` is set.
+    pub progress_format: ProgressFormat,
+    /// Bearer token for the Object API, overriding any token set on the provider.
+    pub auth_token: Option<String>,
+    /// Cancels the download, leaving whatever was already written to the writer.
+    pub cancel: Cancellation,
+    /// Local cache to serve the object from if its CID on chain is cached,
+    /// and to add it to after it's downloaded.
+    /// Range requests bypass the cache.
+    pub cache: Option<ObjectCache>,
+    /// Check the object data against the [`Checksum`] in its metadata,
+    /// failing if it has none or it doesn't match.
+    /// Can't be used with a range.
+    pub verify_checksum: bool,
+}
+
+/// Options for reading the schema of a tabular object with [`ObjectStore::peek_schema`].
+#[derive(Clone, Debug)]
+pub struct PeekSchemaOptions {
+    /// Object format, or `None` to detect it from the key's file extension.
+    pub format: Option<SchemaFormat>,
+    /// Number of bytes to read from the start of CSV objects, or the end of Parquet objects.
+    /// A Parquet footer that doesn't fit takes a second request.
+    pub sample_size: usize,
+    /// Query block height.
+    pub height: FvmQueryHeight,
+    /// Bearer token for the Object API, overriding any token set on the provider.
+    pub auth_token: Option<String>,
+}
+
+impl Default for PeekSchemaOptions {
+    fn default() -> Self {
+        Self {
+            format: None,
+            sample_size: 64 * 1024,
+            height: FvmQueryHeight::Committed,
+            auth_token: None,
+        }
+    }
+}
+
+/// Options for downloading objects by prefix with [`ObjectStore::get_prefix`].
+#[derive(Clone, Default, Debug)]
+pub struct GetPrefixOptions {
+    /// Query block height.
+    pub height: FvmQueryHeight,
+    /// Whether to show progress-related output (useful for command-line interfaces).
+    pub show_progress: bool,
+    /// How progress is shown if `show_progress` is set.
+    pub progress_format: ProgressFormat,
+    /// Bearer token for the Object API, overriding any token set on the provider.
+    pub auth_token: Option<String>,
+    /// Cancels all downloads in progress.
+    /// Completed downloads stay recorded, so the download can be resumed.
+    pub cancel: Cancellation,
+    /// Local cache to serve objects from and add downloaded objects to.
+    /// See [`GetOptions::cache`].
+    pub cache: Option<ObjectCache>,
+    /// Overwrite local files that aren't recorded in the [`PREFIX_DOWNLOAD_MANIFEST`],
+    /// e.g., ones that were created some other way.
+    /// Files recorded in the manifest are always replaced when their object changes.
+    pub overwrite: bool,
+}
+
+/// The result of [`ObjectStore::get_prefix`].
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct PrefixDownload {
+    /// The block height at which the objects were listed and downloaded.
+    pub height: u64,
+    /// Keys of downloaded objects.
+    pub downloaded: Vec<String>,
+    /// Keys of objects that were skipped because they were already downloaded,
+    /// are soft-deleted, or are not resolved yet.
+    pub skipped: Vec<String>,
+}
+
+/// Options for copying objects between object stores with [`ObjectStore::copy_to`].
+#[derive(Clone, Default, Debug)]
+pub struct CopyOptions {
+    /// Overwrite objects that already exist in the target object store.
+    pub overwrite: bool,
+    /// Query block height at which the source objects are listed.
+    pub height: FvmQueryHeight,
+    /// Broadcast mode for the transactions.
+    pub broadcast_mode: BroadcastMode,
+    /// Gas params for the transactions.
+    pub gas_params: GasParams,
+    /// Stops the copy before the next object.
+    /// Objects that were already copied are skipped when the copy is resumed.
+    pub cancel: Cancellation,
 }
 
+/// The result of [`ObjectStore::copy_to`].
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct PrefixCopy {
+    /// The block height at which the source objects were listed.
+    pub height: u64,
+    /// Keys of copied objects.
+    pub copied: Vec<String>,
+    /// Keys of objects that were skipped because they already exist in the target,
+    /// are soft-deleted, or are not resolved yet.
+    pub skipped: Vec<String>,
+}
+
+/// Name of the file in which [`ObjectStore::get_prefix`] records completed downloads.
+pub const PREFIX_DOWNLOAD_MANIFEST: &str = ".adm-download.json";
+
 /// Object query options.
 #[derive(Clone, Debug)]
 pub struct QueryOptions {
@@ -96,6 +312,115 @@ pub struct QueryOptions {
     pub limit: u64,
     /// Query block height.
     pub height: FvmQueryHeight,
+    /// Resume a listing after the previous page.
+    ///
+    /// When set, `offset` and `height` are taken from the cursor.
+    pub cursor: Option<Cursor>,
+    /// Cancels the query.
+    pub cancel: Cancellation,
+}
+
+/// A page of objects returned by [`ObjectStore::query_page`].
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ObjectPage {
+    /// The listed objects and common prefixes.
+    pub list: ObjectList,
+    /// Cursor for the next page, or `None` if this is the last page.
+    pub next: Option<Cursor>,
+}
+
+/// An object's state.
+///
+/// This and [`ObjectList`] mirror their actor counterparts,
+/// so the SDK API doesn't change with actor upgrades.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Object {
+    /// The object content CID.
+    pub cid: Cid,
+    /// The object size in bytes.
+    pub size: usize,
+    /// The object metadata.
+    pub metadata: HashMap<String, String>,
+    /// Whether the object content has been resolved by the network.
+    pub resolved: bool,
+}
+
+impl TryFrom<fendermint_actor_objectstore::Object> for Object {
+    type Error = anyhow::Error;
+
+    fn try_from(v: fendermint_actor_objectstore::Object) -> anyhow::Result<Self> {
+        Ok(Self {
+            cid: cid::Cid::try_from(v.cid.0)?.into(),
+            size: v.size,
+            metadata: v.metadata,
+            resolved: v.resolved,
+        })
+    }
+}
+
+/// Objects and common prefixes returned by [`ObjectStore::query`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObjectList {
+    /// The listed objects as (key, object) pairs, in key order.
+    pub objects: Vec<(Vec<u8>, Object)>,
+    /// Keys up to and including the first delimiter after the prefix,
+    /// for objects that were rolled up.
+    pub common_prefixes: Vec<Vec<u8>>,
+}
+
+impl TryFrom<fendermint_actor_objectstore::ObjectList> for ObjectList {
+    type Error = anyhow::Error;
+
+    fn try_from(v: fendermint_actor_objectstore::ObjectList) -> anyhow::Result<Self> {
+        let objects = v
+            .objects
+            .into_iter()
+            .map(|(key, object)| Ok((key, object.try_into()?)))
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self {
+            objects,
+            common_prefixes: v.common_prefixes,
+        })
+    }
+}
+
+/// Options for creating a funded object store with [`ObjectStore::new_funded`].
+#[derive(Clone, Debug)]
+pub struct NewFundedOptions {
+    /// Write access for the new object store.
+    pub write_access: WriteAccess,
+    /// Gas params for the deploy transaction.
+    pub gas_params: GasParams,
+    /// How often to check the subnet for the deposited funds.
+    pub poll_interval: Duration,
+    /// How long to wait for the deposited funds to become available in the subnet.
+    pub timeout: Duration,
+    /// Whether to show progress-related output (useful for command-line interfaces).
+    pub show_progress: bool,
+    /// How progress is shown if `show_progress` is set.
+    pub progress_format: ProgressFormat,
+}
+
+impl Default for NewFundedOptions {
+    fn default() -> Self {
+        Self {
+            write_access: WriteAccess::OnlyOwner,
+            gas_params: Default::default(),
+            poll_interval: Duration::from_secs(5),
+            timeout: Duration::from_secs(600),
+            show_progress: false,
+            progress_format: Default::default(),
+        }
+    }
+}
+
+/// Receipts from [`ObjectStore::new_funded`].
+#[derive(Clone, Debug)]
+pub struct NewFundedReceipt {
+    /// The parent-chain deposit transaction receipt.
+    pub deposit: TransactionReceipt,
+    /// The object store deploy transaction receipt.
+    pub deploy: DeployTxReceipt,
 }
 
 impl Default for QueryOptions {
@@ -106,41 +431,196 @@ impl Default for QueryOptions {
             offset: Default::default(),
             limit: Default::default(),
             height: Default::default(),
+            cursor: Default::default(),
+            cancel: Default::default(),
         }
     }
 }
 
+/// Page size used when listing all objects for a [`Manifest`].
+const MANIFEST_PAGE_SIZE: u64 = 1000;
+
+/// Initial delay between resolution checks in [`ObjectStore::wait_resolved`].
+const RESOLVE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Maximum delay between resolution checks in [`ObjectStore::wait_resolved`].
+const RESOLVE_POLL_MAX_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Base delay between upload retries in [`ObjectStore::add`].
+/// The delay grows linearly with each attempt.
+const UPLOAD_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Size of the chunks an object is read in for uploading.
+const UPLOAD_CHUNK_SIZE: usize = 256 * 1024;
+
+/// Default maximum number of bytes read ahead of an upload.
+pub const DEFAULT_UPLOAD_BUFFER_SIZE: usize = 8 * 1024 * 1024;
+
+/// An estimate of the cost of adding an object, made before uploading it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AddCostEstimate {
+    /// The estimated gas limit of the add transaction.
+    pub gas_limit: u64,
+    /// The gas cost, i.e., the gas limit times the gas price.
+    pub gas_cost: TokenAmount,
+    /// The size-based fee, i.e., the object size times the fee per byte.
+    pub storage_cost: TokenAmount,
+    /// The total cost.
+    pub total: TokenAmount,
+    /// The sender's current balance in the subnet.
+    pub balance: TokenAmount,
+}
+
+impl AddCostEstimate {
+    /// Returns whether the sender's balance covers the estimated cost.
+    pub fn is_sufficient(&self) -> bool {
+        self.balance >= self.total
+    }
+}
+
+/// Usage statistics of an object store.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ObjectStoreStats {
+    /// The height the statistics were computed at.
+    pub height: u64,
+    /// The number of objects.
+    pub objects: u64,
+    /// The number of objects that haven't been resolved by the network yet.
+    pub unresolved: u64,
+    /// The total size of all objects in bytes.
+    pub bytes: u64,
+    /// The height at which the object store was last modified.
+    pub last_modified: u64,
+}
+
+/// An object entry in a [`Manifest`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// The object CID.
+    pub cid: Cid,
+    /// The object size in bytes.
+    pub size: usize,
+    /// Whether the object has been resolved by the network.
+    pub resolved: bool,
+    /// The object metadata.
+    pub metadata: BTreeMap<String, String>,
+}
+
+/// A signature over the objects in a [`Manifest`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ManifestSignature {
+    /// The signer address.
+    pub signer: String,
+    /// The chain ID the signature is bound to.
+    pub chain_id: u64,
+    /// The base64-encoded secp256k1 signature.
+    pub signature: String,
+}
+
+/// An index of all objects (key → CID, size, metadata) in a store at a given height.
+///
+/// Publishers can sign a manifest and distribute it as a verifiable index of a dataset release.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    /// The object store address.
+    pub address: String,
+    /// The block height at which the manifest was generated.
+    pub height: u64,
+    /// All objects in the store, ordered by key.
+    pub objects: BTreeMap<String, ManifestEntry>,
+    /// The manifest signature, if signed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<ManifestSignature>,
+}
+
+impl Manifest {
+    /// Sign the manifest objects with the given [`Signer`].
+    pub fn sign(&mut self, signer: &impl Signer) -> anyhow::Result<()> {
+        let (chain_id, network) = match signer.subnet_id() {
+            Some(id) => (id.chain_id(), id.address_network()),
+            None => {
+                return Err(anyhow!("failed to get subnet ID from signer"));
+            }
+        };
+        let message = object_upload_message(
+            signer.address(),
+            parse_address(&self.address)?,
+            0,
+            self.params()?,
+        );
+        let signed = signer.sign_message(message, None)?;
+        self.signature = Some(ManifestSignature {
+            signer: format_address(&signer.address(), network),
+            chain_id: chain_id.into(),
+            signature: general_purpose::STANDARD.encode(signed.signature.bytes()),
+        });
+        Ok(())
+    }
+
+    /// Verify the manifest signature against the manifest objects.
+    pub fn verify(&self) -> anyhow::Result<()> {
+        let sig = self
+            .signature
+            .as_ref()
+            .ok_or_else(|| anyhow!("manifest is not signed"))?;
+        verify_params(
+            parse_address(&sig.signer)?,
+            parse_address(&self.address)?,
+            self.params()?,
+            &general_purpose::STANDARD.decode(&sig.signature)?,
+            sig.chain_id,
+        )
+        .map_err(|e| anyhow!("invalid manifest signature: {e}"))
+    }
+
+    /// Writes the JSON-encoded manifest to the writer.
+    pub async fn write<W>(&self, mut writer: W) -> anyhow::Result<()>
+    where
+        W: AsyncWrite + Unpin + Send,
+    {
+        let json = serde_json::to_vec_pretty(self)?;
+        writer.write_all(&json).await?;
+        writer.flush().await?;
+        Ok(())
+    }
+
+    /// Returns the encoded manifest, which is signed as the params of a read-only message.
+    fn params(&self) -> anyhow::Result<RawBytes> {
+        Ok(RawBytes::serialize((self.height, &self.objects))?)
+    }
+}
+
 /// A machine for S3-like object storage.
 pub struct ObjectStore {
     address: Address,
+    /// Accumulator that records every add and delete, if any.
+    audit_log: Option<Accumulator>,
+    /// Quota usage, counted by the first add that checks a [`Policy`] quota.
+    usage: Mutex<Option<Usage>>,
 }
 
 #[async_trait]
 impl Machine for ObjectStore {
     const KIND: Kind = Kind::ObjectStore;
 
-    async fn new<C>(
+    async fn deploy<C>(
         provider: &impl Provider<C>,
         signer: &mut impl Signer,
-        write_access: WriteAccess,
-        gas_params: GasParams,
+        options: DeployOptions,
     ) -> anyhow::Result<(Self, DeployTxReceipt)>
     where
         C: Client + Send + Sync,
     {
-        let (address, tx) = deploy_machine(
-            provider,
-            signer,
-            Kind::ObjectStore,
-            write_access,
-            gas_params,
-        )
-        .await?;
+        let (address, tx) = deploy_machine(provider, signer, Kind::ObjectStore, options).await?;
         Ok((Self::attach(address), tx))
     }
 
     fn attach(address: Address) -> Self {
-        ObjectStore { address }
+        ObjectStore {
+            address,
+            audit_log: None,
+            usage: Mutex::new(None),
+        }
     }
 
     fn address(&self) -> Address {
@@ -149,7 +629,160 @@ impl Machine for ObjectStore {
 }
 
 impl ObjectStore {
+    /// Deposit funds from the parent chain, wait for them to arrive in the subnet,
+    /// and then deploy a new object store.
+    ///
+    /// This collapses onboarding for a new account into a single call.
+    /// The same key is used to sign the deposit on the parent (described by `parent`)
+    /// and to deploy the machine in the subnet.
+    pub async fn new_funded<C>(
+        provider: &impl Provider<C>,
+        wallet: &mut Wallet,
+        parent: EVMSubnet,
+        amount: TokenAmount,
+        options: NewFundedOptions,
+    ) -> anyhow::Result<(Self, NewFundedReceipt)>
+    where
+        C: Client + Send + Sync,
+    {
+        let started = Instant::now();
+        let bars = new_multi_bar(!options.show_progress, options.progress_format);
+        let msg_bar = bars.add(new_message_bar());
+
+        let subnet_id = wallet
+            .subnet_id()
+            .ok_or_else(|| anyhow!("failed to get subnet ID from signer"))?;
+        let secret_key = wallet
+            .secret_key()
+            .ok_or_else(|| anyhow!("failed to get secret key from signer"))?;
+        // The deposit signer must target the parent subnet
+        let parent_signer =
+            Wallet::new_secp256k1(secret_key, AccountKind::Ethereum, subnet_id.parent()?)?;
+
+        let before = subnet_balance(provider, wallet.address()).await?;
+
+        msg_bar.set_prefix("[1/3]");
+        msg_bar.set_message(format!("Depositing {} to subnet...", amount));
+        let deposit = Account::deposit(
+            &parent_signer,
+            wallet.address(),
+            parent,
+            amount.clone(),
+            Default::default(),
+        )
+        .await?
+        .receipt;
+
+        msg_bar.set_prefix("[2/3]");
+        msg_bar.set_message("Waiting for funds to arrive in subnet...");
+        let expected = before + amount;
+        let waited = Instant::now();
+        loop {
+            if subnet_balance(provider, wallet.address()).await? >= expected {
+                break;
+            }
+            if waited.elapsed() > options.timeout {
+                return Err(anyhow!(
+                    "timed out after {} waiting for deposit to arrive in subnet",
+                    HumanDuration(options.timeout)
+                ));
+            }
+            tokio::time::sleep(options.poll_interval).await;
+        }
+
+        msg_bar.set_prefix("[3/3]");
+        msg_bar.set_message("Deploying object store...");
+        wallet.init_sequence(provider).await?;
+        let (machine, deploy) =
+            Self::new(provider, wallet, options.write_access, options.gas_params).await?;
+
+        msg_bar.println(format!(
+            "{} Created object store {} in {}",
+            SPARKLE,
+            machine.address(),
+            HumanDuration(started.elapsed())
+        ));
+        msg_bar.finish_and_clear();
+
+        Ok((machine, NewFundedReceipt { deposit, deploy }))
+    }
+
+    /// Record every add and delete in an accumulator, giving the store an immutable audit log.
+    ///
+    /// After each change's transaction is committed, an [`AuditRecord`] is pushed into the
+    /// accumulator with the same signer, broadcast mode, and gas params, so the accumulator
+    /// should have the same owner as the store.
+    /// Changes must be broadcast with [`BroadcastMode::Commit`]. If a record can't be pushed,
+    /// the change's method fails with an [`AuditError`] that carries the change's receipt.
+    /// Objects copied into the store with [`ObjectStore::copy_to`] are recorded too,
+    /// but changes sent with [`ObjectStore::tx_add`] or [`ObjectStore::tx_delete`] aren't recorded.
+    pub fn with_audit_log(mut self, accumulator: Accumulator) -> Self {
+        self.audit_log = Some(accumulator);
+        self
+    }
+
+    /// Returns the accumulator that records changes, if any.
+    pub fn audit_log(&self) -> Option<&Accumulator> {
+        self.audit_log.as_ref()
+    }
+
+    /// Fails if the store has an audit log and changes sent with `broadcast_mode` wouldn't be
+    /// committed before they're recorded.
+    fn check_audit_mode(&self, broadcast_mode: BroadcastMode) -> anyhow::Result<()> {
+        if self.audit_log.is_some() && !matches!(broadcast_mode, BroadcastMode::Commit) {
+            return Err(anyhow!(
+                "object store has an audit log, so changes must be broadcast in commit mode"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Push a record of a committed change into the audit log, if the store has one,
+    /// and return the change's receipt.
+    ///
+    /// The change can't be undone, so if the record isn't pushed,
+    /// the error is an [`AuditError`] that carries the receipt.
+    async fn audit<C>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        tx: TxReceipt<Cid>,
+        record: impl FnOnce(&TxReceipt<Cid>) -> anyhow::Result<AuditRecord>,
+        broadcast_mode: BroadcastMode,
+        gas_params: GasParams,
+    ) -> anyhow::Result<TxReceipt<Cid>>
+    where
+        C: Client + Send + Sync,
+    {
+        let Some(accumulator) = &self.audit_log else {
+            return Ok(tx);
+        };
+        let options = PushOptions {
+            broadcast_mode,
+            gas_params,
+            ..Default::default()
+        };
+        let pushed = async {
+            if !matches!(tx.status, TxStatus::Committed) {
+                return Err(anyhow!("the change isn't committed yet"));
+            }
+            let record = record(&tx)?;
+            accumulator
+                .push(provider, signer, record.encode()?, options)
+                .await?;
+            Ok::<_, anyhow::Error>(())
+        }
+        .await;
+        match pushed {
+            Ok(()) => Ok(tx),
+            Err(source) => Err(AuditError { tx, source }.into()),
+        }
+    }
+
     /// Add an object into the object store.
+    ///
+    /// If the store has a [`Policy`], the object is checked against its quotas before it's
+    /// uploaded, and it's set to expire if the policy has a TTL.
     pub async fn add<C, R>(
         &self,
         provider: &impl Provider<C>,
@@ -162,80 +795,114 @@ impl ObjectStore {
         C: Client + Send + Sync,
         R: AsyncRead + AsyncSeek + Unpin + Send + 'static,
     {
+        let key = Key::new(key)?;
+        self.check_audit_mode(options.broadcast_mode)?;
         let started = Instant::now();
-        let bars = new_multi_bar(!options.show_progress);
+        let bars = new_multi_bar(!options.show_progress, options.progress_format);
         let msg_bar = bars.add(new_message_bar());
-        // Generate object Cid
-        // We do this here to avoid moving the reader
-        let chunk_size = 1024 * 1024; // size-1048576
-        let adder = FileAdder::builder()
-            .with_chunker(Chunker::Size(chunk_size))
-            .build();
-        let buffer = vec![0; chunk_size];
-        let mut reader_size: usize = 0;
-        let mut object_size: usize = 0;
+        // Dropping the upload on cancellation closes its connection, aborting it server-side
+        let (object_cid, object_size, metadata, usage_change) = options
+            .cancel
+            .run(async {
+                // Generate object Cid
+                // We do this here to avoid moving the reader
+                msg_bar.set_prefix("[1/3]");
+                let (object_cid, object_size, checksum) =
+                    generate_cid(&mut reader, &msg_bar, options.checksum).await?;
 
-        msg_bar.set_prefix("[1/3]");
-        let chunk = Cid::from(cid::Cid::default());
-        let object_cid = generate_cid(
-            &mut reader,
-            buffer,
-            &mut reader_size,
-            adder,
-            chunk,
-            &msg_bar,
-            &mut object_size,
-        )
-        .await?;
+                let mut metadata = options.metadata.clone();
+                if let Some((checksum, digest)) = checksum {
+                    metadata.insert(checksum.metadata_key().into(), digest);
+                }
+                let usage_change = if key.as_str() != POLICY_KEY {
+                    msg_bar.set_message("Checking object store policy...");
+                    self.apply_policy(provider, &key, object_size, &mut metadata)
+                        .await?
+                } else {
+                    None
+                };
+                if options.attest {
+                    let attestation =
+                        Attestation::new(&*signer, key.as_str(), object_cid, object_size)?;
+                    metadata.insert(ATTESTATION_METADATA_KEY.into(), attestation.encode()?);
+                }
 
-        // Rewind and stream for uploading
-        msg_bar.set_prefix("[2/3]");
-        msg_bar.set_message(format!("Uploading {} to network...", object_cid));
-        let pro_bar = bars.add(new_progress_bar(reader_size));
-        reader.rewind().await?;
-        let mut stream = ReaderStream::new(reader);
-        let async_stream = async_stream::stream! {
-            let mut progress: usize = 0;
-            while let Some(chunk) = stream.next().await {
-                if let Ok(chunk) = &chunk {
-                    progress = min(progress + chunk.len(), reader_size);
-                    pro_bar.set_position(progress as u64);
+                // Rewind and stream for uploading
+                msg_bar.set_prefix("[2/3]");
+                let pro_bar = bars.add(new_progress_bar(object_size));
+                let buffer_size = match options.upload_buffer_size {
+                    0 => DEFAULT_UPLOAD_BUFFER_SIZE,
+                    size => size,
+                };
+                let mut attempt = 0;
+                let response_cid = loop {
+                    attempt += 1;
+                    msg_bar.set_message(format!("Uploading {} to network...", object_cid));
+                    // The reader fills a bounded channel that the request body drains,
+                    // so reading never gets more than `buffer_size` bytes ahead of the network
+                    let (tx, mut rx) = mpsc::channel((buffer_size / UPLOAD_CHUNK_SIZE).max(1));
+                    let pro_bar = pro_bar.clone();
+                    let body = async_stream::stream! {
+                        let mut progress: usize = 0;
+                        pro_bar.set_position(0);
+                        while let Some(chunk) = rx.recv().await {
+                            if let Ok(chunk) = &chunk {
+                                progress = min(progress + chunk.len(), object_size);
+                                pro_bar.set_position(progress as u64);
+                            }
+                            yield chunk;
+                        }
+                    };
+
+                    // Upload Object to Object API while reading it, on the same task
+                    let (_, result) = tokio::join!(
+                        send_chunks(&mut reader, tx),
+                        self.upload(
+                            provider,
+                            signer,
+                            key.as_str(),
+                            body,
+                            object_cid,
+                            object_size,
+                            metadata.clone(),
+                            options.overwrite,
+                            options.auth_token.clone(),
+                        )
+                    );
+                    match result {
+                        Err(e) if attempt <= options.upload_retries && is_transient(&e) => {
+                            msg_bar.set_message(format!(
+                                "Upload failed; retrying ({}/{})...",
+                                attempt, options.upload_retries
+                            ));
+                            tokio::time::sleep(UPLOAD_RETRY_DELAY * attempt).await;
+                        }
+                        result => break result?,
+                    }
+                };
+                pro_bar.finish_and_clear();
+
+                // Verify uploaded CID with locally computed CID
+                if response_cid != object_cid {
+                    return Err(UploadError::cid_mismatch(&object_cid, &response_cid).into());
                 }
-                yield chunk;
-            }
-            pro_bar.finish_and_clear();
-        };
 
-        // Upload Object to Object API
-        let response_cid = self
-            .upload(
-                provider,
-                signer,
-                key,
-                async_stream,
-                object_cid,
-                object_size,
-                options.metadata.clone(),
-                options.overwrite,
-            )
+                anyhow::Ok((object_cid, object_size, metadata, usage_change))
+            })
             .await?;
-
-        // Verify uploaded CID with locally computed CID
-        if response_cid != object_cid {
-            return Err(anyhow!("cannot verify object; cid does not match remote"));
-        }
+        options.cancel.check()?;
 
         // Broadcast transaction with Object's CID
         msg_bar.set_prefix("[3/3]");
         msg_bar.set_message("Broadcasting transaction...");
         let params = AddParams {
-            key: key.into(),
+            key: key.as_str().into(),
             cid: object_cid.0,
             overwrite: options.overwrite,
-            metadata: options.metadata,
+            metadata,
             size: object_size,
         };
-        let serialized_params = RawBytes::serialize(params.clone())?;
+        let serialized_params = RawBytes::serialize(&params)?;
         let object = Some(MessageObject::new(
             params.key.clone(),
             object_cid.0,
@@ -248,12 +915,36 @@ impl ObjectStore {
                 AddObject as u64,
                 serialized_params,
                 object,
-                options.gas_params,
+                options.gas_params.clone(),
             )
             .await?;
         let tx = provider
             .perform(message, options.broadcast_mode, decode_cid)
             .await?;
+        if let Some(change) = usage_change {
+            if let Some(usage) = self.usage.lock().unwrap().as_mut() {
+                usage.apply(&change);
+            }
+        }
+        if self.audit_log.is_some() {
+            msg_bar.set_message("Recording change in audit log...");
+        }
+        let tx = self
+            .audit(
+                provider,
+                signer,
+                tx,
+                |tx| {
+                    Ok(AuditRecord {
+                        cid: Some(object_cid),
+                        size: Some(object_size),
+                        ..AuditRecord::new(AuditAction::Add, self.address, key.as_str(), tx)?
+                    })
+                },
+                options.broadcast_mode,
+                options.gas_params,
+            )
+            .await?;
         msg_bar.println(format!(
             "{} Added object in {} (cid={}; size={})",
             SPARKLE,
@@ -265,6 +956,57 @@ impl ObjectStore {
         Ok(tx)
     }
 
+    /// Check an object of `size` bytes at `key` against the store's [`Policy`],
+    /// and set its expiry in `metadata` if the policy has a TTL.
+    ///
+    /// Returns the change to record in the usage counter once the object is added,
+    /// if the policy has a quota.
+    async fn apply_policy(
+        &self,
+        provider: &impl QueryProvider,
+        key: &Key,
+        size: usize,
+        metadata: &mut HashMap<String, String>,
+    ) -> anyhow::Result<Option<UsageChange>> {
+        let Some(policy) = self.policy(provider, FvmQueryHeight::Pending).await? else {
+            return Ok(None);
+        };
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let mut change = None;
+        if policy.has_quota() {
+            let usage = self.usage(provider, now).await?;
+            // Only look up the object being overwritten, not the whole store
+            let replaced = self
+                .get_object(provider, key, FvmQueryHeight::Pending)
+                .await?
+                .filter(|object| !expired(object.metadata.get(EXPIRES_METADATA_KEY), now))
+                .map(|object| object.size as u64);
+            let usage_change = UsageChange {
+                size: size as u64,
+                replaced,
+            };
+            policy.check(&usage, &usage_change)?;
+            change = Some(usage_change);
+        }
+        if let Some(expires) = policy.expires_at(now) {
+            metadata.insert(EXPIRES_METADATA_KEY.into(), expires.to_string());
+        }
+        Ok(change)
+    }
+
+    /// Returns the quota usage counter, counting it from a listing of the store the first time.
+    async fn usage(&self, provider: &impl QueryProvider, now: u64) -> anyhow::Result<Usage> {
+        if let Some(usage) = *self.usage.lock().unwrap() {
+            return Ok(usage);
+        }
+        let (_, objects) = self
+            .list_entries(provider, "", FvmQueryHeight::Pending)
+            .await?;
+        let usage = Usage::of(&objects, now);
+        *self.usage.lock().unwrap() = Some(usage);
+        Ok(usage)
+    }
+
     /// Uploads an object to the Object API for staging.
     #[allow(clippy::too_many_arguments)]
     async fn upload<S>(
@@ -277,6 +1019,7 @@ impl ObjectStore {
         size: usize,
         metadata: HashMap<String, String>,
         overwrite: bool,
+        auth_token: Option<String>,
     ) -> anyhow::Result<Cid>
     where
         S: futures_core::stream::TryStream + Send + 'static,
@@ -315,6 +1058,7 @@ impl ObjectStore {
                 size,
                 general_purpose::URL_SAFE.encode(&serialized_signed_message),
                 chain_id.into(),
+                auth_token,
             )
             .await?;
 
@@ -332,76 +1076,727 @@ impl ObjectStore {
     where
         C: Client + Send + Sync,
     {
-        let params = DeleteParams { key: key.into() };
-        let params = RawBytes::serialize(params)?;
-        let message = signer
-            .transaction(
-                self.address,
-                Default::default(),
-                DeleteObject as u64,
-                params,
-                None,
-                options.gas_params,
-            )
+        let key = Key::new(key)?;
+        self.check_audit_mode(options.broadcast_mode)?;
+        if options.soft {
+            let object = options
+                .cancel
+                .run(self.get_object(provider, &key, FvmQueryHeight::Pending))
+                .await?
+                .ok_or_else(|| NotFound::new(format!("object not found for key '{}'", key)))?;
+            options.cancel.check()?;
+            if object.metadata.contains_key(DELETED_METADATA_KEY) {
+                return Err(anyhow!("object for key '{}' is already deleted", key));
+            }
+            let deleted_at = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            let mut metadata = object.metadata.clone();
+            metadata.insert(DELETED_METADATA_KEY.into(), deleted_at.to_string());
+            let size = object.size;
+            let tx = self
+                .set_metadata(
+                    provider,
+                    signer,
+                    &key,
+                    object,
+                    metadata,
+                    options.broadcast_mode,
+                    options.gas_params.clone(),
+                )
+                .await?;
+            return self
+                .audit(
+                    provider,
+                    signer,
+                    tx,
+                    |tx| {
+                        Ok(AuditRecord {
+                            size: Some(size),
+                            soft: true,
+                            ..AuditRecord::new(AuditAction::Delete, self.address, key.as_str(), tx)?
+                        })
+                    },
+                    options.broadcast_mode,
+                    options.gas_params,
+                )
+                .await;
+        }
+
+        options.cancel.check()?;
+        let tx = self
+            .tx_delete(key.as_str())?
+            .gas_params(options.gas_params.clone())
+            .broadcast(options.broadcast_mode)
+            .send(provider, signer)
             .await?;
-        provider
-            .perform(message, options.broadcast_mode, decode_cid)
-            .await
+        // The deleted object's size isn't known here, so count usage again on the next add
+        *self.usage.lock().unwrap() = None;
+        self.audit(
+            provider,
+            signer,
+            tx,
+            |tx| AuditRecord::new(AuditAction::Delete, self.address, key.as_str(), tx),
+            options.broadcast_mode,
+            options.gas_params,
+        )
+        .await
     }
 
-    /// Get an object at the given key, range, and height.
-    pub async fn get<W>(
+    /// Estimate the cost of adding an object of `size` bytes at `key` without uploading it.
+    ///
+    /// The add transaction is simulated with a placeholder CID to estimate its gas limit,
+    /// which is priced at `gas_price` per unit of gas.
+    /// Subnets that charge for storage by size can pass their `fee_per_byte`; otherwise, pass zero.
+    pub async fn estimate_add_cost(
         &self,
-        provider: &(impl QueryProvider + ObjectProvider),
+        provider: &impl QueryProvider,
+        from: Address,
         key: &str,
-        mut writer: W,
-        options: GetOptions,
-    ) -> anyhow::Result<()>
-    where
-        W: AsyncWrite + Unpin + Send + 'static,
-    {
-        let started = Instant::now();
-        let bars = new_multi_bar(!options.show_progress);
-        let msg_bar = bars.add(new_message_bar());
-
-        msg_bar.set_prefix("[1/2]");
-        msg_bar.set_message("Getting object info...");
-        let params = GetParams { key: key.into() };
+        size: usize,
+        gas_price: &TokenAmount,
+        fee_per_byte: &TokenAmount,
+    ) -> anyhow::Result<AddCostEstimate> {
+        let key = Key::new(key)?;
+        let params = AddParams {
+            key: key.as_str().into(),
+            cid: cid::Cid::default(),
+            // Overwrite so an existing object doesn't fail the simulation
+            overwrite: true,
+            metadata: HashMap::new(),
+            size,
+        };
+        let params = RawBytes::serialize(params)?;
+        let message = object_upload_message(from, self.address, AddObject as u64, params);
+        let estimate = provider
+            .estimate_gas(message, FvmQueryHeight::Committed)
+            .await?
+            .value;
+        if !estimate.exit_code.is_success() {
+            return Err(anyhow!(
+                "failed to estimate gas for adding object: {} (exit code {})",
+                estimate.info,
+                estimate.exit_code.value()
+            ));
+        }
+
+        let gas_cost = gas_price * estimate.gas_limit;
+        let storage_cost = fee_per_byte * size as u64;
+        let total = &gas_cost + &storage_cost;
+        let balance = subnet_balance(provider, from).await?;
+        Ok(AddCostEstimate {
+            gas_limit: estimate.gas_limit,
+            gas_cost,
+            storage_cost,
+            total,
+            balance,
+        })
+    }
+
+    /// Copy the objects under `prefix` to another object store without re-uploading them.
+    ///
+    /// Each object is added to `target` with the same key, CID, size, and metadata,
+    /// and the network resolves its data from the nodes that already have it.
+    /// Soft-deleted and unresolved objects are skipped, as are objects that already exist
+    /// in `target` unless `overwrite` is set, so an interrupted copy can be resumed by
+    /// running it again.
+    ///
+    /// Write access to `target` is checked by simulating the first add before sending
+    /// any transactions.
+    pub async fn copy_to<C>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        target: &ObjectStore,
+        prefix: &str,
+        options: CopyOptions,
+    ) -> anyhow::Result<PrefixCopy>
+    where
+        C: Client + Send + Sync,
+    {
+        if target.address == self.address {
+            return Err(anyhow!("cannot copy objects to the same object store"));
+        }
+        target.check_audit_mode(options.broadcast_mode)?;
+        let (height, objects) = options
+            .cancel
+            .run(self.list_entries(provider, prefix, options.height))
+            .await?;
+        let existing = if options.overwrite {
+            BTreeMap::new()
+        } else {
+            options
+                .cancel
+                .run(target.list_entries(provider, prefix, FvmQueryHeight::Committed))
+                .await?
+                .1
+        };
+
+        let mut result = PrefixCopy {
+            height,
+            ..Default::default()
+        };
+        let mut checked = false;
+        for (key, entry) in objects {
+            options.cancel.check()?;
+            if !entry.resolved
+                || entry.metadata.contains_key(DELETED_METADATA_KEY)
+                || existing.contains_key(&key)
+                || key == POLICY_KEY
+            {
+                result.skipped.push(key);
+                continue;
+            }
+            let params = AddParams {
+                key: key.as_str().into(),
+                cid: entry.cid.0,
+                overwrite: options.overwrite,
+                metadata: entry.metadata.into_iter().collect(),
+                size: entry.size,
+            };
+            let object = MessageObject::new(params.key.clone(), entry.cid.0, target.address);
+            let params = RawBytes::serialize(params)?;
+            if !checked {
+                let message = object_upload_message(
+                    signer.address(),
+                    target.address,
+                    AddObject as u64,
+                    params.clone(),
+                );
+                let estimate = provider
+                    .estimate_gas(message, FvmQueryHeight::Pending)
+                    .await?
+                    .value;
+                if !estimate.exit_code.is_success() {
+                    return Err(anyhow!(
+                        "cannot add objects to {}: {} (exit code {})",
+                        target.address,
+                        estimate.info,
+                        estimate.exit_code.value()
+                    ));
+                }
+                checked = true;
+            }
+            let tx = TxBuilder::new(target.address, AddObject as u64, params, decode_cid)
+                .object(object)
+                .gas_params(options.gas_params.clone())
+                .broadcast(options.broadcast_mode)
+                .send(provider, signer)
+                .await
+                .with_context(|| format!("failed to copy object '{}'", key))?;
+            target
+                .audit(
+                    provider,
+                    signer,
+                    tx,
+                    |tx| {
+                        Ok(AuditRecord {
+                            cid: Some(entry.cid),
+                            size: Some(entry.size),
+                            ..AuditRecord::new(AuditAction::Add, target.address, &key, tx)?
+                        })
+                    },
+                    options.broadcast_mode,
+                    options.gas_params.clone(),
+                )
+                .await?;
+            result.copied.push(key);
+        }
+        Ok(result)
+    }
+
+    /// Returns a [`TxBuilder`] that adds an already uploaded object with the given [`Cid`] and size.
+    ///
+    /// The object is added without metadata and fails if the key exists.
+    /// Use [`ObjectStore::add`] to upload and add an object in one step.
+    pub fn tx_add(&self, key: &str, cid: Cid, size: usize) -> anyhow::Result<TxBuilder<Cid>> {
+        let key = Key::new(key)?;
+        let params = AddParams {
+            key: key.as_str().into(),
+            cid: cid.0,
+            overwrite: false,
+            metadata: HashMap::new(),
+            size,
+        };
+        let params = RawBytes::serialize(params)?;
+        let object = MessageObject::new(key.as_str().into(), cid.0, self.address);
+        let tx = TxBuilder::new(self.address, AddObject as u64, params, decode_cid);
+        Ok(tx.object(object))
+    }
+
+    /// Returns a [`TxBuilder`] that deletes the object at the given key.
+    pub fn tx_delete(&self, key: &str) -> anyhow::Result<TxBuilder<Cid>> {
+        let key = Key::new(key)?;
+        let params = DeleteParams {
+            key: key.as_str().into(),
+        };
+        let params = RawBytes::serialize(params)?;
+        Ok(TxBuilder::new(
+            self.address,
+            DeleteObject as u64,
+            params,
+            decode_cid,
+        ))
+    }
+
+    /// Restore a soft-deleted object.
+    pub async fn restore<C>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        key: &str,
+        options: RestoreOptions,
+    ) -> anyhow::Result<TxReceipt<Cid>>
+    where
+        C: Client + Send + Sync,
+    {
+        let key = Key::new(key)?;
+        self.check_audit_mode(options.broadcast_mode)?;
+        let object = options
+            .cancel
+            .run(self.get_object(provider, &key, FvmQueryHeight::Pending))
+            .await?
+            .ok_or_else(|| NotFound::new(format!("object not found for key '{}'", key)))?;
+        if !object.metadata.contains_key(DELETED_METADATA_KEY) {
+            return Err(anyhow!("object for key '{}' is not deleted", key));
+        }
+        options.cancel.check()?;
+        let mut metadata = object.metadata.clone();
+        metadata.remove(DELETED_METADATA_KEY);
+        let size = object.size;
+        let tx = self
+            .set_metadata(
+                provider,
+                signer,
+                &key,
+                object,
+                metadata,
+                options.broadcast_mode,
+                options.gas_params.clone(),
+            )
+            .await?;
+        self.audit(
+            provider,
+            signer,
+            tx,
+            |tx| {
+                Ok(AuditRecord {
+                    size: Some(size),
+                    ..AuditRecord::new(AuditAction::Restore, self.address, key.as_str(), tx)?
+                })
+            },
+            options.broadcast_mode,
+            options.gas_params,
+        )
+        .await
+    }
+
+    /// Permanently delete all soft-deleted objects,
+    /// and all objects that have expired under the store's [`Policy`].
+    ///
+    /// Returns the keys of the purged objects.
+    pub async fn purge<C>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        options: DeleteOptions,
+    ) -> anyhow::Result<Vec<String>>
+    where
+        C: Client + Send + Sync,
+    {
+        let (_, objects) = options
+            .cancel
+            .run(self.list_entries(provider, "", FvmQueryHeight::Pending))
+            .await?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let keys: Vec<String> = objects
+            .into_iter()
+            .filter(|(_, entry)| {
+                entry.metadata.contains_key(DELETED_METADATA_KEY)
+                    || is_expired(&entry.metadata, now)
+            })
+            .map(|(key, _)| key)
+            .collect();
+        for key in &keys {
+            self.delete(
+                provider,
+                signer,
+                key,
+                DeleteOptions {
+                    soft: false,
+                    ..options.clone()
+                },
+            )
+            .await?;
+        }
+        Ok(keys)
+    }
+
+    /// Set the object store's [`Policy`], replacing any existing policy.
+    ///
+    /// The policy is uploaded as a small object at [`POLICY_KEY`] and attested by the signer,
+    /// which must be the store owner, so the provider must have an Object API URL.
+    pub async fn set_policy<C>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        policy: &Policy,
+        options: PolicyOptions,
+    ) -> anyhow::Result<TxReceipt<Cid>>
+    where
+        C: Client + Send + Sync,
+    {
+        let owner = options
+            .cancel
+            .run(info(provider, self.address, FvmQueryHeight::Pending))
+            .await?
+            .owner;
+        if signer.address() != owner {
+            return Err(anyhow!(
+                "only the owner {} can set the policy of object store {}",
+                owner,
+                self.address
+            ));
+        }
+        let value = policy.encode()?;
+        let metadata = HashMap::from([(POLICY_METADATA_KEY.to_string(), value.clone())]);
+        self.add(
+            provider,
+            signer,
+            POLICY_KEY,
+            std::io::Cursor::new(value.into_bytes()),
+            AddOptions {
+                overwrite: true,
+                broadcast_mode: options.broadcast_mode,
+                gas_params: options.gas_params,
+                metadata,
+                attest: true,
+                cancel: options.cancel,
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    /// Get the object store's [`Policy`] at the given height, or `None` if it has no policy.
+    ///
+    /// The policy must be attested by the store owner,
+    /// so accounts with public write access can't replace it.
+    /// A policy object that can't be decoded or wasn't attested by the owner is ignored
+    /// with a warning, so overwriting it doesn't block adds to the store.
+    pub async fn policy(
+        &self,
+        provider: &impl QueryProvider,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<Option<Policy>> {
+        let key = Key::new(POLICY_KEY)?;
+        let Some(object) = self.get_object(provider, &key, height).await? else {
+            return Ok(None);
+        };
+        if object.metadata.contains_key(DELETED_METADATA_KEY) {
+            return Ok(None);
+        }
+        let owner = info(provider, self.address, height).await?.owner;
+        match self.decode_policy(&object, owner) {
+            Ok(policy) => Ok(Some(policy)),
+            Err(e) => {
+                tracing::warn!(
+                    "Ignoring invalid policy of object store {}: {:#}",
+                    self.address,
+                    e
+                );
+                Ok(None)
+            }
+        }
+    }
+
+    /// Decode the policy in a policy object, checking that it was attested by `owner`.
+    fn decode_policy(&self, object: &Object, owner: Address) -> anyhow::Result<Policy> {
+        let value = object
+            .metadata
+            .get(POLICY_METADATA_KEY)
+            .ok_or_else(|| anyhow!("policy object has no '{}' metadata", POLICY_METADATA_KEY))?;
+        // The attestation covers the object content, which must be the policy in the metadata
+        let cid = object.cid.0;
+        verify_object(value.as_bytes(), &cid)
+            .map_err(|e| anyhow!("policy metadata does not match the policy object: {e}"))?;
+        let attestation = object
+            .metadata
+            .get(ATTESTATION_METADATA_KEY)
+            .ok_or_else(|| anyhow!("policy object has no attestation"))?;
+        let attestation = Attestation::decode(attestation)?;
+        attestation.verify(POLICY_KEY, cid.into(), object.size)?;
+        if parse_address(&attestation.signer)? != owner {
+            return Err(anyhow!(
+                "policy of object store {} was not set by its owner {}",
+                self.address,
+                owner
+            ));
+        }
+        Policy::decode(value)
+    }
+
+    /// Wait until the object at the given key is resolved by the network.
+    ///
+    /// Resolution status is polled at the committed height with exponential backoff.
+    /// Returns the resolved object, or an error if it's not resolved within `timeout`.
+    pub async fn wait_resolved(
+        &self,
+        provider: &impl QueryProvider,
+        key: &str,
+        timeout: Duration,
+    ) -> anyhow::Result<Object> {
+        let key = Key::new(key)?;
+        let started = Instant::now();
+        let mut interval = RESOLVE_POLL_INTERVAL;
+        loop {
+            let object = self
+                .get_object(provider, &key, FvmQueryHeight::Committed)
+                .await?
+                .ok_or_else(|| NotFound::new(format!("object not found for key '{}'", key)))?;
+            if object.resolved {
+                return Ok(object);
+            }
+            let elapsed = started.elapsed();
+            if elapsed >= timeout {
+                return Err(anyhow!(
+                    "object for key '{}' was not resolved within {}",
+                    key,
+                    HumanDuration(timeout)
+                ));
+            }
+            tokio::time::sleep(min(interval, timeout - elapsed)).await;
+            interval = min(interval * 2, RESOLVE_POLL_MAX_INTERVAL);
+        }
+    }
+
+    /// Get an object's state at the given height.
+    pub(crate) async fn get_object(
+        &self,
+        provider: &impl QueryProvider,
+        key: &Key,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<Option<Object>> {
+        let params = GetParams {
+            key: key.as_str().into(),
+        };
         let params = RawBytes::serialize(params)?;
         let message = local_message(self.address, GetObject as u64, params);
-        let response = provider.call(message, options.height, decode_get).await?;
+        let response = provider.call(message, height, decode_get).await?;
+        Ok(response.value)
+    }
+
+    /// Re-add an existing object with new metadata.
+    #[allow(clippy::too_many_arguments)]
+    async fn set_metadata<C>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        key: &Key,
+        object: Object,
+        metadata: HashMap<String, String>,
+        broadcast_mode: BroadcastMode,
+        gas_params: GasParams,
+    ) -> anyhow::Result<TxReceipt<Cid>>
+    where
+        C: Client + Send + Sync,
+    {
+        let params = AddParams {
+            key: key.as_str().into(),
+            cid: object.cid.0,
+            overwrite: true,
+            metadata,
+            size: object.size,
+        };
+        let serialized_params = RawBytes::serialize(&params)?;
+        let message_object = Some(MessageObject::new(
+            params.key.clone(),
+            object.cid.0,
+            self.address,
+        ));
+        let message = signer
+            .transaction(
+                self.address,
+                Default::default(),
+                AddObject as u64,
+                serialized_params,
+                message_object,
+                gas_params,
+            )
+            .await?;
+        provider.perform(message, broadcast_mode, decode_cid).await
+    }
+
+    /// Get object content by CID, without knowing its key or object store,
+    /// e.g., with a CID from an accumulator leaf or a transaction receipt.
+    ///
+    /// The Object API only serves content by key, so the content is requested from the
+    /// IPFS gateways in `options`, in order. There are none by default, so at least one must be set.
+    /// The content is checked against the CID as it's written, so gateways can't serve the wrong
+    /// bytes undetected. On a mismatch, `writer` has already received them and must be discarded.
+    pub async fn get_by_cid<W>(
+        cid: Cid,
+        writer: W,
+        options: GetByCidOptions,
+    ) -> anyhow::Result<()>
+    where
+        W: AsyncWrite + Unpin + Send + 'static,
+    {
+        let cancel = options.cancel.clone();
+        cancel.run(download_cid(cid, writer, options)).await
+    }
+
+    /// Get an object at the given key, range, and height.
+    ///
+    /// If a [`GetOptions::cache`] is set, the object's CID is looked up on chain and its data is
+    /// served from the cache when present, and downloaded into the cache otherwise.
+    /// If [`GetOptions::verify_checksum`] is set, the checksum is only checked once all data
+    /// is written, so on a mismatch, `writer` has already received it and must be discarded.
+    pub async fn get<W>(
+        &self,
+        provider: &(impl QueryProvider + ObjectProvider),
+        key: &str,
+        writer: W,
+        options: GetOptions,
+    ) -> anyhow::Result<()>
+    where
+        W: AsyncWrite + Unpin + Send + 'static,
+    {
+        let cancel = options.cancel.clone();
+        cancel
+            .run(self.download(provider, key, writer, options))
+            .await
+    }
+
+    /// Downloads an object for [`ObjectStore::get`].
+    async fn download<W>(
+        &self,
+        provider: &(impl QueryProvider + ObjectProvider),
+        key: &str,
+        mut writer: W,
+        options: GetOptions,
+    ) -> anyhow::Result<()>
+    where
+        W: AsyncWrite + Unpin + Send + 'static,
+    {
+        let key = Key::new(key)?;
+        let started = Instant::now();
+        let bars = new_multi_bar(!options.show_progress, options.progress_format);
+        let msg_bar = bars.add(new_message_bar());
 
-        let object = response
-            .value
-            .ok_or_else(|| anyhow!("object not found for key '{}'", key))?;
+        msg_bar.set_prefix("[1/2]");
+        msg_bar.set_message("Getting object info...");
+        let object = self
+            .get_object(provider, &key, options.height)
+            .await?
+            .ok_or_else(|| NotFound::new(format!("object not found for key '{}'", key)))?;
+        if object.metadata.contains_key(DELETED_METADATA_KEY) {
+            return Err(anyhow!(
+                "object for key '{}' is deleted; restore it to get it",
+                key
+            ));
+        }
 
-        let cid = cid::Cid::try_from(object.cid.0)?;
+        let cid = object.cid.0;
         if !object.resolved {
-            return Err(anyhow!("object is not resolved"));
+            return Err(anyhow!(
+                "object for key '{}' is not resolved yet; the network is still fetching it (cid={})",
+                key,
+                cid
+            ));
         }
+        let mut checksum = if options.verify_checksum {
+            if options.range.is_some() {
+                return Err(anyhow!("cannot verify the checksum of a range"));
+            }
+            Some(metadata_hasher(&object.metadata, key.as_str())?)
+        } else {
+            None
+        };
         msg_bar.set_prefix("[2/2]");
+        // Objects are immutable per CID, so a cached copy is as good as a download
+        let cache = options.cache.as_ref().filter(|_| options.range.is_none());
+        if let Some(cache) = cache {
+            if let Some(mut file) = cache.get(&cid).await? {
+                msg_bar.set_message(format!("Reading {} from cache...", cid));
+                let mut buffer = vec![0; CHUNK_SIZE];
+                loop {
+                    let n = file.read(&mut buffer).await?;
+                    if n == 0 {
+                        break;
+                    }
+                    if let Some(checksum) = checksum.as_mut() {
+                        checksum.update(&buffer[..n]);
+                    }
+                    writer.write_all(&buffer[..n]).await?;
+                }
+                writer.flush().await?;
+                if let Some(checksum) = checksum {
+                    verify_checksum(&object.metadata, checksum)?;
+                }
+                msg_bar.println(format!(
+                    "{} Read cached object in {} (cid={})",
+                    SPARKLE,
+                    HumanDuration(started.elapsed()),
+                    cid
+                ));
+                msg_bar.finish_and_clear();
+                return Ok(());
+            }
+        }
         msg_bar.set_message(format!("Downloading {}... ", cid));
 
         let object_size = provider
-            .size(self.address, key, options.height.into())
+            .size(
+                self.address,
+                key.as_str(),
+                options.height.into(),
+                options.auth_token.clone(),
+            )
             .await?;
         let pro_bar = bars.add(new_progress_bar(object_size));
         let response = provider
-            .download(self.address, key, options.range, options.height.into())
+            .download(
+                self.address,
+                key.as_str(),
+                options.range,
+                options.height.into(),
+                options.auth_token,
+            )
             .await?;
         let mut stream = response.bytes_stream();
-        let mut progress = 0;
-        while let Some(item) = stream.next().await {
-            match item {
-                Ok(chunk) => {
-                    writer.write_all(&chunk).await?;
-                    progress = min(progress + chunk.len(), object_size);
-                    pro_bar.set_position(progress as u64);
+        let mut cached = match cache {
+            Some(cache) => Some(cache.writer(&cid).await?),
+            None => None,
+        };
+        let result = async {
+            let mut progress = 0;
+            while let Some(item) = stream.next().await {
+                let chunk = item.map_err(|e| anyhow!(e))?;
+                writer.write_all(&chunk).await?;
+                if let Some(cached) = cached.as_mut() {
+                    cached.write(&chunk).await?;
                 }
-                Err(e) => {
-                    return Err(anyhow!(e));
+                if let Some(checksum) = checksum.as_mut() {
+                    checksum.update(&chunk);
                 }
+                progress = min(progress + chunk.len(), object_size);
+                pro_bar.set_position(progress as u64);
             }
+            writer.flush().await?;
+            anyhow::Ok(())
+        }
+        .await;
+        match (result, cached) {
+            (Ok(()), Some(cached)) => {
+                cached.commit().await?;
+            }
+            (Err(e), Some(cached)) => {
+                cached.abort().await;
+                return Err(e);
+            }
+            (result, None) => result?,
+        }
+        if let Some(checksum) = checksum {
+            verify_checksum(&object.metadata, checksum)?;
         }
         pro_bar.finish_and_clear();
         msg_bar.println(format!(
@@ -415,6 +1810,282 @@ impl ObjectStore {
         Ok(())
     }
 
+    /// Get the columns and row count of a CSV, TSV, or Parquet object with range requests,
+    /// e.g., for data catalogs, without downloading the whole object.
+    ///
+    /// CSV and TSV headers are read from the start of the object, and their row counts are
+    /// estimated from the sample unless it covers the whole object.
+    /// Parquet schemas and row counts are read from the file metadata in the footer.
+    pub async fn peek_schema(
+        &self,
+        provider: &impl ObjectProvider,
+        key: &str,
+        options: PeekSchemaOptions,
+    ) -> anyhow::Result<ObjectSchema> {
+        let key = Key::new(key)?;
+        let format = options
+            .format
+            .or_else(|| SchemaFormat::from_key(key.as_str()))
+            .ok_or_else(|| {
+                anyhow!(
+                    "cannot detect the format of key '{}' from its extension; set it explicitly",
+                    key
+                )
+            })?;
+        let size = provider
+            .size(
+                self.address,
+                key.as_str(),
+                options.height.into(),
+                options.auth_token.clone(),
+            )
+            .await? as u64;
+        if size == 0 {
+            return Err(anyhow!("object for key '{}' is empty", key));
+        }
+        let sample_size = options.sample_size.max(PARQUET_FOOTER_LEN) as u64;
+        match format {
+            SchemaFormat::Csv | SchemaFormat::Tsv => {
+                let sample = self
+                    .read_range(provider, &key, 0, min(sample_size, size), &options)
+                    .await?;
+                csv_schema(format, &sample, size)
+            }
+            SchemaFormat::Parquet => {
+                let tail = self
+                    .read_range(
+                        provider,
+                        &key,
+                        size.saturating_sub(sample_size),
+                        size,
+                        &options,
+                    )
+                    .await?;
+                let len = parquet_metadata_len(&tail, size)?;
+                let footer_start = tail.len() - PARQUET_FOOTER_LEN;
+                let metadata = if len <= footer_start {
+                    tail.slice(footer_start - len..footer_start)
+                } else {
+                    let end = size - PARQUET_FOOTER_LEN as u64;
+                    self.read_range(provider, &key, end - len as u64, end, &options)
+                        .await?
+                };
+                parquet_schema(&metadata, size)
+            }
+        }
+    }
+
+    /// Reads bytes `start..end` of an object with a range request.
+    async fn read_range(
+        &self,
+        provider: &impl ObjectProvider,
+        key: &Key,
+        start: u64,
+        end: u64,
+        options: &PeekSchemaOptions,
+    ) -> anyhow::Result<Bytes> {
+        let response = provider
+            .download(
+                self.address,
+                key.as_str(),
+                Some(format!("{}-{}", start, end - 1)),
+                options.height.into(),
+                options.auth_token.clone(),
+            )
+            .await?;
+        let data = response.bytes().await?;
+        if data.len() as u64 != end - start {
+            return Err(anyhow!(
+                "expected {} bytes of object for key '{}', got {}",
+                end - start,
+                key,
+                data.len()
+            ));
+        }
+        Ok(data)
+    }
+
+    /// Get an object at the given key and write it to a file at `path`.
+    ///
+    /// Missing parent directories are created.
+    /// The object is first downloaded to a temporary file next to `path`, which is renamed
+    /// once the download completes, so a failed download never leaves a partial file at `path`.
+    /// Fails if `path` already exists unless `overwrite` is set.
+    pub async fn get_to_file(
+        &self,
+        provider: &(impl QueryProvider + ObjectProvider),
+        key: &str,
+        path: impl AsRef<Path>,
+        overwrite: bool,
+        options: GetOptions,
+    ) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        if !overwrite && fs::try_exists(path).await? {
+            return Err(anyhow!(
+                "{} already exists; use overwrite to replace it",
+                path.display()
+            ));
+        }
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| anyhow!("invalid output path {}", path.display()))?;
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).await?;
+        }
+        let mut part_name = OsString::from(".");
+        part_name.push(file_name);
+        part_name.push(".part");
+        let part_path = path.with_file_name(part_name);
+
+        let file = fs::File::create(&part_path).await?;
+        if let Err(e) = self.get(provider, key, file, options).await {
+            let _ = fs::remove_file(&part_path).await;
+            return Err(e);
+        }
+        fs::rename(&part_path, path).await?;
+        Ok(())
+    }
+
+    /// Download all objects with the given key prefix into `dest_dir`, preserving the key hierarchy.
+    ///
+    /// Up to `concurrency` objects are downloaded in parallel.
+    /// Completed downloads are recorded in a [`PREFIX_DOWNLOAD_MANIFEST`] file in `dest_dir`,
+    /// so an interrupted download can be resumed by calling this again:
+    /// objects that were already downloaded and haven't changed since are skipped.
+    /// Objects that are soft-deleted or not yet resolved are skipped too.
+    ///
+    /// Fails before downloading anything if a local file that isn't recorded in the manifest
+    /// would be replaced, unless [`GetPrefixOptions::overwrite`] is set,
+    /// or if an object key maps to the manifest file itself.
+    pub async fn get_prefix(
+        &self,
+        provider: &(impl QueryProvider + ObjectProvider),
+        prefix: &str,
+        dest_dir: impl AsRef<Path>,
+        concurrency: usize,
+        options: GetPrefixOptions,
+    ) -> anyhow::Result<PrefixDownload> {
+        let dest_dir = dest_dir.as_ref();
+        let started = Instant::now();
+        let bars = new_multi_bar(!options.show_progress, options.progress_format);
+        let msg_bar = bars.add(new_message_bar());
+
+        msg_bar.set_prefix("[1/2]");
+        msg_bar.set_message(format!("Listing objects with prefix '{}'...", prefix));
+        let (height, entries) = options
+            .cancel
+            .run(self.list_entries(provider, prefix, options.height))
+            .await?;
+
+        fs::create_dir_all(dest_dir).await?;
+        let manifest_path = dest_dir.join(PREFIX_DOWNLOAD_MANIFEST);
+        let mut completed: BTreeMap<String, Cid> = match fs::read(&manifest_path).await {
+            Ok(bytes) => serde_json::from_slice(&bytes)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut result = PrefixDownload {
+            height,
+            ..Default::default()
+        };
+        let mut pending = Vec::new();
+        for (key, entry) in entries {
+            let path = Key::new(&key)?.to_path(dest_dir)?;
+            if path == manifest_path {
+                return Err(anyhow!(
+                    "object key '{}' collides with the download manifest {}",
+                    key,
+                    PREFIX_DOWNLOAD_MANIFEST
+                ));
+            }
+            let unavailable = entry.metadata.contains_key(DELETED_METADATA_KEY) || !entry.resolved;
+            let exists = fs::try_exists(&path).await?;
+            let recorded = completed.contains_key(&key);
+            let done = completed.get(&key) == Some(&entry.cid) && exists;
+            if unavailable || done {
+                result.skipped.push(key);
+                continue;
+            }
+            if exists && !recorded && !options.overwrite {
+                return Err(anyhow!(
+                    "{} already exists and wasn't downloaded by an earlier run; use overwrite to replace it",
+                    path.display()
+                ));
+            }
+            // Only files this function created, or that the caller allowed, are replaced
+            let overwrite = recorded || options.overwrite;
+            pending.push((key, entry, path, overwrite));
+        }
+
+        msg_bar.set_prefix("[2/2]");
+        msg_bar.set_message(format!("Downloading {} objects...", pending.len()));
+        let total_size = pending.iter().map(|(_, entry, _, _)| entry.size).sum();
+        let pro_bar = bars.add(new_progress_bar(total_size));
+        let auth_token = &options.auth_token;
+        let cancel = &options.cancel;
+        let cache = &options.cache;
+        let mut downloads = futures_util::stream::iter(pending)
+            .map(|(key, entry, path, overwrite)| async move {
+                let get_options = GetOptions {
+                    range: None,
+                    height: FvmQueryHeight::Height(height),
+                    show_progress: false,
+                    progress_format: Default::default(),
+                    auth_token: auth_token.clone(),
+                    cancel: cancel.clone(),
+                    cache: cache.clone(),
+                    verify_checksum: false,
+                };
+                self.get_to_file(provider, &key, &path, overwrite, get_options)
+                    .await
+                    .map(|_| (key, entry))
+            })
+            .buffer_unordered(concurrency.max(1));
+        while let Some(download) = downloads.next().await {
+            let (key, entry) = download?;
+            pro_bar.inc(entry.size as u64);
+            completed.insert(key.clone(), entry.cid);
+            fs::write(&manifest_path, serde_json::to_vec_pretty(&completed)?).await?;
+            result.downloaded.push(key);
+        }
+        pro_bar.finish_and_clear();
+        msg_bar.println(format!(
+            "{} Downloaded {} objects in {} ({} skipped)",
+            SPARKLE,
+            result.downloaded.len(),
+            HumanDuration(started.elapsed()),
+            result.skipped.len()
+        ));
+
+        msg_bar.finish_and_clear();
+        Ok(result)
+    }
+
+    /// Verify the [`Attestation`] in an object's metadata against its key, CID, and size
+    /// at the given height.
+    ///
+    /// Returns the attestation, so callers can check who signed it and when.
+    pub async fn verify_attestation(
+        &self,
+        provider: &impl QueryProvider,
+        key: &str,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<Attestation> {
+        let key = Key::new(key)?;
+        let object = self
+            .get_object(provider, &key, height)
+            .await?
+            .ok_or_else(|| NotFound::new(format!("object not found for key '{}'", key)))?;
+        let attestation = object
+            .metadata
+            .get(ATTESTATION_METADATA_KEY)
+            .ok_or_else(|| anyhow!("object for key '{}' has no attestation", key))?;
+        let attestation = Attestation::decode(attestation)?;
+        attestation.verify(key.as_str(), object.cid, object.size)?;
+        Ok(attestation)
+    }
+
     /// Query for objects with params at the given height.
     ///
     /// Use [`QueryOptions`] for filtering and pagination.
@@ -423,63 +2094,318 @@ impl ObjectStore {
         provider: &impl QueryProvider,
         options: QueryOptions,
     ) -> anyhow::Result<ObjectList> {
+        Ok(self.query_page(provider, options).await?.list)
+    }
+
+    /// Query for a page of objects, along with a [`Cursor`] for the next page.
+    ///
+    /// A next cursor is only returned when `limit` is set and the page is full.
+    /// The actor lists by offset, so a cursor pins the listing to the height of the first
+    /// page and checks that the previous page's last key still precedes the next page.
+    pub async fn query_page(
+        &self,
+        provider: &impl QueryProvider,
+        options: QueryOptions,
+    ) -> anyhow::Result<ObjectPage> {
+        Key::validate_prefix(&options.prefix)?;
+        let (offset, height) = match &options.cursor {
+            // Re-list the last key of the previous page to check the cursor
+            Some(cursor) => (cursor.offset - 1, FvmQueryHeight::Height(cursor.height)),
+            None => (options.offset, options.height),
+        };
+        let limit = match (&options.cursor, options.limit) {
+            (Some(_), limit) if limit > 0 => limit + 1,
+            (_, limit) => limit,
+        };
         let params = fendermint_actor_objectstore::ListParams {
             prefix: options.prefix.into(),
             delimiter: options.delimiter.into(),
-            offset: options.offset,
-            limit: options.limit,
+            offset,
+            limit,
         };
         let params = RawBytes::serialize(params)?;
         let message = local_message(self.address, ListObjects as u64, params);
-        let response = provider.call(message, options.height, decode_list).await?;
-        Ok(response.value)
+        let response = options
+            .cancel
+            .run(provider.call(message, height, decode_list))
+            .await?;
+        let mut list = response.value;
+
+        let mut offset = offset;
+        if let Some(cursor) = &options.cursor {
+            match list.objects.first() {
+                Some((key, _)) if *key == cursor.last_key => {
+                    list.objects.remove(0);
+                    offset += 1;
+                }
+                _ => {
+                    return Err(anyhow!(
+                        "cursor does not match the listing; restart the query without a cursor"
+                    ))
+                }
+            }
+        }
+
+        let count = list.objects.len() as u64;
+        let next = match list.objects.last() {
+            Some((key, _)) if options.limit > 0 && count == options.limit => Some(Cursor {
+                height: response.height.value(),
+                offset: offset + count,
+                last_key: key.clone(),
+            }),
+            _ => None,
+        };
+        Ok(ObjectPage { list, next })
     }
-}
 
-async fn generate_cid<R: AsyncRead + Unpin>(
-    reader: &mut R,
-    mut buffer: Vec<u8>,
-    reader_size: &mut usize,
-    mut adder: FileAdder,
-    mut chunk: Cid,
-    msg_bar: &indicatif::ProgressBar,
-    object_size: &mut usize,
-) -> Result<Cid, anyhow::Error> {
-    loop {
-        match reader.read(&mut buffer).await {
-            Ok(0) => {
+    /// Generate an unsigned [`Manifest`] of all objects in the store at the given height,
+    /// with the store address formatted for `network`.
+    ///
+    /// Use [`Manifest::sign`] to sign it before distribution.
+    pub async fn manifest(
+        &self,
+        provider: &impl QueryProvider,
+        height: FvmQueryHeight,
+        network: Network,
+    ) -> anyhow::Result<Manifest> {
+        let (height, objects) = self.list_entries(provider, "", height).await?;
+        Ok(Manifest {
+            address: format_address(&self.address, network),
+            height,
+            objects,
+            signature: None,
+        })
+    }
+
+    /// Get usage statistics of the object store at a given height.
+    ///
+    /// Statistics are computed from a listing of all objects, so this takes one query per
+    /// page of objects, plus a few historical state queries to find the last-modified height.
+    pub async fn stats(
+        &self,
+        provider: &impl QueryProvider,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<ObjectStoreStats> {
+        let (height, objects) = self.list_entries(provider, "", height).await?;
+        let last_modified = last_modified(provider, self.address, height).await?;
+        Ok(ObjectStoreStats {
+            height,
+            objects: objects.len() as u64,
+            unresolved: objects.values().filter(|o| !o.resolved).count() as u64,
+            bytes: objects.values().map(|o| o.size as u64).sum(),
+            last_modified,
+        })
+    }
+
+    /// Lists all objects with the given key prefix, paging through the store as needed.
+    ///
+    /// All pages are queried at the height of the first page, which is returned with the entries.
+    async fn list_entries(
+        &self,
+        provider: &impl QueryProvider,
+        prefix: &str,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<(u64, BTreeMap<String, ManifestEntry>)> {
+        Key::validate_prefix(prefix)?;
+        let mut objects = BTreeMap::new();
+        let mut height = height;
+        let mut pinned = None;
+        let mut offset = 0;
+        loop {
+            let params = fendermint_actor_objectstore::ListParams {
+                prefix: prefix.into(),
+                delimiter: Default::default(),
+                offset,
+                limit: MANIFEST_PAGE_SIZE,
+            };
+            let params = RawBytes::serialize(params)?;
+            let message = local_message(self.address, ListObjects as u64, params);
+            let response = provider.call(message, height, decode_list).await?;
+
+            // Pin the remaining pages to the height of the first page
+            if pinned.is_none() {
+                pinned = Some(response.height.value());
+                height = FvmQueryHeight::Height(response.height.value());
+            }
+
+            let count = response.value.objects.len() as u64;
+            for (key, object) in response.value.objects {
+                let key = String::from_utf8(key)
+                    .map_err(|e| anyhow!("object key is not valid UTF-8: {e}"))?;
+                objects.insert(
+                    key,
+                    ManifestEntry {
+                        cid: object.cid,
+                        size: object.size,
+                        resolved: object.resolved,
+                        metadata: object.metadata.into_iter().collect(),
+                    },
+                );
+            }
+            if count < MANIFEST_PAGE_SIZE {
                 break;
             }
-            Ok(n) => {
-                *reader_size += n;
-                let (leaf, n) = adder.push(&buffer[..n]);
-                for (c, _) in leaf {
-                    chunk = Cid::from(cid::Cid::try_from(c.to_bytes())?);
-                    msg_bar.set_message(format!("Processed chunk: {}", c));
+            offset += count;
+        }
+        Ok((pinned.unwrap_or_default(), objects))
+    }
+}
+
+/// Returns whether a failed upload may succeed if it's retried.
+fn is_transient(err: &anyhow::Error) -> bool {
+    match err.downcast_ref::<UploadError>() {
+        Some(err) => err.is_retryable(),
+        // Transport errors, e.g., a dropped connection or a timeout
+        None => err.downcast_ref::<reqwest::Error>().is_some(),
+    }
+}
+
+/// Returns the balance of an account in the subnet, or zero if the account doesn't exist yet.
+async fn subnet_balance(
+    provider: &impl QueryProvider,
+    address: Address,
+) -> anyhow::Result<TokenAmount> {
+    let response = provider
+        .actor_state(&address, FvmQueryHeight::Committed)
+        .await?;
+    Ok(response
+        .value
+        .map(|(_, state)| state.balance)
+        .unwrap_or_default())
+}
+
+/// Downloads content by CID from the first IPFS gateway that has it.
+async fn download_cid<W>(cid: Cid, writer: W, options: GetByCidOptions) -> anyhow::Result<()>
+where
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    if options.gateways.is_empty() {
+        return Err(anyhow!(
+            "no IPFS gateways are set to get content for {} from",
+            cid
+        ));
+    }
+    let client = reqwest::Client::new();
+    let mut errors = Vec::new();
+    for gateway in &options.gateways {
+        let url = format!("{}/ipfs/{}", gateway.trim_end_matches('/'), cid);
+        let result = client
+            .get(&url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status());
+        match result {
+            Ok(response) => return write_cid_content(response, cid, writer).await,
+            Err(e) => errors.push(format!("{}: {}", gateway, e)),
+        }
+    }
+    Err(anyhow!(
+        "content for {} not found in IPFS gateways [{}]",
+        cid,
+        errors.join("; ")
+    ))
+}
+
+/// Writes the body of `response` to `writer`, failing once it's written if it doesn't match `cid`.
+pub(crate) async fn write_cid_content<W>(
+    response: reqwest::Response,
+    cid: Cid,
+    mut writer: W,
+) -> anyhow::Result<()>
+where
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    let mut hasher = ObjectHasher::new();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        hasher.update(&chunk)?;
+        writer.write_all(&chunk).await?;
+    }
+    writer.flush().await?;
+    let actual: Cid = hasher.finish()?.into();
+    if actual != cid {
+        return Err(anyhow!(
+            "downloaded content does not match {}; got {}",
+            cid,
+            actual
+        ));
+    }
+    Ok(())
+}
+
+/// Rewinds the reader and sends all of it to `tx` in chunks of [`UPLOAD_CHUNK_SIZE`] bytes.
+///
+/// Sending waits while the channel is full, which bounds the memory held by an upload.
+/// Read errors are sent on to fail the upload. Reading stops early if the receiver is dropped,
+/// e.g., because the upload failed.
+async fn send_chunks<R>(reader: &mut R, tx: mpsc::Sender<std::io::Result<Bytes>>)
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    if let Err(e) = reader.rewind().await {
+        let _ = tx.send(Err(e)).await;
+        return;
+    }
+    loop {
+        let mut chunk = BytesMut::with_capacity(UPLOAD_CHUNK_SIZE);
+        // Fill the chunk, so partial reads don't multiply the number of buffered chunks
+        while chunk.len() < UPLOAD_CHUNK_SIZE {
+            match reader.read_buf(&mut chunk).await {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(e) => {
+                    let _ = tx.send(Err(e)).await;
+                    return;
                 }
-                *object_size += n;
-            }
-            Err(e) => {
-                return Err(e.into());
             }
         }
+        if chunk.is_empty() {
+            return;
+        }
+        let done = chunk.len() < UPLOAD_CHUNK_SIZE;
+        if tx.send(Ok(chunk.freeze())).await.is_err() || done {
+            return;
+        }
+    }
+}
+
+/// Computes the CID and size of an object, and its `checksum` if any, by reading all of it.
+async fn generate_cid<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    msg_bar: &ProgressBar,
+    checksum: Option<Checksum>,
+) -> anyhow::Result<(Cid, usize, Option<(Checksum, String)>)> {
+    let mut hasher = ObjectHasher::new();
+    let mut checksum_hasher = checksum.map(ChecksumHasher::new);
+    let mut buffer = vec![0; CHUNK_SIZE];
+    loop {
+        let n = reader.read(&mut buffer).await?;
+        if n == 0 {
+            break;
+        }
+        if let Some(checksum_hasher) = checksum_hasher.as_mut() {
+            checksum_hasher.update(&buffer[..n]);
+        }
+        if let Some(chunk) = hasher.update(&buffer[..n])? {
+            msg_bar.set_message(format!("Processed chunk: {}", chunk));
+        }
     }
-    let unixfs_iterator = adder.finish();
-    let last = unixfs_iterator.last();
-    let object_cid = match last {
-        Some((c, _)) => Cid::from(cid::Cid::try_from(c.to_bytes())?),
-        None => chunk,
-    };
-    Ok(object_cid)
+    let size = hasher.size();
+    let checksum = checksum_hasher.map(|h| (h.checksum(), h.finish()));
+    Ok((hasher.finish()?.into(), size, checksum))
 }
 
 fn decode_get(deliver_tx: &DeliverTx) -> anyhow::Result<Option<Object>> {
     let data = decode_bytes(deliver_tx)?;
-    fvm_ipld_encoding::from_slice(&data)
-        .map_err(|e| anyhow!("error parsing as Option<Object>: {e}"))
+    let object: Option<fendermint_actor_objectstore::Object> = fvm_ipld_encoding::from_slice(&data)
+        .map_err(|e| anyhow!("error parsing as Option<Object>: {e}"))?;
+    object.map(Object::try_from).transpose()
 }
 
 fn decode_list(deliver_tx: &DeliverTx) -> anyhow::Result<ObjectList> {
     let data = decode_bytes(deliver_tx)?;
-    fvm_ipld_encoding::from_slice(&data).map_err(|e| anyhow!("error parsing as ObjectList: {e}"))
+    let list: fendermint_actor_objectstore::ObjectList = fvm_ipld_encoding::from_slice(&data)
+        .map_err(|e| anyhow!("error parsing as ObjectList: {e}"))?;
+    list.try_into()
 }
@@ -0,0 +1,108 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::anyhow;
+use base64::{engine::general_purpose, Engine};
+use fvm_ipld_encoding::RawBytes;
+use serde::{Deserialize, Serialize};
+
+use adm_provider::{message::object_upload_message, response::Cid, util::parse_address};
+use adm_signer::Signer;
+use adm_verify::signature::verify_params;
+
+/// Metadata key that holds an object's JSON-encoded [`Attestation`].
+pub const ATTESTATION_METADATA_KEY: &str = "adm:attestation";
+
+/// An uploader's signature over an object's key, CID, size, and upload time.
+///
+/// Attestations aren't bound to an object store,
+/// so they stay valid when objects are copied to another store.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attestation {
+    /// The signer address.
+    pub signer: String,
+    /// The chain ID the signature is bound to.
+    pub chain_id: u64,
+    /// The Unix timestamp (in seconds) of when the object was attested.
+    pub timestamp: u64,
+    /// The base64-encoded secp256k1 signature.
+    pub signature: String,
+}
+
+impl Attestation {
+    /// Attest to an object with the given [`Signer`] at the current time.
+    pub fn new(signer: &impl Signer, key: &str, cid: Cid, size: usize) -> anyhow::Result<Self> {
+        let chain_id = match signer.subnet_id() {
+            Some(id) => id.chain_id(),
+            None => {
+                return Err(anyhow!("failed to get subnet ID from signer"));
+            }
+        };
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let from = signer.address();
+        let message = object_upload_message(from, from, 0, params(key, cid, size, timestamp)?);
+        let signed = signer.sign_message(message, None)?;
+        Ok(Self {
+            signer: from.to_string(),
+            chain_id: chain_id.into(),
+            timestamp,
+            signature: general_purpose::STANDARD.encode(signed.signature.bytes()),
+        })
+    }
+
+    /// Verify the attestation signature against the object's key, CID, and size.
+    pub fn verify(&self, key: &str, cid: Cid, size: usize) -> anyhow::Result<()> {
+        let from = parse_address(&self.signer)?;
+        verify_params(
+            from,
+            from,
+            params(key, cid, size, self.timestamp)?,
+            &general_purpose::STANDARD.decode(&self.signature)?,
+            self.chain_id,
+        )
+        .map_err(|e| anyhow!("invalid attestation signature: {e}"))
+    }
+
+    /// Decode an attestation from an object metadata value.
+    pub fn decode(value: &str) -> anyhow::Result<Self> {
+        serde_json::from_str(value).map_err(|e| anyhow!("invalid attestation: {e}"))
+    }
+
+    /// Encode the attestation as an object metadata value.
+    pub fn encode(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+/// Returns the encoded object fields, which are signed as the params of a read-only message
+/// from the signer to itself.
+fn params(key: &str, cid: Cid, size: usize, timestamp: u64) -> anyhow::Result<RawBytes> {
+    Ok(RawBytes::serialize((key, cid.0, size, timestamp))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use adm_signer::{key::random_secretkey, AccountKind, SubnetID, Wallet};
+
+    use super::*;
+
+    #[test]
+    fn test_attestation() {
+        let subnet_id =
+            SubnetID::from_str("/r314159/t410f726d2jv6uj4mpkcbgg5ndlpp3l7dd5rlcpgzkoi").unwrap();
+        let signer =
+            Wallet::new_secp256k1(random_secretkey(), AccountKind::Ethereum, subnet_id).unwrap();
+        let cid = Cid::from(cid::Cid::default());
+
+        let attestation = Attestation::new(&signer, "foo/bar", cid, 42).unwrap();
+        let decoded = Attestation::decode(&attestation.encode().unwrap()).unwrap();
+        assert_eq!(decoded, attestation);
+        assert!(decoded.verify("foo/bar", cid, 42).is_ok());
+        assert!(decoded.verify("foo/baz", cid, 42).is_err());
+        assert!(decoded.verify("foo/bar", cid, 43).is_err());
+    }
+}
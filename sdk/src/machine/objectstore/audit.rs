@@ -0,0 +1,144 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::fmt::{self, Display};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::anyhow;
+use bytes::Bytes;
+use fvm_shared::address::Address;
+use serde::{Deserialize, Serialize};
+
+use adm_provider::{response::Cid, tx::TxReceipt};
+
+/// Prefix that marks an accumulator leaf as an [`AuditRecord`].
+const AUDIT_RECORD_PREFIX: &[u8] = b"adm:audit:v1\0";
+
+/// A change to an object store.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    /// An object was added or overwritten.
+    Add,
+    /// An object was deleted.
+    Delete,
+    /// A soft-deleted object was restored.
+    Restore,
+}
+
+/// A record of an object store change, pushed into the store's audit log.
+///
+/// Records are only pushed after the change's transaction is committed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// The change.
+    pub action: AuditAction,
+    /// The object store address.
+    pub store: String,
+    /// The object key.
+    pub key: String,
+    /// The object CID, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cid: Option<Cid>,
+    /// The object size in bytes, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<usize>,
+    /// Whether the object was soft-deleted.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub soft: bool,
+    /// The hash of the change's transaction.
+    pub tx_hash: String,
+    /// Unix timestamp (in seconds) of the change according to the signer's clock.
+    pub timestamp: u64,
+}
+
+impl AuditRecord {
+    /// Create a record of a change made by `tx`.
+    pub(crate) fn new(
+        action: AuditAction,
+        store: Address,
+        key: &str,
+        tx: &TxReceipt<Cid>,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            action,
+            store: store.to_string(),
+            key: key.into(),
+            cid: tx.data,
+            size: None,
+            soft: false,
+            tx_hash: tx.hash.to_string(),
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        })
+    }
+
+    /// Encode the record as an accumulator payload.
+    pub fn encode(&self) -> anyhow::Result<Bytes> {
+        let mut data = AUDIT_RECORD_PREFIX.to_vec();
+        data.extend(serde_json::to_vec(self)?);
+        Ok(Bytes::from(data))
+    }
+
+    /// Decode a record from an accumulator payload.
+    ///
+    /// Returns `None` if the payload isn't an audit record,
+    /// e.g., if other data was pushed into the same accumulator.
+    pub fn decode(payload: &[u8]) -> anyhow::Result<Option<Self>> {
+        match payload.strip_prefix(AUDIT_RECORD_PREFIX) {
+            Some(record) => serde_json::from_slice(record)
+                .map(Some)
+                .map_err(|e| anyhow!("error parsing audit record: {e}")),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Returned when an object store change was committed, but its [`AuditRecord`] wasn't pushed.
+///
+/// Use [`anyhow::Error::downcast_ref`] to get the change's receipt from a failed operation.
+#[derive(Debug)]
+pub struct AuditError {
+    /// The receipt of the committed change.
+    pub tx: TxReceipt<Cid>,
+    /// Why the record wasn't pushed.
+    pub source: anyhow::Error,
+}
+
+impl Display for AuditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "object store changed in tx {}, but its audit record wasn't pushed",
+            self.tx.hash
+        )
+    }
+}
+
+impl std::error::Error for AuditError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_record_encoding() {
+        let record = AuditRecord {
+            action: AuditAction::Delete,
+            store: Address::new_id(100).to_string(),
+            key: "my/object".into(),
+            cid: Some(Cid::from(cid::Cid::default())),
+            size: None,
+            soft: true,
+            tx_hash: "ABCD".into(),
+            timestamp: 1700000000,
+        };
+        let encoded = record.encode().unwrap();
+        assert_eq!(AuditRecord::decode(&encoded).unwrap(), Some(record));
+        assert_eq!(AuditRecord::decode(b"other data").unwrap(), None);
+        assert!(AuditRecord::decode(AUDIT_RECORD_PREFIX).is_err());
+    }
+}
@@ -0,0 +1,168 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+use adm_verify::object::{ObjectHasher, CHUNK_SIZE};
+
+/// A local content-addressed cache of object data, e.g., to speed up workflows that
+/// repeatedly read the same objects.
+///
+/// Objects are stored in a directory as files named by their CID.
+/// Since objects are immutable per CID, a cached object is served as long as the CID of
+/// the key on chain still matches, and its data is checked against the CID before it's served.
+/// The cache is never evicted automatically; remove files or call [`ObjectCache::clear`]
+/// to reclaim space.
+#[derive(Clone, Debug)]
+pub struct ObjectCache {
+    dir: PathBuf,
+}
+
+impl ObjectCache {
+    /// Create a cache in `dir`, which is created on first write.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Returns the cache directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Remove all cached objects.
+    pub async fn clear(&self) -> anyhow::Result<()> {
+        match fs::remove_dir_all(&self.dir).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn path(&self, cid: &cid::Cid) -> PathBuf {
+        self.dir.join(cid.to_string())
+    }
+
+    /// Open the cached data of `cid`, if any.
+    ///
+    /// Data that doesn't match its CID, e.g., because the file was corrupted, is removed.
+    pub(crate) async fn get(&self, cid: &cid::Cid) -> anyhow::Result<Option<fs::File>> {
+        let path = self.path(cid);
+        let mut file = match fs::File::open(&path).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let mut hasher = ObjectHasher::new();
+        let mut buffer = vec![0; CHUNK_SIZE];
+        loop {
+            let n = file.read(&mut buffer).await?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buffer[..n])?;
+        }
+        if hasher.finish()? != *cid {
+            drop(file);
+            fs::remove_file(&path).await?;
+            return Ok(None);
+        }
+        file.rewind().await?;
+        Ok(Some(file))
+    }
+
+    /// Start writing the data of `cid` into the cache.
+    pub(crate) async fn writer(&self, cid: &cid::Cid) -> anyhow::Result<CacheWriter> {
+        fs::create_dir_all(&self.dir).await?;
+        let path = self.path(cid);
+        let part_path = self
+            .dir
+            .join(format!(".{}.{:x}.part", cid, rand::random::<u64>()));
+        Ok(CacheWriter {
+            file: fs::File::create(&part_path).await?,
+            hasher: ObjectHasher::new(),
+            cid: *cid,
+            path,
+            part_path,
+        })
+    }
+}
+
+/// Writes downloaded data into an [`ObjectCache`].
+///
+/// Data is written to a temporary file, which is only moved into the cache
+/// if the data matches its CID.
+pub(crate) struct CacheWriter {
+    file: fs::File,
+    hasher: ObjectHasher,
+    cid: cid::Cid,
+    path: PathBuf,
+    part_path: PathBuf,
+}
+
+impl CacheWriter {
+    /// Write the next bytes of the object.
+    pub async fn write(&mut self, data: &[u8]) -> anyhow::Result<()> {
+        self.hasher.update(data)?;
+        self.file.write_all(data).await?;
+        Ok(())
+    }
+
+    /// Finish writing, and add the data to the cache if it matches the CID.
+    ///
+    /// Returns whether the data was cached.
+    pub async fn commit(mut self) -> anyhow::Result<bool> {
+        self.file.flush().await?;
+        drop(self.file);
+        let cached = self.hasher.finish()? == self.cid;
+        if cached {
+            fs::rename(&self.part_path, &self.path).await?;
+        } else {
+            fs::remove_file(&self.part_path).await?;
+        }
+        Ok(cached)
+    }
+
+    /// Discard the written data.
+    pub async fn abort(self) {
+        drop(self.file);
+        let _ = fs::remove_file(&self.part_path).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use adm_verify::object::object_cid;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_object_cache() {
+        let dir = std::env::temp_dir().join(format!("adm-cache-{:x}", rand::random::<u64>()));
+        let cache = ObjectCache::new(&dir);
+        let data = b"hello world";
+        let cid = object_cid(data).unwrap();
+        assert!(cache.get(&cid).await.unwrap().is_none());
+
+        let mut writer = cache.writer(&cid).await.unwrap();
+        writer.write(data).await.unwrap();
+        assert!(writer.commit().await.unwrap());
+        let mut cached = Vec::new();
+        let mut file = cache.get(&cid).await.unwrap().unwrap();
+        file.read_to_end(&mut cached).await.unwrap();
+        assert_eq!(cached, data);
+
+        // Data that doesn't match the CID isn't cached, and corrupted data is removed
+        let mut writer = cache.writer(&cid).await.unwrap();
+        writer.write(b"other").await.unwrap();
+        assert!(!writer.commit().await.unwrap());
+        fs::write(cache.path(&cid), b"corrupted").await.unwrap();
+        assert!(cache.get(&cid).await.unwrap().is_none());
+        assert!(!fs::try_exists(cache.path(&cid)).await.unwrap());
+
+        cache.clear().await.unwrap();
+        assert!(!fs::try_exists(&dir).await.unwrap());
+    }
+}
@@ -0,0 +1,135 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::collections::HashMap;
+
+use anyhow::anyhow;
+use sha2::{Digest, Sha256};
+
+/// Metadata key that holds an object's hex-encoded SHA-256 digest.
+pub const SHA256_METADATA_KEY: &str = "adm:sha256";
+
+/// A plain digest of an object's data, stored in its metadata alongside its CID,
+/// e.g., for tools that don't understand IPLD.
+///
+/// Checksums are set by the uploader and aren't checked by the network,
+/// so they're only as trustworthy as the object's writers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Checksum {
+    /// SHA-256, stored under [`SHA256_METADATA_KEY`].
+    #[default]
+    Sha256,
+}
+
+impl Checksum {
+    /// All supported checksums.
+    pub const ALL: &'static [Checksum] = &[Checksum::Sha256];
+
+    /// Returns the metadata key that holds the checksum.
+    pub fn metadata_key(&self) -> &'static str {
+        match self {
+            Checksum::Sha256 => SHA256_METADATA_KEY,
+        }
+    }
+
+    /// Returns the hex-encoded checksum of `data`.
+    pub fn digest(&self, data: &[u8]) -> String {
+        let mut hasher = ChecksumHasher::new(*self);
+        hasher.update(data);
+        hasher.finish()
+    }
+}
+
+/// Computes a [`Checksum`] over data that's read in chunks.
+#[derive(Clone, Debug)]
+pub(crate) struct ChecksumHasher {
+    checksum: Checksum,
+    sha256: Sha256,
+}
+
+impl ChecksumHasher {
+    pub fn new(checksum: Checksum) -> Self {
+        Self {
+            checksum,
+            sha256: Sha256::new(),
+        }
+    }
+
+    /// Returns the checksum being computed.
+    pub fn checksum(&self) -> Checksum {
+        self.checksum
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        match self.checksum {
+            Checksum::Sha256 => self.sha256.update(data),
+        }
+    }
+
+    /// Returns the hex-encoded checksum.
+    pub fn finish(self) -> String {
+        match self.checksum {
+            Checksum::Sha256 => format!("{:x}", self.sha256.finalize()),
+        }
+    }
+}
+
+/// Returns a hasher for the first checksum in `metadata`,
+/// or an error naming `key` if the object has none.
+pub(crate) fn metadata_hasher(
+    metadata: &HashMap<String, String>,
+    key: &str,
+) -> anyhow::Result<ChecksumHasher> {
+    Checksum::ALL
+        .iter()
+        .find(|c| metadata.contains_key(c.metadata_key()))
+        .map(|c| ChecksumHasher::new(*c))
+        .ok_or_else(|| anyhow!("object for key '{}' has no checksum to verify", key))
+}
+
+/// Checks the checksum computed by `hasher` against the one in `metadata`.
+pub(crate) fn verify_checksum(
+    metadata: &HashMap<String, String>,
+    hasher: ChecksumHasher,
+) -> anyhow::Result<()> {
+    let metadata_key = hasher.checksum().metadata_key();
+    let expected = metadata
+        .get(metadata_key)
+        .ok_or_else(|| anyhow!("object has no '{}' metadata", metadata_key))?;
+    let actual = hasher.finish();
+    if !expected.eq_ignore_ascii_case(&actual) {
+        return Err(anyhow!(
+            "checksum mismatch: '{}' metadata is {}, but data hashes to {}",
+            metadata_key,
+            expected,
+            actual
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum() {
+        let data = b"hello world";
+        let digest = Checksum::Sha256.digest(data);
+        assert_eq!(
+            digest,
+            "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+        );
+
+        let metadata = HashMap::from([(SHA256_METADATA_KEY.to_string(), digest.to_uppercase())]);
+        let mut hasher = metadata_hasher(&metadata, "foo").unwrap();
+        hasher.update(data);
+        assert!(verify_checksum(&metadata, hasher).is_ok());
+
+        let mut hasher = metadata_hasher(&metadata, "foo").unwrap();
+        hasher.update(b"other");
+        assert!(verify_checksum(&metadata, hasher).is_err());
+
+        assert!(metadata_hasher(&HashMap::new(), "foo").is_err());
+    }
+}
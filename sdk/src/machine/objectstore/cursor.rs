@@ -0,0 +1,122 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use base64::{engine::general_purpose, Engine};
+use serde::{Deserialize, Serialize};
+
+/// An opaque continuation cursor for paging through object store query results.
+///
+/// A cursor resumes a listing right after the last object of the previous page.
+/// It pins the listing to the height of the first page, so pages stay consistent while new
+/// blocks land, and carries the last key so a resumed listing can be checked against it.
+///
+/// Cursors serialize as their opaque string form, and are validated when deserialized.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Cursor {
+    /// The block height the listing is pinned to.
+    pub(crate) height: u64,
+    /// The number of objects listed before the next page.
+    pub(crate) offset: u64,
+    /// The key of the last object of the previous page.
+    pub(crate) last_key: Vec<u8>,
+}
+
+/// The CBOR-encoded contents of a [`Cursor`].
+#[derive(Serialize, Deserialize)]
+struct CursorData {
+    height: u64,
+    offset: u64,
+    last_key: Vec<u8>,
+}
+
+impl Cursor {
+    /// Returns the block height the listing is pinned to.
+    pub fn height(&self) -> u64 {
+        self.height
+    }
+}
+
+impl Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let data = CursorData {
+            height: self.height,
+            offset: self.offset,
+            last_key: self.last_key.clone(),
+        };
+        let data = fvm_ipld_encoding::to_vec(&data).map_err(|_| fmt::Error)?;
+        write!(f, "{}", general_purpose::URL_SAFE_NO_PAD.encode(data))
+    }
+}
+
+impl FromStr for Cursor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let data = general_purpose::URL_SAFE_NO_PAD
+            .decode(s)
+            .map_err(|e| anyhow!("invalid cursor: {e}"))?;
+        let data: CursorData =
+            fvm_ipld_encoding::from_slice(&data).map_err(|e| anyhow!("invalid cursor: {e}"))?;
+        if data.offset == 0 {
+            return Err(anyhow!("invalid cursor: offset must be greater than zero"));
+        }
+        Ok(Self {
+            height: data.height,
+            offset: data.offset,
+            last_key: data.last_key,
+        })
+    }
+}
+
+impl TryFrom<String> for Cursor {
+    type Error = anyhow::Error;
+
+    fn try_from(v: String) -> Result<Self, Self::Error> {
+        v.parse()
+    }
+}
+
+impl From<Cursor> for String {
+    fn from(v: Cursor) -> Self {
+        v.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_roundtrip() {
+        let cursor = Cursor {
+            height: 42,
+            offset: 100,
+            last_key: b"my/object".to_vec(),
+        };
+        let encoded = cursor.to_string();
+        assert_eq!(Cursor::from_str(&encoded).unwrap(), cursor);
+        assert!(Cursor::from_str("not a cursor").is_err());
+
+        let json = serde_json::to_string(&cursor).unwrap();
+        assert_eq!(json, format!("\"{}\"", encoded));
+        assert_eq!(serde_json::from_str::<Cursor>(&json).unwrap(), cursor);
+    }
+
+    #[test]
+    fn test_cursor_zero_offset() {
+        let cursor = Cursor {
+            height: 42,
+            offset: 0,
+            last_key: Vec::new(),
+        };
+        let encoded = cursor.to_string();
+        assert!(Cursor::from_str(&encoded).is_err());
+        let json = format!("\"{}\"", encoded);
+        assert!(serde_json::from_str::<Cursor>(&json).is_err());
+    }
+}
@@ -0,0 +1,191 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::fmt::{self, Display};
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Maximum length of an object key in bytes.
+pub const MAX_KEY_LENGTH: usize = 1024;
+
+/// Default delimiter used to define object hierarchy.
+pub const DEFAULT_DELIMITER: char = '/';
+
+/// A validated object key.
+///
+/// Keys must be non-empty UTF-8 strings of at most [`MAX_KEY_LENGTH`] bytes that do not start
+/// with the [`DEFAULT_DELIMITER`], do not start or end with whitespace,
+/// and do not contain control characters.
+/// Keys are never rewritten, so a key always refers to the object stored under it.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Key(String);
+
+impl Key {
+    /// Validate a key.
+    pub fn new(key: &str) -> anyhow::Result<Self> {
+        if key.is_empty() {
+            return Err(anyhow!("invalid key: key cannot be empty"));
+        }
+        if key.trim() != key {
+            return Err(anyhow!(
+                "invalid key: key cannot start or end with whitespace"
+            ));
+        }
+        validate("key", key)?;
+        Ok(Self(key.to_string()))
+    }
+
+    /// Validate a key from raw bytes, which must be valid UTF-8.
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        let key = std::str::from_utf8(bytes)
+            .map_err(|e| anyhow!("invalid key: key is not valid UTF-8: {e}"))?;
+        Self::new(key)
+    }
+
+    /// Validate a key prefix used for queries.
+    ///
+    /// Unlike keys, prefixes may be empty.
+    pub fn validate_prefix(prefix: &str) -> anyhow::Result<()> {
+        if prefix.is_empty() {
+            return Ok(());
+        }
+        validate("prefix", prefix)
+    }
+
+    /// Returns the key as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the key as bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+
+    /// Returns the path under `dir` that preserves the key's hierarchy,
+    /// e.g., `my/object` maps to `<dir>/my/object`.
+    ///
+    /// Fails if a key segment is empty, or is not a plain file name (like `.` or `..`),
+    /// so that keys can't escape `dir`.
+    pub fn to_path(&self, dir: impl AsRef<Path>) -> anyhow::Result<PathBuf> {
+        let mut path = dir.as_ref().to_path_buf();
+        for segment in self.0.split(DEFAULT_DELIMITER) {
+            let mut components = Path::new(segment).components();
+            match (components.next(), components.next()) {
+                (Some(Component::Normal(name)), None) => path.push(name),
+                _ => {
+                    return Err(anyhow!(
+                        "key '{}' cannot be mapped to a file path: invalid segment '{}'",
+                        self.0,
+                        segment
+                    ))
+                }
+            }
+        }
+        Ok(path)
+    }
+}
+
+/// Validate a key or prefix, where `kind` is used to describe the value in errors.
+fn validate(kind: &str, value: &str) -> anyhow::Result<()> {
+    if value.len() > MAX_KEY_LENGTH {
+        return Err(anyhow!(
+            "invalid {kind}: {kind} is {} bytes but the max is {} bytes",
+            value.len(),
+            MAX_KEY_LENGTH
+        ));
+    }
+    if value.starts_with(DEFAULT_DELIMITER) {
+        return Err(anyhow!(
+            "invalid {kind}: {kind} cannot start with the delimiter '{}'",
+            DEFAULT_DELIMITER
+        ));
+    }
+    if let Some((i, c)) = value.char_indices().find(|(_, c)| c.is_control()) {
+        return Err(anyhow!(
+            "invalid {kind}: {kind} contains control character {:?} at byte {}",
+            c,
+            i
+        ));
+    }
+    Ok(())
+}
+
+impl FromStr for Key {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<&str> for Key {
+    type Error = anyhow::Error;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Self::new(s)
+    }
+}
+
+impl AsRef<str> for Key {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Self::new(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Serialize for Key {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_validation() {
+        assert_eq!(Key::new("my object").unwrap().as_str(), "my object");
+        assert!(Key::new(" my/object").is_err());
+        assert!(Key::new("my/object ").is_err());
+        assert!(Key::new("").is_err());
+        assert!(Key::new("   ").is_err());
+        assert!(Key::new("/my/object").is_err());
+        assert!(Key::new("my\nobject").is_err());
+        assert!(Key::new(&"a".repeat(MAX_KEY_LENGTH + 1)).is_err());
+        assert!(Key::new(&"a".repeat(MAX_KEY_LENGTH)).is_ok());
+        assert!(Key::from_bytes(&[0xff, 0xfe]).is_err());
+
+        assert!(Key::validate_prefix("").is_ok());
+        assert!(Key::validate_prefix("my/").is_ok());
+        assert!(Key::validate_prefix("/my").is_err());
+    }
+
+    #[test]
+    fn test_key_to_path() {
+        let key = Key::new("logs/2024/app.log").unwrap();
+        assert_eq!(
+            key.to_path("out").unwrap(),
+            Path::new("out").join("logs").join("2024").join("app.log")
+        );
+        assert!(Key::new("logs/../secret").unwrap().to_path("out").is_err());
+        assert!(Key::new("logs//app.log").unwrap().to_path("out").is_err());
+        assert!(Key::new("logs/").unwrap().to_path("out").is_err());
+    }
+}
@@ -0,0 +1,307 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use fendermint_vm_message::query::FvmQueryHeight;
+use fvm_shared::address::Address;
+use tendermint_rpc::Client;
+use tokio::io::{AsyncRead, AsyncSeek, AsyncWrite};
+
+use adm_provider::{
+    object::ObjectProvider, query::QueryProvider, response::Cid, tx::TxReceipt, Provider,
+};
+use adm_signer::Signer;
+
+use super::{
+    key::DEFAULT_DELIMITER, AddOptions, Attestation, DeleteOptions, GetOptions, Key, Object,
+    ObjectList, ObjectPage, ObjectStore, QueryOptions, RestoreOptions,
+};
+use crate::machine::Machine;
+use crate::tx::TxBuilder;
+
+/// An [`ObjectStore`] view that keeps all keys under a namespace,
+/// e.g., to multiplex many tenants' buckets over one machine.
+///
+/// Keys passed in are relative to the namespace, and keys in query results are returned
+/// relative to it too, so objects outside the namespace are never visible.
+/// The namespace `tenant-a` maps the key `my/object` to `tenant-a/my/object`.
+pub struct NamespacedObjectStore {
+    store: ObjectStore,
+    /// The key prefix, which always ends with the delimiter.
+    prefix: String,
+}
+
+impl NamespacedObjectStore {
+    /// Create a view of the object store at `address` under `namespace`.
+    ///
+    /// The namespace can itself be nested, e.g., `org/team`, and may end with a single delimiter.
+    /// Like keys, namespaces are never rewritten otherwise, so surrounding whitespace is rejected.
+    pub fn new(address: Address, namespace: &str) -> anyhow::Result<Self> {
+        if namespace.trim() != namespace {
+            return Err(anyhow!(
+                "invalid namespace: namespace cannot start or end with whitespace"
+            ));
+        }
+        let namespace = namespace
+            .strip_suffix(DEFAULT_DELIMITER)
+            .unwrap_or(namespace);
+        if namespace.is_empty() {
+            return Err(anyhow!("invalid namespace: namespace cannot be empty"));
+        }
+        if namespace.ends_with(DEFAULT_DELIMITER) {
+            return Err(anyhow!(
+                "invalid namespace: namespace cannot end with more than one delimiter '{}'",
+                DEFAULT_DELIMITER
+            ));
+        }
+        Key::validate_prefix(namespace)?;
+        Ok(Self {
+            store: ObjectStore::attach(address),
+            prefix: format!("{}{}", namespace, DEFAULT_DELIMITER),
+        })
+    }
+
+    /// Returns the namespace.
+    pub fn namespace(&self) -> &str {
+        self.prefix.trim_end_matches(DEFAULT_DELIMITER)
+    }
+
+    /// Returns the underlying object store.
+    pub fn store(&self) -> &ObjectStore {
+        &self.store
+    }
+
+    /// Returns the full key of a key relative to the namespace.
+    pub fn key(&self, key: &str) -> anyhow::Result<Key> {
+        Key::new(&format!("{}{}", self.prefix, Key::new(key)?))
+    }
+
+    /// Add an object under the namespace.
+    ///
+    /// See [`ObjectStore::add`].
+    pub async fn add<C, R>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        key: &str,
+        reader: R,
+        options: AddOptions,
+    ) -> anyhow::Result<TxReceipt<Cid>>
+    where
+        C: Client + Send + Sync,
+        R: AsyncRead + AsyncSeek + Unpin + Send + 'static,
+    {
+        let key = self.key(key)?;
+        self.store
+            .add(provider, signer, key.as_str(), reader, options)
+            .await
+    }
+
+    /// Delete an object under the namespace.
+    ///
+    /// See [`ObjectStore::delete`].
+    pub async fn delete<C>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        key: &str,
+        options: DeleteOptions,
+    ) -> anyhow::Result<TxReceipt<Cid>>
+    where
+        C: Client + Send + Sync,
+    {
+        let key = self.key(key)?;
+        self.store
+            .delete(provider, signer, key.as_str(), options)
+            .await
+    }
+
+    /// Restore a soft-deleted object under the namespace.
+    pub async fn restore<C>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        key: &str,
+        options: RestoreOptions,
+    ) -> anyhow::Result<TxReceipt<Cid>>
+    where
+        C: Client + Send + Sync,
+    {
+        let key = self.key(key)?;
+        self.store
+            .restore(provider, signer, key.as_str(), options)
+            .await
+    }
+
+    /// Returns a [`TxBuilder`] that adds an already uploaded object under the namespace.
+    ///
+    /// See [`ObjectStore::tx_add`].
+    pub fn tx_add(&self, key: &str, cid: Cid, size: usize) -> anyhow::Result<TxBuilder<Cid>> {
+        self.store.tx_add(self.key(key)?.as_str(), cid, size)
+    }
+
+    /// Returns a [`TxBuilder`] that deletes the object at the given key under the namespace.
+    pub fn tx_delete(&self, key: &str) -> anyhow::Result<TxBuilder<Cid>> {
+        self.store.tx_delete(self.key(key)?.as_str())
+    }
+
+    /// Get an object under the namespace.
+    pub async fn get<W>(
+        &self,
+        provider: &(impl QueryProvider + ObjectProvider),
+        key: &str,
+        writer: W,
+        options: GetOptions,
+    ) -> anyhow::Result<()>
+    where
+        W: AsyncWrite + Unpin + Send + 'static,
+    {
+        let key = self.key(key)?;
+        self.store
+            .get(provider, key.as_str(), writer, options)
+            .await
+    }
+
+    /// Get an object under the namespace and write it to a file.
+    ///
+    /// See [`ObjectStore::get_to_file`].
+    pub async fn get_to_file(
+        &self,
+        provider: &(impl QueryProvider + ObjectProvider),
+        key: &str,
+        path: impl AsRef<Path>,
+        overwrite: bool,
+        options: GetOptions,
+    ) -> anyhow::Result<()> {
+        let key = self.key(key)?;
+        self.store
+            .get_to_file(provider, key.as_str(), path, overwrite, options)
+            .await
+    }
+
+    /// Wait until the object at the given key under the namespace is resolved by the network.
+    pub async fn wait_resolved(
+        &self,
+        provider: &impl QueryProvider,
+        key: &str,
+        timeout: Duration,
+    ) -> anyhow::Result<Object> {
+        let key = self.key(key)?;
+        self.store
+            .wait_resolved(provider, key.as_str(), timeout)
+            .await
+    }
+
+    /// Verify the [`Attestation`] of an object under the namespace.
+    ///
+    /// Attestations sign the full key, so objects attested through a namespace
+    /// only verify under that namespace.
+    pub async fn verify_attestation(
+        &self,
+        provider: &impl QueryProvider,
+        key: &str,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<Attestation> {
+        let key = self.key(key)?;
+        self.store
+            .verify_attestation(provider, key.as_str(), height)
+            .await
+    }
+
+    /// Query for objects under the namespace.
+    ///
+    /// The prefix in `options` is relative to the namespace,
+    /// and keys and common prefixes in the results are too.
+    pub async fn query(
+        &self,
+        provider: &impl QueryProvider,
+        options: QueryOptions,
+    ) -> anyhow::Result<ObjectList> {
+        Ok(self.query_page(provider, options).await?.list)
+    }
+
+    /// Query for a page of objects under the namespace, along with a cursor for the next page.
+    ///
+    /// See [`ObjectStore::query_page`].
+    pub async fn query_page(
+        &self,
+        provider: &impl QueryProvider,
+        options: QueryOptions,
+    ) -> anyhow::Result<ObjectPage> {
+        Key::validate_prefix(&options.prefix)?;
+        let options = QueryOptions {
+            prefix: format!("{}{}", self.prefix, options.prefix),
+            ..options
+        };
+        let mut page = self.store.query_page(provider, options).await?;
+        self.strip_list(&mut page.list);
+        Ok(page)
+    }
+
+    /// Strips the namespace from the keys and common prefixes of a listing.
+    fn strip_list(&self, list: &mut ObjectList) {
+        let prefix = self.prefix.as_bytes();
+        for (key, _) in list.objects.iter_mut() {
+            if key.starts_with(prefix) {
+                key.drain(..prefix.len());
+            }
+        }
+        for key in list.common_prefixes.iter_mut() {
+            if key.starts_with(prefix) {
+                key.drain(..prefix.len());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_namespaced_keys() {
+        let address = Address::new_id(100);
+        let store = NamespacedObjectStore::new(address, "tenant-a/").unwrap();
+        assert_eq!(store.namespace(), "tenant-a");
+        assert_eq!(
+            store.key("my/object").unwrap().as_str(),
+            "tenant-a/my/object"
+        );
+        assert!(store.key(" my/object ").is_err());
+        assert!(store.key("").is_err());
+        assert!(store.key("/my/object").is_err());
+
+        let nested = NamespacedObjectStore::new(address, "org/team").unwrap();
+        assert_eq!(nested.key("a").unwrap().as_str(), "org/team/a");
+
+        assert!(NamespacedObjectStore::new(address, "").is_err());
+        assert!(NamespacedObjectStore::new(address, "/").is_err());
+        assert!(NamespacedObjectStore::new(address, "/tenant").is_err());
+        assert!(NamespacedObjectStore::new(address, " tenant").is_err());
+        assert!(NamespacedObjectStore::new(address, "tenant\n").is_err());
+        assert!(NamespacedObjectStore::new(address, "tenant//").is_err());
+    }
+
+    #[test]
+    fn test_strip_list() {
+        let store = NamespacedObjectStore::new(Address::new_id(100), "tenant-a").unwrap();
+        let mut list = ObjectList {
+            objects: vec![(
+                b"tenant-a/my/object".to_vec(),
+                Object {
+                    cid: cid::Cid::default().into(),
+                    size: 0,
+                    metadata: Default::default(),
+                    resolved: true,
+                },
+            )],
+            common_prefixes: vec![b"tenant-a/my/".to_vec()],
+        };
+        store.strip_list(&mut list);
+        assert_eq!(list.objects[0].0, b"my/object".to_vec());
+        assert_eq!(list.common_prefixes, vec![b"my/".to_vec()]);
+    }
+}
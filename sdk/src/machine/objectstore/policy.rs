@@ -0,0 +1,244 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::collections::BTreeMap;
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+use super::ManifestEntry;
+
+/// Reserved key of the object that holds an object store's [`Policy`].
+pub const POLICY_KEY: &str = ".adm/policy";
+
+/// Metadata key that holds the JSON-encoded [`Policy`] on the policy object.
+pub const POLICY_METADATA_KEY: &str = "adm:policy";
+
+/// Metadata key that holds the Unix timestamp (in seconds) after which an object expires.
+/// It's set on objects added to a store whose policy has a TTL.
+pub const EXPIRES_METADATA_KEY: &str = "adm:expires";
+
+/// Quota and retention limits of an object store.
+///
+/// Policies are enforced client-side by [`super::ObjectStore::add`],
+/// so they keep well-behaved clients from filling up a shared store,
+/// but the actor doesn't reject transactions that exceed them.
+/// Expired objects and the policy object itself don't count toward quotas.
+///
+/// Usage is counted from one listing of the store per [`super::ObjectStore`] handle,
+/// and then kept up to date by the handle's own adds,
+/// so objects added by other writers in the meantime aren't counted.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Policy {
+    /// The maximum number of objects.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_objects: Option<u64>,
+    /// The maximum total size of all objects in bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<u64>,
+    /// How long objects are kept after they're added, in seconds.
+    /// Expired objects are removed by [`super::ObjectStore::purge`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u64>,
+}
+
+impl Policy {
+    /// Returns whether the policy limits the number or total size of objects.
+    pub fn has_quota(&self) -> bool {
+        self.max_objects.is_some() || self.max_bytes.is_some()
+    }
+
+    /// Returns the expiry timestamp of an object added at `now`, if the policy has a TTL.
+    pub fn expires_at(&self, now: u64) -> Option<u64> {
+        self.ttl.map(|ttl| now.saturating_add(ttl))
+    }
+
+    /// Check that `change` keeps a store with the given `usage` within quota.
+    pub(crate) fn check(&self, usage: &Usage, change: &UsageChange) -> anyhow::Result<()> {
+        // The replaced object, if any, no longer counts
+        let count = usage
+            .objects
+            .saturating_sub(u64::from(change.replaced.is_some()));
+        let bytes = usage
+            .bytes
+            .saturating_sub(change.replaced.unwrap_or_default());
+        let size = change.size;
+        if let Some(max) = self.max_objects {
+            if count + 1 > max {
+                return Err(anyhow!(
+                    "object store policy allows at most {} objects, and the store has {}",
+                    max,
+                    count
+                ));
+            }
+        }
+        if let Some(max) = self.max_bytes {
+            if bytes + size > max {
+                return Err(anyhow!(
+                    "object store policy allows at most {} bytes, and the store has {} bytes",
+                    max,
+                    bytes
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Decode a policy from an object metadata value.
+    pub fn decode(value: &str) -> anyhow::Result<Self> {
+        serde_json::from_str(value).map_err(|e| anyhow!("invalid policy: {e}"))
+    }
+
+    /// Encode the policy as an object metadata value.
+    pub fn encode(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+/// Number and total size of the objects in a store that count toward [`Policy`] quotas.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Usage {
+    pub objects: u64,
+    pub bytes: u64,
+}
+
+impl Usage {
+    /// Count the objects of a store listing that aren't expired at `now`.
+    pub fn of(objects: &BTreeMap<String, ManifestEntry>, now: u64) -> Self {
+        objects
+            .iter()
+            .filter(|(key, entry)| key.as_str() != POLICY_KEY && !is_expired(&entry.metadata, now))
+            .fold(Self::default(), |usage, (_, entry)| Self {
+                objects: usage.objects + 1,
+                bytes: usage.bytes + entry.size as u64,
+            })
+    }
+
+    /// Apply an added object to the usage.
+    pub fn apply(&mut self, change: &UsageChange) {
+        match change.replaced {
+            Some(replaced) => self.bytes = self.bytes.saturating_sub(replaced) + change.size,
+            None => {
+                self.objects += 1;
+                self.bytes += change.size;
+            }
+        }
+    }
+}
+
+/// An object being added to a store, as it changes the store's [`Usage`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct UsageChange {
+    /// The size of the added object in bytes.
+    pub size: u64,
+    /// The size of the object it overwrites, if that object counts toward quotas.
+    pub replaced: Option<u64>,
+}
+
+/// Returns whether an object with the given metadata has expired at `now`.
+pub fn is_expired(metadata: &BTreeMap<String, String>, now: u64) -> bool {
+    expired(metadata.get(EXPIRES_METADATA_KEY), now)
+}
+
+/// Returns whether an object with the given `adm:expires` metadata value has expired at `now`.
+pub(crate) fn expired(expires: Option<&String>, now: u64) -> bool {
+    expires
+        .and_then(|expires| expires.parse::<u64>().ok())
+        .is_some_and(|expires| expires <= now)
+}
+
+#[cfg(test)]
+mod tests {
+    use adm_provider::response::Cid;
+
+    use super::*;
+
+    fn entry(size: usize, expires: Option<u64>) -> ManifestEntry {
+        ManifestEntry {
+            cid: Cid::from(cid::Cid::default()),
+            size,
+            resolved: true,
+            metadata: expires
+                .map(|e| (EXPIRES_METADATA_KEY.to_string(), e.to_string()))
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_policy_check() {
+        let objects: BTreeMap<String, ManifestEntry> = [
+            ("a".to_string(), entry(10, None)),
+            ("b".to_string(), entry(20, Some(200))),
+            (POLICY_KEY.to_string(), entry(30, None)),
+        ]
+        .into_iter()
+        .collect();
+
+        let usage = Usage::of(&objects, 100);
+        assert_eq!(
+            usage,
+            Usage {
+                objects: 2,
+                bytes: 30
+            }
+        );
+        let add = |size| UsageChange {
+            size,
+            replaced: None,
+        };
+
+        let policy = Policy {
+            max_objects: Some(3),
+            max_bytes: Some(40),
+            ttl: None,
+        };
+        assert!(policy.check(&usage, &add(10)).is_ok());
+        assert!(policy.check(&usage, &add(11)).is_err());
+        // Overwriting an object replaces its size
+        let overwrite = UsageChange {
+            size: 20,
+            replaced: Some(20),
+        };
+        assert!(policy.check(&usage, &overwrite).is_ok());
+        // Expired objects don't count
+        assert!(policy.check(&Usage::of(&objects, 200), &add(30)).is_ok());
+
+        let policy = Policy {
+            max_objects: Some(1),
+            ..Default::default()
+        };
+        assert!(policy.check(&Usage::of(&objects, 200), &add(0)).is_err());
+        let overwrite = UsageChange {
+            size: 0,
+            replaced: Some(10),
+        };
+        assert!(policy.check(&Usage::of(&objects, 200), &overwrite).is_ok());
+
+        // Adds are counted without listing the store again
+        let mut usage = usage;
+        usage.apply(&add(5));
+        usage.apply(&overwrite);
+        assert_eq!(
+            usage,
+            Usage {
+                objects: 3,
+                bytes: 25
+            }
+        );
+    }
+
+    #[test]
+    fn test_policy_encoding() {
+        let policy = Policy {
+            max_objects: Some(100),
+            max_bytes: None,
+            ttl: Some(3600),
+        };
+        let encoded = policy.encode().unwrap();
+        assert_eq!(encoded, r#"{"max_objects":100,"ttl":3600}"#);
+        assert_eq!(Policy::decode(&encoded).unwrap(), policy);
+        assert_eq!(policy.expires_at(10), Some(3610));
+        assert!(Policy::decode("{").is_err());
+    }
+}
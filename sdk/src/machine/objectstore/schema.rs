@@ -0,0 +1,518 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use anyhow::anyhow;
+use serde::Serialize;
+
+/// Magic bytes at the start and end of a Parquet file.
+pub(crate) const PARQUET_MAGIC: &[u8] = b"PAR1";
+
+/// Length of the Parquet footer: a 4-byte metadata length followed by the magic bytes.
+pub(crate) const PARQUET_FOOTER_LEN: usize = 8;
+
+/// A tabular object format understood by [`crate::machine::objectstore::ObjectStore::peek_schema`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SchemaFormat {
+    /// Comma-separated values with a header row.
+    Csv,
+    /// Tab-separated values with a header row.
+    Tsv,
+    /// Apache Parquet.
+    Parquet,
+}
+
+impl SchemaFormat {
+    /// Returns the format for a key's file extension, if it's a known one.
+    pub fn from_key(key: &str) -> Option<Self> {
+        let (_, ext) = key.rsplit_once('.')?;
+        match ext.to_ascii_lowercase().as_str() {
+            "csv" => Some(SchemaFormat::Csv),
+            "tsv" | "tab" => Some(SchemaFormat::Tsv),
+            "parquet" | "pq" => Some(SchemaFormat::Parquet),
+            _ => None,
+        }
+    }
+
+    fn delimiter(&self) -> u8 {
+        match self {
+            SchemaFormat::Tsv => b'\t',
+            _ => b',',
+        }
+    }
+}
+
+/// A column of a tabular object.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SchemaColumn {
+    /// The column name.
+    /// Nested Parquet columns are named by their dot-separated path.
+    pub name: String,
+    /// The Parquet physical type, e.g., `INT64`, or `None` for CSV columns, which are untyped.
+    pub data_type: Option<String>,
+}
+
+/// The schema and size of a tabular object, read without downloading all of it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ObjectSchema {
+    /// The object format.
+    pub format: SchemaFormat,
+    /// The object size in bytes.
+    pub size: u64,
+    /// The columns, in order.
+    pub columns: Vec<SchemaColumn>,
+    /// The number of rows, or `None` if it couldn't be estimated.
+    pub rows: Option<u64>,
+    /// Whether `rows` is an estimate.
+    /// Parquet row counts are always exact; CSV row counts are exact only if the sample
+    /// covered the whole object.
+    pub rows_estimated: bool,
+}
+
+/// Parses a CSV or TSV header and estimates the row count from a sample at the start of the
+/// object.
+///
+/// Rows are estimated from the average length of the complete rows in the sample,
+/// so quoted fields that span lines, or rows whose length varies a lot, skew the estimate.
+pub(crate) fn csv_schema(
+    format: SchemaFormat,
+    sample: &[u8],
+    size: u64,
+) -> anyhow::Result<ObjectSchema> {
+    let complete = sample.len() as u64 >= size;
+    let bom = if sample.starts_with(b"\xEF\xBB\xBF") {
+        3
+    } else {
+        0
+    };
+    let header_end = match sample[bom..].iter().position(|b| *b == b'\n') {
+        Some(i) => bom + i + 1,
+        None if complete => sample.len(),
+        None => {
+            return Err(anyhow!(
+                "header row is longer than the sample; increase the sample size"
+            ))
+        }
+    };
+    let header = std::str::from_utf8(&sample[bom..header_end])
+        .map_err(|e| anyhow!("header row is not valid UTF-8: {}", e))?
+        .trim_end_matches(['\r', '\n']);
+    if header.is_empty() {
+        return Err(anyhow!("object has no header row"));
+    }
+    let columns = split_row(header, format.delimiter() as char)
+        .into_iter()
+        .map(|name| SchemaColumn {
+            name,
+            data_type: None,
+        })
+        .collect();
+
+    let body = &sample[header_end..];
+    let (rows, rows_estimated) = if complete {
+        let mut rows = body.iter().filter(|b| **b == b'\n').count() as u64;
+        if body.last().is_some_and(|b| *b != b'\n') {
+            rows += 1;
+        }
+        (Some(rows), false)
+    } else {
+        // Only count rows that end in the sample, since the last one is likely cut off
+        match body.iter().rposition(|b| *b == b'\n') {
+            Some(last) => {
+                let sampled_rows = body[..=last].iter().filter(|b| **b == b'\n').count() as u64;
+                let remaining = size - header_end as u64;
+                let rows = (remaining as f64 * sampled_rows as f64 / (last + 1) as f64).round();
+                (Some(rows as u64), true)
+            }
+            None => (None, true),
+        }
+    };
+    Ok(ObjectSchema {
+        format,
+        size,
+        columns,
+        rows,
+        rows_estimated,
+    })
+}
+
+/// Splits a delimited row into fields, handling double-quoted fields with `""` escapes.
+fn split_row(row: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = row.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Returns the length of the Parquet file metadata from the footer at the end of `tail`.
+pub(crate) fn parquet_metadata_len(tail: &[u8], size: u64) -> anyhow::Result<usize> {
+    if size < (PARQUET_MAGIC.len() + PARQUET_FOOTER_LEN) as u64 || tail.len() < PARQUET_FOOTER_LEN {
+        return Err(anyhow!("object is too small to be a Parquet file"));
+    }
+    let footer = &tail[tail.len() - PARQUET_FOOTER_LEN..];
+    match &footer[4..] {
+        m if m == PARQUET_MAGIC => {}
+        b"PARE" => return Err(anyhow!("encrypted Parquet footers are not supported")),
+        _ => return Err(anyhow!("object is not a Parquet file")),
+    }
+    let len = u32::from_le_bytes([footer[0], footer[1], footer[2], footer[3]]) as u64;
+    if len + (PARQUET_MAGIC.len() + PARQUET_FOOTER_LEN) as u64 > size {
+        return Err(anyhow!("invalid Parquet metadata length {}", len));
+    }
+    Ok(len as usize)
+}
+
+/// Parses the Thrift-encoded Parquet `FileMetaData` for its leaf columns and row count.
+pub(crate) fn parquet_schema(metadata: &[u8], size: u64) -> anyhow::Result<ObjectSchema> {
+    let mut reader = CompactReader::new(metadata);
+    let mut elements = Vec::new();
+    let mut rows = None;
+    reader.read_struct(|reader, field, ty| {
+        match (field, ty) {
+            (2, TYPE_LIST) => {
+                let (count, elem_ty) = reader.read_list_header()?;
+                if elem_ty != TYPE_STRUCT {
+                    return Err(anyhow!("invalid Parquet schema"));
+                }
+                for _ in 0..count {
+                    elements.push(read_schema_element(reader)?);
+                }
+            }
+            (3, TYPE_I64) => rows = Some(reader.read_varint_signed()?),
+            _ => reader.skip(ty)?,
+        }
+        Ok(())
+    })?;
+    let rows = rows.ok_or_else(|| anyhow!("Parquet metadata has no row count"))?;
+    let rows = u64::try_from(rows).map_err(|_| anyhow!("invalid Parquet row count {}", rows))?;
+
+    // Elements are a depth-first flattening of the schema tree, starting with the root
+    let mut columns = Vec::new();
+    let mut stack: Vec<(String, i32)> = Vec::new();
+    for (i, element) in elements.iter().enumerate() {
+        if i > 0 {
+            let path = stack
+                .iter()
+                .skip(1)
+                .map(|(name, _)| name.as_str())
+                .chain([element.name.as_str()])
+                .collect::<Vec<_>>()
+                .join(".");
+            if element.num_children == 0 {
+                columns.push(SchemaColumn {
+                    name: path,
+                    data_type: element.physical_type.map(physical_type_name),
+                });
+            }
+            if let Some((_, remaining)) = stack.last_mut() {
+                *remaining -= 1;
+            }
+        }
+        if element.num_children > 0 {
+            stack.push((element.name.clone(), element.num_children));
+        }
+        while stack.last().is_some_and(|(_, remaining)| *remaining == 0) {
+            stack.pop();
+        }
+    }
+    Ok(ObjectSchema {
+        format: SchemaFormat::Parquet,
+        size,
+        columns,
+        rows: Some(rows),
+        rows_estimated: false,
+    })
+}
+
+struct SchemaElement {
+    physical_type: Option<i32>,
+    name: String,
+    num_children: i32,
+}
+
+fn read_schema_element(reader: &mut CompactReader) -> anyhow::Result<SchemaElement> {
+    let mut element = SchemaElement {
+        physical_type: None,
+        name: String::new(),
+        num_children: 0,
+    };
+    reader.read_struct(|reader, field, ty| {
+        match (field, ty) {
+            (1, TYPE_I32) => element.physical_type = Some(reader.read_varint_signed()? as i32),
+            (4, TYPE_BINARY) => {
+                element.name = String::from_utf8(reader.read_binary()?.to_vec())
+                    .map_err(|_| anyhow!("Parquet column name is not valid UTF-8"))?
+            }
+            (5, TYPE_I32) => element.num_children = reader.read_varint_signed()? as i32,
+            _ => reader.skip(ty)?,
+        }
+        Ok(())
+    })?;
+    Ok(element)
+}
+
+fn physical_type_name(ty: i32) -> String {
+    match ty {
+        0 => "BOOLEAN".into(),
+        1 => "INT32".into(),
+        2 => "INT64".into(),
+        3 => "INT96".into(),
+        4 => "FLOAT".into(),
+        5 => "DOUBLE".into(),
+        6 => "BYTE_ARRAY".into(),
+        7 => "FIXED_LEN_BYTE_ARRAY".into(),
+        ty => format!("UNKNOWN({})", ty),
+    }
+}
+
+const TYPE_STOP: u8 = 0;
+const TYPE_BOOL_TRUE: u8 = 1;
+const TYPE_BOOL_FALSE: u8 = 2;
+const TYPE_BYTE: u8 = 3;
+const TYPE_I16: u8 = 4;
+const TYPE_I32: u8 = 5;
+const TYPE_I64: u8 = 6;
+const TYPE_DOUBLE: u8 = 7;
+const TYPE_BINARY: u8 = 8;
+const TYPE_LIST: u8 = 9;
+const TYPE_SET: u8 = 10;
+const TYPE_MAP: u8 = 11;
+const TYPE_STRUCT: u8 = 12;
+
+/// Maximum nesting of Thrift structs and containers, to bound recursion on malformed input.
+const MAX_DEPTH: usize = 64;
+
+/// A reader for the subset of the Thrift compact protocol used by Parquet metadata.
+struct CompactReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> CompactReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            pos: 0,
+            depth: 0,
+        }
+    }
+
+    fn read_byte(&mut self) -> anyhow::Result<u8> {
+        let byte = *self
+            .data
+            .get(self.pos)
+            .ok_or_else(|| anyhow!("unexpected end of Parquet metadata"))?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn read_bytes(&mut self, len: usize) -> anyhow::Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| anyhow!("unexpected end of Parquet metadata"))?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn read_varint(&mut self) -> anyhow::Result<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.read_byte()?;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(anyhow!("invalid varint in Parquet metadata"))
+    }
+
+    /// Reads a zigzag-encoded varint, as used for i16, i32, and i64.
+    fn read_varint_signed(&mut self) -> anyhow::Result<i64> {
+        let value = self.read_varint()?;
+        Ok((value >> 1) as i64 ^ -((value & 1) as i64))
+    }
+
+    fn read_binary(&mut self) -> anyhow::Result<&'a [u8]> {
+        let len = self.read_varint()? as usize;
+        self.read_bytes(len)
+    }
+
+    fn read_list_header(&mut self) -> anyhow::Result<(usize, u8)> {
+        let header = self.read_byte()?;
+        let count = match header >> 4 {
+            15 => self.read_varint()? as usize,
+            count => count as usize,
+        };
+        Ok((count, header & 0x0f))
+    }
+
+    /// Reads a struct, calling `f` with the id and type of each field, which it must consume.
+    fn read_struct(
+        &mut self,
+        mut f: impl FnMut(&mut Self, i16, u8) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(anyhow!("Parquet metadata is nested too deeply"));
+        }
+        let mut last_field = 0i16;
+        loop {
+            let header = self.read_byte()?;
+            let ty = header & 0x0f;
+            if ty == TYPE_STOP {
+                break;
+            }
+            let field = match header >> 4 {
+                0 => self.read_varint_signed()? as i16,
+                delta => last_field.wrapping_add(delta as i16),
+            };
+            last_field = field;
+            f(self, field, ty)?;
+        }
+        self.depth -= 1;
+        Ok(())
+    }
+
+    /// Skips a value of type `ty`.
+    fn skip(&mut self, ty: u8) -> anyhow::Result<()> {
+        match ty {
+            TYPE_BOOL_TRUE | TYPE_BOOL_FALSE => {}
+            TYPE_BYTE => {
+                self.read_byte()?;
+            }
+            TYPE_I16 | TYPE_I32 | TYPE_I64 => {
+                self.read_varint()?;
+            }
+            TYPE_DOUBLE => {
+                self.read_bytes(8)?;
+            }
+            TYPE_BINARY => {
+                self.read_binary()?;
+            }
+            TYPE_LIST | TYPE_SET => {
+                let (count, elem_ty) = self.read_list_header()?;
+                self.skip_elements(count, elem_ty)?;
+            }
+            TYPE_MAP => {
+                let count = self.read_varint()? as usize;
+                if count > 0 {
+                    let types = self.read_byte()?;
+                    for _ in 0..count {
+                        self.skip_element(types >> 4)?;
+                        self.skip_element(types & 0x0f)?;
+                    }
+                }
+            }
+            TYPE_STRUCT => self.read_struct(|reader, _, ty| reader.skip(ty))?,
+            ty => return Err(anyhow!("invalid Thrift type {} in Parquet metadata", ty)),
+        }
+        Ok(())
+    }
+
+    fn skip_elements(&mut self, count: usize, ty: u8) -> anyhow::Result<()> {
+        for _ in 0..count {
+            self.skip_element(ty)?;
+        }
+        Ok(())
+    }
+
+    /// Skips a container element. Unlike fields, booleans in containers take a byte each.
+    fn skip_element(&mut self, ty: u8) -> anyhow::Result<()> {
+        match ty {
+            TYPE_BOOL_TRUE | TYPE_BOOL_FALSE => self.read_byte().map(|_| ()),
+            ty => self.skip(ty),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_schema() {
+        let data = b"id,\"name, full\",\"say \"\"hi\"\"\"\r\n1,a,b\r\n2,c,d\r\n";
+        let schema = csv_schema(SchemaFormat::Csv, data, data.len() as u64).unwrap();
+        let names: Vec<_> = schema.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["id", "name, full", "say \"hi\""]);
+        assert_eq!(schema.rows, Some(2));
+        assert!(!schema.rows_estimated);
+
+        // 10-byte rows, with the sample cutting the third row off
+        let sample = b"a,b\n1234,6789\n1234,6789\n1234";
+        let schema = csv_schema(SchemaFormat::Csv, sample, 4 + 10 * 100).unwrap();
+        assert_eq!(schema.rows, Some(100));
+        assert!(schema.rows_estimated);
+
+        assert!(csv_schema(SchemaFormat::Csv, b"a,b,c", 100).is_err());
+    }
+
+    #[test]
+    fn test_parquet_schema() {
+        // FileMetaData {
+        //   1: version = 1,
+        //   2: schema = [
+        //     {name: "root", num_children: 2},
+        //     {type: INT64, name: "id"},
+        //     {name: "point", num_children: 1},
+        //     {type: DOUBLE, name: "x"},
+        //   ],
+        //   3: num_rows = 42,
+        //   4: row_groups = [],
+        // }
+        let mut metadata = vec![0x15, 0x02, 0x19, 0x4c];
+        metadata.extend([0x48, 0x04]);
+        metadata.extend(b"root");
+        metadata.extend([0x15, 0x04, 0x00]);
+        metadata.extend([0x15, 0x04, 0x38, 0x02]);
+        metadata.extend(b"id");
+        metadata.push(0x00);
+        metadata.extend([0x48, 0x05]);
+        metadata.extend(b"point");
+        metadata.extend([0x15, 0x02, 0x00]);
+        metadata.extend([0x15, 0x0a, 0x38, 0x01]);
+        metadata.extend(b"x");
+        metadata.push(0x00);
+        metadata.extend([0x16, 0x54, 0x19, 0x0c, 0x00]);
+
+        let schema = parquet_schema(&metadata, 1000).unwrap();
+        assert_eq!(
+            schema.columns,
+            vec![
+                SchemaColumn {
+                    name: "id".into(),
+                    data_type: Some("INT64".into()),
+                },
+                SchemaColumn {
+                    name: "point.x".into(),
+                    data_type: Some("DOUBLE".into()),
+                },
+            ]
+        );
+        assert_eq!(schema.rows, Some(42));
+        assert!(!schema.rows_estimated);
+
+        let mut tail = metadata.clone();
+        tail.extend((metadata.len() as u32).to_le_bytes());
+        tail.extend(PARQUET_MAGIC);
+        assert_eq!(parquet_metadata_len(&tail, 1000).unwrap(), metadata.len());
+        assert!(parquet_metadata_len(b"not a parquet file", 1000).is_err());
+    }
+}
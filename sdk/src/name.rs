@@ -0,0 +1,311 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Human-readable names for machine addresses.
+//!
+//! Names are resolved by a [`NameResolver`]. Two resolvers are provided:
+//! - [`AliasFile`]: names kept in a local JSON file, e.g., for personal shortcuts.
+//! - [`NamingMachine`]: names registered in an object store, so they can be shared.
+//!
+//! Names are 1 to 64 characters of lowercase letters, digits, `-`, `_`, and `.`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use fendermint_vm_message::query::FvmQueryHeight;
+use fvm_shared::address::{Address, Network};
+use tendermint_rpc::Client;
+
+use adm_provider::{
+    query::{NotFound, QueryProvider},
+    response::Cid,
+    tx::TxReceipt,
+    util::{format_address, parse_address},
+    Provider,
+};
+use adm_signer::Signer;
+
+use crate::machine::{
+    objectstore::{AddOptions, Key, ObjectStore, DELETED_METADATA_KEY},
+    Machine,
+};
+
+/// Key prefix under which a [`NamingMachine`] stores names.
+pub const NAME_KEY_PREFIX: &str = "names/";
+
+/// Object metadata key holding the address a name resolves to.
+pub const NAME_ADDRESS_METADATA_KEY: &str = "address";
+
+/// Maximum length of a name.
+const MAX_NAME_LEN: usize = 64;
+
+/// Validate a name.
+pub fn validate_name(name: &str) -> anyhow::Result<()> {
+    if name.is_empty() || name.len() > MAX_NAME_LEN {
+        return Err(anyhow!(
+            "invalid name '{}': must be 1 to {} characters",
+            name,
+            MAX_NAME_LEN
+        ));
+    }
+    let valid = name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "-_.".contains(c));
+    if !valid {
+        return Err(anyhow!(
+            "invalid name '{}': only lowercase letters, digits, '-', '_', and '.' are allowed",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// Parse an address or a name, preferring the address if `s` is both.
+pub fn parse_address_or_name(s: &str) -> anyhow::Result<AddressOrName> {
+    s.parse()
+}
+
+/// A machine address, or a name to be resolved to one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AddressOrName {
+    /// A raw address.
+    Address(Address),
+    /// A name.
+    Name(String),
+}
+
+impl AddressOrName {
+    /// Resolve to an address with the given resolvers, tried in order.
+    pub async fn resolve(&self, resolvers: &[&dyn NameResolver]) -> anyhow::Result<Address> {
+        let name = match self {
+            AddressOrName::Address(address) => return Ok(*address),
+            AddressOrName::Name(name) => name,
+        };
+        for resolver in resolvers {
+            if let Some(address) = resolver.resolve(name).await? {
+                return Ok(address);
+            }
+        }
+        Err(NotFound::new(format!("name '{}' not found", name)).into())
+    }
+}
+
+impl FromStr for AddressOrName {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(address) = parse_address(s) {
+            return Ok(AddressOrName::Address(address));
+        }
+        validate_name(s).map_err(|e| anyhow!("not an address or a valid name: {}", e))?;
+        Ok(AddressOrName::Name(s.into()))
+    }
+}
+
+/// Resolves names to addresses.
+#[async_trait]
+pub trait NameResolver: Send + Sync {
+    /// Returns the address for `name`, or `None` if the name isn't known.
+    async fn resolve(&self, name: &str) -> anyhow::Result<Option<Address>>;
+}
+
+/// Names kept in a local JSON file that maps names to addresses.
+///
+/// A missing file is treated as empty and created on [`AliasFile::save`].
+#[derive(Clone, Debug)]
+pub struct AliasFile {
+    path: PathBuf,
+    names: BTreeMap<String, String>,
+}
+
+impl AliasFile {
+    /// Load the alias file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let names = match std::fs::read(&path) {
+            Ok(data) => serde_json::from_slice(&data)
+                .with_context(|| format!("failed to parse alias file {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { path, names })
+    }
+
+    /// Returns the address for `name` if it's in the file.
+    pub fn get(&self, name: &str) -> anyhow::Result<Option<Address>> {
+        self.names
+            .get(name)
+            .map(|address| parse_address(address))
+            .transpose()
+    }
+
+    /// Returns the names in the file with their addresses, in name order.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.names
+            .iter()
+            .map(|(name, address)| (name.as_str(), address.as_str()))
+    }
+
+    /// Map `name` to `address`, formatted for `network`, replacing any existing mapping.
+    /// Call [`AliasFile::save`] to persist the change.
+    pub fn insert(&mut self, name: &str, address: Address, network: Network) -> anyhow::Result<()> {
+        validate_name(name)?;
+        self.names
+            .insert(name.into(), format_address(&address, network));
+        Ok(())
+    }
+
+    /// Remove `name`, returning whether it was in the file.
+    /// Call [`AliasFile::save`] to persist the change.
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.names.remove(name).is_some()
+    }
+
+    /// Write the file, creating parent directories as needed.
+    pub fn save(&self) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_vec_pretty(&self.names)?)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl NameResolver for AliasFile {
+    async fn resolve(&self, name: &str) -> anyhow::Result<Option<Address>> {
+        self.get(name)
+    }
+}
+
+/// Names registered in an object store that acts as a naming machine.
+///
+/// Each name is an object at `names/<name>` whose metadata holds the address it resolves to,
+/// so names can be shared by everyone with access to the object store.
+/// Only accounts with write access to the object store can register names.
+pub struct NamingMachine<P> {
+    store: ObjectStore,
+    provider: P,
+}
+
+impl<P> NamingMachine<P>
+where
+    P: QueryProvider,
+{
+    /// Create a naming machine backed by the object store at `address`.
+    pub fn new(address: Address, provider: P) -> Self {
+        Self {
+            store: ObjectStore::attach(address),
+            provider,
+        }
+    }
+
+    /// Returns the address of the backing object store.
+    pub fn address(&self) -> Address {
+        self.store.address()
+    }
+
+    /// Register `name` to resolve to `address`.
+    ///
+    /// This uploads a small object, so the provider must have an Object API URL.
+    /// The address is formatted for the network of the signer's subnet.
+    /// Set `options.overwrite` to change the address of an existing name.
+    pub async fn register<C>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        name: &str,
+        address: Address,
+        options: AddOptions,
+    ) -> anyhow::Result<TxReceipt<Cid>>
+    where
+        C: Client + Send + Sync,
+    {
+        validate_name(name)?;
+        let network = signer
+            .subnet_id()
+            .ok_or_else(|| anyhow!("failed to get subnet ID from signer"))?
+            .address_network();
+        let address = format_address(&address, network);
+        let mut metadata = options.metadata.clone();
+        metadata.insert(NAME_ADDRESS_METADATA_KEY.into(), address.clone());
+        let reader = Cursor::new(address.into_bytes());
+        self.store
+            .add(
+                provider,
+                signer,
+                &name_key(name),
+                reader,
+                AddOptions {
+                    metadata,
+                    ..options
+                },
+            )
+            .await
+    }
+}
+
+#[async_trait]
+impl<P> NameResolver for NamingMachine<P>
+where
+    P: QueryProvider,
+{
+    async fn resolve(&self, name: &str) -> anyhow::Result<Option<Address>> {
+        validate_name(name)?;
+        let key = Key::new(&name_key(name))?;
+        let object = self
+            .store
+            .get_object(&self.provider, &key, FvmQueryHeight::Committed)
+            .await?;
+        let metadata: HashMap<String, String> = match object {
+            Some(object) => object.metadata,
+            None => return Ok(None),
+        };
+        if metadata.contains_key(DELETED_METADATA_KEY) {
+            return Ok(None);
+        }
+        metadata
+            .get(NAME_ADDRESS_METADATA_KEY)
+            .map(|address| parse_address(address))
+            .transpose()
+    }
+}
+
+/// Returns the object key for a name.
+fn name_key(name: &str) -> String {
+    format!("{}{}", NAME_KEY_PREFIX, name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_resolve_alias() {
+        let address = Address::new_id(1001);
+        let path = std::env::temp_dir().join(format!("adm-names-{}.json", std::process::id()));
+        let mut aliases = AliasFile::load(&path).unwrap();
+        aliases
+            .insert("my-store", address, Network::Testnet)
+            .unwrap();
+        aliases.save().unwrap();
+
+        let aliases = AliasFile::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let resolvers: [&dyn NameResolver; 1] = [&aliases];
+        let name = AddressOrName::from_str("my-store").unwrap();
+        assert_eq!(name.resolve(&resolvers).await.unwrap(), address);
+        let raw = AddressOrName::from_str("f01001").unwrap();
+        assert_eq!(raw, AddressOrName::Address(address));
+        assert!(AddressOrName::from_str("missing")
+            .unwrap()
+            .resolve(&resolvers)
+            .await
+            .is_err());
+        assert!(AddressOrName::from_str("Not Valid").is_err());
+    }
+}
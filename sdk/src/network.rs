@@ -6,9 +6,13 @@ use std::time::Duration;
 
 use anyhow::anyhow;
 use fvm_shared::address::{set_current_network, Address, Network as FvmNetwork};
+use fvm_shared::econ::TokenAmount;
 use tendermint_rpc::Url;
 
-use adm_provider::util::parse_address;
+use adm_provider::{
+    message::GasParams,
+    util::{format_address, parse_address, parse_address_strict},
+};
 use adm_signer::SubnetID;
 
 use crate::ipc::subnet::EVMSubnet;
@@ -33,6 +37,12 @@ const TESTNET_PARENT_EVM_REGISTRY_ADDRESS: &str = "0x7Eb0a3511BB5DB2b5f945e6EB80
 const TESTNET_OBJECT_API_URL: &str = "https://object-api.n1.testnet.basin.storage";
 const LOCALNET_OBJECT_API_URL: &str = "http://127.0.0.1:8001";
 
+// Default gas params for networks with fee markets.
+// The fee cap is the minimum base fee of Filecoin-based networks.
+const DEFAULT_GAS_LIMIT: u64 = 2_500_000_000;
+const DEFAULT_GAS_FEE_CAP_ATTO: u64 = 100;
+const DEFAULT_GAS_PREMIUM_ATTO: u64 = 10;
+
 /// Options for [`EVMSubnet`] configurations.
 #[derive(Debug, Clone)]
 pub struct SubnetOptions {
@@ -65,16 +75,35 @@ pub enum Network {
 }
 
 impl Network {
-    /// Sets the current [`FvmNetwork`].
-    /// Note: This _must_ be called before using the SDK.
+    /// Sets the current [`FvmNetwork`] globally, which [`Address`]'s `Display` uses.
+    ///
+    /// This is only needed to print addresses with `Display`, and it affects the whole process,
+    /// so don't use it in processes that handle more than one network.
+    /// Use [`Network::format_address`] and [`Network::parse_address`] instead.
     pub fn init(&self) -> &Self {
+        set_current_network(self.address_network());
+        self
+    }
+
+    /// Returns the [`FvmNetwork`] of the network's addresses.
+    pub fn address_network(&self) -> FvmNetwork {
         match self {
-            Network::Mainnet => set_current_network(FvmNetwork::Mainnet),
-            Network::Testnet | Network::Localnet | Network::Devnet => {
-                set_current_network(FvmNetwork::Testnet)
-            }
+            Network::Mainnet => FvmNetwork::Mainnet,
+            Network::Testnet | Network::Localnet | Network::Devnet => FvmNetwork::Testnet,
         }
-        self
+    }
+
+    /// Format an address for the network, e.g., `t410f...` for testnets,
+    /// without depending on the global network set by [`Network::init`].
+    pub fn format_address(&self, address: &Address) -> String {
+        format_address(address, self.address_network())
+    }
+
+    /// Parse an f-address or eth-address on the network.
+    ///
+    /// Unlike [`parse_address`], f-addresses of other networks are rejected.
+    pub fn parse_address(&self, s: &str) -> anyhow::Result<Address> {
+        parse_address_strict(s, self.address_network())
     }
 
     /// Returns the network [`SubnetID`].
@@ -182,4 +211,49 @@ impl Network {
             Network::Localnet | Network::Devnet => Err(anyhow!("network has no parent")),
         }
     }
+
+    /// Returns the default [`GasParams`] for transactions on the network.
+    ///
+    /// Networks with fee markets get a gas limit below the block gas limit and non-zero fees,
+    /// so transactions don't claim whole blocks or get stuck behind paying ones.
+    /// Local networks keep [`GasParams::default`], i.e., the block gas limit and no fees.
+    pub fn default_gas_params(&self) -> GasParams {
+        match self {
+            Network::Mainnet | Network::Testnet => GasParams {
+                gas_limit: DEFAULT_GAS_LIMIT,
+                gas_fee_cap: TokenAmount::from_atto(DEFAULT_GAS_FEE_CAP_ATTO),
+                gas_premium: TokenAmount::from_atto(DEFAULT_GAS_PREMIUM_ATTO),
+            },
+            Network::Localnet | Network::Devnet => GasParams::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_addresses() {
+        let address = Address::new_id(1001);
+        assert_eq!(Network::Localnet.format_address(&address), "t01001");
+        assert_eq!(Network::Mainnet.format_address(&address), "f01001");
+        assert_eq!(Network::Testnet.parse_address("t01001").unwrap(), address);
+        assert!(Network::Testnet.parse_address("f01001").is_err());
+        assert!(Network::Mainnet
+            .parse_address("0x0000000000000000000000000000000000000001")
+            .is_ok());
+    }
+
+    #[test]
+    fn test_default_gas_params() {
+        let gas_params = Network::Testnet.default_gas_params();
+        assert!(gas_params.gas_limit < fvm_shared::BLOCK_GAS_LIMIT);
+        assert!(gas_params.gas_premium.is_positive());
+        assert!(gas_params.gas_fee_cap >= gas_params.gas_premium);
+
+        let gas_params = Network::Devnet.default_gas_params();
+        assert_eq!(gas_params.gas_limit, fvm_shared::BLOCK_GAS_LIMIT);
+        assert!(gas_params.gas_fee_cap.is_zero());
+    }
 }
@@ -1,54 +1,233 @@
 // Copyright 2024 ADM Contributors
 // SPDX-License-Identifier: Apache-2.0, MIT
 
-use std::fmt::Write;
-use std::sync::Arc;
-use std::time::Duration;
-
-use console::Emoji;
-use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressState, ProgressStyle};
-use lazy_static::lazy_static;
-
-pub(crate) static SPARKLE: Emoji<'_, '_> = Emoji("✨ ", ":-)");
-
-lazy_static! {
-    static ref SPINNER_STYLE: ProgressStyle =
-        ProgressStyle::with_template("{prefix:.bold.dim} {spinner:.green} {wide_msg}")
-            .unwrap()
-            .tick_strings(&["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"]);
-    static ref PROGRESS_STYLE: ProgressStyle = ProgressStyle::with_template(
-        "[{elapsed_precise}] [{wide_bar:.cyan/blue}] {bytes}/{total_bytes} ({eta})"
-    )
-    .unwrap()
-    .with_key("eta", |state: &ProgressState, w: &mut dyn Write| write!(
-        w,
-        "{:.1}s",
-        state.eta().as_secs_f64()
-    )
-    .unwrap())
-    .progress_chars("#>-");
+//! Progress output for command-line interfaces.
+//!
+//! Without the `progress` feature, the bars are no-ops, so the SDK doesn't pull in
+//! terminal dependencies.
+
+#[cfg(feature = "progress")]
+pub(crate) use bars::*;
+#[cfg(not(feature = "progress"))]
+pub(crate) use noop::*;
+
+/// How progress is shown by operations with `show_progress` set.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProgressFormat {
+    /// Progress bars drawn on the terminal.
+    #[default]
+    Bars,
+    /// Newline-delimited JSON progress events on stderr, e.g., for desktop apps that wrap a
+    /// command-line interface and render their own progress.
+    ///
+    /// Every event has the `stage` of the operation, e.g., "2/3", and its `message`.
+    /// Events for transfers also have the `bytes` transferred, the `total` bytes,
+    /// and the `eta` in seconds.
+    Json,
 }
 
-/// Create a new progress bar. Use `hide` to hide all child bars.
-pub(crate) fn new_multi_bar(hide: bool) -> Arc<MultiProgress> {
-    if hide {
-        Arc::new(MultiProgress::with_draw_target(ProgressDrawTarget::hidden()))
-    } else {
-        Arc::new(MultiProgress::new())
+#[cfg(feature = "progress")]
+mod bars {
+    use std::fmt::Write;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use console::Emoji;
+    pub(crate) use indicatif::{HumanDuration, ProgressBar};
+    use indicatif::{ProgressDrawTarget, ProgressState, ProgressStyle};
+    use lazy_static::lazy_static;
+    use serde_json::{json, Value};
+
+    use super::ProgressFormat;
+
+    /// How often JSON progress events are reported.
+    const JSON_EVENT_INTERVAL: Duration = Duration::from_millis(250);
+
+    pub(crate) static SPARKLE: Emoji<'_, '_> = Emoji("✨ ", ":-)");
+
+    lazy_static! {
+        static ref SPINNER_STYLE: ProgressStyle =
+            ProgressStyle::with_template("{prefix:.bold.dim} {spinner:.green} {wide_msg}")
+                .unwrap()
+                .tick_strings(&["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"]);
+        static ref PROGRESS_STYLE: ProgressStyle = ProgressStyle::with_template(
+            "[{elapsed_precise}] [{wide_bar:.cyan/blue}] {bytes}/{total_bytes} ({eta})"
+        )
+        .unwrap()
+        .with_key("eta", |state: &ProgressState, w: &mut dyn Write| write!(
+            w,
+            "{:.1}s",
+            state.eta().as_secs_f64()
+        )
+        .unwrap())
+        .progress_chars("#>-");
+    }
+
+    /// A group of progress bars, drawn on the terminal or reported as JSON events.
+    pub(crate) struct MultiProgress {
+        inner: indicatif::MultiProgress,
+        reporter: Option<Arc<JsonReporter>>,
+    }
+
+    impl MultiProgress {
+        pub fn add(&self, bar: ProgressBar) -> ProgressBar {
+            let bar = self.inner.add(bar);
+            if let Some(reporter) = &self.reporter {
+                reporter.bars.lock().unwrap().push((bar.clone(), None));
+            }
+            bar
+        }
+    }
+
+    impl Drop for MultiProgress {
+        fn drop(&mut self) {
+            if let Some(reporter) = &self.reporter {
+                // Report the final state, e.g., of bars that finished since the last report
+                reporter.report();
+                reporter.stopped.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Prints the state of progress bars as JSON lines on stderr when it changes.
+    struct JsonReporter {
+        /// Bars with their last reported event.
+        bars: Mutex<Vec<(ProgressBar, Option<Value>)>>,
+        stopped: AtomicBool,
+    }
+
+    impl JsonReporter {
+        fn start() -> Arc<Self> {
+            let reporter = Arc::new(Self {
+                bars: Mutex::new(Vec::new()),
+                stopped: AtomicBool::new(false),
+            });
+            let background = reporter.clone();
+            std::thread::spawn(move || {
+                while !background.stopped.load(Ordering::Relaxed) {
+                    std::thread::sleep(JSON_EVENT_INTERVAL);
+                    background.report();
+                }
+            });
+            reporter
+        }
+
+        fn report(&self) {
+            let mut bars = self.bars.lock().unwrap();
+            let mut stage = String::new();
+            let mut message = String::new();
+            for (bar, last) in bars.iter_mut() {
+                // Message bars have a prefix like "[2/3]"; the others track bytes of a transfer
+                let prefix = bar.prefix();
+                let event = if prefix.is_empty() {
+                    json!({
+                        "stage": stage,
+                        "message": message,
+                        "bytes": bar.position(),
+                        "total": bar.length().unwrap_or_default(),
+                        "eta": (bar.eta().as_secs_f64() * 10.0).round() / 10.0,
+                    })
+                } else {
+                    stage = prefix.trim_matches(|c| c == '[' || c == ']').to_string();
+                    message = bar.message();
+                    json!({"stage": stage, "message": message})
+                };
+                if last.as_ref() != Some(&event) {
+                    eprintln!("{}", event);
+                    *last = Some(event);
+                }
+            }
+        }
+    }
+
+    /// Create a new group of progress bars. Use `hide` to hide all child bars.
+    pub(crate) fn new_multi_bar(hide: bool, format: ProgressFormat) -> Arc<MultiProgress> {
+        let hidden = || indicatif::MultiProgress::with_draw_target(ProgressDrawTarget::hidden());
+        let (inner, reporter) = match format {
+            _ if hide => (hidden(), None),
+            ProgressFormat::Bars => (indicatif::MultiProgress::new(), None),
+            ProgressFormat::Json => (hidden(), Some(JsonReporter::start())),
+        };
+        Arc::new(MultiProgress { inner, reporter })
+    }
+
+    /// Create a new progress bar.
+    pub(crate) fn new_progress_bar(size: usize) -> ProgressBar {
+        let pb = ProgressBar::new(size as u64);
+        pb.set_style(PROGRESS_STYLE.clone());
+        pb
     }
-}
 
-/// Create a new progress bar.
-pub(crate) fn new_progress_bar(size: usize) -> ProgressBar {
-    let pb = ProgressBar::new(size as u64);
-    pb.set_style(PROGRESS_STYLE.clone());
-    pb
+    /// Create a new message bar.
+    pub(crate) fn new_message_bar() -> ProgressBar {
+        let pb = ProgressBar::new(0);
+        pb.set_style(SPINNER_STYLE.clone());
+        pb.enable_steady_tick(Duration::from_millis(80));
+        pb
+    }
 }
 
-/// Create a new message bar.
-pub(crate) fn new_message_bar() -> ProgressBar {
-    let pb = ProgressBar::new(0);
-    pb.set_style(SPINNER_STYLE.clone());
-    pb.enable_steady_tick(Duration::from_millis(80));
-    pb
+#[cfg(not(feature = "progress"))]
+mod noop {
+    use std::fmt;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    pub(crate) static SPARKLE: &str = "";
+
+    /// Formats a duration in its largest whole unit, e.g., "10 minutes".
+    pub(crate) struct HumanDuration(pub Duration);
+
+    impl fmt::Display for HumanDuration {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            const UNITS: [(u64, &str); 4] = [
+                (86400, "day"),
+                (3600, "hour"),
+                (60, "minute"),
+                (1, "second"),
+            ];
+            let secs = self.0.as_secs();
+            let (size, name) = UNITS
+                .into_iter()
+                .find(|(size, _)| secs >= *size)
+                .unwrap_or(UNITS[3]);
+            let count = secs / size;
+            write!(f, "{} {}{}", count, name, if count == 1 { "" } else { "s" })
+        }
+    }
+
+    /// A progress bar that draws nothing.
+    #[derive(Clone, Default)]
+    pub(crate) struct ProgressBar;
+
+    impl ProgressBar {
+        pub fn set_prefix(&self, _prefix: &'static str) {}
+        pub fn set_message(&self, _message: impl Into<String>) {}
+        pub fn set_position(&self, _position: u64) {}
+        pub fn inc(&self, _delta: u64) {}
+        pub fn println(&self, _message: impl AsRef<str>) {}
+        pub fn finish_and_clear(&self) {}
+    }
+
+    /// A group of progress bars that draws nothing.
+    pub(crate) struct MultiProgress;
+
+    impl MultiProgress {
+        pub fn add(&self, bar: ProgressBar) -> ProgressBar {
+            bar
+        }
+    }
+
+    pub(crate) fn new_multi_bar(_hide: bool, _format: super::ProgressFormat) -> Arc<MultiProgress> {
+        Arc::new(MultiProgress)
+    }
+
+    pub(crate) fn new_progress_bar(_size: usize) -> ProgressBar {
+        ProgressBar
+    }
+
+    pub(crate) fn new_message_bar() -> ProgressBar {
+        ProgressBar
+    }
 }
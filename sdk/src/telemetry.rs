@@ -0,0 +1,37 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::future::Future;
+
+use adm_signer::SubnetID;
+
+/// Runs a cross-chain operation that moves funds into or out of `subnet`,
+/// exporting it to OpenTelemetry if the `otel` feature is enabled.
+#[cfg(feature = "otel")]
+pub(crate) async fn cross_chain<F, T>(
+    name: &'static str,
+    subnet: &SubnetID,
+    future: F,
+) -> anyhow::Result<T>
+where
+    F: Future<Output = anyhow::Result<T>>,
+{
+    let attributes = vec![opentelemetry::KeyValue::new(
+        "adm.subnet",
+        subnet.to_string(),
+    )];
+    adm_provider::telemetry::instrument(name, "evm", attributes, future).await
+}
+
+/// Runs a cross-chain operation that moves funds into or out of `subnet`.
+#[cfg(not(feature = "otel"))]
+pub(crate) async fn cross_chain<F, T>(
+    _name: &'static str,
+    _subnet: &SubnetID,
+    future: F,
+) -> anyhow::Result<T>
+where
+    F: Future<Output = anyhow::Result<T>>,
+{
+    future.await
+}
@@ -0,0 +1,745 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! In-memory test fixtures for apps built on the SDK.
+//!
+//! [`MockProvider`] implements the same provider traits as
+//! [`adm_provider::json_rpc::JsonRpcProvider`], but executes messages against an in-memory
+//! model of accounts, object stores, and accumulators instead of a network.
+//! This makes it possible to unit test code that uses [`crate::machine`] without a localnet.
+//!
+//! The mock is deterministic, but it is not a full chain:
+//! - Signatures and gas are not checked, but sequences (nonces) are.
+//! - Every transaction is committed in its own block, and queries always run against the
+//!   latest state regardless of the requested height.
+//! - Accumulator roots and peaks are stable, but don't match the on-chain actor.
+//! - Object contents are only kept if the upload body is buffered.
+//!   Streaming uploads, like those made by [`crate::machine::objectstore::ObjectStore::add`],
+//!   only record the object. Use [`MockProvider::put_content`] to seed their contents.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine};
+use bytes::Bytes;
+use cid::multihash::Multihash;
+use fendermint_actor_accumulator::Method::{Count, Get, Peaks, Push, Root};
+use fendermint_actor_machine::{Metadata, WriteAccess, GET_METADATA_METHOD};
+use fendermint_actor_objectstore::{
+    AddParams, DeleteParams, GetParams, ListParams,
+    Method::{AddObject, DeleteObject, GetObject, ListObjects},
+    Object, ObjectList,
+};
+use fendermint_vm_actor_interface::adm::{
+    self, CreateExternalParams, CreateExternalReturn, Kind, ListMetadataParams,
+    Method::CreateExternal, Method::ListMetadata, ADM_ACTOR_ADDR,
+};
+use fendermint_vm_message::{
+    chain::ChainMessage,
+    query::{ActorState, FvmQuery, FvmQueryHeight},
+    signed::SignedMessage,
+};
+use fvm_ipld_encoding::{BytesDe, DAG_CBOR};
+use fvm_shared::{
+    address::Address, chainid::ChainID, econ::TokenAmount, message::Message, ActorID,
+};
+use serde::Serialize;
+use tendermint::{abci::response::DeliverTx, block::Height, Hash, Time};
+use tendermint_rpc::{endpoint::abci_query::AbciQuery, HttpClient};
+
+use adm_provider::{
+    object::ObjectProvider,
+    query::{ChainHead, QueryProvider, QueryResponse},
+    response::Cid,
+    tx::{BroadcastMode, TxProvider, TxReceipt},
+    Provider, TendermintClient,
+};
+use adm_verify::accumulator::{bag_peaks, leaf_hash, node_hash};
+
+use crate::machine::predict_address;
+
+/// Multicodec code for Keccak-256, used to hash mock object store state.
+const KECCAK_256: u64 = 0x1b;
+
+/// First actor ID assigned to mock accounts and machines.
+const FIRST_ACTOR_ID: ActorID = 1000;
+
+/// An in-memory provider for unit tests.
+///
+/// Clones share the same state.
+#[derive(Clone)]
+pub struct MockProvider {
+    state: Arc<Mutex<MockState>>,
+    chain_id: ChainID,
+    client: HttpClient,
+}
+
+#[derive(Default)]
+struct MockState {
+    height: u64,
+    next_actor_id: ActorID,
+    accounts: HashMap<Address, MockAccount>,
+    machines: HashMap<Address, MockMachine>,
+    contents: HashMap<cid::Cid, Bytes>,
+}
+
+struct MockAccount {
+    id: ActorID,
+    sequence: u64,
+    balance: TokenAmount,
+}
+
+struct MockMachine {
+    owner: Address,
+    kind: Kind,
+    write_access: WriteAccess,
+    objects: BTreeMap<Vec<u8>, Object>,
+    accumulator: MockAccumulator,
+}
+
+#[derive(Default)]
+struct MockAccumulator {
+    leaves: Vec<Vec<u8>>,
+    /// Peaks of the Merkle mountain range as (height, node) pairs, from left to right.
+    peaks: Vec<(u32, cid::Cid)>,
+}
+
+impl MockProvider {
+    /// Create a new empty mock network with the given chain ID.
+    pub fn new(chain_id: ChainID) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(MockState {
+                next_actor_id: FIRST_ACTOR_ID,
+                ..Default::default()
+            })),
+            chain_id,
+            // The mock never talks to a node; this is only here to satisfy `TendermintClient`.
+            client: HttpClient::new("http://127.0.0.1:26657").expect("valid url"),
+        }
+    }
+
+    /// Create an account with the given balance, or set the balance of an existing account.
+    ///
+    /// Accounts must exist before a [`adm_signer::Wallet`] can initialize its sequence.
+    pub fn fund(&self, address: Address, balance: TokenAmount) {
+        let mut state = self.state.lock().unwrap();
+        state.account(address).balance = balance;
+    }
+
+    /// Store object contents so they can be downloaded by CID.
+    pub fn put_content(&self, cid: Cid, data: impl Into<Bytes>) {
+        let mut state = self.state.lock().unwrap();
+        state.contents.insert(cid.0, data.into());
+    }
+
+    /// Returns the current block height.
+    pub fn height(&self) -> u64 {
+        self.state.lock().unwrap().height
+    }
+}
+
+impl MockState {
+    /// Returns the account at the given address, creating it if needed.
+    fn account(&mut self, address: Address) -> &mut MockAccount {
+        if !self.accounts.contains_key(&address) {
+            let id = self.next_id();
+            self.accounts.insert(
+                address,
+                MockAccount {
+                    id,
+                    sequence: 0,
+                    balance: TokenAmount::default(),
+                },
+            );
+        }
+        self.accounts.get_mut(&address).expect("account exists")
+    }
+
+    fn next_id(&mut self) -> ActorID {
+        let id = self.next_actor_id;
+        self.next_actor_id += 1;
+        id
+    }
+
+    fn machine(&mut self, address: &Address) -> anyhow::Result<&mut MockMachine> {
+        self.machines
+            .get_mut(address)
+            .ok_or_else(|| anyhow!("actor {} not found", address))
+    }
+
+    /// Execute a message and return its CBOR-encoded return value.
+    /// Only read-only methods are allowed unless `mutate` is set.
+    fn execute(&mut self, message: &Message, mutate: bool) -> anyhow::Result<Vec<u8>> {
+        let method = message.method_num;
+        if message.to == ADM_ACTOR_ADDR {
+            return if method == ListMetadata as u64 {
+                let params: ListMetadataParams = message.params.deserialize()?;
+                let list: Vec<adm::Metadata> = self
+                    .machines
+                    .iter()
+                    .filter(|(_, m)| m.owner == params.owner)
+                    .map(|(address, m)| adm::Metadata {
+                        kind: m.kind.clone(),
+                        address: *address,
+                    })
+                    .collect();
+                encode(&list)
+            } else if method == CreateExternal as u64 && mutate {
+                let params: CreateExternalParams = message.params.deserialize()?;
+                let actor_id = self.next_id();
+                let robust_address = predict_address(&message.from, message.sequence)?;
+                self.machines.insert(
+                    robust_address,
+                    MockMachine {
+                        owner: message.from,
+                        kind: params.kind,
+                        write_access: params.write_access,
+                        objects: BTreeMap::new(),
+                        accumulator: MockAccumulator::default(),
+                    },
+                );
+                encode(&CreateExternalReturn {
+                    actor_id,
+                    robust_address: Some(robust_address),
+                })
+            } else {
+                Err(unsupported(message))
+            };
+        }
+
+        let from = message.from;
+        let machine = self.machine(&message.to)?;
+        if method == GET_METADATA_METHOD {
+            return encode(&Metadata {
+                kind: machine.kind.clone(),
+                owner: machine.owner,
+            });
+        }
+        let is_mutation = [AddObject as u64, DeleteObject as u64, Push as u64].contains(&method);
+        if is_mutation {
+            if !mutate {
+                return Err(anyhow!("method {} cannot be called read-only", method));
+            }
+            if matches!(machine.write_access, WriteAccess::OnlyOwner) && from != machine.owner {
+                return Err(anyhow!("caller {} is not the machine owner", from));
+            }
+        }
+
+        if matches!(machine.kind, Kind::ObjectStore) {
+            if method == AddObject as u64 {
+                let params: AddParams = message.params.deserialize()?;
+                let key = params.key.to_vec();
+                if !params.overwrite && machine.objects.contains_key(&key) {
+                    return Err(anyhow!("key exists; use overwrite"));
+                }
+                machine.objects.insert(
+                    key,
+                    Object {
+                        cid: params.cid.into(),
+                        size: params.size,
+                        metadata: params.metadata,
+                        resolved: true,
+                    },
+                );
+                encode(&machine.root()?)
+            } else if method == DeleteObject as u64 {
+                let params: DeleteParams = message.params.deserialize()?;
+                machine
+                    .objects
+                    .remove(&params.key.to_vec())
+                    .ok_or_else(|| anyhow!("key not found"))?;
+                encode(&machine.root()?)
+            } else if method == GetObject as u64 {
+                let params: GetParams = message.params.deserialize()?;
+                encode(&machine.objects.get(&params.key.to_vec()).cloned())
+            } else if method == ListObjects as u64 {
+                let params: ListParams = message.params.deserialize()?;
+                encode(&machine.list(params))
+            } else {
+                Err(unsupported(message))
+            }
+        } else if matches!(machine.kind, Kind::Accumulator) {
+            let acc = &mut machine.accumulator;
+            if method == Push as u64 {
+                let payload: BytesDe = message.params.deserialize()?;
+                let index = acc.push(payload.0)?;
+                encode(&fendermint_actor_accumulator::PushReturn {
+                    root: acc.root()?,
+                    index,
+                })
+            } else if method == Get as u64 {
+                let index: u64 = message.params.deserialize()?;
+                encode(&acc.leaves.get(index as usize).cloned())
+            } else if method == Count as u64 {
+                encode(&(acc.leaves.len() as u64))
+            } else if method == Peaks as u64 {
+                encode(&acc.peaks.iter().map(|(_, c)| *c).collect::<Vec<_>>())
+            } else if method == Root as u64 {
+                encode(&acc.root()?)
+            } else {
+                Err(unsupported(message))
+            }
+        } else {
+            Err(unsupported(message))
+        }
+    }
+}
+
+impl MockMachine {
+    /// Returns a stable identifier for the object store contents.
+    fn root(&self) -> anyhow::Result<cid::Cid> {
+        let entries: Vec<(&Vec<u8>, &Object)> = self.objects.iter().collect();
+        hash(&fvm_ipld_encoding::to_vec(&entries)?)
+    }
+
+    /// List objects using the same prefix, delimiter, and pagination rules as the actor.
+    fn list(&self, params: ListParams) -> ObjectList {
+        let prefix = params.prefix.to_vec();
+        let delimiter = params.delimiter.to_vec();
+        let mut objects = Vec::new();
+        let mut common_prefixes: Vec<Vec<u8>> = Vec::new();
+        let mut skipped = 0;
+        for (key, object) in self.objects.range(prefix.clone()..) {
+            if !key.starts_with(&prefix) {
+                break;
+            }
+            if !delimiter.is_empty() {
+                let rest = &key[prefix.len()..];
+                if let Some(i) = rest
+                    .windows(delimiter.len())
+                    .position(|w| w == delimiter.as_slice())
+                {
+                    let common = key[..prefix.len() + i + delimiter.len()].to_vec();
+                    if common_prefixes.last() != Some(&common) {
+                        common_prefixes.push(common);
+                    }
+                    continue;
+                }
+            }
+            if skipped < params.offset {
+                skipped += 1;
+                continue;
+            }
+            if params.limit > 0 && objects.len() as u64 >= params.limit {
+                break;
+            }
+            objects.push((key.clone(), object.clone()));
+        }
+        ObjectList {
+            objects,
+            common_prefixes,
+        }
+    }
+}
+
+impl MockAccumulator {
+    /// Push a leaf, merging peaks of equal height, and return the leaf index.
+    fn push(&mut self, leaf: Vec<u8>) -> anyhow::Result<u64> {
+        let mut node = leaf_hash(&leaf)?;
+        let mut height = 0;
+        while let Some((h, left)) = self.peaks.last().copied() {
+            if h != height {
+                break;
+            }
+            self.peaks.pop();
+            node = node_hash(&left, &node)?;
+            height += 1;
+        }
+        self.peaks.push((height, node));
+        self.leaves.push(leaf);
+        Ok(self.leaves.len() as u64 - 1)
+    }
+
+    /// Bag the peaks from right to left into a single root.
+    fn root(&self) -> anyhow::Result<cid::Cid> {
+        let peaks: Vec<cid::Cid> = self.peaks.iter().map(|(_, c)| *c).collect();
+        bag_peaks(&peaks)
+    }
+}
+
+/// Hash bytes into a CID.
+fn hash(data: &[u8]) -> anyhow::Result<cid::Cid> {
+    let digest = ethers::utils::keccak256(data);
+    let mh = Multihash::wrap(KECCAK_256, &digest)?;
+    Ok(cid::Cid::new_v1(DAG_CBOR, mh))
+}
+
+fn encode<T: Serialize>(value: &T) -> anyhow::Result<Vec<u8>> {
+    Ok(fvm_ipld_encoding::to_vec(value)?)
+}
+
+fn unsupported(message: &Message) -> anyhow::Error {
+    anyhow!(
+        "method {} is not supported by the mock for actor {}",
+        message.method_num,
+        message.to
+    )
+}
+
+/// Wrap return data the way Tendermint does in [`DeliverTx`].
+fn deliver_tx(data: Vec<u8>) -> DeliverTx {
+    DeliverTx {
+        data: general_purpose::STANDARD.encode(data).into_bytes().into(),
+        ..Default::default()
+    }
+}
+
+/// Parse an HTTP byte range like `0-99`, `100-`, or `-100` into a half-open range.
+fn parse_range(range: &str, len: usize) -> anyhow::Result<(usize, usize)> {
+    let (start, end) = range
+        .split_once('-')
+        .ok_or_else(|| anyhow!("invalid range: {}", range))?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => (len.saturating_sub(suffix.parse()?), len),
+        (start, "") => (start.parse()?, len),
+        (start, end) => (
+            start.parse()?,
+            end.parse::<usize>()?.saturating_add(1).min(len),
+        ),
+    };
+    if start >= end {
+        return Err(anyhow!("invalid range: {}", range));
+    }
+    Ok((start, end))
+}
+
+impl Provider<HttpClient> for MockProvider {}
+
+impl TendermintClient<HttpClient> for MockProvider {
+    fn underlying(&self) -> &HttpClient {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl QueryProvider for MockProvider {
+    async fn call<F, T>(
+        &self,
+        message: Message,
+        _height: FvmQueryHeight,
+        f: F,
+    ) -> anyhow::Result<QueryResponse<T>>
+    where
+        F: FnOnce(&DeliverTx) -> anyhow::Result<T> + Sync + Send,
+        T: Sync + Send,
+    {
+        let (height, data) = {
+            let mut state = self.state.lock().unwrap();
+            (state.height, state.execute(&message, false)?)
+        };
+        let value = f(&deliver_tx(data))?;
+        Ok(QueryResponse {
+            height: Height::try_from(height)?,
+            value,
+        })
+    }
+
+    async fn actor_state(
+        &self,
+        address: &Address,
+        _height: FvmQueryHeight,
+    ) -> anyhow::Result<QueryResponse<Option<(ActorID, ActorState)>>> {
+        let state = self.state.lock().unwrap();
+        let value = state.accounts.get(address).map(|account| {
+            (
+                account.id,
+                ActorState {
+                    code: Default::default(),
+                    state: Default::default(),
+                    sequence: account.sequence,
+                    balance: account.balance.clone(),
+                    delegated_address: None,
+                },
+            )
+        });
+        Ok(QueryResponse {
+            height: Height::try_from(state.height)?,
+            value,
+        })
+    }
+
+    async fn chain_id(&self) -> anyhow::Result<ChainID> {
+        Ok(self.chain_id)
+    }
+
+    async fn chain_head(&self) -> anyhow::Result<ChainHead> {
+        let height = self.height();
+        Ok(ChainHead {
+            height: Height::try_from(height)?,
+            hash: Hash::None,
+            time: self.block_time(height).await?,
+            catching_up: false,
+        })
+    }
+
+    async fn block_time(&self, height: u64) -> anyhow::Result<Time> {
+        // One second per block from the Unix epoch keeps times deterministic.
+        Ok(Time::from_unix_timestamp(height as i64, 0)?)
+    }
+
+    async fn query(&self, query: FvmQuery, _height: FvmQueryHeight) -> anyhow::Result<AbciQuery> {
+        Err(anyhow!("query {:?} is not supported by the mock", query))
+    }
+}
+
+#[async_trait]
+impl TxProvider for MockProvider {
+    async fn perform<F, T>(
+        &self,
+        message: ChainMessage,
+        broadcast_mode: BroadcastMode,
+        f: F,
+    ) -> anyhow::Result<TxReceipt<T>>
+    where
+        F: FnOnce(&DeliverTx) -> anyhow::Result<T> + Sync + Send,
+        T: Sync + Send,
+    {
+        let hash = Hash::Sha256(ethers::utils::keccak256(adm_provider::message::serialize(
+            &message,
+        )?));
+        let ChainMessage::Signed(SignedMessage { message, .. }) = message else {
+            return Err(anyhow!("only signed messages are supported by the mock"));
+        };
+
+        let (height, data) = {
+            let mut state = self.state.lock().unwrap();
+            let account = state.account(message.from);
+            if message.sequence != account.sequence {
+                return Err(anyhow!(
+                    "expected sequence {}, got {}",
+                    account.sequence,
+                    message.sequence
+                ));
+            }
+            account.sequence += 1;
+            let data = state.execute(&message, true)?;
+            state.height += 1;
+            (state.height, data)
+        };
+
+        match broadcast_mode {
+            BroadcastMode::Async | BroadcastMode::Sync => Ok(TxReceipt::pending(hash)),
+            BroadcastMode::Commit => {
+                let deliver_tx = deliver_tx(data);
+                let data = f(&deliver_tx)?;
+                Ok(TxReceipt::committed(
+                    hash,
+                    Height::try_from(height)?,
+                    0,
+                    Some(data),
+                    Vec::new(),
+                ))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ObjectProvider for MockProvider {
+    async fn upload(
+        &self,
+        body: reqwest::Body,
+        _size: usize,
+        msg: String,
+        _chain_id: u64,
+        _auth_token: Option<String>,
+    ) -> anyhow::Result<Cid> {
+        let msg = general_purpose::URL_SAFE.decode(msg)?;
+        let signed: SignedMessage = fvm_ipld_encoding::from_slice(&msg)?;
+        let params: AddParams = signed.message.params.deserialize()?;
+        if let Some(data) = body.as_bytes() {
+            let mut state = self.state.lock().unwrap();
+            state
+                .contents
+                .insert(params.cid, Bytes::copy_from_slice(data));
+        }
+        Ok(params.cid.into())
+    }
+
+    async fn download(
+        &self,
+        address: Address,
+        key: &str,
+        range: Option<String>,
+        _height: u64,
+        _auth_token: Option<String>,
+    ) -> anyhow::Result<reqwest::Response> {
+        let data = self.content(address, key)?;
+        let (status, data) = match range {
+            Some(range) => {
+                let (start, end) = parse_range(&range, data.len())?;
+                (206, data.slice(start..end))
+            }
+            None => (200, data),
+        };
+        let response = http::Response::builder().status(status).body(data)?;
+        Ok(reqwest::Response::from(response))
+    }
+
+    async fn size(
+        &self,
+        address: Address,
+        key: &str,
+        _height: u64,
+        _auth_token: Option<String>,
+    ) -> anyhow::Result<usize> {
+        Ok(self.content(address, key)?.len())
+    }
+}
+
+impl MockProvider {
+    /// Returns the contents of the object at the given key.
+    fn content(&self, address: Address, key: &str) -> anyhow::Result<Bytes> {
+        let mut state = self.state.lock().unwrap();
+        let object = state
+            .machine(&address)?
+            .objects
+            .get(key.as_bytes())
+            .cloned()
+            .ok_or_else(|| anyhow!("object not found"))?;
+        let cid = cid::Cid::try_from(object.cid.0)?;
+        state
+            .contents
+            .get(&cid)
+            .cloned()
+            .ok_or_else(|| anyhow!("object contents for {} are not stored in the mock", cid))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fvm_shared::address::Network;
+    use tokio::io::AsyncReadExt;
+
+    use super::*;
+    use crate::machine::{
+        accumulator::Accumulator,
+        objectstore::{write_cid_content, GetByCidOptions, ObjectStore},
+        Machine,
+    };
+
+    #[test]
+    fn test_accumulator_peaks() {
+        let mut acc = MockAccumulator::default();
+        assert_eq!(acc.root().unwrap(), cid::Cid::default());
+        for i in 0..7u8 {
+            assert_eq!(acc.push(vec![i]).unwrap(), i as u64);
+        }
+        // 7 leaves = peaks of heights 2, 1, 0
+        let heights: Vec<u32> = acc.peaks.iter().map(|(h, _)| *h).collect();
+        assert_eq!(heights, vec![2, 1, 0]);
+
+        let mut other = MockAccumulator::default();
+        for i in 0..7u8 {
+            other.push(vec![i]).unwrap();
+        }
+        assert_eq!(acc.root().unwrap(), other.root().unwrap());
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("0-9", 100).unwrap(), (0, 10));
+        assert_eq!(parse_range("90-", 100).unwrap(), (90, 100));
+        assert_eq!(parse_range("-10", 100).unwrap(), (90, 100));
+        assert_eq!(parse_range("0-999", 100).unwrap(), (0, 100));
+        assert!(parse_range("50-10", 100).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_key_with_spaces() {
+        let provider = MockProvider::new(ChainID::from(1));
+        let data = b"id,name\n1,a b\n".to_vec();
+        let cid: Cid = adm_verify::object::object_cid(&data).unwrap().into();
+        provider.put_content(cid, data);
+        let address = Address::new_id(2000);
+        let key = "my dir/a b.csv";
+        provider.state.lock().unwrap().machines.insert(
+            address,
+            MockMachine {
+                owner: address,
+                kind: Kind::ObjectStore,
+                write_access: WriteAccess::OnlyOwner,
+                objects: BTreeMap::from([(
+                    key.as_bytes().to_vec(),
+                    Object {
+                        cid: cid.0.into(),
+                        size: 14,
+                        metadata: HashMap::new(),
+                        resolved: true,
+                    },
+                )]),
+                accumulator: MockAccumulator::default(),
+            },
+        );
+
+        // Keys are passed to the provider as-is; encoding them is the provider's job
+        let store = ObjectStore::attach(address);
+        let schema = store
+            .peek_schema(&provider, key, Default::default())
+            .await
+            .unwrap();
+        assert_eq!(schema.size, 14);
+        assert_eq!(schema.columns.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_accumulator_snapshot() {
+        let provider = MockProvider::new(ChainID::from(1));
+        let address = Address::new_id(2000);
+        let mut accumulator = MockAccumulator::default();
+        for i in 0..11u8 {
+            accumulator.push(vec![i]).unwrap();
+        }
+        provider.state.lock().unwrap().machines.insert(
+            address,
+            MockMachine {
+                owner: address,
+                kind: Kind::Accumulator,
+                write_access: WriteAccess::OnlyOwner,
+                objects: BTreeMap::new(),
+                accumulator,
+            },
+        );
+
+        // Leaves are fetched concurrently but returned in push order
+        let snapshot = Accumulator::attach(address)
+            .snapshot(&provider, FvmQueryHeight::Committed, Network::Testnet)
+            .await
+            .unwrap();
+        let leaves: Vec<Vec<u8>> = (0..11u8).map(|i| vec![i]).collect();
+        assert_eq!(snapshot.leaves, leaves);
+        assert!(snapshot.verify().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_get_by_cid() {
+        let data = b"hello world".to_vec();
+        let cid: Cid = adm_verify::object::object_cid(&data).unwrap().into();
+        let response = |data: &[u8]| reqwest::Response::from(http::Response::new(data.to_vec()));
+
+        let (mut reader, writer) = tokio::io::duplex(64);
+        write_cid_content(response(&data), cid, writer)
+            .await
+            .unwrap();
+        let mut out = Vec::new();
+        reader.read_to_end(&mut out).await.unwrap();
+        assert_eq!(out, data);
+
+        // Content that doesn't match its CID is rejected
+        let other: Cid = adm_verify::object::object_cid(b"other").unwrap().into();
+        let (_reader, writer) = tokio::io::duplex(64);
+        assert!(write_cid_content(response(&data), other, writer)
+            .await
+            .is_err());
+
+        // Gateways are opt-in
+        let (_reader, writer) = tokio::io::duplex(64);
+        assert!(
+            ObjectStore::get_by_cid(cid, writer, GetByCidOptions::default())
+                .await
+                .is_err()
+        );
+    }
+}
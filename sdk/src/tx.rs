@@ -0,0 +1,197 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use fendermint_vm_message::{chain::ChainMessage, signed::Object as MessageObject};
+use fvm_ipld_encoding::RawBytes;
+use fvm_shared::{address::Address, econ::TokenAmount, message::Message, MethodNum};
+use tendermint::abci::response::DeliverTx;
+use tendermint_rpc::Client;
+
+use adm_provider::{
+    message::GasParams,
+    tx::{BroadcastMode, TxReceipt},
+    Provider,
+};
+use adm_signer::Signer;
+
+use crate::TxParams;
+
+/// A fluent builder for machine transactions.
+///
+/// Builders are usually created by machine methods like [`crate::machine::objectstore::ObjectStore::tx_add`],
+/// which fill in the method number and params, and then customized before being sent, e.g.,
+///
+/// ```ignore
+/// let tx = store
+///     .tx_add("my/key", cid, size)?
+///     .gas_limit(10_000_000)
+///     .broadcast(BroadcastMode::Sync)
+///     .send(&provider, &mut signer)
+///     .await?;
+/// ```
+#[derive(Clone)]
+pub struct TxBuilder<T> {
+    to: Address,
+    method_num: MethodNum,
+    params: RawBytes,
+    value: TokenAmount,
+    object: Option<MessageObject>,
+    gas_params: GasParams,
+    sequence: Option<u64>,
+    broadcast_mode: BroadcastMode,
+    decode: fn(&DeliverTx) -> anyhow::Result<T>,
+}
+
+impl<T> TxBuilder<T>
+where
+    T: Send + Sync,
+{
+    /// Create a new builder for a transaction calling `method_num` on `to`.
+    ///
+    /// `decode` is used to decode the transaction's return value.
+    pub fn new(
+        to: Address,
+        method_num: MethodNum,
+        params: RawBytes,
+        decode: fn(&DeliverTx) -> anyhow::Result<T>,
+    ) -> Self {
+        Self {
+            to,
+            method_num,
+            params,
+            value: Default::default(),
+            object: None,
+            gas_params: Default::default(),
+            sequence: None,
+            broadcast_mode: Default::default(),
+            decode,
+        }
+    }
+
+    /// Set the amount of FIL sent with the transaction.
+    pub fn value(mut self, value: TokenAmount) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Set the object carried by the transaction.
+    pub fn object(mut self, object: MessageObject) -> Self {
+        self.object = Some(object);
+        self
+    }
+
+    /// Set all gas params.
+    pub fn gas_params(mut self, gas_params: GasParams) -> Self {
+        self.gas_params = gas_params;
+        self
+    }
+
+    /// Set the gas limit.
+    pub fn gas_limit(mut self, gas_limit: u64) -> Self {
+        self.gas_params.gas_limit = gas_limit;
+        self
+    }
+
+    /// Set the gas fee cap.
+    pub fn gas_fee_cap(mut self, gas_fee_cap: TokenAmount) -> Self {
+        self.gas_params.gas_fee_cap = gas_fee_cap;
+        self
+    }
+
+    /// Set the gas premium.
+    pub fn gas_premium(mut self, gas_premium: TokenAmount) -> Self {
+        self.gas_params.gas_premium = gas_premium;
+        self
+    }
+
+    /// Override the sender sequence (nonce).
+    ///
+    /// By default, the signer's next sequence is used.
+    /// Overriding it does not change the sequence tracked by the signer.
+    pub fn sequence(mut self, sequence: u64) -> Self {
+        self.sequence = Some(sequence);
+        self
+    }
+
+    /// Apply common [`TxParams`], i.e., gas params and an optional sequence override.
+    pub fn tx_params(mut self, params: TxParams) -> Self {
+        self.gas_params = params.gas_params;
+        self.sequence = params.sequence.or(self.sequence);
+        self
+    }
+
+    /// Set the broadcast mode.
+    pub fn broadcast(mut self, broadcast_mode: BroadcastMode) -> Self {
+        self.broadcast_mode = broadcast_mode;
+        self
+    }
+
+    /// Sign and broadcast the transaction.
+    pub async fn send<C>(
+        self,
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+    ) -> anyhow::Result<TxReceipt<T>>
+    where
+        C: Client + Send + Sync,
+    {
+        let message = match self.sequence {
+            Some(sequence) => {
+                let message = Message {
+                    version: Default::default(),
+                    from: signer.address(),
+                    to: self.to,
+                    sequence,
+                    value: self.value,
+                    method_num: self.method_num,
+                    params: self.params,
+                    gas_limit: self.gas_params.gas_limit,
+                    gas_fee_cap: self.gas_params.gas_fee_cap,
+                    gas_premium: self.gas_params.gas_premium,
+                };
+                ChainMessage::Signed(signer.sign_message(message, self.object)?)
+            }
+            None => {
+                signer
+                    .transaction(
+                        self.to,
+                        self.value,
+                        self.method_num,
+                        self.params,
+                        self.object,
+                        self.gas_params,
+                    )
+                    .await?
+            }
+        };
+        provider
+            .perform(message, self.broadcast_mode, self.decode)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adm_provider::response::decode_cid;
+
+    #[test]
+    fn test_tx_builder() {
+        let tx = TxBuilder::new(Address::new_id(100), 2, RawBytes::default(), decode_cid)
+            .gas_limit(10)
+            .gas_premium(TokenAmount::from_atto(2))
+            .sequence(5)
+            .tx_params(TxParams {
+                sequence: None,
+                gas_params: GasParams {
+                    gas_limit: 20,
+                    ..Default::default()
+                },
+            })
+            .broadcast(BroadcastMode::Sync);
+        assert_eq!(tx.gas_params.gas_limit, 20);
+        assert_eq!(tx.gas_params.gas_premium, TokenAmount::default());
+        assert_eq!(tx.sequence, Some(5));
+        assert!(matches!(tx.broadcast_mode, BroadcastMode::Sync));
+    }
+}
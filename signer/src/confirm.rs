@@ -0,0 +1,419 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::fmt::{self, Display};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use fendermint_crypto::SecretKey;
+use fendermint_vm_message::{chain::ChainMessage, signed::Object, signed::SignedMessage};
+use fvm_ipld_encoding::RawBytes;
+use fvm_shared::{
+    address::Address, crypto::signature::Signature, econ::TokenAmount, message::Message, MethodNum,
+};
+use tokio::runtime::{Handle, RuntimeFlavor};
+
+use adm_provider::message::GasParams;
+
+use crate::signer::Signer;
+use crate::SubnetID;
+
+/// Number of params bytes shown in [`MessageReview::params_summary`].
+const PARAMS_PREVIEW_LEN: usize = 32;
+
+/// A message that's about to be signed, surfaced for review by [`Signer::with_confirmation`].
+#[derive(Clone, Debug)]
+pub struct MessageReview {
+    /// The signer address.
+    pub from: Address,
+    /// The recipient address, e.g., a machine.
+    pub to: Address,
+    /// The message sequence, if it's already assigned.
+    pub sequence: Option<u64>,
+    /// The method to call.
+    pub method_num: MethodNum,
+    /// The CBOR-encoded method params.
+    pub params: RawBytes,
+    /// The amount of funds sent with the message.
+    pub value: TokenAmount,
+    /// The gas limit and fees.
+    pub gas_params: GasParams,
+    /// Whether the message carries an object, e.g., an object store upload.
+    pub has_object: bool,
+}
+
+impl MessageReview {
+    fn new(message: &Message, has_object: bool) -> Self {
+        Self {
+            from: message.from,
+            to: message.to,
+            sequence: Some(message.sequence),
+            method_num: message.method_num,
+            params: message.params.clone(),
+            value: message.value.clone(),
+            gas_params: GasParams {
+                gas_limit: message.gas_limit,
+                gas_fee_cap: message.gas_fee_cap.clone(),
+                gas_premium: message.gas_premium.clone(),
+            },
+            has_object,
+        }
+    }
+
+    /// Returns the most the message can cost in gas, i.e., its gas limit priced at its gas fee cap.
+    pub fn max_fee(&self) -> TokenAmount {
+        TokenAmount::from_atto(self.gas_params.gas_fee_cap.atto() * self.gas_params.gas_limit)
+    }
+
+    /// Returns the size of the params and their first bytes in hex, e.g., "3 bytes: 0x010203".
+    pub fn params_summary(&self) -> String {
+        let params = self.params.bytes();
+        match params.len() {
+            0 => "none".into(),
+            n if n <= PARAMS_PREVIEW_LEN => format!("{} bytes: 0x{}", n, hex::encode(params)),
+            n => format!(
+                "{} bytes: 0x{}...",
+                n,
+                hex::encode(&params[..PARAMS_PREVIEW_LEN])
+            ),
+        }
+    }
+}
+
+/// Formats the review as aligned lines, with amounts in FIL.
+impl Display for MessageReview {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "from:     {}", self.from)?;
+        writeln!(f, "to:       {}", self.to)?;
+        if let Some(sequence) = self.sequence {
+            writeln!(f, "sequence: {}", sequence)?;
+        }
+        writeln!(f, "method:   {}", self.method_num)?;
+        writeln!(f, "params:   {}", self.params_summary())?;
+        if self.has_object {
+            writeln!(f, "object:   yes")?;
+        }
+        writeln!(f, "value:    {} FIL", self.value)?;
+        write!(
+            f,
+            "gas:      limit {}, fee cap {} attoFIL, premium {} attoFIL (max fee {} FIL)",
+            self.gas_params.gas_limit,
+            self.gas_params.gas_fee_cap.atto(),
+            self.gas_params.gas_premium.atto(),
+            self.max_fee()
+        )
+    }
+}
+
+/// Callback that decides whether a reviewed message is signed.
+pub type Confirmation = Arc<dyn Fn(&MessageReview) -> anyhow::Result<bool> + Send + Sync>;
+
+/// Error returned when a [`Confirming`] signer's callback declines a message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NotConfirmed {
+    /// The recipient address of the declined message.
+    pub to: Address,
+    /// The method of the declined message.
+    pub method_num: MethodNum,
+}
+
+impl Display for NotConfirmed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "message to {} (method {}) was not confirmed",
+            self.to, self.method_num
+        )
+    }
+}
+
+impl std::error::Error for NotConfirmed {}
+
+/// [`Signer`] that asks for confirmation before signing each message.
+///
+/// Created with [`Signer::with_confirmation`].
+/// Messages are only signed if the callback returns `true`; otherwise signing fails
+/// with [`NotConfirmed`], and a signer that manages a sequence doesn't use one up.
+///
+/// The callback may block, e.g., on terminal input, so it's called on a blocking thread.
+/// [`Signer::sign_message`] is synchronous, so on a multi-threaded Tokio runtime its worker
+/// thread is handed over to blocking first, and elsewhere the callback runs on the caller's thread.
+///
+/// An object upload is only confirmed once: after its signature is confirmed, signing the same
+/// message again (e.g., to retry the upload) isn't reviewed, and neither is the transaction that
+/// adds the object.
+///
+/// Only messages signed through the [`Signer`] trait are reviewed.
+/// The secret key isn't exposed, so EVM transactions, which are signed by an Ethereum wallet
+/// (e.g., deposits and transfers), fail instead of skipping the callback.
+#[derive(Clone)]
+pub struct Confirming<S> {
+    inner: S,
+    confirm: Confirmation,
+    /// The last confirmed object upload, whose add transaction doesn't need confirmation.
+    upload: Arc<Mutex<Option<ObjectMessage>>>,
+}
+
+/// Identifies a message that carries an object by what it does, not how it's signed,
+/// so an upload signature matches the transaction that adds the object.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ObjectMessage {
+    to: Address,
+    method_num: MethodNum,
+    params: RawBytes,
+    value: TokenAmount,
+}
+
+impl ObjectMessage {
+    fn of(review: &MessageReview) -> Option<Self> {
+        review.has_object.then(|| Self {
+            to: review.to,
+            method_num: review.method_num,
+            params: review.params.clone(),
+            value: review.value.clone(),
+        })
+    }
+}
+
+impl<S: Signer> Confirming<S> {
+    /// Wrap `inner` so `confirm` is called before it signs a message.
+    pub fn new(inner: S, confirm: Confirmation) -> Self {
+        Self {
+            inner,
+            confirm,
+            upload: Default::default(),
+        }
+    }
+
+    /// Returns the wrapped signer.
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Returns the wrapped signer mutably, e.g., to reset its sequence.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Returns the wrapped signer, which no longer asks for confirmation.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Confirms an upload signature, unless the same upload was already confirmed.
+    fn check_upload(&self, review: &MessageReview) -> anyhow::Result<()> {
+        let upload = ObjectMessage::of(review);
+        if upload.is_some() && *self.upload.lock().unwrap() == upload {
+            return Ok(());
+        }
+        let confirmed = match Handle::try_current() {
+            // Let the runtime move its other tasks off this thread while the callback blocks
+            Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+                tokio::task::block_in_place(|| (self.confirm)(review))?
+            }
+            _ => (self.confirm)(review)?,
+        };
+        verdict(confirmed, review)?;
+        if upload.is_some() {
+            *self.upload.lock().unwrap() = upload;
+        }
+        Ok(())
+    }
+
+    /// Confirms a transaction, calling the callback on a blocking thread,
+    /// so it doesn't stall the async runtime while it waits.
+    ///
+    /// A transaction that adds the last confirmed upload isn't reviewed again.
+    async fn check_blocking(&self, review: MessageReview) -> anyhow::Result<()> {
+        if let Some(message) = ObjectMessage::of(&review) {
+            let mut upload = self.upload.lock().unwrap();
+            if upload.as_ref() == Some(&message) {
+                *upload = None;
+                return Ok(());
+            }
+        }
+        let confirm = self.confirm.clone();
+        let (confirmed, review) = tokio::task::spawn_blocking(move || {
+            let confirmed = confirm(&review)?;
+            anyhow::Ok((confirmed, review))
+        })
+        .await??;
+        verdict(confirmed, &review)
+    }
+}
+
+fn verdict(confirmed: bool, review: &MessageReview) -> anyhow::Result<()> {
+    if confirmed {
+        Ok(())
+    } else {
+        Err(NotConfirmed {
+            to: review.to,
+            method_num: review.method_num,
+        }
+        .into())
+    }
+}
+
+#[async_trait]
+impl<S: Signer> Signer for Confirming<S> {
+    fn address(&self) -> Address {
+        self.inner.address()
+    }
+
+    /// Returns `None`, since anything signed with the key directly wouldn't be reviewed.
+    fn secret_key(&self) -> Option<SecretKey> {
+        None
+    }
+
+    fn subnet_id(&self) -> Option<SubnetID> {
+        self.inner.subnet_id()
+    }
+
+    async fn transaction(
+        &mut self,
+        to: Address,
+        value: TokenAmount,
+        method_num: MethodNum,
+        params: RawBytes,
+        object: Option<Object>,
+        gas_params: GasParams,
+    ) -> anyhow::Result<ChainMessage> {
+        // The sequence is assigned by the inner signer, so only use it up once confirmed
+        let review = MessageReview {
+            from: self.address(),
+            to,
+            sequence: None,
+            method_num,
+            params: params.clone(),
+            value: value.clone(),
+            gas_params: gas_params.clone(),
+            has_object: object.is_some(),
+        };
+        self.check_blocking(review).await?;
+        self.inner
+            .transaction(to, value, method_num, params, object, gas_params)
+            .await
+    }
+
+    fn sign_message(
+        &self,
+        message: Message,
+        object: Option<Object>,
+    ) -> anyhow::Result<SignedMessage> {
+        self.check_upload(&MessageReview::new(&message, object.is_some()))?;
+        self.inner.sign_message(message, object)
+    }
+
+    fn verify_message(
+        &self,
+        message: &Message,
+        object: &Option<Object>,
+        signature: &Signature,
+    ) -> anyhow::Result<()> {
+        self.inner.verify_message(message, object, signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::{key::random_secretkey, AccountKind, Wallet};
+
+    #[tokio::test]
+    async fn test_confirmation() {
+        let subnet_id = SubnetID::from_str("r/foobar").unwrap();
+        let wallet =
+            Wallet::new_secp256k1(random_secretkey(), AccountKind::Ethereum, subnet_id).unwrap();
+        let reviews = Arc::new(Mutex::new(Vec::new()));
+        let seen = reviews.clone();
+        let mut signer = wallet.with_confirmation(move |review: &MessageReview| {
+            seen.lock().unwrap().push(review.clone());
+            Ok(review.method_num != 3)
+        });
+
+        let to = Address::new_id(100);
+        let params = RawBytes::new(vec![1, 2, 3]);
+        let tx = |method_num| {
+            let mut signer = signer.clone();
+            let params = params.clone();
+            async move {
+                signer
+                    .transaction(
+                        to,
+                        TokenAmount::default(),
+                        method_num,
+                        params,
+                        None,
+                        GasParams::default(),
+                    )
+                    .await
+            }
+        };
+        let err = tx(3).await.unwrap_err();
+        assert!(err.downcast_ref::<NotConfirmed>().is_some());
+        assert!(tx(2).await.is_ok());
+
+        // The rejected message didn't use up a sequence
+        let ChainMessage::Signed(signed) = signer
+            .transaction(
+                to,
+                TokenAmount::default(),
+                2,
+                RawBytes::default(),
+                None,
+                GasParams::default(),
+            )
+            .await
+            .unwrap()
+        else {
+            panic!("expected a signed message");
+        };
+        assert_eq!(signed.message.sequence, 1);
+
+        let reviews = reviews.lock().unwrap();
+        assert_eq!(reviews.len(), 3);
+        assert_eq!(reviews[0].to, to);
+        assert_eq!(reviews[0].params_summary(), "3 bytes: 0x010203");
+        assert!(signer.secret_key().is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_upload_confirmation() {
+        let subnet_id = SubnetID::from_str("r/foobar").unwrap();
+        let wallet =
+            Wallet::new_secp256k1(random_secretkey(), AccountKind::Ethereum, subnet_id).unwrap();
+        let reviews = Arc::new(Mutex::new(0));
+        let seen = reviews.clone();
+        let mut signer = wallet.with_confirmation(move |_: &MessageReview| {
+            *seen.lock().unwrap() += 1;
+            Ok(true)
+        });
+
+        let to = Address::new_id(100);
+        let params = RawBytes::new(vec![1, 2, 3]);
+        let object = || Some(Object::new(b"foo".to_vec(), cid::Cid::default(), to));
+        let message =
+            adm_provider::message::object_upload_message(signer.address(), to, 2, params.clone());
+        // Retrying the upload signs the same message again
+        signer.sign_message(message.clone(), object()).unwrap();
+        signer.sign_message(message, object()).unwrap();
+        // The upload's confirmation covers its add once
+        for reviewed in [1, 2] {
+            signer
+                .transaction(
+                    to,
+                    TokenAmount::default(),
+                    2,
+                    params.clone(),
+                    object(),
+                    GasParams::default(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(*reviews.lock().unwrap(), reviewed);
+        }
+    }
+}
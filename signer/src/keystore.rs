@@ -0,0 +1,189 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+use fendermint_crypto::SecretKey;
+use serde::{Deserialize, Serialize};
+
+use crate::key::parse_secret_key;
+
+/// On-disk format of a [`Keystore`].
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct KeystoreFile {
+    /// Name of the default key.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default: Option<String>,
+    /// Hex-encoded secret keys by name.
+    keys: BTreeMap<String, String>,
+}
+
+/// A local, file-backed store of named secret keys.
+///
+/// Keys are stored hex-encoded and unencrypted in a JSON file.
+/// On Unix, the file is only readable and writable by its owner.
+#[derive(Debug)]
+pub struct Keystore {
+    path: PathBuf,
+    file: KeystoreFile,
+}
+
+impl Keystore {
+    /// Open the keystore at the given path.
+    /// An empty keystore is returned if the file does not exist yet.
+    pub fn open(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let path = path.into();
+        let file = if path.exists() {
+            let data = fs::read(&path)
+                .with_context(|| format!("failed to read keystore {}", path.display()))?;
+            serde_json::from_slice(&data)
+                .with_context(|| format!("failed to parse keystore {}", path.display()))?
+        } else {
+            KeystoreFile::default()
+        };
+        Ok(Self { path, file })
+    }
+
+    /// Returns the keystore file path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the names of all stored keys.
+    pub fn names(&self) -> Vec<String> {
+        self.file.keys.keys().cloned().collect()
+    }
+
+    /// Returns the name of the default key if it exists.
+    pub fn default_name(&self) -> Option<String> {
+        self.file.default.clone()
+    }
+
+    /// Add a new named key. Fails if a key with the same name already exists.
+    pub fn insert(&mut self, name: &str, sk: &SecretKey) -> anyhow::Result<()> {
+        if name.is_empty() {
+            return Err(anyhow!("key name cannot be empty"));
+        }
+        if self.file.keys.contains_key(name) {
+            return Err(anyhow!("key '{}' already exists", name));
+        }
+        self.file
+            .keys
+            .insert(name.to_string(), hex::encode(sk.serialize()));
+        Ok(())
+    }
+
+    /// Remove a named key. The default is unset if it refers to the removed key.
+    pub fn remove(&mut self, name: &str) -> anyhow::Result<()> {
+        if self.file.keys.remove(name).is_none() {
+            return Err(anyhow!("key '{}' not found", name));
+        }
+        if self.file.default.as_deref() == Some(name) {
+            self.file.default = None;
+        }
+        Ok(())
+    }
+
+    /// Get a named key.
+    pub fn get(&self, name: &str) -> anyhow::Result<SecretKey> {
+        let sk_hex = self
+            .file
+            .keys
+            .get(name)
+            .ok_or_else(|| anyhow!("key '{}' not found", name))?;
+        parse_secret_key(sk_hex)
+    }
+
+    /// Get the default key if one is set.
+    pub fn default_key(&self) -> anyhow::Result<Option<SecretKey>> {
+        match &self.file.default {
+            Some(name) => Ok(Some(self.get(name)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Set the named key as the default.
+    pub fn set_default(&mut self, name: &str) -> anyhow::Result<()> {
+        if !self.file.keys.contains_key(name) {
+            return Err(anyhow!("key '{}' not found", name));
+        }
+        self.file.default = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Write the keystore to disk, creating parent directories as needed.
+    ///
+    /// The keystore is written to a new file that only the owner can read,
+    /// which then replaces the old one, so keys are never exposed or half-written.
+    pub fn save(&self) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let data = serde_json::to_vec_pretty(&self.file)?;
+        let mut part_path = self.path.clone().into_os_string();
+        part_path.push(".part");
+        let part_path = PathBuf::from(part_path);
+        // A leftover file from an earlier failed save may have other permissions
+        if let Err(e) = fs::remove_file(&part_path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                return Err(e.into());
+            }
+        }
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options
+            .open(&part_path)
+            .with_context(|| format!("failed to create {}", part_path.display()))?;
+        file.write_all(&data)
+            .and_then(|()| file.sync_all())
+            .with_context(|| format!("failed to write keystore {}", self.path.display()))?;
+        fs::rename(&part_path, &self.path)
+            .with_context(|| format!("failed to write keystore {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key::random_secretkey;
+
+    #[test]
+    fn test_keystore_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("adm-keystore-{}", rand::random::<u64>()));
+        let path = dir.join("keystore.json");
+
+        let sk = random_secretkey();
+        let mut ks = Keystore::open(&path).unwrap();
+        assert!(ks.default_key().unwrap().is_none());
+        ks.insert("alice", &sk).unwrap();
+        assert!(ks.insert("alice", &sk).is_err());
+        ks.set_default("alice").unwrap();
+        ks.save().unwrap();
+
+        let ks = Keystore::open(&path).unwrap();
+        assert_eq!(ks.names(), vec!["alice".to_string()]);
+        assert_eq!(ks.default_name(), Some("alice".to_string()));
+        assert_eq!(
+            hex::encode(ks.default_key().unwrap().unwrap().serialize()),
+            hex::encode(sk.serialize())
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
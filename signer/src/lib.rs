@@ -5,12 +5,21 @@
 //!
 //! A transaction signer for the ADM.
 
+mod confirm;
 pub mod key;
+pub mod keystore;
+mod nonce;
+mod remote;
 mod signer;
 mod subnet;
 mod void;
 mod wallet;
 
+pub use confirm::{Confirmation, Confirming, MessageReview, NotConfirmed};
+pub use nonce::{NonceManager, Reservation};
+#[cfg(feature = "aws-kms")]
+pub use remote::KmsSigningService;
+pub use remote::{HttpSigningService, RemoteSigner, SigningService};
 pub use signer::Signer;
 pub use subnet::SubnetID;
 pub use void::Void;
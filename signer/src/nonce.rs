@@ -0,0 +1,164 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use fendermint_vm_message::query::FvmQueryHeight;
+use fvm_shared::address::Address;
+use tokio::sync::Mutex;
+
+use adm_provider::query::{NotFound, QueryProvider};
+
+/// Hands out sequences (nonces) to pipelined transactions.
+///
+/// Waiting for each transaction to commit before sending the next is too slow for
+/// high-throughput ingestion. Instead, a [`NonceManager`] reserves a contiguous range of
+/// sequences up front, which can be handed to parallel transaction builders that set an
+/// explicit sequence, e.g., with `TxBuilder::sequence` in the SDK.
+///
+/// Cloning is cheap: clones share the same next sequence.
+/// A manager created with [`crate::Wallet::nonce_manager`] also shares it with the wallet,
+/// so transactions signed by the wallet never reuse a reserved sequence.
+#[derive(Debug, Clone)]
+pub struct NonceManager {
+    address: Address,
+    next: Arc<Mutex<u64>>,
+}
+
+impl NonceManager {
+    /// Create a new manager for `address` that starts at the given sequence.
+    pub fn new(address: Address, next: u64) -> Self {
+        Self::with_shared(address, Arc::new(Mutex::new(next)))
+    }
+
+    /// Create a new manager using a shared next sequence.
+    pub(crate) fn with_shared(address: Address, next: Arc<Mutex<u64>>) -> Self {
+        Self { address, next }
+    }
+
+    /// Create a new manager for `address` that starts at the account's sequence,
+    /// including pending transactions.
+    pub async fn from_chain(
+        address: Address,
+        provider: &impl QueryProvider,
+    ) -> anyhow::Result<Self> {
+        let manager = Self::new(address, 0);
+        manager.resync(provider).await?;
+        Ok(manager)
+    }
+
+    /// Returns the managed account address.
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Returns the next unreserved sequence.
+    pub async fn next(&self) -> u64 {
+        *self.next.lock().await
+    }
+
+    /// Reserve `count` contiguous sequences.
+    pub async fn reserve(&self, count: u64) -> Reservation {
+        let mut next = self.next.lock().await;
+        let start = *next;
+        *next += count;
+        Reservation {
+            manager: self.clone(),
+            range: start..*next,
+        }
+    }
+
+    /// Reset the next sequence to the account's sequence, including pending transactions.
+    ///
+    /// Use this to recover when transactions were lost or sent with sequences from elsewhere.
+    pub async fn resync(&self, provider: &impl QueryProvider) -> anyhow::Result<()> {
+        let res = provider
+            .actor_state(&self.address, FvmQueryHeight::Pending)
+            .await?;
+        match res.value {
+            Some((_, state)) => {
+                *self.next.lock().await = state.sequence;
+                Ok(())
+            }
+            None => Err(NotFound::new(format!(
+                "failed to sync sequence; actor {} cannot be found",
+                self.address
+            ))
+            .into()),
+        }
+    }
+}
+
+/// A contiguous range of sequences reserved with [`NonceManager::reserve`].
+#[derive(Debug)]
+pub struct Reservation {
+    manager: NonceManager,
+    range: Range<u64>,
+}
+
+impl Reservation {
+    /// Returns the reserved sequences.
+    pub fn range(&self) -> Range<u64> {
+        self.range.clone()
+    }
+
+    /// Returns the number of reserved sequences.
+    pub fn len(&self) -> u64 {
+        self.range.end - self.range.start
+    }
+
+    /// Returns whether no sequences were reserved.
+    pub fn is_empty(&self) -> bool {
+        self.range.is_empty()
+    }
+
+    /// Release the sequences from `from` to the end of the reservation after a failure,
+    /// so the next reservation starts at `from`.
+    ///
+    /// The network won't accept a transaction whose sequence follows a gap, so any
+    /// reservation made after this one is invalidated as well and must not be used.
+    /// Returns an error if `from` is outside the reservation.
+    pub async fn rollback(self, from: u64) -> anyhow::Result<()> {
+        if !self.range.contains(&from) {
+            return Err(anyhow!(
+                "sequence {} is not in reservation {:?}",
+                from,
+                self.range
+            ));
+        }
+        let mut next = self.manager.next.lock().await;
+        *next = (*next).min(from);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reserve_and_rollback() {
+        let manager = NonceManager::new(Address::new_id(1001), 10);
+
+        let first = manager.reserve(5).await;
+        let second = manager.reserve(3).await;
+        assert_eq!(first.range(), 10..15);
+        assert_eq!(second.range(), 15..18);
+        assert_eq!(manager.next().await, 18);
+
+        second.rollback(16).await.unwrap();
+        assert_eq!(manager.next().await, 16);
+
+        // Rolling back an earlier reservation rewinds past later ones
+        first.rollback(12).await.unwrap();
+        assert_eq!(manager.next().await, 12);
+
+        let third = manager.reserve(2).await;
+        assert_eq!(third.range(), 12..14);
+        assert_eq!(third.len(), 2);
+        assert!(third.rollback(20).await.is_err());
+        assert!(manager.reserve(0).await.is_empty());
+    }
+}
@@ -0,0 +1,506 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use fendermint_crypto::SecretKey;
+use fendermint_vm_actor_interface::eam::EthAddress;
+use fendermint_vm_message::{
+    chain::ChainMessage,
+    query::FvmQueryHeight,
+    signed::{Object, Signable, SignedMessage},
+};
+use fvm_ipld_encoding::RawBytes;
+use fvm_shared::{
+    address::Address, chainid::ChainID, crypto::signature::Signature, econ::TokenAmount,
+    message::Message, MethodNum,
+};
+use libsecp256k1::{PublicKey, PublicKeyFormat, RecoveryId};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    runtime::{Handle, RuntimeFlavor},
+    sync::Mutex,
+};
+
+use adm_provider::{
+    message::GasParams,
+    query::{NotFound, QueryProvider},
+};
+
+use crate::signer::Signer;
+use crate::{AccountKind, SubnetID};
+
+#[cfg(feature = "aws-kms")]
+mod kms;
+
+#[cfg(feature = "aws-kms")]
+pub use kms::KmsSigningService;
+
+/// An external service that holds a secp256k1 key and signs digests with it.
+#[async_trait]
+pub trait SigningService: Send + Sync {
+    /// Returns the public key of the signing key.
+    async fn public_key(&self) -> anyhow::Result<PublicKey>;
+
+    /// Sign a 32-byte digest and return the compact (r, s) signature.
+    ///
+    /// The digest is already hashed and must be signed as is.
+    async fn sign_digest(&self, digest: &[u8; 32]) -> anyhow::Result<[u8; 64]>;
+}
+
+/// [`SigningService`] implementation that talks to a signing server over HTTP.
+///
+/// The server must implement two JSON endpoints, where all bytes are hex-encoded:
+///
+/// - `GET {url}/keys/{key_id}` returns `{"public_key": "04..."}`,
+///   the uncompressed public key.
+/// - `POST {url}/keys/{key_id}/sign` with `{"digest": "..."}` returns `{"signature": "..."}`,
+///   the 64-byte compact (r, s) signature. A trailing recovery byte is ignored.
+///
+/// If a token is set, it's sent as a bearer token with every request.
+#[derive(Clone, Debug)]
+pub struct HttpSigningService {
+    client: reqwest::Client,
+    url: Url,
+    key_id: String,
+    token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PublicKeyResponse {
+    public_key: String,
+}
+
+#[derive(Serialize)]
+struct SignRequest {
+    digest: String,
+}
+
+#[derive(Deserialize)]
+struct SignResponse {
+    signature: String,
+}
+
+impl HttpSigningService {
+    /// Create a new HTTP signing service for the key with the given ID.
+    pub fn new(url: Url, key_id: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+            key_id: key_id.into(),
+            token: None,
+        }
+    }
+
+    /// Set a bearer token used to authenticate requests.
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Returns the URL of the key, followed by `segments`.
+    ///
+    /// The key ID is percent-encoded as a single path segment, so it can contain `/` or `?`.
+    fn key_url(&self, segments: &[&str]) -> anyhow::Result<Url> {
+        let mut url = self.url.clone();
+        url.path_segments_mut()
+            .map_err(|_| anyhow!("invalid signing service URL: {}", self.url))?
+            .pop_if_empty()
+            .push("keys")
+            .push(&self.key_id)
+            .extend(segments);
+        Ok(url)
+    }
+
+    fn authorize(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+}
+
+#[async_trait]
+impl SigningService for HttpSigningService {
+    async fn public_key(&self) -> anyhow::Result<PublicKey> {
+        let request = self.authorize(self.client.get(self.key_url(&[])?));
+        let response: PublicKeyResponse = request.send().await?.error_for_status()?.json().await?;
+        let bytes = decode_hex(&response.public_key)?;
+        PublicKey::parse_slice(&bytes, None).map_err(|e| anyhow!("invalid public key: {:?}", e))
+    }
+
+    async fn sign_digest(&self, digest: &[u8; 32]) -> anyhow::Result<[u8; 64]> {
+        let request = self
+            .authorize(self.client.post(self.key_url(&["sign"])?))
+            .json(&SignRequest {
+                digest: hex::encode(digest),
+            });
+        let response: SignResponse = request.send().await?.error_for_status()?.json().await?;
+        let bytes = decode_hex(&response.signature)?;
+        if bytes.len() != 64 && bytes.len() != 65 {
+            return Err(anyhow!(
+                "invalid signature length: expected 64 bytes, got {}",
+                bytes.len()
+            ));
+        }
+        let mut signature = [0u8; 64];
+        signature.copy_from_slice(&bytes[..64]);
+        Ok(signature)
+    }
+}
+
+/// [`Signer`] implementation that delegates signing to a [`SigningService`],
+/// so that the secret key never enters process memory.
+///
+/// Like [`crate::Wallet`], [`RemoteSigner`] manages the account's sequence (nonce) with a mutex.
+///
+/// Note, [`Signer::sign_message`] is synchronous, so it blocks on the service.
+/// It must be called from within a multi-threaded Tokio runtime, and fails otherwise.
+/// Prefer [`RemoteSigner::sign`] from async code.
+pub struct RemoteSigner<S> {
+    service: Arc<S>,
+    addr: Address,
+    subnet_id: SubnetID,
+    sequence: Arc<Mutex<u64>>,
+}
+
+// Implemented manually because the service itself doesn't need to be cloneable.
+impl<S> Clone for RemoteSigner<S> {
+    fn clone(&self) -> Self {
+        Self {
+            service: self.service.clone(),
+            addr: self.addr,
+            subnet_id: self.subnet_id.clone(),
+            sequence: self.sequence.clone(),
+        }
+    }
+}
+
+impl<S: SigningService> RemoteSigner<S> {
+    /// Returns a new [`RemoteSigner`] for the key held by the given [`SigningService`].
+    ///
+    /// Note, subnets only support [`AccountKind::Ethereum`].
+    pub async fn new(service: S, kind: AccountKind, subnet_id: SubnetID) -> anyhow::Result<Self> {
+        let pk = service.public_key().await?.serialize();
+        let addr = match kind {
+            AccountKind::Regular => Address::new_secp256k1(&pk)?,
+            AccountKind::Ethereum => Address::from(EthAddress::new_secp256k1(&pk)?),
+        };
+        Ok(Self {
+            service: Arc::new(service),
+            addr,
+            subnet_id,
+            sequence: Arc::new(Mutex::new(0)),
+        })
+    }
+
+    /// Initialize sequence from the actor's on-chain state.
+    pub async fn init_sequence(&mut self, provider: &impl QueryProvider) -> anyhow::Result<()> {
+        let res = provider
+            .actor_state(&self.addr, FvmQueryHeight::Pending)
+            .await?;
+
+        match res.value {
+            Some((_, state)) => {
+                let mut sequence_guard = self.sequence.lock().await;
+                *sequence_guard = state.sequence;
+                Ok(())
+            }
+            None => Err(NotFound::new(format!(
+                "failed to init sequence; actor {} cannot be found",
+                self.addr
+            ))
+            .into()),
+        }
+    }
+
+    /// Set the sequence to the given value.
+    /// If `maybe_sequence` is `None`, it's fetched from the actor's on-chain state.
+    pub async fn set_sequence(
+        &mut self,
+        maybe_sequence: Option<u64>,
+        provider: &impl QueryProvider,
+    ) -> anyhow::Result<()> {
+        if let Some(sequence) = maybe_sequence {
+            let mut sequence_guard = self.sequence.lock().await;
+            *sequence_guard = sequence;
+        } else {
+            self.init_sequence(provider).await?;
+        }
+        Ok(())
+    }
+
+    /// Sign a message with the remote key.
+    ///
+    /// The signature is verified locally before it's returned.
+    pub async fn sign(
+        &self,
+        message: Message,
+        object: Option<Object>,
+    ) -> anyhow::Result<SignedMessage> {
+        let chain_id = self.subnet_id.chain_id();
+        let digest = signing_digest(&message, &object, &chain_id)?;
+        let compact = self.service.sign_digest(&digest).await?;
+        let signature = Signature::new_secp256k1(recoverable_signature(&digest, &compact, |pk| {
+            self.is_signer_key(pk)
+        })?);
+        SignedMessage::verify_signature(&message, &object, &signature, &chain_id)?;
+        Ok(SignedMessage {
+            message,
+            signature,
+            object,
+        })
+    }
+
+    fn is_signer_key(&self, pk: &PublicKey) -> bool {
+        let pk = pk.serialize();
+        let addr = if self.addr.protocol() == fvm_shared::address::Protocol::Delegated {
+            EthAddress::new_secp256k1(&pk).map(Address::from).ok()
+        } else {
+            Address::new_secp256k1(&pk).ok()
+        };
+        addr == Some(self.addr)
+    }
+}
+
+#[async_trait]
+impl<S: SigningService + 'static> Signer for RemoteSigner<S> {
+    fn address(&self) -> Address {
+        self.addr
+    }
+
+    fn secret_key(&self) -> Option<SecretKey> {
+        None
+    }
+
+    fn subnet_id(&self) -> Option<SubnetID> {
+        Some(self.subnet_id.clone())
+    }
+
+    async fn transaction(
+        &mut self,
+        to: Address,
+        value: TokenAmount,
+        method_num: MethodNum,
+        params: RawBytes,
+        object: Option<Object>,
+        gas_params: GasParams,
+    ) -> anyhow::Result<ChainMessage> {
+        let mut sequence_guard = self.sequence.lock().await;
+        let sequence = *sequence_guard;
+        let message = Message {
+            version: Default::default(),
+            from: self.addr,
+            to,
+            sequence,
+            value,
+            method_num,
+            params,
+            gas_limit: gas_params.gas_limit,
+            gas_fee_cap: gas_params.gas_fee_cap,
+            gas_premium: gas_params.gas_premium,
+        };
+        // Only consume the sequence if the service signed the message.
+        let signed = self.sign(message, object).await?;
+        *sequence_guard += 1;
+        Ok(ChainMessage::Signed(signed))
+    }
+
+    fn sign_message(
+        &self,
+        message: Message,
+        object: Option<Object>,
+    ) -> anyhow::Result<SignedMessage> {
+        // `block_in_place` panics on a current-thread runtime, so check the flavor first
+        let handle = Handle::try_current()
+            .map_err(|_| anyhow!("remote signing requires a Tokio runtime"))?;
+        if handle.runtime_flavor() != RuntimeFlavor::MultiThread {
+            return Err(anyhow!(
+                "remote signing with sign_message requires a multi-threaded Tokio runtime; use RemoteSigner::sign instead"
+            ));
+        }
+        tokio::task::block_in_place(|| handle.block_on(self.sign(message, object)))
+    }
+
+    fn verify_message(
+        &self,
+        message: &Message,
+        object: &Option<Object>,
+        signature: &Signature,
+    ) -> anyhow::Result<()> {
+        SignedMessage::verify_signature(message, object, signature, &self.subnet_id.chain_id())?;
+        Ok(())
+    }
+}
+
+/// Returns the 32-byte digest that must be signed for the given message.
+///
+/// Messages from Ethereum accounts are signed over the Ethereum transaction hash.
+/// Other messages are signed over the Blake2b-256 hash of their signable bytes.
+fn signing_digest(
+    message: &Message,
+    object: &Option<Object>,
+    chain_id: &ChainID,
+) -> anyhow::Result<[u8; 32]> {
+    match SignedMessage::signable(message, object, chain_id)? {
+        Signable::Ethereum((hash, _)) => Ok(hash.0),
+        Signable::Regular(data) | Signable::RegularFromEth((data, _)) => {
+            let hash = blake2b_simd::Params::new()
+                .hash_length(32)
+                .to_state()
+                .update(&data)
+                .finalize();
+            let mut digest = [0u8; 32];
+            digest.copy_from_slice(hash.as_bytes());
+            Ok(digest)
+        }
+    }
+}
+
+/// Returns a 65-byte (r, s, v) signature by finding the recovery ID that
+/// recovers a public key accepted by `is_signer`.
+fn recoverable_signature(
+    digest: &[u8; 32],
+    compact: &[u8; 64],
+    is_signer: impl Fn(&PublicKey) -> bool,
+) -> anyhow::Result<Vec<u8>> {
+    let mut signature = libsecp256k1::Signature::parse_standard(compact)
+        .map_err(|e| anyhow!("invalid signature: {:?}", e))?;
+    // Remote services may return a high-s signature, which the FVM rejects.
+    signature.normalize_s();
+    let message = libsecp256k1::Message::parse(digest);
+    for v in 0..2 {
+        let recovery_id = RecoveryId::parse(v).expect("recovery id is valid");
+        if let Ok(pk) = libsecp256k1::recover(&message, &signature, &recovery_id) {
+            if is_signer(&pk) {
+                let mut bytes = signature.serialize().to_vec();
+                bytes.push(v);
+                return Ok(bytes);
+            }
+        }
+    }
+    Err(anyhow!("signature was not created by the signer key"))
+}
+
+fn decode_hex(s: &str) -> anyhow::Result<Vec<u8>> {
+    Ok(hex::decode(s.trim_start_matches("0x"))?)
+}
+
+/// Parse an uncompressed public key from the end of a DER-encoded `SubjectPublicKeyInfo`.
+#[cfg_attr(not(feature = "aws-kms"), allow(dead_code))]
+fn parse_spki_public_key(der: &[u8]) -> anyhow::Result<PublicKey> {
+    let len = libsecp256k1::util::FULL_PUBLIC_KEY_SIZE;
+    if der.len() < len {
+        return Err(anyhow!("invalid public key: too short"));
+    }
+    PublicKey::parse_slice(&der[der.len() - len..], Some(PublicKeyFormat::Full))
+        .map_err(|e| anyhow!("invalid public key: {:?}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::{key::random_secretkey, Wallet};
+
+    /// A signing service backed by a local key.
+    struct LocalService(libsecp256k1::SecretKey);
+
+    #[async_trait]
+    impl SigningService for LocalService {
+        async fn public_key(&self) -> anyhow::Result<PublicKey> {
+            Ok(PublicKey::from_secret_key(&self.0))
+        }
+
+        async fn sign_digest(&self, digest: &[u8; 32]) -> anyhow::Result<[u8; 64]> {
+            let (signature, _) = libsecp256k1::sign(&libsecp256k1::Message::parse(digest), &self.0);
+            Ok(signature.serialize())
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_remote_signer_matches_wallet() {
+        let sk = random_secretkey();
+        let subnet_id = SubnetID::from_str("r/foobar").unwrap();
+        let wallet =
+            Wallet::new_secp256k1(sk.clone(), AccountKind::Ethereum, subnet_id.clone()).unwrap();
+        let service = LocalService(libsecp256k1::SecretKey::parse_slice(&sk.serialize()).unwrap());
+        let remote = RemoteSigner::new(service, AccountKind::Ethereum, subnet_id)
+            .await
+            .unwrap();
+        assert_eq!(remote.address(), wallet.address());
+
+        let message = Message {
+            version: Default::default(),
+            from: wallet.address(),
+            to: Address::new_id(100),
+            sequence: 7,
+            value: TokenAmount::from_atto(1),
+            method_num: 0,
+            params: Default::default(),
+            gas_limit: 1_000_000,
+            gas_fee_cap: TokenAmount::from_atto(100),
+            gas_premium: TokenAmount::from_atto(100),
+        };
+        let expected = wallet.sign_message(message.clone(), None).unwrap();
+        let signed = remote.sign_message(message.clone(), None).unwrap();
+        assert_eq!(signed.signature, expected.signature);
+        remote
+            .verify_message(&message, &None, &signed.signature)
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_remote_signer_current_thread() {
+        let sk = random_secretkey();
+        let service = LocalService(libsecp256k1::SecretKey::parse_slice(&sk.serialize()).unwrap());
+        let subnet_id = SubnetID::from_str("r/foobar").unwrap();
+        let remote = RemoteSigner::new(service, AccountKind::Ethereum, subnet_id)
+            .await
+            .unwrap();
+        let message = Message {
+            version: Default::default(),
+            from: remote.address(),
+            to: Address::new_id(100),
+            sequence: 0,
+            value: TokenAmount::from_atto(1),
+            method_num: 0,
+            params: Default::default(),
+            gas_limit: 1_000_000,
+            gas_fee_cap: TokenAmount::from_atto(100),
+            gas_premium: TokenAmount::from_atto(100),
+        };
+        // Fails instead of panicking in block_in_place
+        assert!(remote.sign_message(message.clone(), None).is_err());
+        assert!(remote.sign(message, None).await.is_ok());
+    }
+
+    #[test]
+    fn test_key_url() {
+        let service =
+            HttpSigningService::new(Url::parse("https://signer.test/v1/").unwrap(), "a/b c");
+        assert_eq!(
+            service.key_url(&["sign"]).unwrap().as_str(),
+            "https://signer.test/v1/keys/a%2Fb%20c/sign"
+        );
+        assert_eq!(
+            service.key_url(&[]).unwrap().as_str(),
+            "https://signer.test/v1/keys/a%2Fb%20c"
+        );
+    }
+
+    #[test]
+    fn test_parse_spki_public_key() {
+        let sk = libsecp256k1::SecretKey::parse_slice(&random_secretkey().serialize()).unwrap();
+        let pk = PublicKey::from_secret_key(&sk);
+        // secp256k1 SubjectPublicKeyInfo header followed by the uncompressed point.
+        let mut der = hex::decode("3056301006072a8648ce3d020106052b8104000a034200").unwrap();
+        der.extend_from_slice(&pk.serialize());
+        assert_eq!(parse_spki_public_key(&der).unwrap(), pk);
+        assert!(parse_spki_public_key(&der[..10]).is_err());
+    }
+}
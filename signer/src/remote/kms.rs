@@ -0,0 +1,66 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use aws_sdk_kms::{
+    primitives::Blob,
+    types::{MessageType, SigningAlgorithmSpec},
+    Client,
+};
+use libsecp256k1::PublicKey;
+
+use super::{parse_spki_public_key, SigningService};
+
+/// [`SigningService`] implementation backed by an AWS KMS asymmetric key.
+///
+/// The key must have the `ECC_SECG_P256K1` key spec and `SIGN_VERIFY` key usage.
+#[derive(Clone, Debug)]
+pub struct KmsSigningService {
+    client: Client,
+    key_id: String,
+}
+
+impl KmsSigningService {
+    /// Create a new KMS signing service for the key with the given ID, ARN, or alias.
+    pub fn new(client: Client, key_id: impl Into<String>) -> Self {
+        Self {
+            client,
+            key_id: key_id.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl SigningService for KmsSigningService {
+    async fn public_key(&self) -> anyhow::Result<PublicKey> {
+        let output = self
+            .client
+            .get_public_key()
+            .key_id(&self.key_id)
+            .send()
+            .await?;
+        let der = output
+            .public_key()
+            .ok_or_else(|| anyhow!("kms key {} has no public key", self.key_id))?;
+        parse_spki_public_key(der.as_ref())
+    }
+
+    async fn sign_digest(&self, digest: &[u8; 32]) -> anyhow::Result<[u8; 64]> {
+        let output = self
+            .client
+            .sign()
+            .key_id(&self.key_id)
+            .message(Blob::new(digest.to_vec()))
+            .message_type(MessageType::Digest)
+            .signing_algorithm(SigningAlgorithmSpec::EcdsaSha256)
+            .send()
+            .await?;
+        let der = output
+            .signature()
+            .ok_or_else(|| anyhow!("kms returned no signature"))?;
+        let signature = libsecp256k1::Signature::parse_der(der.as_ref())
+            .map_err(|e| anyhow!("invalid signature: {:?}", e))?;
+        Ok(signature.serialize())
+    }
+}
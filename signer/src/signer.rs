@@ -1,6 +1,8 @@
 // Copyright 2024 ADM Contributors
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use fendermint_crypto::SecretKey;
 use fendermint_vm_actor_interface::eam::EthAddress;
@@ -13,7 +15,7 @@ use fvm_shared::{
 use adm_provider::message::GasParams;
 use adm_provider::util::get_delegated_address;
 
-use crate::SubnetID;
+use crate::{Confirming, MessageReview, SubnetID};
 
 /// Trait that must be implemented by all signers.
 ///
@@ -62,4 +64,15 @@ pub trait Signer: Clone + Send + Sync {
         object: &Option<Object>,
         signature: &Signature,
     ) -> anyhow::Result<()>;
+
+    /// Returns a signer that calls `confirm` with a review of each message before signing it,
+    /// e.g., to prompt a user or show a review screen.
+    /// Messages are only signed if `confirm` returns `true`.
+    fn with_confirmation<F>(self, confirm: F) -> Confirming<Self>
+    where
+        Self: Sized,
+        F: Fn(&MessageReview) -> anyhow::Result<bool> + Send + Sync + 'static,
+    {
+        Confirming::new(self, Arc::new(confirm))
+    }
 }
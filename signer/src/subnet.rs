@@ -9,10 +9,16 @@ use std::hash::Hasher;
 use std::str::FromStr;
 
 use fnv::FnvHasher;
-use fvm_shared::chainid::ChainID;
+use fvm_shared::{
+    address::{Address, Network},
+    chainid::ChainID,
+};
 use ipc_api::{error::Error, subnet_id::MAX_CHAIN_ID};
 
-use adm_provider::util::parse_address;
+use adm_provider::util::{format_address, parse_address};
+
+/// Chain ID of the Filecoin mainnet root.
+const MAINNET_ROOT_ID: u64 = 314;
 
 fn hash(bytes: &[u8]) -> u64 {
     let mut hasher = FnvHasher::default();
@@ -56,7 +62,42 @@ impl SubnetID {
         }
     }
 
+    /// Returns the ID of the child subnet managed by the given subnet actor.
+    pub fn child(&self, actor: Address) -> anyhow::Result<Self> {
+        if !self.faux.is_empty() {
+            return Err(anyhow!("subnet {} cannot have children", self.faux));
+        }
+        Ok(Self {
+            faux: Default::default(),
+            real: ipc_api::subnet_id::SubnetID::new_from_parent(&self.real, actor),
+        })
+    }
+
+    /// Returns the address of the subnet actor that manages this subnet.
+    /// Root subnets don't have a subnet actor.
+    pub fn actor(&self) -> Option<Address> {
+        self.real.children_as_ref().last().copied()
+    }
+
+    /// Returns whether this subnet is a direct child of the given subnet.
+    pub fn is_child_of(&self, parent: &SubnetID) -> bool {
+        parent.faux.is_empty() && self.real.parent().is_some_and(|p| p == parent.real)
+    }
+
+    /// Returns the network of the subnet's addresses, i.e., [`Network::Mainnet`] for subnets
+    /// rooted in the Filecoin mainnet, and [`Network::Testnet`] otherwise.
+    pub fn address_network(&self) -> Network {
+        if self.faux.is_empty() && self.real.root_id() == MAINNET_ROOT_ID {
+            Network::Mainnet
+        } else {
+            Network::Testnet
+        }
+    }
+
     /// Returns the chain ID representation.
+    ///
+    /// Child subnet IDs are hashed from their [`fmt::Display`] string, so the chain ID doesn't
+    /// depend on the global network.
     pub fn chain_id(&self) -> ChainID {
         if self.real.is_root() {
             return if self.faux.is_empty() {
@@ -65,19 +106,31 @@ impl SubnetID {
                 ChainID::from(hash(self.faux.clone().as_bytes()))
             };
         }
-        ChainID::from(self.real.chain_id())
+        ChainID::from(hash(self.to_string().as_bytes()))
     }
 }
 
+impl From<ipc_api::subnet_id::SubnetID> for SubnetID {
+    fn from(real: ipc_api::subnet_id::SubnetID) -> Self {
+        Self {
+            faux: Default::default(),
+            real,
+        }
+    }
+}
+
+/// Child addresses are formatted for the subnet's [`SubnetID::address_network`],
+/// not the global network.
 impl fmt::Display for SubnetID {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.faux.is_empty() {
+            let network = self.address_network();
             let children_str =
                 self.real
                     .children_as_ref()
                     .iter()
                     .fold(String::new(), |mut output, s| {
-                        let _ = write!(output, "/{s}");
+                        let _ = write!(output, "/{}", format_address(s, network));
                         output
                     });
             write!(f, "/r{}{}", self.real.root_id(), children_str)
@@ -118,3 +171,67 @@ impl FromStr for SubnetID {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use fvm_shared::address::{Address, Network};
+
+    use super::{hash, SubnetID};
+
+    #[test]
+    fn test_child_subnet() {
+        let parent = SubnetID::from_str("/r314159").unwrap();
+        assert!(parent.actor().is_none());
+
+        let actor = Address::new_id(1001);
+        let child = parent.child(actor).unwrap();
+        assert_eq!(child.actor(), Some(actor));
+        assert!(child.is_child_of(&parent));
+        assert!(!parent.is_child_of(&child));
+        assert_eq!(child.parent().unwrap().to_string(), parent.to_string());
+
+        let faux = SubnetID::from_str("test").unwrap();
+        assert!(faux.child(actor).is_err());
+    }
+
+    #[test]
+    fn test_address_network() {
+        // Child addresses keep the subnet's network prefix, whatever the global network is
+        let id = "/r314159/t410f726d2jv6uj4mpkcbgg5ndlpp3l7dd5rlcpgzkoi";
+        let testnet = SubnetID::from_str(id).unwrap();
+        assert_eq!(testnet.address_network(), Network::Testnet);
+        assert_eq!(testnet.to_string(), id);
+
+        let mainnet =
+            SubnetID::from_str("/r314/t410f726d2jv6uj4mpkcbgg5ndlpp3l7dd5rlcpgzkoi").unwrap();
+        assert_eq!(mainnet.address_network(), Network::Mainnet);
+        assert_eq!(
+            mainnet.to_string(),
+            "/r314/f410f726d2jv6uj4mpkcbgg5ndlpp3l7dd5rlcpgzkoi"
+        );
+        assert_eq!(
+            SubnetID::from_str("test").unwrap().address_network(),
+            Network::Testnet
+        );
+    }
+
+    #[test]
+    fn test_chain_id() {
+        assert_eq!(
+            u64::from(SubnetID::from_str("/r314159").unwrap().chain_id()),
+            314159
+        );
+
+        // Child chain IDs hash the subnet's own address prefix, whatever the global network is
+        let testnet = "/r314159/t410f726d2jv6uj4mpkcbgg5ndlpp3l7dd5rlcpgzkoi";
+        let mainnet = "/r314/f410f726d2jv6uj4mpkcbgg5ndlpp3l7dd5rlcpgzkoi";
+        for id in [testnet, mainnet] {
+            assert_eq!(
+                u64::from(SubnetID::from_str(id).unwrap().chain_id()),
+                hash(id.as_bytes())
+            );
+        }
+    }
+}
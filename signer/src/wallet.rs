@@ -1,7 +1,6 @@
 // Copyright 2024 ADM Contributors
 // SPDX-License-Identifier: Apache-2.0, MIT
 
-use anyhow::anyhow;
 use async_trait::async_trait;
 use fendermint_crypto::SecretKey;
 use fendermint_vm_actor_interface::eam::EthAddress;
@@ -15,10 +14,13 @@ use fvm_shared::{
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
-use adm_provider::{message::GasParams, query::QueryProvider};
+use adm_provider::{
+    message::GasParams,
+    query::{NotFound, QueryProvider},
+};
 
 use crate::signer::Signer;
-use crate::SubnetID;
+use crate::{NonceManager, SubnetID};
 
 /// Indicates how an [`Address`] should be derived from a public key.
 ///
@@ -142,13 +144,22 @@ impl Wallet {
                 *sequence_guard = state.sequence;
                 Ok(())
             }
-            None => Err(anyhow!(
+            None => Err(NotFound::new(format!(
                 "failed to init sequence; actor {} cannot be found",
                 self.addr
-            )),
+            ))
+            .into()),
         }
     }
 
+    /// Returns a [`NonceManager`] that shares this wallet's sequence.
+    ///
+    /// Sequences reserved with the manager are skipped by transactions the wallet signs,
+    /// and vice versa.
+    pub fn nonce_manager(&self) -> NonceManager {
+        NonceManager::with_shared(self.addr, self.sequence.clone())
+    }
+
     /// Set the sequence to the given value.
     /// If `maybe_sequence` is `None`, it's fetched from the actor's on-chain state.
     pub async fn set_sequence(
@@ -0,0 +1,226 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Accumulator inclusion proofs.
+//!
+//! An accumulator is a Merkle mountain range: a list of perfect binary trees (peaks) of
+//! decreasing height, one for each set bit of the leaf count.
+//! Leaves are hashed with Keccak-256, parent nodes hash the DAG-CBOR encoding of their children,
+//! and the root bags the peaks from right to left.
+
+use anyhow::anyhow;
+use cid::{multihash::Multihash, Cid};
+use fvm_ipld_encoding::DAG_CBOR;
+use serde::{Deserialize, Serialize};
+use tiny_keccak::{Hasher, Keccak};
+
+/// Multihash code for Keccak-256.
+const KECCAK_256: u64 = 0x1b;
+
+/// Hash a leaf.
+pub fn leaf_hash(leaf: &[u8]) -> anyhow::Result<Cid> {
+    hash(leaf)
+}
+
+/// Hash two child nodes into their parent node.
+pub fn node_hash(left: &Cid, right: &Cid) -> anyhow::Result<Cid> {
+    hash(&fvm_ipld_encoding::to_vec(&(left, right))?)
+}
+
+/// Bag the peaks, ordered from left to right, into the accumulator root.
+///
+/// The root of an empty accumulator is the default CID.
+pub fn bag_peaks(peaks: &[Cid]) -> anyhow::Result<Cid> {
+    let mut peaks = peaks.iter().rev();
+    let Some(mut root) = peaks.next().copied() else {
+        return Ok(Cid::default());
+    };
+    for peak in peaks {
+        root = node_hash(peak, &root)?;
+    }
+    Ok(root)
+}
+
+/// A proof that a leaf is in an accumulator with a given root.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Proof {
+    /// The leaf index.
+    pub index: u64,
+    /// The number of leaves in the accumulator.
+    pub leaf_count: u64,
+    /// Sibling nodes on the path from the leaf to its peak, from the bottom up.
+    pub siblings: Vec<Cid>,
+    /// All peaks of the accumulator, from left to right.
+    pub peaks: Vec<Cid>,
+}
+
+impl Proof {
+    /// Generate a proof for the leaf at `index` from all leaves of an accumulator,
+    /// e.g., from an accumulator export.
+    pub fn generate<L: AsRef<[u8]>>(leaves: &[L], index: u64) -> anyhow::Result<Self> {
+        let leaf_count = leaves.len() as u64;
+        let (position, offset, height) = mountain(leaf_count, index)?;
+
+        let mut peaks = Vec::new();
+        let mut siblings = Vec::new();
+        let mut start = 0;
+        for h in (0..u64::BITS).rev().filter(|h| leaf_count & (1 << h) != 0) {
+            let end = start + (1usize << h);
+            let mut level = leaves[start..end]
+                .iter()
+                .map(|leaf| leaf_hash(leaf.as_ref()))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            let target = peaks.len() == position;
+            let mut local = if target { (index - offset) as usize } else { 0 };
+            while level.len() > 1 {
+                if target {
+                    siblings.push(level[local ^ 1]);
+                    local /= 2;
+                }
+                level = level
+                    .chunks(2)
+                    .map(|pair| node_hash(&pair[0], &pair[1]))
+                    .collect::<anyhow::Result<_>>()?;
+            }
+            peaks.push(level[0]);
+            start = end;
+        }
+        debug_assert_eq!(siblings.len(), height as usize);
+
+        Ok(Self {
+            index,
+            leaf_count,
+            siblings,
+            peaks,
+        })
+    }
+
+    /// Check that the leaf is in the accumulator with the given root.
+    pub fn verify(&self, leaf: &[u8], root: &Cid) -> anyhow::Result<()> {
+        let (position, offset, height) = mountain(self.leaf_count, self.index)?;
+        if self.peaks.len() != self.leaf_count.count_ones() as usize {
+            return Err(anyhow!(
+                "invalid proof: expected {} peaks for {} leaves, got {}",
+                self.leaf_count.count_ones(),
+                self.leaf_count,
+                self.peaks.len()
+            ));
+        }
+        if self.siblings.len() != height as usize {
+            return Err(anyhow!(
+                "invalid proof: expected {} siblings, got {}",
+                height,
+                self.siblings.len()
+            ));
+        }
+
+        let local = self.index - offset;
+        let mut node = leaf_hash(leaf)?;
+        for (level, sibling) in self.siblings.iter().enumerate() {
+            node = if local & (1 << level) == 0 {
+                node_hash(&node, sibling)?
+            } else {
+                node_hash(sibling, &node)?
+            };
+        }
+        if node != self.peaks[position] {
+            return Err(anyhow!("invalid proof: leaf is not under its peak"));
+        }
+        let bagged = bag_peaks(&self.peaks)?;
+        if bagged != *root {
+            return Err(anyhow!(
+                "invalid proof: peaks bag to {}, expected root {}",
+                bagged,
+                root
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Returns the position, first leaf index, and height of the peak whose mountain holds the leaf.
+fn mountain(leaf_count: u64, index: u64) -> anyhow::Result<(usize, u64, u32)> {
+    if index >= leaf_count {
+        return Err(anyhow!(
+            "leaf index {} is out of range for {} leaves",
+            index,
+            leaf_count
+        ));
+    }
+    let mut offset = 0;
+    for (position, h) in (0..u64::BITS)
+        .rev()
+        .filter(|h| leaf_count & (1 << h) != 0)
+        .enumerate()
+    {
+        let size = 1 << h;
+        if index < offset + size {
+            return Ok((position, offset, h));
+        }
+        offset += size;
+    }
+    unreachable!("index is less than the leaf count")
+}
+
+/// Hash bytes into a Keccak-256 DAG-CBOR CID.
+fn hash(data: &[u8]) -> anyhow::Result<Cid> {
+    let mut digest = [0u8; 32];
+    let mut keccak = Keccak::v256();
+    keccak.update(data);
+    keccak.finalize(&mut digest);
+    let mh = Multihash::wrap(KECCAK_256, &digest)?;
+    Ok(Cid::new_v1(DAG_CBOR, mh))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Push leaves one at a time, merging peaks of equal height, like the accumulator actor.
+    fn pushed_root(leaves: &[Vec<u8>]) -> Cid {
+        let mut peaks: Vec<(u32, Cid)> = Vec::new();
+        for leaf in leaves {
+            let mut node = leaf_hash(leaf).unwrap();
+            let mut height = 0;
+            while let Some((h, left)) = peaks.last().copied() {
+                if h != height {
+                    break;
+                }
+                peaks.pop();
+                node = node_hash(&left, &node).unwrap();
+                height += 1;
+            }
+            peaks.push((height, node));
+        }
+        let peaks: Vec<Cid> = peaks.into_iter().map(|(_, c)| c).collect();
+        bag_peaks(&peaks).unwrap()
+    }
+
+    #[test]
+    fn test_proofs() {
+        for count in 1..=9u8 {
+            let leaves: Vec<Vec<u8>> = (0..count).map(|i| vec![i]).collect();
+            let root = pushed_root(&leaves);
+            for index in 0..count as u64 {
+                let proof = Proof::generate(&leaves, index).unwrap();
+                assert!(proof.verify(&[index as u8], &root).is_ok());
+                assert!(proof.verify(&[count], &root).is_err());
+            }
+        }
+        assert!(Proof::generate(&[vec![0u8]], 1).is_err());
+        assert_eq!(bag_peaks(&[]).unwrap(), Cid::default());
+    }
+
+    #[test]
+    fn test_tampered_proof() {
+        let leaves: Vec<Vec<u8>> = (0..7u8).map(|i| vec![i]).collect();
+        let root = pushed_root(&leaves);
+        let mut proof = Proof::generate(&leaves, 2).unwrap();
+        proof.siblings[0] = leaf_hash(b"other").unwrap();
+        assert!(proof.verify(&[2], &root).is_err());
+
+        let mut proof = Proof::generate(&leaves, 6).unwrap();
+        proof.leaf_count = 8;
+        assert!(proof.verify(&[6], &root).is_err());
+    }
+}
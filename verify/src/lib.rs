@@ -0,0 +1,16 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! # ADM Verify
+//!
+//! Lightweight verification of ADM data for light clients and off-chain workers,
+//! without the provider and signing stack.
+//!
+//! - [`object`]: Compute and check object CIDs.
+//! - [`accumulator`]: Generate and check accumulator inclusion proofs.
+//! - [`signature`]: Check signatures made by ADM signers (requires the `signatures` feature).
+
+pub mod accumulator;
+pub mod object;
+#[cfg(feature = "signatures")]
+pub mod signature;
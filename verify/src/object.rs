@@ -0,0 +1,109 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use anyhow::anyhow;
+use cid::Cid;
+use unixfs_v1::file::adder::{Chunker, FileAdder};
+
+/// Size of the chunks an object is split into when computing its CID.
+pub const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Computes an object CID incrementally, the same way the Object API does.
+pub struct ObjectHasher {
+    adder: FileAdder,
+    chunk: Cid,
+    size: usize,
+}
+
+impl Default for ObjectHasher {
+    fn default() -> Self {
+        Self {
+            adder: FileAdder::builder()
+                .with_chunker(Chunker::Size(CHUNK_SIZE))
+                .build(),
+            chunk: Cid::default(),
+            size: 0,
+        }
+    }
+}
+
+impl ObjectHasher {
+    /// Create a new hasher for an empty object.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the next bytes of the object.
+    ///
+    /// Returns the CID of the last chunk the bytes completed, if any, e.g., for progress output.
+    pub fn update(&mut self, mut data: &[u8]) -> anyhow::Result<Option<Cid>> {
+        let mut completed = None;
+        while !data.is_empty() {
+            let (blocks, n) = self.adder.push(data);
+            for (cid, _) in blocks {
+                completed = Some(Cid::try_from(cid.to_bytes())?);
+            }
+            self.size += n;
+            data = &data[n..];
+        }
+        if let Some(cid) = completed {
+            self.chunk = cid;
+        }
+        Ok(completed)
+    }
+
+    /// Returns the number of bytes added so far.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Finish hashing and return the object CID.
+    pub fn finish(self) -> anyhow::Result<Cid> {
+        match self.adder.finish().last() {
+            Some((cid, _)) => Ok(Cid::try_from(cid.to_bytes())?),
+            None => Ok(self.chunk),
+        }
+    }
+}
+
+/// Compute the CID of an object.
+pub fn object_cid(data: &[u8]) -> anyhow::Result<Cid> {
+    let mut hasher = ObjectHasher::new();
+    hasher.update(data)?;
+    hasher.finish()
+}
+
+/// Check that an object has the expected CID.
+pub fn verify_object(data: &[u8], expected: &Cid) -> anyhow::Result<()> {
+    let actual = object_cid(data)?;
+    if actual != *expected {
+        return Err(anyhow!(
+            "object CID mismatch: expected {}, got {}",
+            expected,
+            actual
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_object_cid() {
+        let data: Vec<u8> = (0..CHUNK_SIZE * 2 + 100).map(|i| i as u8).collect();
+        let cid = object_cid(&data).unwrap();
+
+        // Hashing in uneven pieces gives the same CID
+        let mut hasher = ObjectHasher::new();
+        for piece in data.chunks(CHUNK_SIZE / 3 + 7) {
+            hasher.update(piece).unwrap();
+        }
+        assert_eq!(hasher.size(), data.len());
+        assert_eq!(hasher.finish().unwrap(), cid);
+
+        assert!(verify_object(&data, &cid).is_ok());
+        assert!(verify_object(&data[1..], &cid).is_err());
+    }
+}
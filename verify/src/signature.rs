@@ -0,0 +1,36 @@
+// Copyright 2024 ADM Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use fendermint_vm_message::signed::SignedMessage;
+use fvm_ipld_encoding::RawBytes;
+use fvm_shared::{
+    address::Address, chainid::ChainID, crypto::signature::Signature, message::Message,
+};
+
+/// Verify a secp256k1 signature over off-chain data, e.g., a manifest or an attestation.
+///
+/// ADM signers sign off-chain data as the params of a read-only message from the signer to
+/// `to` that's never sent, so the signature is bound to the signer and the chain.
+pub fn verify_params(
+    from: Address,
+    to: Address,
+    params: RawBytes,
+    signature: &[u8],
+    chain_id: u64,
+) -> anyhow::Result<()> {
+    let message = Message {
+        version: Default::default(),
+        from,
+        to,
+        sequence: 0,
+        value: Default::default(),
+        method_num: 0,
+        params,
+        gas_limit: Default::default(),
+        gas_fee_cap: Default::default(),
+        gas_premium: Default::default(),
+    };
+    let signature = Signature::new_secp256k1(signature.to_vec());
+    SignedMessage::verify_signature(&message, &None, &signature, &ChainID::from(chain_id))
+        .map_err(|e| anyhow::anyhow!("{e}"))
+}